@@ -0,0 +1,107 @@
+// Etapas del pipeline por separado sobre entradas fijas: la esfera de planeta con más
+// detalle, vista de frente a 800x600 (misma cámara y matrices en cada corrida).
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use nalgebra_glm::Vec3;
+use std::hint::black_box;
+use std::sync::Arc;
+use space_travel_simulator::camera::{create_perspective_matrix, create_view_matrix, create_viewport_matrix, ScreenProjection};
+use space_travel_simulator::celestial_body::ShaderType;
+use space_travel_simulator::fragment::Fragment;
+use space_travel_simulator::renderer::create_model_matrix;
+use space_travel_simulator::scene::create_planet_lod;
+use space_travel_simulator::shaders::{fragment_shader, vertex_shader, FrameUniforms, Uniforms};
+use space_travel_simulator::ship_material::ShipMaterial;
+use space_travel_simulator::sphere::{SphereMesh, SphereTessellation};
+use space_travel_simulator::triangle::triangle;
+use space_travel_simulator::vertex::Vertex;
+use space_travel_simulator::Camera;
+
+const WIDTH: f32 = 800.0;
+const HEIGHT: f32 = 600.0;
+
+struct Inputs {
+    uniforms: Uniforms,
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    transformed: Vec<Vertex>,
+    fragments: Vec<Fragment>,
+}
+
+fn inputs() -> Inputs {
+    let camera = Camera::new(Vec3::new(0.0, 0.0, 3.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+    let projection = ScreenProjection::new(
+        create_view_matrix(&camera),
+        create_perspective_matrix(WIDTH, HEIGHT),
+        create_viewport_matrix(WIDTH, HEIGHT),
+    );
+    let frame = Arc::new(FrameUniforms::new(&projection));
+    let uniforms = Uniforms::new(frame, create_model_matrix(Vec3::zeros(), 1.0, Vec3::new(0.3, 0.5, 0.0)), 1.5)
+        .with_ship_material(ShipMaterial::default());
+
+    // Nivel de detalle máximo (radio en pantalla enorme)
+    let lod = create_planet_lod(SphereMesh::Icosphere, SphereTessellation::Medium, true, None);
+    let mesh = lod.level_for(f32::MAX);
+    let transformed: Vec<Vertex> = mesh.vertices.iter().map(|vertex| vertex_shader(vertex, &uniforms)).collect();
+    let fragments = mesh
+        .indices
+        .chunks_exact(3)
+        .flat_map(|tri| triangle(&transformed[tri[0] as usize], &transformed[tri[1] as usize], &transformed[tri[2] as usize]))
+        .collect();
+
+    Inputs { uniforms, vertices: mesh.vertices.clone(), indices: mesh.indices.clone(), transformed, fragments }
+}
+
+fn pipeline_stages(c: &mut Criterion) {
+    let inputs = inputs();
+
+    let mut group = c.benchmark_group("stages");
+    group.throughput(Throughput::Elements(inputs.vertices.len() as u64));
+    group.bench_function("vertex_shader", |b| {
+        b.iter(|| {
+            let transformed: Vec<Vertex> = inputs.vertices.iter().map(|vertex| vertex_shader(vertex, &inputs.uniforms)).collect();
+            black_box(transformed)
+        })
+    });
+
+    group.throughput(Throughput::Elements(inputs.indices.len() as u64 / 3));
+    group.bench_function("rasterize", |b| {
+        let transformed = &inputs.transformed;
+        b.iter(|| {
+            let fragment_count: usize = inputs
+                .indices
+                .chunks_exact(3)
+                .map(|tri| triangle(&transformed[tri[0] as usize], &transformed[tri[1] as usize], &transformed[tri[2] as usize]).len())
+                .sum();
+            black_box(fragment_count)
+        })
+    });
+    group.finish();
+
+    // Cada shader procedural sobre los mismos fragmentos
+    let mut group = c.benchmark_group("fragment_shader");
+    group.throughput(Throughput::Elements(inputs.fragments.len() as u64));
+    let shader_types = [
+        ShaderType::Sun,
+        ShaderType::RockyPlanet,
+        ShaderType::GasGiant,
+        ShaderType::Moon,
+        ShaderType::RingedPlanet,
+        ShaderType::Starfield,
+        ShaderType::Ship,
+    ];
+    for shader_type in shader_types {
+        group.bench_with_input(BenchmarkId::from_parameter(shader_type.display_name()), &shader_type, |b, shader_type| {
+            b.iter(|| {
+                let mut checksum = 0u32;
+                for fragment in &inputs.fragments {
+                    checksum = checksum.wrapping_add(fragment_shader(fragment, &inputs.uniforms, shader_type).to_hex());
+                }
+                black_box(checksum)
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, pipeline_stages);
+criterion_main!(benches);
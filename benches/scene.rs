@@ -0,0 +1,41 @@
+// De punta a punta y sin ventana: 500 frames de la escena por defecto (simulación + dibujo)
+// con los ajustes por defecto y la cámara inicial del simulador.
+use criterion::{criterion_group, criterion_main, Criterion};
+use nalgebra_glm::Vec3;
+use std::cell::RefCell;
+use std::hint::black_box;
+use std::time::Duration;
+use space_travel_simulator::assets::{self, Assets};
+use space_travel_simulator::{Camera, Renderer, Scene, Settings};
+
+const FRAMES: usize = 500;
+const FRAME_TIME: f32 = 1.0 / 60.0;
+const WIDTH: usize = 600;
+const HEIGHT: usize = 450;  // 75% de 800x600, la resolución interna por defecto
+
+fn render_default_scene(c: &mut Criterion) {
+    let settings = Settings::default();
+    // Cada muestra arranca de una escena nueva; los assets se comparten entre muestras
+    let assets = RefCell::new(Assets::new(assets::resolve_asset_root(None)));
+    let camera = Camera::new(Vec3::new(0.0, 3.0, 8.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+
+    let mut group = c.benchmark_group("scene");
+    group.sample_size(10).measurement_time(Duration::from_secs(30));
+    group.bench_function("default_scene_500_frames", |b| {
+        b.iter_batched(
+            || (Scene::solar_system(&mut assets.borrow_mut()), Renderer::new(WIDTH, HEIGHT, settings.resolution), vec![0; WIDTH * HEIGHT]),
+            |(mut scene, mut renderer, mut pixels)| {
+                for _ in 0..FRAMES {
+                    scene.update(FRAME_TIME, settings.time_scale, true);
+                    renderer.render_scene(&scene, &camera, &mut pixels, WIDTH, HEIGHT).expect("the target matches the size");
+                }
+                black_box(pixels[0])
+            },
+            criterion::BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, render_default_scene);
+criterion_main!(benches);
@@ -0,0 +1,69 @@
+use crate::framebuffer::Framebuffer;
+
+// Contraste mínimo (absoluto y relativo al más brillante del vecindario) para tratar un pixel como borde
+const EDGE_THRESHOLD_MIN: f32 = 0.0312;
+const EDGE_THRESHOLD: f32 = 0.125;
+// Cuánto se mezcla como máximo con el vecino del otro lado del borde
+const MAX_BLEND: f32 = 0.5;
+
+// Brillo percibido de un color 0RGB (0.0..=1.0)
+pub fn luma(color: u32) -> f32 {
+    let r = ((color >> 16) & 0xFF) as f32;
+    let g = ((color >> 8) & 0xFF) as f32;
+    let b = (color & 0xFF) as f32;
+    (0.299 * r + 0.587 * g + 0.114 * b) / 255.0
+}
+
+fn lerp_color(a: u32, b: u32, t: f32) -> u32 {
+    let channel = |shift: u32| {
+        let from = ((a >> shift) & 0xFF) as f32;
+        let to = ((b >> shift) & 0xFF) as f32;
+        ((from + (to - from) * t).round() as u32) << shift
+    };
+    channel(16) | channel(8) | channel(0)
+}
+
+// FXAA simplificado (sin búsqueda de extremos del borde): en los pixeles con mucho contraste
+// local se decide si el borde es horizontal o vertical y se mezcla con el vecino que está al
+// otro lado, más cuanto más "aislado" se ve el pixel respecto a su vecindario.
+pub fn apply_fxaa(framebuffer: &mut Framebuffer) {
+    let (width, height) = (framebuffer.width, framebuffer.height);
+    if width < 3 || height < 3 {
+        return;
+    }
+    let source = framebuffer.buffer.clone();
+    let lumas: Vec<f32> = source.iter().map(|&color| luma(color)).collect();
+
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            let index = y * width + x;
+            let center = lumas[index];
+            let (north, south) = (lumas[index - width], lumas[index + width]);
+            let (west, east) = (lumas[index - 1], lumas[index + 1]);
+
+            let max = center.max(north).max(south).max(west).max(east);
+            let min = center.min(north).min(south).min(west).min(east);
+            let contrast = max - min;
+            if contrast < EDGE_THRESHOLD_MIN.max(max * EDGE_THRESHOLD) {
+                continue;
+            }
+
+            // Cuanto más se aleja el pixel del promedio de sus vecinos, más se suaviza
+            let average = (north + south + west + east) / 4.0;
+            let subpixel = ((average - center).abs() / contrast).clamp(0.0, 1.0);
+            let blend = subpixel * subpixel * (3.0 - 2.0 * subpixel) * MAX_BLEND;
+
+            // Borde horizontal (cambia de arriba a abajo) => mezclar en vertical, y viceversa
+            let horizontal = (north + south - 2.0 * center).abs() >= (west + east - 2.0 * center).abs();
+            let neighbor = if horizontal {
+                if (north - center).abs() >= (south - center).abs() { index - width } else { index + width }
+            } else if (west - center).abs() >= (east - center).abs() {
+                index - 1
+            } else {
+                index + 1
+            };
+
+            framebuffer.buffer[index] = lerp_color(source[index], source[neighbor], blend);
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::io;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use log::{info, warn};
+use crate::obj_loader::{MeshOptions, ProcessedModel};
+use crate::texture::{Sampler, Texture};
+use crate::hot_reload::{self, AssetWatcher};
+
+// Referencia liviana a un asset cargado; se copia libremente y se resuelve con `Assets`
+pub struct Handle<T> {
+    index: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> std::fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Handle({})", self.index)
+    }
+}
+
+struct Slot<T> {
+    path: PathBuf,
+    asset: Arc<T>,
+    ref_count: usize,
+}
+
+// Assets de un mismo tipo indexados por ruta; cada `acquire` suma una referencia
+struct Store<T> {
+    slots: Vec<Option<Slot<T>>>,
+    by_path: HashMap<PathBuf, usize>,
+}
+
+impl<T> Store<T> {
+    fn new() -> Self {
+        Store { slots: Vec::new(), by_path: HashMap::new() }
+    }
+
+    fn acquire<E>(&mut self, path: &Path, load: impl FnOnce(&Path) -> Result<T, E>) -> Result<Handle<T>, E> {
+        if let Some(&index) = self.by_path.get(path) {
+            if let Some(slot) = self.slots[index].as_mut() {
+                slot.ref_count += 1;
+                return Ok(Handle { index, marker: PhantomData });
+            }
+        }
+
+        let asset = Arc::new(load(path)?);
+        let slot = Slot { path: path.to_path_buf(), asset, ref_count: 1 };
+        // Reutilizar un hueco libre si lo hay para que los índices no crezcan sin límite
+        let index = match self.slots.iter().position(Option::is_none) {
+            Some(index) => {
+                self.slots[index] = Some(slot);
+                index
+            }
+            None => {
+                self.slots.push(Some(slot));
+                self.slots.len() - 1
+            }
+        };
+        self.by_path.insert(path.to_path_buf(), index);
+        Ok(Handle { index, marker: PhantomData })
+    }
+
+    fn get(&self, handle: Handle<T>) -> Option<Arc<T>> {
+        self.slots.get(handle.index)?.as_ref().map(|slot| Arc::clone(&slot.asset))
+    }
+
+    // Suelta una referencia; al llegar a cero el asset se descarga
+    fn release(&mut self, handle: Handle<T>) {
+        let Some(Some(slot)) = self.slots.get_mut(handle.index) else {
+            return;
+        };
+        slot.ref_count -= 1;
+        if slot.ref_count == 0 {
+            self.by_path.remove(&slot.path);
+            self.slots[handle.index] = None;
+        }
+    }
+
+    // Vuelve a leer del disco los assets que cumplan `stale` (el cargador recibe la versión
+    // anterior); los handles siguen siendo válidos y si una recarga falla se conserva lo que había.
+    fn reload<E: std::fmt::Display>(
+        &mut self,
+        stale: impl Fn(&Path, &T) -> bool,
+        load: impl Fn(&Path, &T) -> Result<T, E>,
+    ) -> usize {
+        let mut reloaded = 0;
+        for slot in self.slots.iter_mut().flatten().filter(|slot| stale(&slot.path, &slot.asset)) {
+            match load(&slot.path, &slot.asset) {
+                Ok(asset) => {
+                    slot.asset = Arc::new(asset);
+                    reloaded += 1;
+                }
+                Err(e) => warn!("Could not reload {}: {}", slot.path.display(), e),
+            }
+        }
+        reloaded
+    }
+
+    fn len(&self) -> usize {
+        self.by_path.len()
+    }
+}
+
+pub const ASSET_ROOT_ENV: &str = "SPACE_TRAVEL_ASSETS";
+const DEFAULT_ASSET_DIR: &str = "assets";
+
+// Carpeta de assets, en orden de prioridad:
+//   1. `from_flag`: `--assets <dir>` en la línea de comandos
+//   2. la variable de entorno SPACE_TRAVEL_ASSETS
+//   3. `assets/` en el directorio actual
+//   4. `assets/` junto al ejecutable o en una carpeta superior (cubre target/debug y target/release)
+// Si nada existe se devuelve la opción 3 y se usan los assets embebidos en el binario.
+pub fn resolve_asset_root(from_flag: Option<PathBuf>) -> PathBuf {
+    if let Some(root) = from_flag {
+        return checked_root(root, "--assets");
+    }
+    if let Some(root) = std::env::var_os(ASSET_ROOT_ENV) {
+        return checked_root(PathBuf::from(root), ASSET_ROOT_ENV);
+    }
+
+    let in_cwd = PathBuf::from(DEFAULT_ASSET_DIR);
+    if in_cwd.is_dir() {
+        return in_cwd;
+    }
+
+    let exe_dir = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf));
+    let beside_exe = exe_dir
+        .iter()
+        .flat_map(|dir| dir.ancestors().take(3))
+        .map(|dir| dir.join(DEFAULT_ASSET_DIR))
+        .find(|candidate| candidate.is_dir());
+    match beside_exe {
+        Some(root) => {
+            info!("Asset root (next to executable): {}", root.display());
+            root
+        }
+        None => {
+            warn!("No assets/ directory found, using built-in assets");
+            in_cwd
+        }
+    }
+}
+
+fn checked_root(root: PathBuf, source: &str) -> PathBuf {
+    if root.is_dir() {
+        info!("Asset root (from {}): {}", source, root.display());
+    } else {
+        warn!("Asset root {} (from {}) is not a directory, using built-in assets", root.display(), source);
+    }
+    root
+}
+
+// Servicio central de assets: cachea modelos y texturas por ruta y reparte handles
+pub struct Assets {
+    root: PathBuf,
+    models: Store<ProcessedModel>,
+    textures: Store<Texture>,
+    watcher: Option<AssetWatcher>,
+}
+
+impl Assets {
+    pub fn new(root: PathBuf) -> Self {
+        Assets { root, models: Store::new(), textures: Store::new(), watcher: None }
+    }
+
+    // Ruta de un asset relativa a la raíz (las rutas absolutas se devuelven tal cual)
+    pub fn resolve<P: AsRef<Path>>(&self, relative: P) -> PathBuf {
+        self.root.join(relative)
+    }
+
+    // Empieza a vigilar la carpeta de assets; a partir de ahí `reload_changed` recarga lo que se modifique
+    pub fn watch(&mut self) {
+        match AssetWatcher::new(&self.root) {
+            Ok(watcher) => {
+                info!("Watching {} for asset changes", self.root.display());
+                self.watcher = Some(watcher);
+            }
+            Err(e) => warn!("Hot reload disabled, could not watch {}: {}", self.root.display(), e),
+        }
+    }
+
+    // Como con las texturas, las opciones se fijan en la primera carga de cada ruta
+    pub fn load_model<P: AsRef<Path>>(&mut self, path: P, options: MeshOptions) -> io::Result<Handle<ProcessedModel>> {
+        self.models.acquire(path.as_ref(), |path| ProcessedModel::load_cached(path, options))
+    }
+
+    pub fn model(&self, handle: Handle<ProcessedModel>) -> Option<Arc<ProcessedModel>> {
+        self.models.get(handle)
+    }
+
+    pub fn release_model(&mut self, handle: Handle<ProcessedModel>) {
+        self.models.release(handle);
+    }
+
+    // El filtro se fija en la primera carga; los siguientes pedidos de la misma ruta lo comparten
+    pub fn load_texture<P: AsRef<Path>>(&mut self, path: P, sampler: Sampler) -> Result<Handle<Texture>, image::ImageError> {
+        self.textures.acquire(path.as_ref(), |path| {
+            Texture::load(path).map(|texture| texture.with_sampler(sampler))
+        })
+    }
+
+    pub fn texture(&self, handle: Handle<Texture>) -> Option<Arc<Texture>> {
+        self.textures.get(handle)
+    }
+
+    pub fn release_texture(&mut self, handle: Handle<Texture>) {
+        self.textures.release(handle);
+    }
+
+    // Recarga todo desde el disco; devuelve cuántos assets se actualizaron
+    pub fn reload_all(&mut self) -> usize {
+        self.reload_where(|_| true)
+    }
+
+    // Recarga solo los assets cuyos archivos cambiaron desde el último frame (un modelo
+    // también cuenta como cambiado si cambia su .mtl). Sin `watch` no hace nada.
+    pub fn reload_changed(&mut self) -> usize {
+        let Some(changed) = self.watcher.as_ref().map(AssetWatcher::changed_paths) else {
+            return 0;
+        };
+        if changed.is_empty() {
+            return 0;
+        }
+        self.reload_where(|path| changed.contains(&hot_reload::canonical(path)))
+    }
+
+    fn reload_where(&mut self, changed: impl Fn(&Path) -> bool) -> usize {
+        let models = self.models.reload(
+            |path, model| changed(path) || model.source_files.iter().any(|file| changed(file)),
+            |path, previous| ProcessedModel::load_cached(path, previous.options),
+        );
+        let textures = self.textures.reload(
+            |path, _| changed(path),
+            |path, previous| Texture::load(path).map(|texture| texture.with_sampler(previous.sampler)),
+        );
+        models + textures
+    }
+
+    // (modelos, texturas) cargados actualmente
+    pub fn loaded_counts(&self) -> (usize, usize) {
+        (self.models.len(), self.textures.len())
+    }
+}
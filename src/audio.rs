@@ -0,0 +1,330 @@
+// Efectos de un solo disparo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sound {
+    Collision,  // Golpe grave con ruido al chocar con un cuerpo
+    Warp,       // Barrido ascendente al saltar o al viajar hacia un cuerpo
+    Blip,       // Interfaz: menú de pausa
+    Warning,    // Alerta de proximidad; se repite al ritmo del parpadeo
+}
+
+impl Sound {
+    #[cfg(feature = "audio")]
+    const ALL: [Sound; 4] = [Sound::Collision, Sound::Warp, Sound::Blip, Sound::Warning];
+}
+
+// Volumen de cada capa relativo al volumen general
+const MUSIC_VOLUME: f32 = 0.35;
+const ENGINE_VOLUME: f32 = 0.5;
+// Qué tan rápido sube y baja el motor al acelerar o soltar (por segundo)
+const ENGINE_RAMP_RATE: f32 = 4.0;
+
+// Sonido del simulador: música ambiente en bucle, el motor de la nave siguiendo el empuje y
+// efectos sueltos. Todo se sintetiza al arrancar (no hay archivos de audio). Sin
+// `--features audio`, o si no hay dispositivo de salida, queda en silencio y el resto del
+// simulador no se entera.
+pub struct Audio {
+    volume: f32,  // Volumen general 0.0..=1.0 (ajustes)
+    muted: bool,
+    engine_target: f32,
+    engine_level: f32,
+    output: Option<playback::Output>,
+}
+
+impl Audio {
+    // `enabled` = abrir el dispositivo de sonido (las corridas headless no lo hacen)
+    pub fn new(volume: f32, enabled: bool) -> Self {
+        let audio = Audio {
+            volume: volume.clamp(0.0, 1.0),
+            muted: false,
+            engine_target: 0.0,
+            engine_level: 0.0,
+            output: if enabled { playback::Output::open() } else { None },
+        };
+        audio.apply_volumes();
+        audio
+    }
+
+    // Hay un dispositivo abierto (si no, todo es silencio)
+    pub fn is_available(&self) -> bool {
+        self.output.is_some()
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+        self.apply_volumes();
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+        self.apply_volumes();
+    }
+
+    // Volumen efectivo (0 si está silenciado)
+    fn gain(&self) -> f32 {
+        if self.muted { 0.0 } else { self.volume }
+    }
+
+    // Empuje de la nave este frame (0.0 apagado, 1.0 a fondo)
+    pub fn set_engine_throttle(&mut self, throttle: f32) {
+        self.engine_target = throttle.clamp(0.0, 1.0);
+    }
+
+    // Lleva el motor hacia el empuje pedido sin saltos bruscos de volumen
+    pub fn update(&mut self, delta_time: f32) {
+        let step = ENGINE_RAMP_RATE * delta_time;
+        self.engine_level += (self.engine_target - self.engine_level).clamp(-step, step);
+        self.apply_volumes();
+    }
+
+    pub fn play(&self, sound: Sound) {
+        if let Some(output) = &self.output {
+            output.play(sound, self.gain());
+        }
+    }
+
+    fn apply_volumes(&self) {
+        if let Some(output) = &self.output {
+            output.set_levels(MUSIC_VOLUME * self.gain(), ENGINE_VOLUME * self.engine_level * self.gain());
+        }
+    }
+}
+
+#[cfg(feature = "audio")]
+mod playback {
+    use log::{info, warn};
+    use std::collections::HashMap;
+    use std::num::NonZero;
+    use rodio::buffer::SamplesBuffer;
+    use rodio::{DeviceSinkBuilder, MixerDeviceSink, Player, Source};
+    use super::{synth, Sound};
+
+    pub struct Output {
+        device: MixerDeviceSink,  // Mientras viva sigue sonando
+        music: Player,
+        engine: Player,
+        effects: HashMap<Sound, SamplesBuffer>,
+    }
+
+    impl Output {
+        pub fn open() -> Option<Output> {
+            match Output::try_open() {
+                Ok(output) => {
+                    info!("Audio output opened");
+                    Some(output)
+                }
+                Err(e) => {
+                    warn!("Audio disabled, could not open the output device: {}", e);
+                    None
+                }
+            }
+        }
+
+        fn try_open() -> Result<Output, rodio::DeviceSinkError> {
+            let mut device = DeviceSinkBuilder::open_default_sink()?;
+            device.log_on_drop(false);
+
+            let music = Player::connect_new(device.mixer());
+            music.set_volume(0.0);
+            music.append(buffer(synth::ambient_music()).repeat_infinite());
+            let engine = Player::connect_new(device.mixer());
+            engine.set_volume(0.0);
+            engine.append(buffer(synth::engine_loop()).repeat_infinite());
+
+            let effects = Sound::ALL.into_iter().map(|sound| (sound, buffer(synth::effect(sound)))).collect();
+            Ok(Output { device, music, engine, effects })
+        }
+
+        pub fn set_levels(&self, music: f32, engine: f32) {
+            self.music.set_volume(music);
+            self.engine.set_volume(engine);
+        }
+
+        // Cada efecto se mezcla aparte, así varios pueden sonar a la vez
+        pub fn play(&self, sound: Sound, gain: f32) {
+            if gain <= 0.0 {
+                return;
+            }
+            if let Some(samples) = self.effects.get(&sound) {
+                self.device.mixer().add(samples.clone().amplify(gain));
+            }
+        }
+    }
+
+    fn buffer(samples: Vec<f32>) -> SamplesBuffer {
+        let channels = NonZero::new(1).expect("mono");
+        let sample_rate = NonZero::new(synth::SAMPLE_RATE).expect("non-zero sample rate");
+        SamplesBuffer::new(channels, sample_rate, samples)
+    }
+}
+
+// Sin `--features audio` no hay salida: `open` nunca devuelve una
+#[cfg(not(feature = "audio"))]
+mod playback {
+    use super::Sound;
+
+    pub enum Output {}
+
+    impl Output {
+        pub fn open() -> Option<Output> {
+            None
+        }
+
+        pub fn set_levels(&self, _music: f32, _engine: f32) {
+            match *self {}
+        }
+
+        pub fn play(&self, _sound: Sound, _gain: f32) {
+            match *self {}
+        }
+    }
+}
+
+// Síntesis de los sonidos (mono, -1.0..=1.0)
+#[cfg(feature = "audio")]
+mod synth {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::f32::consts::TAU;
+    use super::Sound;
+
+    pub const SAMPLE_RATE: u32 = 44_100;
+    const MUSIC_LOOP_SECONDS: f32 = 24.0;
+    const ENGINE_LOOP_SECONDS: f32 = 2.0;
+
+    fn sample_count(seconds: f32) -> usize {
+        (seconds * SAMPLE_RATE as f32) as usize
+    }
+
+    // Frecuencia ajustada para que entre un número entero de ciclos en el bucle (sin clic al repetir)
+    fn loop_frequency(frequency: f32, loop_seconds: f32) -> f32 {
+        (frequency * loop_seconds).round() / loop_seconds
+    }
+
+    // Filtro pasa-bajos de un polo sobre ruido blanco: rumor más grave cuanto menor es `smoothing`
+    fn rumble(rng: &mut StdRng, count: usize, smoothing: f32) -> Vec<f32> {
+        let mut value = 0.0;
+        (0..count)
+            .map(|_| {
+                value += (rng.random_range(-1.0..1.0) - value) * smoothing;
+                value
+            })
+            .collect()
+    }
+
+    fn normalize(samples: &mut [f32], peak: f32) {
+        let max = samples.iter().fold(0.0_f32, |max, sample| max.max(sample.abs()));
+        if max > 0.0 {
+            samples.iter_mut().for_each(|sample| *sample *= peak / max);
+        }
+    }
+
+    // Acorde grave (la menor con quinta) cuyas notas entran y salen despacio
+    pub fn ambient_music() -> Vec<f32> {
+        const NOTES: [(f32, f32); 5] = [(55.0, 0.0), (82.41, 1.3), (110.0, 2.1), (130.81, 3.7), (164.81, 5.2)];
+        let mut samples: Vec<f32> = (0..sample_count(MUSIC_LOOP_SECONDS))
+            .map(|index| {
+                let t = index as f32 / SAMPLE_RATE as f32;
+                NOTES
+                    .iter()
+                    .enumerate()
+                    .map(|(voice, &(frequency, phase))| {
+                        // Cada voz respira con un período distinto que divide al bucle
+                        let swell = 0.5 + 0.5 * (TAU * (voice + 1) as f32 * t / MUSIC_LOOP_SECONDS + phase).sin();
+                        let frequency = loop_frequency(frequency, MUSIC_LOOP_SECONDS);
+                        swell * (TAU * frequency * t).sin()
+                    })
+                    .sum()
+            })
+            .collect();
+        normalize(&mut samples, 0.6);
+        samples
+    }
+
+    // Zumbido del motor: ruido grave más un tono a 45 Hz; el final se funde con el principio
+    pub fn engine_loop() -> Vec<f32> {
+        let mut rng = StdRng::seed_from_u64(7);
+        let count = sample_count(ENGINE_LOOP_SECONDS);
+        let fade = sample_count(0.2);
+        let noise = rumble(&mut rng, count + fade, 0.05);
+        let hum_frequency = loop_frequency(45.0, ENGINE_LOOP_SECONDS);
+        let mut samples: Vec<f32> = (0..count)
+            .map(|index| {
+                let t = index as f32 / SAMPLE_RATE as f32;
+                let noise = if index < fade {
+                    let blend = index as f32 / fade as f32;
+                    noise[index] * blend + noise[count + index] * (1.0 - blend)
+                } else {
+                    noise[index]
+                };
+                noise * 0.8 + (TAU * hum_frequency * t).sin() * 0.3
+            })
+            .collect();
+        normalize(&mut samples, 0.8);
+        samples
+    }
+
+    pub fn effect(sound: Sound) -> Vec<f32> {
+        let mut rng = StdRng::seed_from_u64(sound as u64 + 1);
+        let mut samples: Vec<f32> = match sound {
+            // Golpe seco: ruido grave y un tono que cae, los dos apagándose rápido
+            Sound::Collision => {
+                let count = sample_count(0.6);
+                let noise = rumble(&mut rng, count, 0.15);
+                (0..count)
+                    .map(|index| {
+                        let t = index as f32 / SAMPLE_RATE as f32;
+                        let thump = (TAU * (70.0 - 40.0 * t) * t).sin() * (-t * 9.0).exp();
+                        noise[index] * (-t * 7.0).exp() + thump
+                    })
+                    .collect()
+            }
+            // Barrido de 180 a 1400 Hz con ruido que se abre, subiendo y bajando de volumen
+            Sound::Warp => {
+                let duration = 1.2;
+                let count = sample_count(duration);
+                let mut phase = 0.0;
+                let mut filtered = 0.0;
+                (0..count)
+                    .map(|index| {
+                        let progress = index as f32 / count as f32;
+                        let frequency = 180.0 * (1400.0_f32 / 180.0).powf(progress);
+                        phase = (phase + TAU * frequency / SAMPLE_RATE as f32) % TAU;
+                        filtered += (rng.random_range(-1.0..1.0) - filtered) * (0.02 + 0.3 * progress);
+                        let envelope = (std::f32::consts::PI * progress).sin().powi(2);
+                        (phase.sin() * 0.5 + filtered) * envelope
+                    })
+                    .collect()
+            }
+            // Pitido corto y limpio
+            Sound::Blip => {
+                let count = sample_count(0.06);
+                (0..count)
+                    .map(|index| {
+                        let t = index as f32 / SAMPLE_RATE as f32;
+                        (TAU * 880.0 * t).sin() * (-t * 60.0).exp()
+                    })
+                    .collect()
+            }
+            // Dos tonos agudos seguidos, con armónico para que corte por encima de la música
+            Sound::Warning => {
+                let count = sample_count(0.16);
+                (0..count)
+                    .map(|index| {
+                        let t = index as f32 / SAMPLE_RATE as f32;
+                        let progress = index as f32 / count as f32;
+                        let frequency = if progress < 0.5 { 1320.0 } else { 990.0 };
+                        // Cada mitad sube y baja sin clic
+                        let envelope = (TAU * progress).sin().abs();
+                        ((TAU * frequency * t).sin() + 0.3 * (TAU * 2.0 * frequency * t).sin()) * envelope
+                    })
+                    .collect()
+            }
+        };
+        normalize(&mut samples, 0.8);
+        samples
+    }
+}
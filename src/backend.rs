@@ -0,0 +1,153 @@
+use std::time::Duration;
+use minifb::{KeyRepeat, MouseButton, MouseMode, Window, WindowOptions};
+use crate::error::Error;
+use crate::framebuffer::Framebuffer;
+use crate::input::{InputEvent, InputState};
+
+// En el navegador minifb crea su canvas dentro del elemento con este id (ver web/index.html)
+#[cfg(target_arch = "wasm32")]
+pub const WEB_CONTAINER_ID: &str = "space-travel";
+
+// Ventanas disponibles; winit y wgpu solo si se compilaron con `--features winit` / `wgpu`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BackendKind {
+    Minifb,
+    Winit,  // winit + softbuffer
+    Wgpu,   // winit + wgpu: el framebuffer se sube como textura cada frame
+}
+
+impl BackendKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            BackendKind::Minifb => "minifb",
+            BackendKind::Winit => "winit",
+            BackendKind::Wgpu => "wgpu",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<BackendKind> {
+        [BackendKind::Minifb, BackendKind::Winit, BackendKind::Wgpu]
+            .into_iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(name))
+    }
+
+    // Abre la ventana con este backend
+    pub fn open(self, title: &str, width: usize, height: usize) -> Result<Box<dyn Backend>, Error> {
+        match self {
+            BackendKind::Minifb => Ok(Box::new(MinifbBackend::new(title, width, height)?)),
+            #[cfg(feature = "winit")]
+            BackendKind::Winit => Ok(Box::new(crate::winit_backend::WinitBackend::new(title, width, height, self)?)),
+            #[cfg(not(feature = "winit"))]
+            BackendKind::Winit => Err(Error::Window("this build has no winit backend (rebuild with `--features winit`)".to_string())),
+            #[cfg(feature = "wgpu")]
+            BackendKind::Wgpu => Ok(Box::new(crate::winit_backend::WinitBackend::new(title, width, height, self)?)),
+            #[cfg(not(feature = "wgpu"))]
+            BackendKind::Wgpu => Err(Error::Window("this build has no wgpu backend (rebuild with `--features wgpu`)".to_string())),
+        }
+    }
+}
+
+// La ventana y su presentación: de dónde sale la entrada y a dónde va cada framebuffer.
+// El engine no sabe nada de esto; el binario elige uno con `--backend`.
+pub trait Backend {
+    fn kind(&self) -> BackendKind;
+
+    fn is_open(&self) -> bool;
+
+    // Empieza un frame en `input` y le agrega lo que pasó desde el anterior
+    fn poll(&mut self, input: &mut InputState);
+
+    // Muestra el framebuffer, escalado al tamaño de la ventana si no coinciden. Un error
+    // significa que la ventana ya no puede mostrar nada (no un frame salteado al minimizar).
+    fn present(&mut self, framebuffer: &Framebuffer) -> Result<(), Error>;
+
+    // Espera entrada sin presentar un frame nuevo (ahorro de energía), como mucho `timeout`.
+    // La ventana sigue respondiendo mientras tanto; la entrada se lee en el próximo `poll`.
+    fn wait(&mut self, timeout: Duration) {
+        std::thread::sleep(timeout);
+    }
+
+    // Si `present` ya espera al refresco de la pantalla (el bucle no necesita dormir)
+    fn vsync(&self) -> bool {
+        false
+    }
+}
+
+// Backend por defecto: una ventana de minifb
+pub struct MinifbBackend {
+    window: Window,
+}
+
+impl MinifbBackend {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new(title: &str, width: usize, height: usize) -> Result<Self, Error> {
+        let mut window = Window::new(title, width, height, WindowOptions::default()).map_err(|e| Error::Window(e.to_string()))?;
+        window.set_position(500, 500);
+        window.update();
+        Ok(MinifbBackend { window })
+    }
+
+    // En la web la ventana es un canvas; el título no se usa
+    #[cfg(target_arch = "wasm32")]
+    pub fn new(_title: &str, width: usize, height: usize) -> Result<Self, Error> {
+        let window = Window::new(WEB_CONTAINER_ID, width, height, WindowOptions::default()).map_err(|e| Error::Window(e.to_string()))?;
+        Ok(MinifbBackend { window })
+    }
+}
+
+impl Backend for MinifbBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Minifb
+    }
+
+    fn is_open(&self) -> bool {
+        self.window.is_open()
+    }
+
+    fn poll(&mut self, input: &mut InputState) {
+        let window = &self.window;
+        input.begin_frame();
+
+        let pressed = window.get_keys_pressed(KeyRepeat::No);
+        for &key in &pressed {
+            input.push(InputEvent::KeyPressed(key));
+        }
+        for key in window.get_keys_pressed(KeyRepeat::Yes) {
+            if !pressed.contains(&key) {
+                input.push(InputEvent::KeyRepeated(key));
+            }
+        }
+        for key in window.get_keys_released() {
+            input.push(InputEvent::KeyReleased(key));
+        }
+        // La ventana sabe qué está presionado aunque se haya perdido un evento (p. ej. sin foco)
+        input.set_held(window.get_keys());
+
+        if let Some((x, y)) = window.get_mouse_pos(MouseMode::Discard) {
+            if input.mouse_position != Some((x, y)) {
+                input.push(InputEvent::MouseMoved { x, y });
+            }
+        }
+        for (button, mouse_button) in [MouseButton::Left, MouseButton::Middle, MouseButton::Right].into_iter().enumerate() {
+            let down = window.get_mouse_down(mouse_button);
+            if down != input.mouse_down[button] {
+                input.push(InputEvent::MouseButton { button, down });
+            }
+        }
+        if let Some((x, y)) = window.get_scroll_wheel() {
+            input.push(InputEvent::Scroll { x, y });
+        }
+    }
+
+    // minifb solo procesa los eventos al actualizar la ventana: se actualiza sin imagen nueva
+    fn wait(&mut self, timeout: Duration) {
+        std::thread::sleep(timeout);
+        self.window.update();
+    }
+
+    fn present(&mut self, framebuffer: &Framebuffer) -> Result<(), Error> {
+        self.window
+            .update_with_buffer(&framebuffer.buffer, framebuffer.width, framebuffer.height)
+            .map_err(|e| Error::Present(e.to_string()))
+    }
+}
@@ -0,0 +1,181 @@
+use minifb::Key;
+use crate::framebuffer::Framebuffer;
+use crate::i18n::{tr, tr_with};
+use crate::ruler::format_distance;
+use crate::scene::Scene;
+use crate::settings::FocusOrder;
+use crate::text::{draw_text, text_width, GLYPH_HEIGHT};
+
+const MAX_QUERY: usize = 24;
+const VISIBLE_RESULTS: usize = 8;
+
+const PANEL_COLOR: u32 = 0x000000;
+const TITLE_COLOR: u32 = 0x7FD4FF;
+const LABEL_COLOR: u32 = 0x9AA8B8;
+const TEXT_COLOR: u32 = 0xE0F0FF;
+const SELECTED_COLOR: u32 = 0xFFD060;
+const PADDING: usize = 6;
+
+// Cuerpos que se pueden enfocar (los ocultos siguen simulándose pero no se buscan), en el orden
+// pedido: el de la escena o del más cercano a la nave al más lejano
+fn focus_targets(scene: &Scene, order: FocusOrder) -> Vec<usize> {
+    let mut targets: Vec<usize> = (0..scene.bodies.len()).filter(|&index| scene.bodies[index].flags.visible).collect();
+    if order == FocusOrder::Distance {
+        let ship = scene.spaceship.position;
+        let distance = |index: usize| (scene.bodies[index].position - ship).magnitude();
+        targets.sort_by(|&a, &b| distance(a).total_cmp(&distance(b)));
+    }
+    targets
+}
+
+// Cuerpo `steps` lugares después (negativo = antes) de `current` en el orden de Tab, dando la
+// vuelta. Sin foco (la cámara sigue a la nave) empieza por el primero o el último.
+pub fn cycle_focus(scene: &Scene, order: FocusOrder, current: Option<usize>, steps: i32) -> Option<usize> {
+    let targets = focus_targets(scene, order);
+    if targets.is_empty() {
+        return None;
+    }
+    let next = match current.and_then(|current| targets.iter().position(|&index| index == current)) {
+        Some(position) => (position as i32 + steps).rem_euclid(targets.len() as i32) as usize,
+        None if steps > 0 => 0,
+        None => targets.len() - 1,
+    };
+    Some(targets[next])
+}
+
+// Lo que el engine tiene que hacer tras una tecla de la búsqueda
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchCommand {
+    Close,
+    WarpTo(Option<usize>),  // None = ningún resultado
+}
+
+// Búsqueda rápida de cuerpos (tecla F7): mientras está abierta las teclas escriben el nombre en
+// vez de mover la cámara o la nave. Las flechas eligen entre los resultados y Enter viaja al
+// elegido.
+#[derive(Debug, Default)]
+pub struct BodySearch {
+    query: String,
+    selected: usize,  // Índice en los resultados
+}
+
+impl BodySearch {
+    // Esc cierra, Enter viaja al elegido, las flechas eligen y el resto escribe
+    pub fn handle_key(&mut self, key: Key, scene: &Scene) -> Option<SearchCommand> {
+        match key {
+            Key::Escape => return Some(SearchCommand::Close),
+            Key::Enter | Key::NumPadEnter => return Some(SearchCommand::WarpTo(self.selected_body(scene))),
+            Key::Up => self.move_selection(-1, scene),
+            Key::Down => self.move_selection(1, scene),
+            _ => self.type_key(key),
+        }
+        None
+    }
+
+    // Letra, número o signo que puede ir en un nombre; Backspace borra
+    fn type_key(&mut self, key: Key) {
+        if key == Key::Backspace {
+            self.query.pop();
+        } else if let Some(character) = key_char(key).filter(|_| self.query.len() < MAX_QUERY) {
+            self.query.push(character);
+        } else {
+            return;
+        }
+        self.selected = 0;
+    }
+
+    fn move_selection(&mut self, steps: i32, scene: &Scene) {
+        let count = self.results(scene).len().min(VISIBLE_RESULTS);
+        if count > 0 {
+            self.selected = (self.selected as i32 + steps).rem_euclid(count as i32) as usize;
+        }
+    }
+
+    // Cuerpos cuyo nombre contiene el texto (sin distinguir mayúsculas): primero los que empiezan
+    // con él, después el resto, cada grupo en el orden de la escena
+    fn results(&self, scene: &Scene) -> Vec<usize> {
+        let query = self.query.to_lowercase();
+        let mut results: Vec<(bool, usize)> = focus_targets(scene, FocusOrder::Scene)
+            .into_iter()
+            .filter_map(|index| {
+                let name = scene.bodies[index].name.to_lowercase();
+                name.contains(&query).then(|| (!name.starts_with(&query), index))
+            })
+            .collect();
+        results.sort();
+        results.into_iter().map(|(_, index)| index).collect()
+    }
+
+    fn selected_body(&self, scene: &Scene) -> Option<usize> {
+        self.results(scene).get(self.selected).copied()
+    }
+}
+
+// Las teclas se nombran como en keybindings.cfg: "A", "Key1", "NumPad1"...
+fn key_char(key: Key) -> Option<char> {
+    match key {
+        Key::Space => Some(' '),
+        Key::Minus | Key::NumPadMinus => Some('-'),
+        Key::Period | Key::NumPadDot => Some('.'),
+        Key::Apostrophe => Some('\''),
+        _ => {
+            let name = format!("{:?}", key);
+            let character = name.strip_prefix("NumPad").or_else(|| name.strip_prefix("Key")).unwrap_or(&name);
+            let mut chars = character.chars();
+            match (chars.next(), chars.next()) {
+                (Some(character), None) if character.is_ascii_alphanumeric() => Some(character.to_ascii_lowercase()),
+                _ => None,
+            }
+        }
+    }
+}
+
+// Panel centrado, como el de reasignar teclas: el texto buscado, los resultados con su
+// distancia a la nave y el elegido resaltado, y las teclas (`hint`)
+pub fn render_body_search(framebuffer: &mut Framebuffer, search: &BodySearch, scene: &Scene, hint: &str) {
+    let line = GLYPH_HEIGHT + 3;
+    let title = tr("hud.search_title");
+    let query = format!("> {}_", search.query);
+    let rows: Vec<(&str, String)> = search
+        .results(scene)
+        .into_iter()
+        .take(VISIBLE_RESULTS)
+        .map(|index| {
+            let body = &scene.bodies[index];
+            (body.name, format_distance((body.position - scene.spaceship.position).magnitude(), scene.unit_km))
+        })
+        .collect();
+    let empty = tr_with("hud.search_empty", &[("query", &search.query)]);
+    let name_width = rows.iter().map(|(name, _)| text_width(name)).max().unwrap_or(0);
+    let distance_column = name_width + 2 * text_width(" ");
+    let content_width = rows
+        .iter()
+        .map(|(_, distance)| distance_column + text_width(distance))
+        .chain([text_width(title), text_width(&query), text_width(&empty), text_width(hint)])
+        .max()
+        .unwrap_or(0);
+    let panel_width = (content_width + 2 * PADDING).min(framebuffer.width);
+    let panel_height = (rows.len().max(1) + 3) * line + 2 * PADDING;
+    let panel_x = framebuffer.width.saturating_sub(panel_width) / 2;
+    let panel_y = framebuffer.height.saturating_sub(panel_height) / 2;
+    framebuffer.blend_rect(panel_x, panel_y, panel_width, panel_height, PANEL_COLOR, 0.8);
+    framebuffer.draw_rect(panel_x, panel_y, panel_width, panel_height, LABEL_COLOR);
+
+    let left = (panel_x + PADDING) as i32;
+    let mut y = (panel_y + PADDING) as i32;
+    draw_text(framebuffer, left, y, title, TITLE_COLOR);
+    y += line as i32;
+    draw_text(framebuffer, left, y, &query, TEXT_COLOR);
+    y += line as i32;
+    if rows.is_empty() {
+        draw_text(framebuffer, left, y, &empty, LABEL_COLOR);
+        y += line as i32;
+    }
+    for (index, (name, distance)) in rows.iter().enumerate() {
+        let (name_color, distance_color) = if index == search.selected { (SELECTED_COLOR, SELECTED_COLOR) } else { (TEXT_COLOR, LABEL_COLOR) };
+        draw_text(framebuffer, left, y, name, name_color);
+        draw_text(framebuffer, left + distance_column as i32, y, distance, distance_color);
+        y += line as i32;
+    }
+    draw_text(framebuffer, left, y, hint, LABEL_COLOR);
+}
@@ -1,13 +1,41 @@
-use nalgebra_glm::{Vec3, Mat4, look_at, perspective};
+use nalgebra_glm::{Vec3, Vec4, Mat4, look_at, perspective};
 use std::f32::consts::PI;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CameraMode {
    Orbital,      // Modo orbital alrededor de un punto
    FirstPerson,  // Modo primera persona desde la nave
+   Chase,        // Cámara de persecución detrás de la nave (con retraso)
    Free,         // Modo libre (vuelo libre)
 }
 
+impl CameraMode {
+   pub const ALL: [CameraMode; 4] = [CameraMode::Orbital, CameraMode::FirstPerson, CameraMode::Chase, CameraMode::Free];
+
+   pub fn name(&self) -> &'static str {
+      match self {
+         CameraMode::Orbital => "orbital",
+         CameraMode::FirstPerson => "first-person",
+         CameraMode::Chase => "chase",
+         CameraMode::Free => "free",
+      }
+   }
+
+   // Modos en los que la cámara sigue a la nave en lugar de orbitar un cuerpo
+   pub fn follows_ship(&self) -> bool {
+      matches!(self, CameraMode::FirstPerson | CameraMode::Chase)
+   }
+}
+
+// Parámetros de la cámara de persecución
+const CHASE_DISTANCE: f32 = 2.0;      // Distancia detrás de la nave
+const CHASE_HEIGHT: f32 = 0.7;        // Altura sobre la nave
+const CHASE_LOOK_AHEAD: f32 = 2.0;    // Punto de mira delante de la nave
+const CHASE_STIFFNESS: f32 = 4.0;     // Mayor = menos retraso al seguir
+const CHASE_BANK_FACTOR: f32 = 0.6;   // Cuánto se inclina la cámara con el alabeo de la nave
+const CHASE_MIN_DISTANCE: f32 = 0.4;  // Distancia mínima al acercarse por obstáculos
+const CHASE_OBSTACLE_MARGIN: f32 = 0.15;
+
 pub struct Camera {
    pub eye: Vec3,
    pub center: Vec3,
@@ -33,6 +61,46 @@ impl Camera {
 
    pub fn set_mode(&mut self, mode: CameraMode) {
       self.mode = mode;
+      // Solo la cámara de persecución se inclina; el resto usa el "arriba" del mundo
+      self.up = Vec3::new(0.0, 1.0, 0.0);
+      self.has_changed = true;
+   }
+
+   // Cámara de persecución: sigue a la nave con retraso, se inclina con su alabeo y se acerca
+   // si un cuerpo (centro, radio) se interpone entre la nave y la posición deseada
+   pub fn update_chase(&mut self, ship_position: Vec3, ship_rotation: Vec3, delta_time: f32, obstacles: &[(Vec3, f32)]) {
+      let forward = Vec3::new(ship_rotation.y.sin(), 0.0, ship_rotation.y.cos());
+      let world_up = Vec3::new(0.0, 1.0, 0.0);
+
+      let offset = -forward * CHASE_DISTANCE + world_up * CHASE_HEIGHT;
+      let offset_length = offset.magnitude();
+      let offset_dir = offset / offset_length;
+
+      // Acercar la cámara si un planeta taparía la vista
+      let mut allowed = offset_length;
+      for &(center, radius) in obstacles {
+         if let Some(hit) = ray_sphere_hit(ship_position, offset_dir, center, radius) {
+            if hit < allowed {
+               allowed = (hit - CHASE_OBSTACLE_MARGIN).max(CHASE_MIN_DISTANCE);
+            }
+         }
+      }
+      let desired_eye = ship_position + offset_dir * allowed;
+
+      // Retraso posicional (suavizado exponencial independiente del framerate)
+      let blend = 1.0 - (-CHASE_STIFFNESS * delta_time).exp();
+      self.eye += (desired_eye - self.eye) * blend;
+      // Pero nunca quedar dentro del obstáculo mientras se alcanza la posición deseada
+      if (self.eye - ship_position).magnitude() > allowed + 0.01 && allowed < offset_length {
+         self.eye = desired_eye;
+      }
+
+      self.center = ship_position + forward * CHASE_LOOK_AHEAD;
+
+      // Inclinar el vector "arriba" alrededor del eje de avance según el alabeo
+      let bank = ship_rotation.z * CHASE_BANK_FACTOR;
+      self.up = world_up * bank.cos() + forward.cross(&world_up) * bank.sin();
+
       self.has_changed = true;
    }
 
@@ -150,17 +218,45 @@ impl Camera {
    }
 }
 
+// Distancia a lo largo del rayo hasta la primera intersección con la esfera (si existe)
+fn ray_sphere_hit(origin: Vec3, direction: Vec3, center: Vec3, radius: f32) -> Option<f32> {
+   let m = origin - center;
+   let b = m.dot(&direction);
+   let c = m.dot(&m) - radius * radius;
+   if c > 0.0 && b > 0.0 {
+      return None; // Origen fuera de la esfera y apuntando en sentido contrario
+   }
+   let discriminant = b * b - c;
+   if discriminant < 0.0 {
+      return None;
+   }
+   let t = -b - discriminant.sqrt();
+   if t < 0.0 { None } else { Some(t) }
+}
+
 pub fn create_view_matrix(camera: &Camera) -> Mat4 {
    look_at(&camera.eye, &camera.center, &camera.up)
 }
 
+// Campo de visión vertical de la proyección
+pub const FIELD_OF_VIEW_DEGREES: f32 = 45.0;
+
+// Plano cercano de la proyección; no hay plano lejano
+pub const NEAR_PLANE: f32 = 0.1;
+
+// Perspectiva con la profundidad invertida y sin plano lejano: z = -near / distancia, de -1 en
+// el plano cercano hacia 0 en el infinito. El float tiene más precisión cerca de 0, así que el
+// error queda proporcional a la distancia (~1e-7 de ella) en vez de amontonar todo lo lejano en
+// los últimos valores antes de 1 como con `perspective(near, far)`. Se mantiene "menor = más
+// cerca" para el z-buffer.
 pub fn create_perspective_matrix(window_width: f32, window_height: f32) -> Mat4 {
-   let fov = 45.0 * PI / 180.0;
+   let fov = FIELD_OF_VIEW_DEGREES * PI / 180.0;
    let aspect_ratio = window_width / window_height;
-   let near = 0.1;
-   let far = 1000.0;
 
-   perspective(fov, aspect_ratio, near, far)
+   let mut projection = perspective(fov, aspect_ratio, NEAR_PLANE, 1.0);
+   projection[(2, 2)] = 0.0;
+   projection[(2, 3)] = -NEAR_PLANE;
+   projection
 }
 
 pub fn create_viewport_matrix(width: f32, height: f32) -> Mat4 {
@@ -170,4 +266,69 @@ pub fn create_viewport_matrix(width: f32, height: f32) -> Mat4 {
       0.0, 0.0, 1.0, 0.0,
       0.0, 0.0, 0.0, 1.0
    )
-}
\ No newline at end of file
+}
+// Matrices de vista, proyección y viewport de un frame, para llevar puntos del mundo a la pantalla
+pub struct ScreenProjection {
+   pub view_matrix: Mat4,
+   pub projection_matrix: Mat4,
+   pub viewport_matrix: Mat4,
+}
+
+impl ScreenProjection {
+   pub fn new(view_matrix: Mat4, projection_matrix: Mat4, viewport_matrix: Mat4) -> Self {
+      ScreenProjection { view_matrix, projection_matrix, viewport_matrix }
+   }
+
+   // Mundo -> pantalla en una sola matriz (hay que dividir por w después)
+   pub fn screen_matrix(&self) -> Mat4 {
+      self.viewport_matrix * self.projection_matrix * self.view_matrix
+   }
+
+   // Coordenadas de pantalla (x, y en pixeles, z = profundidad) o None si queda detrás de la cámara
+   pub fn project(&self, point: Vec3) -> Option<Vec3> {
+      let clip = self.projection_matrix * self.view_matrix * Vec4::new(point.x, point.y, point.z, 1.0);
+      if clip.w <= 0.0 {
+         return None;
+      }
+      let ndc = Vec4::new(clip.x / clip.w, clip.y / clip.w, clip.z / clip.w, 1.0);
+      let screen = self.viewport_matrix * ndc;
+      Some(Vec3::new(screen.x, screen.y, screen.z))
+   }
+
+   // Proyecta un segmento recortándolo contra el plano cercano, para que las líneas que pasan
+   // detrás de la cámara no se inviertan
+   pub fn project_segment(&self, from: Vec3, to: Vec3) -> Option<(Vec3, Vec3)> {
+      const MIN_W: f32 = 0.01;
+      let view_projection = self.projection_matrix * self.view_matrix;
+      let mut a = view_projection * Vec4::new(from.x, from.y, from.z, 1.0);
+      let mut b = view_projection * Vec4::new(to.x, to.y, to.z, 1.0);
+
+      if a.w < MIN_W && b.w < MIN_W {
+         return None;
+      }
+      if a.w < MIN_W {
+         a = b + (a - b) * ((b.w - MIN_W) / (b.w - a.w));
+      } else if b.w < MIN_W {
+         b = a + (b - a) * ((a.w - MIN_W) / (a.w - b.w));
+      }
+
+      let to_screen = |clip: Vec4| {
+         let ndc = Vec4::new(clip.x / clip.w, clip.y / clip.w, clip.z / clip.w, 1.0);
+         let screen = self.viewport_matrix * ndc;
+         Vec3::new(screen.x, screen.y, screen.z)
+      };
+      Some((to_screen(a), to_screen(b)))
+   }
+
+   // Radio aproximado en pixeles de una esfera del mundo; 0 si está detrás de la cámara y
+   // f32::MAX si la cámara está dentro de ella
+   pub fn projected_radius(&self, center: Vec3, radius: f32) -> f32 {
+      let view = self.view_matrix * Vec4::new(center.x, center.y, center.z, 1.0);
+      let depth = -view.z;
+      if depth <= radius {
+         return if depth + radius > 0.0 { f32::MAX } else { 0.0 };
+      }
+      // projection[(1,1)] = 1/tan(fov/2); viewport[(1,1)] = -alto/2
+      radius * self.projection_matrix[(1, 1)] * self.viewport_matrix[(1, 1)].abs() / depth
+   }
+}
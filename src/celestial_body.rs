@@ -1,4 +1,24 @@
-use nalgebra_glm::Vec3;
+use nalgebra_glm::{Mat4, Vec2, Vec3, Vec4};
+use std::borrow::Cow;
+use std::f32::consts::TAU;
+use crate::color::Color;
+use crate::terrain::Terrain;
+use crate::mesh_registry::MeshId;
+use crate::renderable::{DrawItem, RenderFlags, Renderable, RenderPass, RenderView, Shading};
+use crate::renderer::create_model_matrix;
+use crate::markers::{SurfaceMarker, DOCKING_PORT_COLOR, DOCKING_PORT_LABEL};
+use crate::rings::RingBand;
+use crate::shaders::sun_phase;
+use crate::sim_clock::days_to_seconds;
+use crate::vertex::Vertex;
+
+// Por debajo de este radio en pantalla (pixeles) el cuerpo se dibuja como un disco plano del
+// color de su paleta en vez de la esfera: en escenas con decenas de cuerpos lejanos, cada uno
+// cuesta unos pocos triángulos y pixeles sin ruido
+const IMPOSTOR_RADIUS: f32 = 3.0;
+const IMPOSTOR_SEGMENTS: u32 = 8;
+// Más allá la elipse es casi una línea y el cuerpo pasa demasiado rápido por el periastro
+pub const MAX_ECCENTRICITY: f32 = 0.9;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ShaderType {
@@ -11,16 +31,69 @@ pub enum ShaderType {
    Ship,
 }
 
+impl ShaderType {
+   // Los que puede usar un cuerpo de la escena (el fondo y la nave tienen los suyos)
+   pub const BODIES: [ShaderType; 5] = [
+      ShaderType::Sun,
+      ShaderType::RockyPlanet,
+      ShaderType::GasGiant,
+      ShaderType::Moon,
+      ShaderType::RingedPlanet,
+   ];
+
+   pub fn display_name(&self) -> &'static str {
+      match self {
+         ShaderType::Sun => "Sun",
+         ShaderType::RockyPlanet => "Rocky Planet",
+         ShaderType::GasGiant => "Gas Giant",
+         ShaderType::Moon => "Moon",
+         ShaderType::RingedPlanet => "Ringed Planet",
+         ShaderType::Starfield => "Starfield",
+         ShaderType::Ship => "Spaceship",
+      }
+   }
+
+   // Color dominante de la paleta del shader (minimapa e impostores de cuerpos lejanos)
+   pub fn base_color(&self) -> u32 {
+      match self {
+         ShaderType::Sun => 0xFFD040,
+         ShaderType::RockyPlanet => 0xD06030,
+         ShaderType::GasGiant => 0xD8B080,
+         ShaderType::Moon => 0xA0A0A0,
+         ShaderType::RingedPlanet => 0xC0A060,
+         ShaderType::Starfield | ShaderType::Ship => 0xFFFFFF,
+      }
+   }
+}
+
 pub struct CelestialBody {
+   pub name: &'static str,
    pub position: Vec3,
+   pub velocity: Vec3,  // Medida en el último frame (unidades/s), para apuntar con adelanto
    pub rotation: Vec3,
    pub scale: f32,
    pub shader_type: ShaderType,
    pub rotation_speed: Vec3,
+   pub axial_tilt: f32,          // Radianes de inclinación del eje de giro (y del plano de los anillos), sobre el eje X
    pub orbit_speed: f32,
    pub orbit_radius: f32,
    pub orbit_angle: f32,
+   pub orbit_eccentricity: f32,  // 0 = círculo; `orbit_radius` es el semieje mayor y el centro, un foco
+   pub orbit_inclination: f32,   // Radianes de inclinación del plano de la órbita, sobre el eje X
+   pub orbit_height: f32,        // Altura del plano de la órbita sobre su centro
    pub time: f32,
+   pub terrain: Option<Terrain>,  // Relieve real en la malla (None = esfera lisa)
+   // Metadatos para la ficha del cuerpo
+   pub radius_km: Option<f32>,        // Radio "real" que representa (la escena no está a escala)
+   pub description: &'static str,
+   pub parent: Option<usize>,         // Índice del cuerpo alrededor del cual orbita (None = el origen)
+   pub mesh: Option<MeshId>,      // Malla del registro con la que se dibuja (None = no se dibuja)
+   pub flags: RenderFlags,
+   pub rings: Vec<RingBand>,      // Bandas de anillos, de adentro hacia afuera
+   pub markers: Vec<SurfaceMarker>,  // Puntos fijos en la superficie (giran con el cuerpo)
+   pub subsolar_marker: bool,     // Marcar también el punto donde el sol está en el cenit
+   pub docking_port: Option<SurfaceMarker>,  // Puerto de acople: guía de aproximación al acercarse (docking.rs)
+   pub magnetic_field: bool,      // Tiene magnetosfera: el viento solar la rodea (ver solar_wind.rs)
 }
 
 impl CelestialBody {
@@ -30,15 +103,32 @@ impl CelestialBody {
       shader_type: ShaderType,
    ) -> Self {
       CelestialBody {
+         name: shader_type.display_name(),
          position,
+         velocity: Vec3::zeros(),
          rotation: Vec3::new(0.0, 0.0, 0.0),
          scale,
          shader_type,
          rotation_speed: Vec3::new(0.0, 0.5, 0.0),
+         axial_tilt: 0.0,
          orbit_speed: 0.0,
          orbit_radius: 0.0,
          orbit_angle: 0.0,
+         orbit_eccentricity: 0.0,
+         orbit_inclination: 0.0,
+         orbit_height: position.y,
          time: 0.0,
+         terrain: None,
+         radius_km: None,
+         description: "",
+         parent: None,
+         mesh: None,
+         flags: RenderFlags::default(),
+         rings: Vec::new(),
+         markers: Vec::new(),
+         subsolar_marker: false,
+         docking_port: None,
+         magnetic_field: false,
       }
    }
 
@@ -48,23 +138,333 @@ impl CelestialBody {
       self
    }
 
+   pub fn with_eccentricity(mut self, eccentricity: f32) -> Self {
+      self.orbit_eccentricity = eccentricity.clamp(0.0, MAX_ECCENTRICITY);
+      self
+   }
+
+   pub fn with_inclination(mut self, degrees: f32) -> Self {
+      self.orbit_inclination = degrees.clamp(-90.0, 90.0).to_radians();
+      self
+   }
+
    pub fn with_rotation_speed(mut self, speed: Vec3) -> Self {
       self.rotation_speed = speed;
       self
    }
 
-   pub fn update(&mut self, delta_time: f32) {
-      // Update rotation
-      self.rotation += self.rotation_speed * delta_time;
+   pub fn with_axial_tilt(mut self, degrees: f32) -> Self {
+      self.axial_tilt = degrees.clamp(-90.0, 90.0).to_radians();
+      self
+   }
+
+   // Duración del día en días simulados (negativa = rotación retrógrada): gira sobre Y
+   pub fn with_day_length(mut self, days: f64) -> Self {
+      self.rotation_speed = Vec3::new(0.0, angular_speed(days), 0.0);
+      self
+   }
+
+   // Duración del año en días simulados (negativa = órbita retrógrada); el radio va en `with_orbit`
+   pub fn with_year_length(mut self, days: f64) -> Self {
+      self.orbit_speed = angular_speed(days);
+      self
+   }
+
+   pub fn with_name(mut self, name: &'static str) -> Self {
+      self.name = name;
+      self
+   }
+
+   pub fn with_physical_radius(mut self, radius_km: f32) -> Self {
+      self.radius_km = Some(radius_km);
+      self
+   }
+
+   pub fn with_description(mut self, description: &'static str) -> Self {
+      self.description = description;
+      self
+   }
+
+   pub fn with_parent(mut self, parent: usize) -> Self {
+      self.parent = Some(parent);
+      self
+   }
+
+   pub fn with_terrain(mut self, terrain: Terrain) -> Self {
+      self.terrain = Some(terrain);
+      self
+   }
+
+   pub fn with_render_flags(mut self, flags: RenderFlags) -> Self {
+      self.flags = flags;
+      self
+   }
+
+   pub fn with_ring(mut self, band: RingBand) -> Self {
+      self.rings.push(band);
+      self
+   }
+
+   pub fn with_marker(mut self, marker: SurfaceMarker) -> Self {
+      self.markers.push(marker);
+      self
+   }
+
+   pub fn with_subsolar_marker(mut self) -> Self {
+      self.subsolar_marker = true;
+      self
+   }
+
+   // El eje del puerto es la normal de la superficie en ese punto
+   pub fn with_docking_port(mut self, latitude: f32, longitude: f32) -> Self {
+      self.docking_port = Some(SurfaceMarker::new(DOCKING_PORT_LABEL, latitude, longitude, DOCKING_PORT_COLOR));
+      self
+   }
+
+   pub fn with_magnetic_field(mut self) -> Self {
+      self.magnetic_field = true;
+      self
+   }
+
+   // Si aparece en la imagen (3D, etiquetas, minimapa)
+   pub fn is_drawn(&self) -> bool {
+      self.mesh.is_some() && self.flags.visible
+   }
+
+   // Segundos de simulación por vuelta completa de la órbita (None si no orbita)
+   pub fn orbital_period(&self) -> Option<f32> {
+      (self.orbit_radius > 0.0 && self.orbit_speed != 0.0).then(|| std::f32::consts::TAU / self.orbit_speed.abs())
+   }
+
+   // Segundos de simulación por vuelta sobre su eje (Y)
+   pub fn rotation_period(&self) -> Option<f32> {
+      (self.rotation_speed.y != 0.0).then(|| std::f32::consts::TAU / self.rotation_speed.y.abs())
+   }
+
+   // Dónde estaría en el instante `sim_seconds` orbitando alrededor de `center`, sin moverlo
+   pub fn position_at(&self, sim_seconds: f64, center: Vec3) -> Vec3 {
+      if self.orbit_radius <= 0.0 {
+         return self.position;
+      }
+      let mean_anomaly = (self.orbit_speed as f64 * sim_seconds).rem_euclid(std::f64::consts::TAU) as f32;
+      let eccentric_anomaly = eccentric_anomaly(mean_anomaly, self.orbit_eccentricity);
+      center + orbit_point(self.orbit_radius, self.orbit_eccentricity, self.orbit_inclination, eccentric_anomaly)
+         + Vec3::new(0.0, self.orbit_height, 0.0)
+   }
+
+   // Coloca el cuerpo en el instante absoluto `sim_seconds`, orbitando alrededor de `center`.
+   // Los ángulos se calculan en f64 y se reducen a una vuelta antes de pasar a f32.
+   pub fn place_at(&mut self, sim_seconds: f64, center: Vec3) {
+      let angle = |speed: f32| (speed as f64 * sim_seconds).rem_euclid(std::f64::consts::TAU) as f32;
+      self.rotation = Vec3::new(angle(self.rotation_speed.x), angle(self.rotation_speed.y), angle(self.rotation_speed.z));
 
-      // Update orbit
       if self.orbit_radius > 0.0 {
-         self.orbit_angle += self.orbit_speed * delta_time;
-         self.position.x = self.orbit_angle.cos() * self.orbit_radius;
-         self.position.z = self.orbit_angle.sin() * self.orbit_radius;
+         self.orbit_angle = angle(self.orbit_speed);
+      }
+      self.position = self.position_at(sim_seconds, center);
+
+      // Tiempo interno para las animaciones de los shaders
+      self.time = sim_seconds as f32;
+   }
+
+   // Impostor: disco de radio 1 en el espacio local, de frente a la cámara (así la matriz de
+   // modelo lo deja del tamaño y en la profundidad del cuerpo), de un solo color más oscuro
+   // cuanto menos de la cara iluminada se ve. Los anillos no se dibujan.
+   fn impostor<'a>(&self, camera_eye: Vec3) -> DrawItem<'a> {
+      let inverse = self.model_matrix().try_inverse().unwrap_or_else(Mat4::identity);
+      let local_eye = inverse * Vec4::new(camera_eye.x, camera_eye.y, camera_eye.z, 1.0);
+      let toward_eye = local_eye.xyz().try_normalize(f32::EPSILON).unwrap_or_else(Vec3::z);
+      let helper = if toward_eye.y.abs() < 0.9 { Vec3::y() } else { Vec3::x() };
+      let right = helper.cross(&toward_eye).normalize();
+      let up = toward_eye.cross(&right);
+
+      let mut vertices = vec![Vertex::new(Vec3::zeros(), toward_eye, Vec2::zeros())];
+      for i in 0..IMPOSTOR_SEGMENTS {
+         let (sin, cos) = (i as f32 / IMPOSTOR_SEGMENTS as f32 * TAU).sin_cos();
+         vertices.push(Vertex::new(right * cos + up * sin, toward_eye, Vec2::zeros()));
+      }
+      let indices = (0..IMPOSTOR_SEGMENTS).flat_map(|i| [0, i + 1, (i + 1) % IMPOSTOR_SEGMENTS + 1]).collect();
+
+      let light = if self.flags.receive_light { 0.25 + 0.75 * sun_phase(self.position, camera_eye) } else { 1.0 };
+      DrawItem {
+         vertices: Cow::Owned(vertices),
+         indices: Cow::Owned(indices),
+         shading: Shading::Flat((Color::from_hex(self.shader_type.base_color()) * light).to_hex()),
+         time: self.time,
+      }
+   }
+}
+
+// Punto de la elipse (relativo al foco) en la anomalía excéntrica `anomaly`: en el plano XZ con
+// el periastro sobre +X, inclinado alrededor del eje X
+pub fn orbit_point(semi_major: f32, eccentricity: f32, inclination: f32, anomaly: f32) -> Vec3 {
+   let semi_minor = semi_major * (1.0 - eccentricity * eccentricity).sqrt();
+   let (x, z) = (semi_major * (anomaly.cos() - eccentricity), semi_minor * anomaly.sin());
+   Vec3::new(x, z * inclination.sin(), z * inclination.cos())
+}
+
+// Ecuación de Kepler (M = E - e sen E) por Newton: la velocidad del cuerpo varía a lo largo de la
+// elipse, más rápido cerca del foco. En un círculo es la misma anomalía.
+fn eccentric_anomaly(mean_anomaly: f32, eccentricity: f32) -> f32 {
+   if eccentricity <= 0.0 {
+      return mean_anomaly;
+   }
+   let mut anomaly = if eccentricity > 0.8 { std::f32::consts::PI } else { mean_anomaly };
+   for _ in 0..8 {
+      anomaly -= (anomaly - eccentricity * anomaly.sin() - mean_anomaly) / (1.0 - eccentricity * anomaly.cos());
+   }
+   anomaly
+}
+
+// Radianes por segundo de simulación de una vuelta cada `days` días
+fn angular_speed(days: f64) -> f32 {
+   (std::f64::consts::TAU / days_to_seconds(days)) as f32
+}
+
+impl Renderable for CelestialBody {
+   fn render_pass(&self) -> RenderPass {
+      RenderPass::Opaque
+   }
+
+   fn name(&self) -> &str {
+      self.name
+   }
+
+   // El eje inclinado gira el cuerpo ya rotado alrededor de su centro; los anillos y los
+   // marcadores de superficie lo siguen
+   fn model_matrix(&self) -> Mat4 {
+      let model = create_model_matrix(self.position, self.scale, self.rotation);
+      if self.axial_tilt == 0.0 {
+         return model;
+      }
+      let tilt = nalgebra_glm::rotation(self.axial_tilt, &Vec3::x());
+      nalgebra_glm::translation(&self.position) * tilt * nalgebra_glm::translation(&-self.position) * model
+   }
+
+   // Sus anillos pueden dar sombra sobre él (y él sobre ellos)
+   fn shadow_rings(&self) -> &[RingBand] {
+      if self.flags.cast_shadows { &self.rings } else { &[] }
+   }
+
+   fn render_flags(&self) -> RenderFlags {
+      self.flags
+   }
+
+   // Incluye los anillos, que pueden verse aunque el cuerpo quede lejos
+   fn bounds(&self) -> Option<(Vec3, f32)> {
+      let extent = self.rings.iter().fold(1.0_f32, |extent, band| extent.max(band.outer));
+      Some((self.position, self.scale * extent))
+   }
+
+   // Nivel de detalle según el tamaño en pantalla (las mallas base tienen radio 1)
+   fn draw_items<'a>(&'a self, view: &RenderView<'a>) -> Vec<DrawItem<'a>> {
+      let Some(mesh_id) = self.mesh else {
+         return Vec::new();
+      };
+      let screen_radius = view.projection.projected_radius(self.position, self.scale);
+      if screen_radius <= 0.0 {
+         return Vec::new();  // Entero detrás de la cámara
+      }
+      if screen_radius < IMPOSTOR_RADIUS {
+         return vec![self.impostor(view.camera_eye)];
+      }
+      let screen_radius = screen_radius * view.lod_bias;
+      let mesh = view.meshes.get(mesh_id).level_for(screen_radius);
+      let mut items = vec![DrawItem {
+         vertices: Cow::Borrowed(&mesh.vertices),
+         indices: Cow::Borrowed(&mesh.indices),
+         shading: Shading::Shader(self.shader_type),
+         time: self.time,
+      }];
+      // Cada banda es su propia corona
+      for band in &self.rings {
+         if let Some(ring_mesh) = band.mesh {
+            let ring_mesh = view.meshes.get(ring_mesh).level_for(screen_radius);
+            items.push(DrawItem {
+               vertices: Cow::Borrowed(&ring_mesh.vertices),
+               indices: Cow::Borrowed(&ring_mesh.indices),
+               shading: Shading::Ring(band),
+               time: self.time,
+            });
+         }
+      }
+      items
+   }
+}
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+   use std::f32::consts::PI;
+
+   fn close(a: Vec3, b: Vec3) -> bool {
+      (a - b).magnitude() < 1e-4
+   }
+
+   // Radio 2 y una vuelta cada TAU segundos
+   fn orbiting(eccentricity: f32, inclination: f32) -> CelestialBody {
+      CelestialBody::new(Vec3::zeros(), 0.5, ShaderType::RockyPlanet)
+         .with_orbit(2.0, 1.0)
+         .with_eccentricity(eccentricity)
+         .with_inclination(inclination)
+   }
+
+   #[test]
+   fn kepler_solution_satisfies_the_equation() {
+      for eccentricity in [0.1, 0.5, 0.8, MAX_ECCENTRICITY] {
+         for step in 0..16 {
+            let mean_anomaly = step as f32 / 16.0 * TAU;
+            let anomaly = eccentric_anomaly(mean_anomaly, eccentricity);
+            let residual = anomaly - eccentricity * anomaly.sin() - mean_anomaly;
+            assert!(residual.abs() < 1e-4, "e = {}, M = {}: residual {}", eccentricity, mean_anomaly, residual);
+         }
       }
+   }
+
+   #[test]
+   fn circle_keeps_the_mean_anomaly() {
+      assert_eq!(eccentric_anomaly(1.25, 0.0), 1.25);
+   }
+
+   #[test]
+   fn ellipse_starts_at_periapsis_and_reaches_apoapsis_at_half_period() {
+      let body = orbiting(0.5, 0.0);
+      let center = Vec3::new(1.0, 0.0, -1.0);
+      assert!(close(body.position_at(0.0, center), center + Vec3::new(1.0, 0.0, 0.0)));
+      assert!(close(body.position_at(PI as f64, center), center + Vec3::new(-3.0, 0.0, 0.0)));
+      // Una vuelta después vuelve al periastro
+      assert!(close(body.position_at(TAU as f64, center), body.position_at(0.0, center)));
+   }
 
-      // Update internal time for shader animations
-      self.time += delta_time;
+   #[test]
+   fn ellipse_moves_faster_near_periapsis() {
+      // En el primer cuarto de periodo recorre más de un cuarto de vuelta alrededor del foco
+      let position = orbiting(0.5, 0.0).position_at((PI / 2.0) as f64, Vec3::zeros());
+      assert!(position.x < 0.0 && position.z > 0.0);
    }
-}
\ No newline at end of file
+
+   #[test]
+   fn inclination_tilts_the_orbit_out_of_the_plane() {
+      let flat = orbiting(0.0, 0.0).position_at((PI / 2.0) as f64, Vec3::zeros());
+      let tilted = orbiting(0.0, 30.0).position_at((PI / 2.0) as f64, Vec3::zeros());
+      assert!(close(flat, Vec3::new(0.0, 0.0, 2.0)));
+      assert!(close(tilted, Vec3::new(0.0, 2.0 * 30f32.to_radians().sin(), 2.0 * 30f32.to_radians().cos())));
+   }
+
+   #[test]
+   fn orbit_height_and_fixed_bodies() {
+      let mut body = orbiting(0.0, 0.0);
+      body.orbit_height = 0.5;
+      assert!(close(body.position_at(0.0, Vec3::zeros()), Vec3::new(2.0, 0.5, 0.0)));
+      let fixed = CelestialBody::new(Vec3::new(3.0, 1.0, 0.0), 1.0, ShaderType::Sun);
+      assert_eq!(fixed.position_at(123.0, Vec3::new(9.0, 9.0, 9.0)), Vec3::new(3.0, 1.0, 0.0));
+   }
+
+   #[test]
+   fn huge_times_stay_on_the_orbit() {
+      // El ángulo se reduce en f64: años de simulación no pierden la elipse
+      let position = orbiting(0.3, 10.0).position_at(1.0e12, Vec3::zeros());
+      let distance = position.magnitude();
+      assert!((2.0 * 0.7 - 1e-3..=2.0 * 1.3 + 1e-3).contains(&distance));
+   }
+}
@@ -0,0 +1,178 @@
+use clap::Parser;
+use log::LevelFilter;
+use std::path::PathBuf;
+use crate::backend::BackendKind;
+use crate::camera::CameraMode;
+use crate::engine::LaunchOptions;
+use crate::golden::GOLDEN_DIR;
+use crate::i18n::Language;
+use crate::net_sync::SyncMode;
+use crate::scene::SceneSource;
+use crate::settings::{Resolution, Settings};
+
+// Frames por defecto de `--benchmark` (10 s a 60 FPS)
+pub const DEFAULT_BENCHMARK_FRAMES: &str = "600";
+// Dirección por defecto de `--sync-host`: todas las interfaces, puerto `DEFAULT_SYNC_PORT`
+const DEFAULT_SYNC_HOST: &str = "0.0.0.0:47800";
+
+// Opciones de línea de comandos del binario. Sin argumentos arranca como siempre: ventana con
+// minifb del tamaño de settings.toml, sistema solar, cámara orbital y la resolución interna de
+// settings.toml.
+#[derive(Debug, Clone, Parser)]
+#[command(name = "space-travel-simulator", version, about = "Solar system simulator with a software rasterizer")]
+pub struct Cli {
+    /// Window width in pixels (overrides settings.toml, default 800)
+    #[arg(long, value_parser = clap::value_parser!(u32).range(64..=7680))]
+    pub width: Option<u32>,
+
+    /// Window height in pixels (overrides settings.toml, default 600)
+    #[arg(long, value_parser = clap::value_parser!(u32).range(64..=4320))]
+    pub height: Option<u32>,
+
+    /// Internal framebuffer scale in percent: 50, 75 or 100 (overrides settings.toml)
+    #[arg(long, value_name = "PERCENT", value_parser = parse_render_scale)]
+    pub render_scale: Option<Resolution>,
+
+    /// Interface language for the HUD, menus and messages: en or es (overrides settings.toml)
+    #[arg(long, value_name = "LANG", value_parser = parse_language)]
+    pub lang: Option<Language>,
+
+    /// Scene file to load instead of the built-in solar system
+    #[arg(long, value_name = "FILE")]
+    pub scene: Option<PathBuf>,
+
+    /// Scenario script (Rhai) to run, replacing the one named by the scene file
+    #[arg(long, value_name = "FILE")]
+    pub script: Option<PathBuf>,
+
+    /// Equirectangular sky image for the background, replacing the one named by the scene file
+    #[arg(long, value_name = "IMAGE")]
+    pub sky: Option<PathBuf>,
+
+    /// Seed for the simulation's random numbers, for reproducible runs
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Run without a window: simulate and render `--frames` frames, then exit
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Frames to render in headless mode
+    #[arg(long, default_value_t = 1, requires = "headless")]
+    pub frames: u32,
+
+    /// PNG file where headless mode saves the last frame (plus a .json with its state)
+    #[arg(long, value_name = "PNG", requires = "headless")]
+    pub output: Option<PathBuf>,
+
+    /// Burn body labels and shot details (time, scene, seed, camera) into saved screenshots
+    #[arg(long)]
+    pub annotate: bool,
+
+    /// Restore the state saved next to a screenshot (its .json): same scene, camera, ship and time
+    #[arg(long, value_name = "JSON")]
+    pub shot: Option<PathBuf>,
+
+    /// Share this simulation (bodies, ship, time) with `--sync-join` viewers, listening on ADDR (default 0.0.0.0:47800)
+    #[arg(long, value_name = "ADDR", num_args = 0..=1, default_missing_value = DEFAULT_SYNC_HOST, conflicts_with = "sync_join")]
+    pub sync_host: Option<String>,
+
+    /// Follow the simulation of a `--sync-host` instance (HOST or HOST:PORT) with your own camera
+    #[arg(long, value_name = "HOST")]
+    pub sync_join: Option<String>,
+
+    /// Render the golden-image cases and compare them with the references in DIR (default tests/golden)
+    #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = GOLDEN_DIR)]
+    pub golden: Option<PathBuf>,
+
+    /// With `--golden`: overwrite the reference images with the current output
+    #[arg(long, requires = "golden")]
+    pub update_golden: bool,
+
+    /// Render a fixed number of frames as fast as possible and print frame-time statistics
+    #[arg(long, value_name = "FRAMES", num_args = 0..=1, default_missing_value = DEFAULT_BENCHMARK_FRAMES)]
+    pub benchmark: Option<u32>,
+
+    /// Asset directory (default: $SPACE_TRAVEL_ASSETS, ./assets or next to the executable)
+    #[arg(long, value_name = "DIR")]
+    pub assets: Option<PathBuf>,
+
+    /// Window backend: minifb, winit or wgpu (the last two need `--features winit` / `wgpu`)
+    #[arg(long, default_value = "minifb", value_parser = parse_backend)]
+    pub backend: BackendKind,
+
+    /// Starting camera: orbital, chase or first-person
+    #[arg(long, default_value = "orbital", value_parser = parse_camera_mode)]
+    pub camera: CameraMode,
+
+    /// Log level: off, error, warn, info, debug or trace (default info; RUST_LOG filters per module)
+    #[arg(long, value_name = "LEVEL")]
+    pub log_level: Option<LevelFilter>,
+
+    /// Write the log to this file instead of the terminal
+    #[arg(long, value_name = "FILE")]
+    pub log_file: Option<PathBuf>,
+}
+
+impl Cli {
+    // `--width` / `--height` o, si no se dieron, el tamaño guardado en los ajustes
+    pub fn window_size(&self, settings: &Settings) -> (usize, usize) {
+        (
+            self.width.map_or(settings.window_width, |width| width as usize),
+            self.height.map_or(settings.window_height, |height| height as usize),
+        )
+    }
+
+    pub fn launch_options(&self) -> LaunchOptions {
+        // Las corridas sin ventana o cronometradas no abren el dispositivo de sonido
+        let mut options = LaunchOptions::default()
+            .with_camera_mode(self.camera)
+            .with_audio(!self.headless && self.benchmark.is_none());
+        if let Some(root) = &self.assets {
+            options = options.with_asset_root(root.clone());
+        }
+        if let Some(path) = &self.scene {
+            options = options.with_scene(SceneSource::File(path.clone()));
+        }
+        if let Some(path) = &self.script {
+            options = options.with_script(path.clone());
+        }
+        if let Some(path) = &self.sky {
+            options = options.with_sky(path.clone());
+        }
+        if let Some(seed) = self.seed {
+            options = options.with_seed(seed);
+        }
+        if let Some(address) = &self.sync_host {
+            options = options.with_sync(SyncMode::Host(address.clone()));
+        } else if let Some(host) = &self.sync_join {
+            options = options.with_sync(SyncMode::Join(host.clone()));
+        }
+        options
+    }
+}
+
+fn parse_render_scale(value: &str) -> Result<Resolution, String> {
+    let percent: f32 = value.trim_end_matches('%').parse().map_err(|_| format!("`{}` is not a percentage", value))?;
+    Resolution::ALL
+        .into_iter()
+        .find(|resolution| resolution.scale() * 100.0 == percent)
+        .ok_or_else(|| "expected 50, 75 or 100".to_string())
+}
+
+fn parse_language(value: &str) -> Result<Language, String> {
+    Language::from_code(value).ok_or_else(|| "expected en or es".to_string())
+}
+
+fn parse_backend(value: &str) -> Result<BackendKind, String> {
+    BackendKind::from_name(value).ok_or_else(|| "expected minifb, winit or wgpu".to_string())
+}
+
+// La cámara libre no tiene controles propios todavía, así que no se ofrece
+fn parse_camera_mode(value: &str) -> Result<CameraMode, String> {
+    CameraMode::ALL
+        .into_iter()
+        .filter(|&mode| mode != CameraMode::Free)
+        .find(|mode| mode.name().eq_ignore_ascii_case(value))
+        .ok_or_else(|| "expected orbital, chase or first-person".to_string())
+}
@@ -0,0 +1,143 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, RgbaImage};
+use log::warn;
+use crate::framebuffer::Framebuffer;
+
+// Segundos que se guardan hacia atrás y frames por segundo del clip
+pub const CLIP_SECONDS: f32 = 5.0;
+const CLIP_FPS: f32 = 15.0;
+// Ancho máximo de los frames guardados (se reducen promediando bloques de pixeles)
+const CLIP_MAX_WIDTH: usize = 320;
+// 1 = mejor paleta, 30 = más rápido (velocidad de NeuQuant del codificador GIF)
+const GIF_SPEED: i32 = 10;
+
+struct ClipFrame {
+    width: usize,
+    height: usize,
+    pixels: Vec<u32>,  // 0RGB, como el framebuffer
+}
+
+// Los últimos `CLIP_SECONDS` de imagen (con HUD), reducidos, para guardar un GIF después de que
+// pasó algo. El GIF se codifica en otro hilo; `poll_saved` entrega el resultado.
+pub struct ClipRecorder {
+    frames: VecDeque<ClipFrame>,
+    since_last: f32,  // Segundos desde el último frame guardado
+    saving: usize,    // Clips que todavía se están codificando
+    results: Receiver<Result<PathBuf, String>>,
+    sender: Sender<Result<PathBuf, String>>,
+}
+
+impl Default for ClipRecorder {
+    fn default() -> Self {
+        ClipRecorder::new()
+    }
+}
+
+impl ClipRecorder {
+    pub fn new() -> Self {
+        let (sender, results) = mpsc::channel();
+        ClipRecorder { frames: VecDeque::new(), since_last: 0.0, saving: 0, results, sender }
+    }
+
+    // Guarda una copia reducida de `framebuffer` si ya toca (a `CLIP_FPS`) y descarta lo más viejo
+    pub fn record(&mut self, framebuffer: &Framebuffer, frame_time: f32) {
+        self.since_last += frame_time;
+        if !self.frames.is_empty() && self.since_last < 1.0 / CLIP_FPS {
+            return;
+        }
+        self.since_last = 0.0;
+        self.frames.push_back(downscale(framebuffer));
+        let capacity = (CLIP_SECONDS * CLIP_FPS) as usize;
+        while self.frames.len() > capacity {
+            self.frames.pop_front();
+        }
+    }
+
+    // Segundos que hay grabados
+    pub fn duration(&self) -> f32 {
+        self.frames.len() as f32 / CLIP_FPS
+    }
+
+    pub fn is_saving(&self) -> bool {
+        self.saving > 0
+    }
+
+    // Empieza a codificar lo grabado en `path`; false si todavía no hay nada
+    pub fn save(&mut self, path: PathBuf) -> bool {
+        let Some(last) = self.frames.back() else {
+            return false;
+        };
+        // Solo los frames del tamaño actual: un cambio de resolución a mitad del clip lo corta
+        let (width, height) = (last.width, last.height);
+        let images: Vec<RgbaImage> = self.frames
+            .iter()
+            .filter(|frame| frame.width == width && frame.height == height)
+            .map(to_rgba)
+            .collect();
+
+        self.saving += 1;
+        let sender = self.sender.clone();
+        let encode = move || {
+            let result = write_gif(&path, images).map(|()| path);
+            let _ = sender.send(result);
+        };
+        // Sin hilos (el build web) no hay dónde codificar; el error llega por `poll_saved`
+        if let Err(e) = thread::Builder::new().name("clip-encoder".to_string()).spawn(encode) {
+            warn!("Could not start the clip encoder thread: {}", e);
+            let _ = self.sender.send(Err(format!("no encoder thread ({})", e)));
+        }
+        true
+    }
+
+    // El resultado de un guardado que terminó desde la última llamada, si lo hay
+    pub fn poll_saved(&mut self) -> Option<Result<PathBuf, String>> {
+        let result = self.results.try_recv().ok()?;
+        self.saving = self.saving.saturating_sub(1);
+        Some(result)
+    }
+}
+
+fn write_gif(path: &Path, images: Vec<RgbaImage>) -> Result<(), String> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    let file = File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut encoder = GifEncoder::new_with_speed(BufWriter::new(file), GIF_SPEED);
+    encoder.set_repeat(Repeat::Infinite).map_err(|e| e.to_string())?;
+    let delay = Delay::from_numer_denom_ms(1000, CLIP_FPS as u32);
+    let frames = images.into_iter().map(|image| Frame::from_parts(image, 0, 0, delay));
+    encoder.encode_frames(frames).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// Promedio de bloques de `factor` x `factor` pixeles hasta quedar en `CLIP_MAX_WIDTH` o menos
+fn downscale(framebuffer: &Framebuffer) -> ClipFrame {
+    let factor = framebuffer.width.div_ceil(CLIP_MAX_WIDTH).max(1);
+    let (width, height) = (framebuffer.width / factor, framebuffer.height / factor);
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = [0u32; 3];
+            for sy in y * factor..(y + 1) * factor {
+                for &pixel in &framebuffer.buffer[sy * framebuffer.width + x * factor..][..factor] {
+                    sum[0] += (pixel >> 16) & 0xFF;
+                    sum[1] += (pixel >> 8) & 0xFF;
+                    sum[2] += pixel & 0xFF;
+                }
+            }
+            let count = (factor * factor) as u32;
+            pixels.push(((sum[0] / count) << 16) | ((sum[1] / count) << 8) | (sum[2] / count));
+        }
+    }
+    ClipFrame { width, height, pixels }
+}
+
+fn to_rgba(frame: &ClipFrame) -> RgbaImage {
+    let bytes = frame.pixels.iter().flat_map(|&pixel| [(pixel >> 16) as u8, (pixel >> 8) as u8, pixel as u8, 0xFF]).collect();
+    RgbaImage::from_raw(frame.width as u32, frame.height as u32, bytes).expect("the frame has width * height pixels")
+}
@@ -0,0 +1,146 @@
+use nalgebra_glm::{Mat3, Vec3};
+use crate::vertex::Vertex;
+
+// Nivel de detalle de la prueba de colisión nave-cuerpo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CollisionMode {
+    Sphere,       // Esfera envolvente del modelo (la más barata)
+    OrientedBox,  // Caja orientada con la rotación de la nave
+    Hull,         // Casco de baja resolución (26-DOP)
+}
+
+impl CollisionMode {
+    pub fn next(self) -> Self {
+        match self {
+            CollisionMode::Sphere => CollisionMode::OrientedBox,
+            CollisionMode::OrientedBox => CollisionMode::Hull,
+            CollisionMode::Hull => CollisionMode::Sphere,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CollisionMode::Sphere => "Sphere",
+            CollisionMode::OrientedBox => "OBB",
+            CollisionMode::Hull => "Hull",
+        }
+    }
+}
+
+// 13 ejes: 3 de los ejes coordenados, 6 diagonales de caras y 4 diagonales de esquinas.
+// Con mínimo y máximo por eje forman un politopo de 26 caras que envuelve el modelo.
+const HULL_AXES: [[f32; 3]; 13] = [
+    [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0],
+    [1.0, 1.0, 0.0], [1.0, -1.0, 0.0], [1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0], [0.0, 1.0, 1.0], [0.0, 1.0, -1.0],
+    [1.0, 1.0, 1.0], [1.0, 1.0, -1.0], [1.0, -1.0, 1.0], [-1.0, 1.0, 1.0],
+];
+
+// Volúmenes envolventes calculados a partir de los vértices del modelo (en espacio local, sin escalar)
+pub struct CollisionShape {
+    pub min: Vec3,
+    pub max: Vec3,
+    pub radius: f32,
+    hull_axes: Vec<Vec3>,
+    hull_min: Vec<f32>,
+    hull_max: Vec<f32>,
+}
+
+impl CollisionShape {
+    pub fn from_vertices(vertices: &[Vertex]) -> Self {
+        let hull_axes: Vec<Vec3> = HULL_AXES
+            .iter()
+            .map(|a| Vec3::new(a[0], a[1], a[2]).normalize())
+            .collect();
+
+        let mut min = Vec3::new(f32::MAX, f32::MAX, f32::MAX);
+        let mut max = Vec3::new(f32::MIN, f32::MIN, f32::MIN);
+        let mut radius: f32 = 0.0;
+        let mut hull_min = vec![f32::MAX; hull_axes.len()];
+        let mut hull_max = vec![f32::MIN; hull_axes.len()];
+
+        for vertex in vertices {
+            let p = vertex.position;
+            min = nalgebra_glm::min2(&min, &p);
+            max = nalgebra_glm::max2(&max, &p);
+            // El pivote del modelo es el origen, así que el radio se mide desde ahí
+            radius = radius.max(p.magnitude());
+
+            for (i, axis) in hull_axes.iter().enumerate() {
+                let projection = axis.dot(&p);
+                hull_min[i] = hull_min[i].min(projection);
+                hull_max[i] = hull_max[i].max(projection);
+            }
+        }
+
+        if vertices.is_empty() {
+            min = Vec3::zeros();
+            max = Vec3::zeros();
+            hull_min.iter_mut().for_each(|v| *v = 0.0);
+            hull_max.iter_mut().for_each(|v| *v = 0.0);
+        }
+
+        CollisionShape { min, max, radius, hull_axes, hull_min, hull_max }
+    }
+
+    // Prueba contra una esfera del mundo. `rotation` y `scale` son los de la matriz de modelo.
+    pub fn intersects_sphere(
+        &self,
+        mode: CollisionMode,
+        position: Vec3,
+        rotation: &Mat3,
+        scale: f32,
+        sphere_center: Vec3,
+        sphere_radius: f32,
+    ) -> bool {
+        // Fase amplia: esfera envolvente escalada
+        let distance = (sphere_center - position).magnitude();
+        if distance > sphere_radius + self.radius * scale {
+            return false;
+        }
+        if mode == CollisionMode::Sphere {
+            return true;
+        }
+
+        // Llevar la esfera al espacio local del modelo (la rotación es ortonormal)
+        let local_center = rotation.transpose() * (sphere_center - position) / scale;
+        let local_radius = sphere_radius / scale;
+
+        match mode {
+            CollisionMode::OrientedBox => {
+                let closest = nalgebra_glm::clamp_vec(&local_center, &self.min, &self.max);
+                (local_center - closest).magnitude() <= local_radius
+            }
+            _ => self.hull_axes.iter().enumerate().all(|(i, axis)| {
+                let projection = axis.dot(&local_center);
+                projection + local_radius >= self.hull_min[i] && projection - local_radius <= self.hull_max[i]
+            }),
+        }
+    }
+}
+
+// Orientación de la nave: R = Ry * Rx * Rz (guiñada * cabeceo * alabeo), así el alabeo se aplica
+// en su eje local. Es la misma que usa `Spaceship::model_matrix` para dibujarla.
+pub fn rotation_matrix(rotation: Vec3) -> Mat3 {
+    let (sin_x, cos_x) = rotation.x.sin_cos();
+    let (sin_y, cos_y) = rotation.y.sin_cos();
+    let (sin_z, cos_z) = rotation.z.sin_cos();
+
+    let rx = Mat3::new(
+        1.0, 0.0, 0.0,
+        0.0, cos_x, -sin_x,
+        0.0, sin_x, cos_x,
+    );
+    let ry = Mat3::new(
+        cos_y, 0.0, sin_y,
+        0.0, 1.0, 0.0,
+        -sin_y, 0.0, cos_y,
+    );
+    let rz = Mat3::new(
+        cos_z, -sin_z, 0.0,
+        sin_z, cos_z, 0.0,
+        0.0, 0.0, 1.0,
+    );
+
+    ry * rx * rz
+}
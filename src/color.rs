@@ -39,6 +39,15 @@ pub fn from_hex(hex: u32) -> Self {
 pub fn to_hex(&self) -> u32 {
    ((self.r as u32) << 16) | ((self.g as u32) << 8) | (self.b as u32)
 }
+
+// Multiply two colors channel by channel (e.g. a texel tinted by a material color)
+pub fn modulate(&self, other: &Color) -> Color {
+   Color {
+   r: ((self.r as u16 * other.r as u16) / 255) as u8,
+   g: ((self.g as u16 * other.g as u16) / 255) as u8,
+   b: ((self.b as u16 * other.b as u16) / 255) as u8,
+   }
+}
 }
 
 // Implement addition for Color
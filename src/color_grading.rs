@@ -0,0 +1,104 @@
+use crate::antialias::luma;
+use crate::framebuffer::Framebuffer;
+
+// Exposición automática: brillo medio de la escena que se busca y cuánto puede corregir (EV)
+const TARGET_LUMINANCE: f32 = 0.12;
+const MAX_AUTO_EXPOSURE: f32 = 2.0;
+// Velocidad de adaptación (1/s): como el ojo, se cierra rápido ante la luz y se abre despacio
+const DARKEN_RATE: f32 = 3.0;
+const BRIGHTEN_RATE: f32 = 0.8;
+// Se mide un pixel de cada `SAMPLE_STRIDE` en cada eje
+const SAMPLE_STRIDE: usize = 4;
+// Diferencias menores (EV) ya no se notan: la imagen cuenta como quieta
+const SETTLED_EV: f32 = 0.01;
+
+// Ajustes de imagen sobre la escena 3D, después del FXAA y antes del HUD (que conserva sus
+// colores). Con los valores neutros no se toca el framebuffer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grading {
+    pub exposure: f32,    // En pasos (EV): cada uno duplica la luz, el negro sigue negro
+    pub brightness: f32,  // Desplazamiento de todos los niveles (-0.5..=0.5), levanta también el fondo
+    pub contrast: f32,    // Alrededor del gris medio: 1.0 = sin cambio
+}
+
+impl Grading {
+    // Los pasos del menú no vuelven exactamente a los valores por defecto en punto flotante
+    pub fn is_neutral(&self) -> bool {
+        const EPSILON: f32 = 1e-4;
+        self.exposure.abs() < EPSILON && self.brightness.abs() < EPSILON && (self.contrast - 1.0).abs() < EPSILON
+    }
+
+    // Nivel de un canal (0-255) después de los tres ajustes
+    fn level(&self, value: u8) -> u8 {
+        let exposed = value as f32 / 255.0 * 2.0_f32.powf(self.exposure);
+        let graded = (exposed - 0.5) * self.contrast + 0.5 + self.brightness;
+        (graded.clamp(0.0, 1.0) * 255.0).round() as u8
+    }
+
+    // Valores como se muestran en el menú y los avisos: "+0.5", "+10%", "120%"
+    pub fn exposure_text(&self) -> String {
+        format!("{:+.1}", self.exposure)
+    }
+
+    pub fn brightness_text(&self) -> String {
+        format!("{:+.0}%", self.brightness * 100.0)
+    }
+
+    pub fn contrast_text(&self) -> String {
+        format!("{:.0}%", self.contrast * 100.0)
+    }
+
+    pub fn apply(&self, framebuffer: &mut Framebuffer) {
+        if self.is_neutral() {
+            return;
+        }
+        // Los tres ajustes son por canal: una tabla de 256 niveles sirve para todos los pixeles
+        let table: Vec<u32> = (0..=255u8).map(|value| self.level(value) as u32).collect();
+        for pixel in framebuffer.buffer.iter_mut() {
+            let channel = |shift: u32| table[((*pixel >> shift) & 0xFF) as usize] << shift;
+            *pixel = channel(16) | channel(8) | channel(0);
+        }
+    }
+}
+
+// Brillo medio de la escena (0.0..=1.0), sobre una muestra de los pixeles
+pub fn average_luminance(framebuffer: &Framebuffer) -> f32 {
+    let (mut total, mut count) = (0.0, 0);
+    for y in (0..framebuffer.height).step_by(SAMPLE_STRIDE) {
+        for x in (0..framebuffer.width).step_by(SAMPLE_STRIDE) {
+            total += luma(framebuffer.buffer[y * framebuffer.width + x]);
+            count += 1;
+        }
+    }
+    if count == 0 { 0.0 } else { total / count as f32 }
+}
+
+// Exposición automática (ajuste `auto_exposure`): sigue el brillo medio de cada frame y lo lleva
+// de a poco hacia `TARGET_LUMINANCE`. Mirar el sol oscurece la escena; el espacio vacío la
+// aclara hasta que se ven los cuerpos tenues. Se suma a la exposición manual.
+#[derive(Debug, Default)]
+pub struct AutoExposure {
+    exposure: f32,  // Corrección actual (EV)
+    target: f32,    // La que pide el último brillo medido
+}
+
+impl AutoExposure {
+    pub fn exposure(&self) -> f32 {
+        self.exposure
+    }
+
+    pub fn adapt(&mut self, luminance: f32, delta_time: f32) {
+        self.target = (TARGET_LUMINANCE / luminance.max(f32::EPSILON)).log2().clamp(-MAX_AUTO_EXPOSURE, MAX_AUTO_EXPOSURE);
+        let rate = if self.target < self.exposure { DARKEN_RATE } else { BRIGHTEN_RATE };
+        self.exposure += (self.target - self.exposure) * (1.0 - (-rate * delta_time).exp());
+    }
+
+    // Todavía acercándose a la exposición buscada (la imagen cambia aunque nada se mueva)
+    pub fn is_adapting(&self) -> bool {
+        (self.target - self.exposure).abs() > SETTLED_EV
+    }
+
+    pub fn reset(&mut self) {
+        *self = AutoExposure::default();
+    }
+}
@@ -0,0 +1,222 @@
+use nalgebra_glm::Vec3;
+use crate::camera::ScreenProjection;
+use crate::events::TOUCHDOWN_SPEED;
+use crate::framebuffer::Framebuffer;
+use crate::i18n::{tr, tr_with};
+use crate::palette::Palette;
+use crate::scene::Scene;
+use crate::text::{draw_text, text_width, GLYPH_HEIGHT};
+use crate::vehicle::Vehicle;
+
+// La guía aparece a menos de esta cantidad de radios del cuerpo entre la nave y el puerto, y
+// solo dentro del cono frente al puerto
+const GUIDANCE_RADII: f32 = 6.0;
+const GUIDANCE_CONE_DEGREES: f32 = 60.0;
+// Medio ancho del pasillo de aproximación, en radios del cuerpo
+const CORRIDOR_RADII: f32 = 0.3;
+const CORRIDOR_FRAMES: usize = 6;
+const CORRIDOR_ALPHA: f32 = 0.6;
+// Una velocidad lateral así llega al borde del cuadro de la guía
+const LATERAL_SPEED_SCALE: f32 = 0.5;
+// Por debajo de esta velocidad de cierre no hay cuenta regresiva
+const MIN_CLOSING_SPEED: f32 = 1e-3;
+
+const PANEL_COLOR: u32 = 0x000000;
+const TITLE_COLOR: u32 = 0x7FD4FF;
+const LABEL_COLOR: u32 = 0x9AA8B8;
+const TEXT_COLOR: u32 = 0xE0F0FF;
+const BOX_SIZE: usize = 56;
+// Abajo queda lugar para la alerta de proximidad, que salta siempre al acercarse al puerto
+const BOTTOM_CLEARANCE: usize = 56;
+const PADDING: usize = 5;
+
+// Cómo viene la aproximación: dentro del pasillo y despacio se acopla (un aterrizaje en el puerto)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Alignment {
+    Aligned,
+    OffAxis,
+    TooFast,  // Llegaría a más de la velocidad de un aterrizaje: sería un choque
+}
+
+impl Alignment {
+    fn color(self, palette: &Palette) -> u32 {
+        match self {
+            Alignment::Aligned => palette.proximity[0],
+            Alignment::OffAxis => palette.caution,
+            Alignment::TooFast => palette.danger,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        tr(match self {
+            Alignment::Aligned => "hud.dock_aligned",
+            Alignment::OffAxis => "hud.dock_off_axis",
+            Alignment::TooFast => "hud.dock_too_fast",
+        })
+    }
+}
+
+// La nave vista desde el puerto de un cuerpo. El eje sale del puerto por la normal de la
+// superficie; `right` y `up` son los del piloto mirando hacia el puerto, para que la guía se lea
+// como se ve desde atrás de la nave.
+#[derive(Debug, Clone, Copy)]
+pub struct DockingApproach {
+    pub body: usize,
+    port: Vec3,
+    axis: Vec3,
+    right: Vec3,
+    up: Vec3,
+    corridor: f32,             // Medio ancho del pasillo
+    length: f32,               // Largo del pasillo (el alcance de la guía)
+    gap: f32,                  // Distancia entre la nave y el puerto a lo largo del eje
+    offset: (f32, f32),        // Desvío lateral de la nave respecto al eje
+    closing_speed: f32,        // Velocidad hacia el puerto (negativa = alejándose)
+    lateral_velocity: (f32, f32),
+    speed: f32,                // Velocidad relativa total, la que decide aterrizaje o choque
+}
+
+impl DockingApproach {
+    pub fn alignment(&self) -> Alignment {
+        if self.speed >= TOUCHDOWN_SPEED {
+            Alignment::TooFast
+        } else if self.offset.0.hypot(self.offset.1) > self.corridor {
+            Alignment::OffAxis
+        } else {
+            Alignment::Aligned
+        }
+    }
+
+    pub fn alignment_color(&self, palette: &Palette) -> u32 {
+        self.alignment().color(palette)
+    }
+
+    // Segundos hasta tocar el puerto a la velocidad de cierre actual
+    pub fn time_to_contact(&self) -> Option<f32> {
+        (self.closing_speed > MIN_CLOSING_SPEED).then(|| self.gap.max(0.0) / self.closing_speed)
+    }
+}
+
+// El puerto más cercano frente al que está la nave, si hay alguno al alcance. Las velocidades son
+// respecto al cuerpo, como al tocarlo.
+pub fn docking_approach(scene: &Scene) -> Option<DockingApproach> {
+    let ship = &scene.spaceship;
+    let ship_radius = ship.bounding_radius();
+    scene.bodies
+        .iter()
+        .enumerate()
+        .filter(|(_, body)| body.flags.visible)
+        .filter_map(|(index, body)| {
+            let port = body.docking_port?.world_position(body);
+            let axis = (port - body.position).try_normalize(f32::EPSILON)?;
+            let to_ship = ship.position - port;
+            let along = to_ship.dot(&axis);
+            let gap = along - ship_radius;
+            let length = body.scale * GUIDANCE_RADII;
+            if along <= 0.0 || gap > length || to_ship.angle(&axis) > GUIDANCE_CONE_DEGREES.to_radians() {
+                return None;
+            }
+            // Arriba es el Y del mundo sobre el plano del puerto (Z en los polos)
+            let reference = if axis.y.abs() > 0.99 { Vec3::z() } else { Vec3::y() };
+            let up = (reference - axis * reference.dot(&axis)).normalize();
+            let right = up.cross(&axis);
+            let velocity = ship.velocity - body.velocity;
+            Some(DockingApproach {
+                body: index,
+                port,
+                axis,
+                right,
+                up,
+                corridor: body.scale * CORRIDOR_RADII,
+                length,
+                gap,
+                offset: (to_ship.dot(&right), to_ship.dot(&up)),
+                closing_speed: -velocity.dot(&axis),
+                lateral_velocity: (velocity.dot(&right), velocity.dot(&up)),
+                speed: velocity.magnitude(),
+            })
+        })
+        .min_by(|a, b| a.gap.total_cmp(&b.gap))
+}
+
+// Pasillo de aproximación en el mundo: cuadros a lo largo del eje del puerto unidos por sus esquinas
+pub fn render_corridor(framebuffer: &mut Framebuffer, projection: &ScreenProjection, approach: &DockingApproach, color: u32) {
+    let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+    let corner = |distance: f32, (x, y): (f32, f32)| {
+        approach.port + approach.axis * distance + (approach.right * x + approach.up * y) * approach.corridor
+    };
+    let mut segment = |from: Vec3, to: Vec3| {
+        if let Some((from, to)) = projection.project_segment(from, to) {
+            framebuffer.blend_line(from.x.round() as i32, from.y.round() as i32, to.x.round() as i32, to.y.round() as i32, color, CORRIDOR_ALPHA);
+        }
+    };
+    for frame in 0..=CORRIDOR_FRAMES {
+        let distance = approach.length * frame as f32 / CORRIDOR_FRAMES as f32;
+        for i in 0..corners.len() {
+            segment(corner(distance, corners[i]), corner(distance, corners[(i + 1) % corners.len()]));
+        }
+    }
+    for &point in &corners {
+        segment(corner(0.0, point), corner(approach.length, point));
+    }
+}
+
+// Guía abajo al centro, sobre la alerta de proximidad: un cuadro con el eje del puerto en el
+// centro, el pasillo como círculo, el desvío de la nave como punto y la velocidad lateral como
+// cruz (llevar la cruz al centro frena la deriva), y al lado la distancia, la velocidad de
+// cierre y la cuenta regresiva.
+pub fn render_docking_guide(framebuffer: &mut Framebuffer, approach: &DockingApproach, body_name: &str, palette: &Palette) {
+    let line = GLYPH_HEIGHT + 3;
+    let alignment = approach.alignment();
+    let color = approach.alignment_color(palette);
+    let countdown = approach.time_to_contact().map_or_else(|| "-".to_string(), |seconds| format!("{:.1} s", seconds));
+    let rows = [
+        (tr("hud.dock_distance"), format!("{:.2} u", approach.gap.max(0.0))),
+        (tr("hud.dock_closing"), format!("{:+.2} u/s", approach.closing_speed)),
+        (tr("hud.dock_contact"), countdown),
+    ];
+    let title = tr_with("hud.dock_title", &[("body", &body_name)]);
+    let label_width = rows.iter().map(|(label, _)| text_width(label)).max().unwrap_or(0);
+    let value_column = label_width + text_width(" ");
+    let text_columns = rows
+        .iter()
+        .map(|(_, value)| value_column + text_width(value))
+        .chain([text_width(&title), text_width(alignment.label())])
+        .max()
+        .unwrap_or(0);
+    let panel_width = (BOX_SIZE + PADDING + text_columns + 2 * PADDING).min(framebuffer.width);
+    let panel_height = BOX_SIZE.max((rows.len() + 2) * line) + 2 * PADDING;
+    let panel_x = framebuffer.width.saturating_sub(panel_width) / 2;
+    let panel_y = framebuffer.height.saturating_sub(panel_height + BOTTOM_CLEARANCE);
+    framebuffer.blend_rect(panel_x, panel_y, panel_width, panel_height, PANEL_COLOR, 0.6);
+
+    // El cuadro: ejes, pasillo (un tercio del medio lado), desvío y velocidad lateral
+    let (box_x, box_y) = (panel_x + PADDING, panel_y + PADDING);
+    let half = (BOX_SIZE / 2) as i32;
+    let (center_x, center_y) = (box_x as i32 + half, box_y as i32 + half);
+    framebuffer.draw_rect(box_x, box_y, BOX_SIZE, BOX_SIZE, LABEL_COLOR);
+    framebuffer.blend_line(box_x as i32, center_y, box_x as i32 + BOX_SIZE as i32 - 1, center_y, LABEL_COLOR, 0.4);
+    framebuffer.blend_line(center_x, box_y as i32, center_x, box_y as i32 + BOX_SIZE as i32 - 1, LABEL_COLOR, 0.4);
+    let corridor_radius = half / 3;
+    framebuffer.draw_circle(center_x, center_y, corridor_radius, LABEL_COLOR);
+    // En pixeles del cuadro, con y hacia abajo
+    let to_box = |(x, y): (f32, f32), scale: f32| {
+        let limit = (half - 3) as f32;
+        ((x * scale).clamp(-limit, limit).round() as i32 + center_x, (-y * scale).clamp(-limit, limit).round() as i32 + center_y)
+    };
+    let (dot_x, dot_y) = to_box(approach.offset, corridor_radius as f32 / approach.corridor);
+    framebuffer.blend_rect((dot_x - 1).max(0) as usize, (dot_y - 1).max(0) as usize, 3, 3, color, 1.0);
+    let (cross_x, cross_y) = to_box(approach.lateral_velocity, half as f32 / LATERAL_SPEED_SCALE);
+    framebuffer.draw_line(cross_x - 3, cross_y, cross_x + 3, cross_y, color);
+    framebuffer.draw_line(cross_x, cross_y - 3, cross_x, cross_y + 3, color);
+
+    let left = (box_x + BOX_SIZE + PADDING) as i32;
+    let mut y = box_y as i32;
+    draw_text(framebuffer, left, y, &title, TITLE_COLOR);
+    y += line as i32;
+    for (label, value) in &rows {
+        draw_text(framebuffer, left, y, label, LABEL_COLOR);
+        draw_text(framebuffer, left + value_column as i32, y, value, TEXT_COLOR);
+        y += line as i32;
+    }
+    draw_text(framebuffer, left, y, alignment.label(), color);
+}
@@ -0,0 +1,17 @@
+// Assets por defecto embebidos en el binario, para que el simulador funcione aunque se
+// ejecute fuera del repositorio (sin `assets/`). Los archivos en disco siguen teniendo prioridad.
+
+// Nave por defecto y su material (mismo contenido que assets/models/NavePrototipo2.*)
+pub const DEFAULT_SHIP_NAME: &str = "NavePrototipo2";
+pub const DEFAULT_SHIP_OBJ: &[u8] = include_bytes!("../assets/models/NavePrototipo2.obj");
+pub const DEFAULT_SHIP_MATERIAL: &str = include_str!("../assets/models/NavePrototipo2.ship");
+
+// Fuente bitmap 8x8 (font8x8 "basic", dominio público): 8 bytes por glifo, ASCII 0x20..=0x7E
+pub const DEFAULT_FONT: &[u8; 760] = include_bytes!("../assets/fonts/font8x8_basic.bin");
+
+// Textura pequeña de estrellas y nebulosa para el fondo (PNG 128x64)
+pub const STARFIELD_TEXTURE: &[u8] = include_bytes!("../assets/textures/starfield.png");
+
+// Textos de la interfaz por idioma (tablas TOML con IDs de mensaje, ver i18n.rs)
+pub const STRINGS_EN: &str = include_str!("../assets/i18n/en.toml");
+pub const STRINGS_ES: &str = include_str!("../assets/i18n/es.toml");
@@ -0,0 +1,1596 @@
+use nalgebra_glm::Vec3;
+use std::path::PathBuf;
+use log::{info, warn};
+use tracing::{debug_span, info_span};
+use web_time::Instant;
+use crate::assets::{self, Assets};
+use crate::audio::{Audio, Sound};
+use crate::body_search::{self, BodySearch, SearchCommand};
+use crate::camera::{Camera, CameraMode};
+use crate::color_grading::AutoExposure;
+use crate::docking;
+use crate::error::Error;
+use crate::events::{self, EventKind, EventLog, EventWatch};
+use crate::frame_budget::{FrameBudget, DEFAULT_BUDGET};
+use crate::frame_stats::{self, FrameStats};
+use crate::framebuffer::Framebuffer;
+use crate::input::{GamepadAxis, InputState};
+use crate::interpolation::{self, FixedStep};
+use crate::i18n::{self, on_off, tr, tr_with};
+use crate::hud::{BodyCard, HudData, render_body_card, render_help_overlay, render_hud, render_mission, render_objective, render_proximity_alert, render_rebind_prompt};
+use crate::indicators::{render_lead_indicator, render_reticle, render_trajectory, render_velocity_indicators};
+use crate::keymap::{Action, KeyMap, RebindSession, RebindStep, KEYBINDINGS_FILE, WARP_TARGETS};
+use crate::labels::{self, BodyLabel};
+use crate::maneuver::{self, Maneuver};
+use crate::markers;
+use crate::menu::{MenuCommand, PauseMenu, Setting};
+use crate::minimap::{MinimapData, render_minimap};
+use crate::missions::Missions;
+use crate::orbit_editor::{self, OrbitEditor, EXPORT_PATH};
+use crate::net_sync::{NetSync, SyncEvent, SyncMode, SyncState};
+use crate::projectiles;
+use crate::proximity::{ProximityWarning, WarningBeep, WarningLevel};
+use crate::reference_frame::{self, ReferenceFrame};
+use crate::ruler::{self, Ruler};
+use crate::renderer::{render_warp_streaks, FrameInputs, Renderer, SceneFrame};
+use crate::scene::{Scene, SceneSource};
+use crate::scene_file;
+use crate::storage;
+use crate::screenshot;
+use crate::clip::ClipRecorder;
+use crate::gravity::TrajectoryPredictor;
+use crate::scripting::{Script, ScriptCommand, ScriptEvent};
+use crate::settings::{Settings, MAX_TIME_SCALE, MIN_TIME_SCALE, SETTINGS_FILE};
+use crate::sim_clock::{format_date, TIME_JUMP_DAYS};
+use crate::toast::Toasts;
+use crate::vehicle::{Vehicle, WarpDrive};
+
+// Frames seguidos sin cambios antes de que el bucle de la ventana pueda dejar de dibujar (con el
+// render en hilo la imagen presentada va un frame atrás)
+const STILL_FRAMES_BEFORE_IDLE: u32 = 2;
+// Por debajo de esta velocidad la nave se considera quieta
+const REST_SPEED: f32 = 1e-3;
+// Las sensibilidades de la cámara en settings.toml son por frame a esta frecuencia
+const CAMERA_REFERENCE_FPS: f32 = 60.0;
+
+// Resultado de un frame para el bucle de la ventana
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameStatus {
+    Continue,
+    Quit,  // Se eligió "Quit" en el menú de pausa
+}
+
+// Cómo arranca el engine (lo que el binario recibe por línea de comandos). `Default` es el
+// arranque de siempre: sistema solar, cámara orbital y assets buscados en las rutas habituales.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchOptions {
+    pub asset_root: Option<PathBuf>,  // None = `assets::resolve_asset_root`
+    pub scene: SceneSource,
+    pub seed: Option<u64>,
+    pub camera_mode: CameraMode,
+    pub script: Option<PathBuf>,  // Reemplaza al script que indique el archivo de escena
+    pub sky: Option<PathBuf>,     // Imagen de fondo; reemplaza a la del archivo de escena
+    pub audio: bool,  // Abrir el dispositivo de sonido (headless y benchmark no lo hacen)
+    pub sync: Option<SyncMode>,  // Compartir la simulación por la red (None = sola)
+}
+
+impl Default for LaunchOptions {
+    fn default() -> Self {
+        LaunchOptions {
+            asset_root: None,
+            scene: SceneSource::SolarSystem,
+            seed: None,
+            camera_mode: CameraMode::Orbital,
+            script: None,
+            sky: None,
+            audio: true,
+            sync: None,
+        }
+    }
+}
+
+impl LaunchOptions {
+    pub fn with_asset_root(mut self, root: PathBuf) -> Self {
+        self.asset_root = Some(root);
+        self
+    }
+
+    pub fn with_scene(mut self, scene: SceneSource) -> Self {
+        self.scene = scene;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn with_camera_mode(mut self, mode: CameraMode) -> Self {
+        self.camera_mode = mode;
+        self
+    }
+
+    pub fn with_script(mut self, path: PathBuf) -> Self {
+        self.script = Some(path);
+        self
+    }
+
+    pub fn with_sky(mut self, path: PathBuf) -> Self {
+        self.sky = Some(path);
+        self
+    }
+
+    pub fn with_audio(mut self, enabled: bool) -> Self {
+        self.audio = enabled;
+        self
+    }
+
+    pub fn with_sync(mut self, mode: SyncMode) -> Self {
+        self.sync = Some(mode);
+        self
+    }
+}
+
+struct WarpAnimation {
+    from: Vec3,
+    to: Vec3,
+    target_body_index: Option<usize>, // Índice del cuerpo objetivo para seguimiento
+    progress: f32,
+    duration: f32,
+}
+
+// El simulador completo: escena, renderer, cámara y el estado de la interfaz (HUD, menú,
+// superposiciones). La ventana queda fuera: cada frame recibe un `InputState` y deja la
+// imagen en `renderer.framebuffer` para que quien lo embebe la presente.
+pub struct Engine {
+    pub scene: Scene,
+    pub renderer: Renderer,
+    pub camera: Camera,
+    pub assets: Assets,
+    pub settings: Settings,
+    pub keymap: KeyMap,
+    pub audio: Audio,
+    pub current_body_index: usize,
+    pub orbits_running: bool,
+    pub show_hud: bool,
+    pub show_perf_overlay: bool,
+    pub show_pipeline_stats: bool,
+    pub show_velocity_indicators: bool,
+    pub show_body_labels: bool,
+    pub show_help: bool,
+    pub ruler: Option<Ruler>,  // Modo regla (tecla R)
+    pub orbit_editor: Option<OrbitEditor>,  // Editor de órbitas del cuerpo enfocado (tecla I)
+    pub reference_frame: ReferenceFrame,    // Inercial o el del cuerpo dominante (tecla P)
+    pub maneuver: Option<Maneuver>,  // Planificador de maniobras (tecla X); abierto = simulación en pausa
+    pub body_search: Option<BodySearch>,  // Búsqueda de cuerpos (tecla F7); abierta = las teclas escriben en ella
+    pub show_event_log: bool,
+    pub events: EventLog,  // Sucesos de la simulación; cualquier sistema puede agregar con `record_event`
+    pub missions: Missions,
+    pub menu: Option<PauseMenu>,  // Abierto = simulación en pausa
+    pub toasts: Toasts,
+    pub collision_flash: f32,
+    pub proximity_warning: ProximityWarning,
+    warning_beep: WarningBeep,  // Pitido de la alerta de proximidad
+    pub script: Option<Script>,
+    pub objective: Option<String>,  // Lo fija el script del escenario; se muestra con el HUD
+    pub sync: Option<NetSync>,
+    rebinding: Option<RebindSession>,
+    screenshot_requested: bool,
+    clip: ClipRecorder,  // Los últimos segundos de imagen, para guardarlos como GIF
+    trajectory: TrajectoryPredictor,  // Trayectoria futura de la nave con `ship_gravity`
+    event_watch: EventWatch,  // Detecta conjunciones y acercamientos para el registro
+    body_labels: Vec<BodyLabel>,  // Del último frame, para las capturas anotadas
+    warp_animation: Option<WarpAnimation>,
+    frame_budget: FrameBudget,  // Escalón de calidad con `adaptive_quality`
+    auto_exposure: AutoExposure,  // Corrección de exposición con `auto_exposure`
+    fixed_step: FixedStep,  // Pasos de 30 Hz e interpolación al dibujar con `fixed_tick`
+    still_frames: u32,            // Frames seguidos que dejaron la pantalla igual (ahorro de energía)
+    last_view: (Vec3, Vec3),      // Ojo y objetivo de la cámara en el frame anterior
+    window_size: (usize, usize),
+    launch: LaunchOptions,  // Con qué se arrancó; se conserva al cambiar de escena desde el menú
+}
+
+impl Engine {
+    pub fn new(window_width: usize, window_height: usize, settings: Settings) -> Self {
+        Engine::launch(window_width, window_height, settings, LaunchOptions::default())
+            .expect("the built-in scene always loads")
+    }
+
+    // Falla si la escena pedida o su script no se pueden cargar (archivo ilegible o inválido) o
+    // si no se puede abrir el socket de la sincronización
+    pub fn launch(window_width: usize, window_height: usize, settings: Settings, launch: LaunchOptions) -> Result<Self, Error> {
+        let mut assets = Assets::new(assets::resolve_asset_root(launch.asset_root.clone()));
+        let mut scene = Scene::load(&launch.scene, &mut assets, launch.seed).map_err(Error::Scene)?;
+        if let Some(path) = &launch.sky {
+            scene.set_sky(path, &mut assets);
+        }
+        let script = launch.script.as_ref().or(scene.script.as_ref()).map(|path| Script::load(path)).transpose().map_err(Error::Script)?;
+        let sync = launch.sync.as_ref().map(NetSync::open).transpose().map_err(Error::Sync)?;
+        let mut missions = Missions::default();
+        for spec in &scene.missions {
+            missions.add(spec, &scene);
+        }
+        assets.watch();
+
+        let mut camera = Camera::new(
+            Vec3::new(0.0, 3.0, 8.0),
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+        );
+        camera.set_mode(launch.camera_mode);
+
+        let mut engine = Engine {
+            scene,
+            renderer: Renderer::new(window_width, window_height, settings.resolution),
+            camera,
+            assets,
+            settings,
+            keymap: KeyMap::load(KEYBINDINGS_FILE),
+            audio: Audio::new(settings.master_volume, launch.audio),
+            current_body_index: 0,
+            orbits_running: true,
+            show_hud: true,
+            show_perf_overlay: false,
+            show_pipeline_stats: false,
+            show_velocity_indicators: true,
+            show_body_labels: true,
+            show_help: false,
+            ruler: None,
+            orbit_editor: None,
+            reference_frame: ReferenceFrame::default(),
+            maneuver: None,
+            body_search: None,
+            show_event_log: false,
+            events: EventLog::default(),
+            missions,
+            menu: None,
+            toasts: Toasts::new(),
+            collision_flash: 0.0,
+            proximity_warning: ProximityWarning::clear(),
+            warning_beep: WarningBeep::default(),
+            script,
+            objective: None,
+            sync,
+            rebinding: None,
+            screenshot_requested: false,
+            clip: ClipRecorder::new(),
+            trajectory: TrajectoryPredictor::default(),
+            event_watch: EventWatch::default(),
+            body_labels: Vec::new(),
+            warp_animation: None,
+            frame_budget: FrameBudget::default(),
+            auto_exposure: AutoExposure::default(),
+            fixed_step: FixedStep::default(),
+            still_frames: 0,
+            last_view: (Vec3::zeros(), Vec3::zeros()),
+            window_size: (window_width, window_height),
+            launch,
+        };
+        i18n::set_language(settings.language);
+        engine.apply_pipelining();
+        engine.apply_tessellation();
+        engine.run_script(ScriptEvent::Start);
+        Ok(engine)
+    }
+
+    pub fn launch_options(&self) -> &LaunchOptions {
+        &self.launch
+    }
+
+    pub fn window_size(&self) -> (usize, usize) {
+        self.window_size
+    }
+
+    // Etiquetas de los cuerpos visibles en el último frame (vacía si no se calcularon)
+    pub fn body_labels(&self) -> &[BodyLabel] {
+        &self.body_labels
+    }
+
+    // Escenas de "Load Scene": la incorporada y, si se pasó `--scene`, la del archivo
+    fn scene_sources(&self) -> Vec<SceneSource> {
+        let mut sources = vec![SceneSource::SolarSystem];
+        if self.launch.scene != SceneSource::SolarSystem {
+            sources.push(self.launch.scene.clone());
+        }
+        sources
+    }
+
+    // Lista de controles por consola al arrancar
+    pub fn print_controls(&self) {
+        info!("Using optimized sphere LODs: {:?} vertices", self.scene.meshes.get(self.scene.planet_mesh).vertex_counts());
+        println!("{}", tr_with("console.controls", &[
+            ("file", &KEYBINDINGS_FILE),
+            ("rebind", &self.keymap.key_names(Action::RebindKeys)),
+            ("help", &self.keymap.key_names(Action::ToggleHelp)),
+        ]));
+        for action in Action::all() {
+            println!("  {}: {}", self.keymap.key_names(action), action.description());
+        }
+    }
+
+    // Entrada, simulación y dibujo de un frame. `frame_time` es el tiempo real desde el
+    // frame anterior; con el menú abierto la simulación no avanza.
+    pub fn frame(&mut self, input: &InputState, frame_time: f32) -> FrameStatus {
+        let _span = info_span!("frame").entered();
+        let work_start = Instant::now();
+        // Con el menú o el planificador de maniobras abiertos la simulación se congela, pero la
+        // escena se sigue dibujando
+        let delta_time = if self.menu.is_some() || self.maneuver.is_some() { 0.0 } else { frame_time };
+
+        // Handle input
+        if self.menu.is_some() {
+            match self.handle_menu(input) {
+                Some(MenuCommand::Quit) => return FrameStatus::Quit,
+                Some(MenuCommand::LoadScene(index)) => {
+                    let source = self.scene_sources()[index].clone();
+                    // El sonido y el socket ya abiertos pasan a la escena nueva en vez de reabrirse
+                    let launch = LaunchOptions { scene: source.clone(), audio: false, sync: None, ..self.launch.clone() };
+                    match Engine::launch(self.window_size.0, self.window_size.1, self.settings, launch) {
+                        Ok(mut engine) => {
+                            engine.launch = self.launch.clone();
+                            std::mem::swap(&mut engine.audio, &mut self.audio);
+                            std::mem::swap(&mut engine.sync, &mut self.sync);
+                            *self = engine;
+                            self.toasts.info(tr_with("toast.scene_loaded", &[("scene", &source.name())]));
+                        }
+                        Err(e) => self.toasts.warning(tr_with("toast.scene_failed", &[("scene", &source.name()), ("error", &e)])),
+                    }
+                    return FrameStatus::Continue;
+                }
+                _ => {}
+            }
+        } else if self.rebinding.is_none() && self.body_search.is_none() && self.keymap.is_pressed(input, Action::PauseMenu) {
+            let scenes = self.scene_sources().iter().map(SceneSource::name).collect();
+            self.menu = Some(PauseMenu::new(scenes));
+            self.audio.play(Sound::Blip);
+        } else {
+            self.handle_input(input, delta_time);
+        }
+
+        // El motor suena mientras se acelera; en pausa (o siguiendo a otra instancia) se apaga
+        let thrusting = self.menu.is_none()
+            && self.maneuver.is_none()
+            && self.rebinding.is_none()
+            && self.body_search.is_none()
+            && !self.is_sync_viewer()
+            && self.keymap.is_down(input, Action::ThrustForward);
+        self.audio.set_engine_throttle(if thrusting { 1.0 } else { 0.0 });
+        self.audio.update(frame_time);
+
+        // Hot reload: si un modelo o textura cambió en disco, se recarga sin reiniciar
+        let reloaded = self.assets.reload_changed();
+        if reloaded > 0 {
+            self.scene.spaceship.reload_model(&mut self.assets);
+            self.scene.refresh_sky(&self.assets);
+            self.toasts.info(tr_with("toast.hot_reloaded", &[("count", &reloaded)]));
+        }
+
+        self.sync_network(frame_time);
+        if self.settings.fixed_tick {
+            for _ in 0..self.fixed_step.advance(delta_time) {
+                self.fixed_step.begin_tick(&self.scene, &self.camera);
+                self.update(interpolation::TICK);
+            }
+        } else {
+            self.update(delta_time);
+        }
+        // Se dibuja entre los dos últimos pasos y después se vuelve al estado real
+        let live = self.fixed_step.present(&mut self.scene, &mut self.camera);
+        self.render(frame_time);
+        if let Some(live) = live {
+            live.apply(&mut self.scene, &mut self.camera);
+        }
+        if std::mem::take(&mut self.screenshot_requested) {
+            self.take_screenshot();
+        }
+        self.clip.record(&self.renderer.framebuffer, frame_time);
+        if let Some(result) = self.clip.poll_saved() {
+            match result {
+                Ok(path) => {
+                    info!("Clip saved to {}", path.display());
+                    self.toasts.info(tr_with("toast.clip_saved", &[("path", &path.display())]));
+                }
+                Err(e) => self.toasts.warning(tr_with("toast.clip_failed", &[("error", &e)])),
+            }
+        }
+
+        self.observe_frame_budget(work_start);
+
+        let view = (self.camera.eye, self.camera.center);
+        self.still_frames = if self.is_still() && view == self.last_view { self.still_frames.saturating_add(1) } else { 0 };
+        self.last_view = view;
+        FrameStatus::Continue
+    }
+
+    // Presupuesto de frame: con `adaptive_quality`, el tiempo de trabajo de este frame decide con
+    // qué calidad se dibujan los siguientes (ver frame_budget.rs)
+    fn observe_frame_budget(&mut self, work_start: Instant) {
+        if !self.settings.adaptive_quality {
+            self.frame_budget.reset();
+            return;
+        }
+        let budget = self.settings.frame_budget().unwrap_or(DEFAULT_BUDGET);
+        if let Some(quality) = self.frame_budget.observe(work_start.elapsed(), budget) {
+            info!("Frame budget: quality {}", quality.name());
+        }
+    }
+
+    // Ahorro de energía: con la simulación en pausa, nada animándose y sin entrada, el bucle de
+    // la ventana puede dejar de llamar a `frame` (la imagen presentada ya es la actual) y
+    // esperar entrada
+    pub fn is_idle(&self, input: &InputState) -> bool {
+        self.settings.power_saving && self.still_frames >= STILL_FRAMES_BEFORE_IDLE && input.is_quiet()
+    }
+
+    // Si lo dibujado en el último frame se repetiría tal cual: menú o planificador de maniobras
+    // abiertos, o las órbitas detenidas con la nave quieta, y sin avisos, viajes, disparos ni
+    // contadores en pantalla
+    fn is_still(&self) -> bool {
+        let ship = &self.scene.spaceship;
+        let weapons = &self.scene.weapons;
+        let paused = self.menu.is_some()
+            || self.maneuver.is_some()
+            || (!self.orbits_running
+                && ship.velocity.magnitude() < REST_SPEED
+                && matches!(ship.warp_drive, WarpDrive::Idle)
+                && weapons.projectiles.is_empty()
+                && weapons.particles.is_empty()
+                && self.scene.solar_wind.is_empty());
+        paused
+            && self.toasts.is_empty()
+            && self.warp_animation.is_none()
+            && self.collision_flash <= 0.0
+            && self.proximity_warning.level == WarningLevel::Clear
+            && self.sync.is_none()
+            && !self.show_perf_overlay
+            && !self.show_pipeline_stats
+            && !self.clip.is_saving()
+            && !self.auto_exposure.is_adapting()
+    }
+
+    fn update(&mut self, delta_time: f32) {
+        let _span = info_span!("simulation_update").entered();
+        // Update warp animation
+        self.update_warp(delta_time);
+        let was_jumping = matches!(self.scene.spaceship.warp_drive, WarpDrive::Jumping { .. });
+
+        // Actualizar cámara de tercera persona si está en modo FirstPerson (vista de nave)
+        // Solo actualizar si la nave se movió o rotó significativamente
+        if matches!(self.camera.mode, CameraMode::FirstPerson) {
+            self.camera.update_third_person(self.scene.spaceship.position, self.scene.spaceship.rotation);
+            // En modo tercera persona, no actualizar las órbitas de planetas lejanos
+        }
+
+        self.scene.ship_gravity = self.settings.ship_gravity;
+        self.scene.solar_wind.enabled = self.settings.solar_wind;
+        self.scene.weapons.particle_scale = self.frame_budget.quality().particle_scale();
+        let ship_velocity = self.scene.spaceship.velocity;
+        let collisions = self.scene.update(delta_time, self.settings.time_scale, self.orbits_running);
+        self.update_reference_frame();
+        if self.settings.ship_gravity {
+            let frame = self.reference_frame.body();
+            self.trajectory.update(&self.scene, delta_time, self.settings.time_scale, self.orbits_running, frame);
+        } else {
+            self.trajectory.clear();
+        }
+        if let Some(maneuver) = &mut self.maneuver {
+            let velocity = self.reference_frame.relative_velocity(&self.scene, self.scene.spaceship.velocity);
+            let delta_v = maneuver.delta_v(&self.scene, velocity);
+            let frame = self.reference_frame.body();
+            maneuver.plan(&self.scene, delta_v, self.settings.time_scale, self.orbits_running, frame, self.settings.ship_gravity);
+        }
+        if was_jumping && !matches!(self.scene.spaceship.warp_drive, WarpDrive::Jumping { .. }) {
+            let (nearest_name, _) = self.scene.nearest_body();
+            self.run_script(ScriptEvent::WarpComplete(nearest_name));
+        }
+
+        // Alerta anticipada: tiempo hasta el impacto siguiendo la velocidad relativa a cada cuerpo
+        let named_bodies: Vec<(Vec3, Vec3, f32, &'static str)> = self.scene.bodies
+            .iter()
+            .map(|body| (body.position, body.velocity, body.scale, body.name))
+            .collect();
+        let spaceship = &self.scene.spaceship;
+        self.proximity_warning = ProximityWarning::assess(
+            spaceship.position(),
+            spaceship.velocity(),
+            spaceship.bounding_radius(),
+            &named_bodies,
+        );
+        if self.warning_beep.update(self.proximity_warning.level, delta_time) {
+            self.audio.play(Sound::Warning);
+        }
+
+        // Cámara de persecución: se actualiza después de mover la nave para no ir un frame atrás
+        if matches!(self.camera.mode, CameraMode::Chase) {
+            self.camera.update_chase(
+                self.scene.spaceship.position,
+                self.scene.spaceship.rotation,
+                delta_time,
+                &self.scene.obstacles(),
+            );
+        }
+
+        self.collision_flash = (self.collision_flash - delta_time).max(0.0);
+        for body_name in collisions {
+            let body_index = self.scene.bodies.iter().position(|body| body.name == body_name);
+            if let Some(index) = body_index {
+                self.event_watch.touched(index);
+            }
+            // Solo se avisa al empezar el choque, no en cada frame de contacto
+            if self.collision_flash <= 0.0 {
+                self.toasts.warning(tr_with("toast.collision", &[("body", &body_name)]));
+                self.audio.play(Sound::Collision);
+                // Velocidad de llegada respecto al cuerpo, antes del rebote
+                let body_velocity = body_index.map_or_else(Vec3::zeros, |index| self.scene.bodies[index].velocity);
+                let (kind, text) = events::contact_event(body_name, (ship_velocity - body_velocity).magnitude());
+                if let Some(index) = body_index.filter(|_| kind == EventKind::Touchdown) {
+                    self.missions.touchdown(index);
+                }
+                self.record_event(kind, text);
+                self.run_script(ScriptEvent::Collision(body_name));
+            }
+            self.collision_flash = 1.0; // Se muestra en el HUD
+        }
+        for (kind, text) in self.event_watch.observe(&self.scene) {
+            self.record_event(kind, text);
+        }
+        for text in self.missions.update(&self.scene, delta_time) {
+            self.record_event(EventKind::Mission, text);
+        }
+
+        if delta_time > 0.0 {
+            self.run_script(ScriptEvent::Tick(delta_time));
+        }
+    }
+
+    fn render(&mut self, frame_time: f32) {
+        let _span = info_span!("render").entered();
+        // Lo que se consulta a `self` antes de tomar prestado el renderer
+        let (focus_name, focus_distance) = self.focus_info();
+        let body_card = self.body_card();
+        let mission_status = self.missions.status(&self.scene);
+        let aspect_ratio = self.renderer.aspect_ratio();
+
+        // La exposición automática se suma a la manual solo para dibujar la escena
+        let scene_settings = Settings { exposure: self.settings.exposure + self.auto_exposure.exposure(), ..self.settings };
+        let scene = &self.scene;
+        let inputs = FrameInputs {
+            scene,
+            camera: &self.camera,
+            settings: &scene_settings,
+            with_labels: self.show_body_labels || self.settings.annotate_screenshots,
+            focus: (!self.camera.mode.follows_ship()).then_some(self.current_body_index),
+            quality: self.frame_budget.quality(),
+        };
+        // Pasada `UI` del renderer: indicadores, etiquetas, HUD, menús y overlays sobre la escena ya suavizada
+        let mut ui = |framebuffer: &mut Framebuffer, stats: &mut FrameStats, frame: &SceneFrame| {
+            let _overlays_span = debug_span!("overlays").entered();
+            let palette = self.settings.palette();
+            // Vector de velocidad y marcadores prograde/retrograde de la nave
+            if self.show_velocity_indicators {
+                render_velocity_indicators(
+                    framebuffer,
+                    &frame.projection,
+                    scene.spaceship.position,
+                    self.reference_frame.relative_velocity(scene, scene.spaceship.velocity),
+                    palette,
+                );
+            }
+
+            // Hacia dónde lleva la gravedad a la nave si no acelera
+            render_trajectory(framebuffer, &frame.projection, self.trajectory.points(), palette.trajectory, false);
+            // Y, punteada, hacia dónde la llevaría el encendido que se está planificando
+            if let Some(maneuver) = &self.maneuver {
+                render_trajectory(framebuffer, &frame.projection, maneuver.path(), palette.selection, true);
+            }
+            self.reference_frame.render(framebuffer, &frame.projection, scene, palette.trajectory);
+            // Pasillo de aproximación al puerto de acople más cercano, si la nave está frente a él
+            let docking = docking::docking_approach(scene);
+            if let Some(approach) = &docking {
+                docking::render_corridor(framebuffer, &frame.projection, approach, approach.alignment_color(palette));
+            }
+
+            if self.show_body_labels {
+                markers::render_markers(framebuffer, &frame.markers);
+                labels::render_labels(framebuffer, &frame.labels);
+            }
+
+            // Marca del cuerpo que sigue la cámara (teclas 1-4)
+            if !self.camera.mode.follows_ship() {
+                if let Some(body) = scene.bodies.get(self.current_body_index) {
+                    labels::render_focus_brackets(framebuffer, &frame.projection, body, scene.time, palette.selection);
+                }
+            }
+
+            // Mira y adelanto sobre el cuerpo seleccionado mientras hay disparos en vuelo
+            if self.camera.mode.follows_ship() && !scene.weapons.projectiles.is_empty() {
+                render_reticle(framebuffer);
+                let moving_target = scene.bodies
+                    .get(self.current_body_index)
+                    .filter(|target| target.velocity.magnitude() > 1e-3);
+                if let Some(target) = moving_target {
+                    let lead = projectiles::lead_point(
+                        scene.spaceship.position,
+                        scene.spaceship.velocity,
+                        target.position,
+                        target.velocity,
+                    );
+                    if let Some(lead) = lead {
+                        render_lead_indicator(framebuffer, &frame.projection, target.position, lead, palette);
+                    }
+                }
+            }
+
+            // Efecto visual del warp drive de la nave
+            let warp_intensity = scene.spaceship.warp_effect_intensity();
+            if warp_intensity > 0.0 {
+                let nose = scene.spaceship.position + scene.spaceship.forward() * 50.0;
+                let center = frame.projection.project(nose)
+                    .map(|p| (p.x, p.y))
+                    .unwrap_or((framebuffer.width as f32 * 0.5, framebuffer.height as f32 * 0.5));
+                render_warp_streaks(framebuffer, center, warp_intensity, scene.time);
+            }
+
+            // HUD al final, encima de toda la escena
+            if self.show_hud {
+                let (nearest_name, nearest_clearance) = scene.nearest_body();
+                let hud_data = HudData {
+                    ship_model: scene.spaceship.get_model_name(),
+                    velocity: self.reference_frame.relative_velocity(scene, scene.spaceship.velocity),
+                    frame_name: self.reference_frame.body_name(scene),
+                    position: scene.spaceship.position,
+                    focus_name,
+                    focus_distance,
+                    nearest_name,
+                    nearest_clearance,
+                    time_scale: self.settings.time_scale,
+                    sim_days: scene.clock.days(),
+                    orbits_paused: !self.orbits_running,
+                    collision_flash: self.collision_flash,
+                    warp_drive: scene.spaceship.warp_drive,
+                    collision_mode: scene.spaceship.collision_mode,
+                    ammo: scene.weapons.ammo,
+                    max_ammo: scene.weapons.max_ammo,
+                    weapon_cooldown: scene.weapons.cooldown,
+                    proximity_warning: self.proximity_warning,
+                    play_area: scene.spaceship.play_area,
+                    palette,
+                };
+                let hud_bottom = render_hud(framebuffer, &hud_data);
+                if let Some(card) = &body_card {
+                    render_body_card(framebuffer, card, hud_bottom + 4, palette);
+                }
+
+                let minimap_data = MinimapData {
+                    bodies: &scene.bodies,
+                    orbits: &scene.orbits,
+                    ship_position: scene.spaceship.position,
+                    ship_heading: scene.spaceship.forward(),
+                    camera_eye: self.camera.eye,
+                    camera_target: self.camera.center,
+                    aspect_ratio,
+                    extent: scene.spaceship.play_area.radius,
+                    palette,
+                };
+                render_minimap(framebuffer, &minimap_data);
+            }
+            render_proximity_alert(framebuffer, &self.proximity_warning, scene.time, palette);
+            if let Some(approach) = docking.as_ref().filter(|_| self.show_hud) {
+                docking::render_docking_guide(framebuffer, approach, scene.bodies[approach.body].name, palette);
+            }
+            if self.show_hud {
+                let mut top = 8;
+                if let Some(objective) = self.objective.as_deref() {
+                    top = render_objective(framebuffer, objective) + 6;
+                }
+                if let Some(status) = &mission_status {
+                    render_mission(framebuffer, status, top);
+                }
+            }
+
+            if let Some(ruler) = &self.ruler {
+                ruler.render(framebuffer, &frame.projection, scene, palette.selection);
+            }
+
+            if self.show_help && self.rebinding.is_none() {
+                render_controls_help(framebuffer, &self.keymap);
+            }
+
+            if let Some(session) = &self.rebinding {
+                if let Some(action) = session.current_action() {
+                    let current_keys = self.keymap.key_names(action);
+                    let cancel_keys: Vec<String> = session.cancel_keys().iter().map(|key| format!("{:?}", key)).collect();
+                    let conflict = session.conflict().map(|(key, other)| {
+                        tr_with("hud.rebind_conflict", &[("key", &format!("{:?}", key)), ("action", &other.description())])
+                    });
+                    render_rebind_prompt(framebuffer, &action.description(), &current_keys, &cancel_keys.join("/"), conflict.as_deref(), session.progress());
+                }
+            }
+
+            if let Some(search) = &self.body_search {
+                let hint = tr_with("hud.search_hint", &[("close", &format!("Escape/{}", self.keymap.key_names(Action::SearchBodies)))]);
+                body_search::render_body_search(framebuffer, search, scene, &hint);
+            }
+
+            self.toasts.update(frame_time);
+            self.toasts.render(framebuffer);
+
+            if let Some(menu) = &self.menu {
+                let settings = &self.settings;
+                let (width, height) = (framebuffer.width, framebuffer.height);
+                let values = |setting| match setting {
+                    Setting::Resolution => format!("{} {}x{}", settings.resolution.name(), width, height),
+                    Setting::LodQuality => settings.lod_quality.name().to_string(),
+                    Setting::Tessellation => settings.sphere_tessellation.name().to_string(),
+                    Setting::AntiAliasing => settings.anti_aliasing.name().to_string(),
+                    Setting::TemporalUpscaling => on_off(settings.temporal_upscaling).to_string(),
+                    Setting::FrameLimit => match settings.target_fps {
+                        0 => tr("menu.unlimited").to_string(),
+                        fps => format!("{} FPS", fps),
+                    },
+                    Setting::OrbitLines => on_off(settings.show_orbits).to_string(),
+                    Setting::SimSpeed => format!("x{}", settings.time_scale),
+                    Setting::Hud => on_off(self.show_hud).to_string(),
+                    Setting::BodyLabels => on_off(self.show_body_labels).to_string(),
+                    Setting::PerfOverlay => on_off(self.show_perf_overlay).to_string(),
+                    Setting::Volume => {
+                        let volume = format!("{}%", (settings.master_volume * 100.0).round());
+                        if self.audio.is_muted() { tr_with("menu.muted", &[("volume", &volume)]) } else { volume }
+                    }
+                    Setting::ShotOverlays => on_off(settings.annotate_screenshots).to_string(),
+                    Setting::Pipelining => on_off(settings.pipelined_rendering).to_string(),
+                    Setting::Language => settings.language.name().to_string(),
+                    Setting::PowerSaving => on_off(settings.power_saving).to_string(),
+                    Setting::Exposure => format!("{} EV", settings.grading().exposure_text()),
+                    Setting::AutoExposure if settings.auto_exposure => format!("{} ({:+.1} EV)", on_off(true), self.auto_exposure.exposure()),
+                    Setting::AutoExposure => on_off(false).to_string(),
+                    Setting::Brightness => settings.grading().brightness_text(),
+                    Setting::Contrast => settings.grading().contrast_text(),
+                    Setting::ColorVision => settings.color_vision.name().to_string(),
+                    Setting::SkyExposure => format!("{:+.1} EV", settings.sky_exposure),
+                    Setting::ShipGravity => on_off(settings.ship_gravity).to_string(),
+                    Setting::SolarWind => on_off(settings.solar_wind).to_string(),
+                    Setting::FixedTick => on_off(settings.fixed_tick).to_string(),
+                    Setting::AdaptiveQuality => on_off(settings.adaptive_quality).to_string(),
+                    Setting::FocusOrder => settings.focus_order.name().to_string(),
+                };
+                menu.render(framebuffer, palette, values);
+            }
+
+            let mut overlay_y = 6;
+            if self.show_perf_overlay {
+                let quality = self.settings.adaptive_quality.then(|| self.frame_budget.quality());
+                overlay_y = frame_stats::render_overlay(framebuffer, stats, quality, overlay_y);
+            }
+            if self.show_pipeline_stats {
+                overlay_y = frame_stats::render_pipeline_overlay(framebuffer, stats, overlay_y);
+            }
+            if self.show_event_log {
+                overlay_y = events::render_event_log(framebuffer, &self.events, overlay_y);
+            }
+            if let Some(editor) = &self.orbit_editor {
+                let body = scene.bodies.get(self.current_body_index).filter(|_| !self.camera.mode.follows_ship());
+                let hint = tr_with("hud.editor_hint", &[
+                    ("next", &self.keymap.key_names(Action::EditNextParameter)),
+                    ("change", &format!("{}/{}", self.keymap.key_names(Action::EditIncrease), self.keymap.key_names(Action::EditDecrease))),
+                    ("export", &self.keymap.key_names(Action::ExportScene)),
+                ]);
+                overlay_y = orbit_editor::render_orbit_editor(framebuffer, editor, body, &hint, overlay_y);
+            }
+            if let Some(maneuver) = &self.maneuver {
+                let hint = tr_with("hud.maneuver_hint", &[
+                    ("next", &self.keymap.key_names(Action::EditNextParameter)),
+                    ("change", &format!("{}/{}", self.keymap.key_names(Action::EditIncrease), self.keymap.key_names(Action::EditDecrease))),
+                    ("burn", &self.keymap.key_names(Action::ExecuteBurn)),
+                ]);
+                maneuver::render_maneuver(framebuffer, maneuver, &hint, overlay_y);
+            }
+        };
+        let frame = self.renderer.render_frame(inputs, &mut ui);
+        self.body_labels = frame.labels;
+        if let Some(luminance) = frame.luminance {
+            self.auto_exposure.adapt(luminance, frame_time);
+        }
+    }
+
+    // El sistema local cambia de cuerpo al cruzar una esfera de influencia; la cámara libre se
+    // mueve con el cuerpo
+    fn update_reference_frame(&mut self) {
+        if let Some(name) = self.reference_frame.update(&self.scene).map(|index| self.scene.bodies[index].name) {
+            self.toasts.info(tr_with("toast.reference_frame_body", &[("body", &name)]));
+        }
+        let shift = self.reference_frame.origin_shift(&self.scene);
+        if matches!(self.camera.mode, CameraMode::Free) && shift != Vec3::zeros() {
+            self.camera.eye += shift;
+            self.camera.center += shift;
+            self.camera.has_changed = true;
+        }
+    }
+
+    // Agrega un suceso al registro con la fecha actual de la simulación y lo anuncia
+    pub fn record_event(&mut self, kind: EventKind, text: String) {
+        if kind.is_announced() {
+            self.toasts.info(text.clone());
+        }
+        self.events.push(self.scene.clock.days(), kind, text);
+    }
+
+    // Recrea el framebuffer si cambió la resolución interna
+    fn apply_resolution(&mut self) {
+        if let Some((width, height)) = self.renderer.apply_resolution(self.settings.resolution) {
+            self.toasts.info(tr_with("toast.resolution", &[("width", &width), ("height", &height)]));
+        }
+    }
+
+    // Regenera las esferas de los planetas si el teselado del ajuste no es el de la escena
+    fn apply_tessellation(&mut self) {
+        if self.scene.sphere_tessellation != self.settings.sphere_tessellation {
+            self.scene.sphere_tessellation = self.settings.sphere_tessellation;
+            self.scene.rebuild_planet_meshes();
+        }
+    }
+
+    // Arranca o detiene el hilo de sombreado según los ajustes
+    fn apply_pipelining(&mut self) {
+        let wanted = self.settings.pipelined_rendering;
+        if self.renderer.set_pipelined(wanted) != wanted {
+            self.settings.pipelined_rendering = false;
+            self.toasts.warning(tr("toast.pipelining_unavailable"));
+        }
+    }
+
+    // Nombre y distancia (desde la nave) del objetivo actual de la cámara
+    fn focus_info(&self) -> (&'static str, f32) {
+        if self.camera.mode.follows_ship() {
+            return (tr("hud.spaceship"), 0.0);
+        }
+        let body = &self.scene.bodies[self.current_body_index];
+        let distance = (body.position - self.scene.spaceship.position).magnitude();
+        (body.name, distance)
+    }
+
+    // Ficha del cuerpo enfocado (None si la cámara sigue a la nave)
+    fn body_card(&self) -> Option<BodyCard<'static>> {
+        if self.camera.mode.follows_ship() {
+            return None;
+        }
+        let bodies = &self.scene.bodies;
+        let body = bodies.get(self.current_body_index)?;
+        let parent_name = body.parent.and_then(|index| bodies.get(index)).map_or("center", |parent| parent.name);
+        Some(BodyCard {
+            name: body.name,
+            kind: body.shader_type.display_name(),
+            description: body.description,
+            radius: body.scale,
+            radius_km: body.radius_km,
+            parent_name,
+            orbit_radius: body.orbit_radius,
+            sphere_of_influence: reference_frame::sphere_of_influence(&self.scene, self.current_body_index),
+            orbital_period: body.orbital_period(),
+            rotation_period: body.rotation_period(),
+            camera_distance: (body.position - self.camera.eye).magnitude(),
+            ship_distance: (body.position - self.scene.spaceship.position).magnitude(),
+        })
+    }
+
+    fn start_warp(&mut self, target_position: Vec3, target_body_index: Option<usize>) {
+        self.audio.play(Sound::Warp);
+        self.warp_animation = Some(WarpAnimation {
+            from: self.camera.center,
+            to: target_position,
+            target_body_index,
+            progress: 0.0,
+            duration: 2.0, // 2 segundos de animación
+        });
+    }
+
+    fn update_warp(&mut self, delta_time: f32) {
+        let bodies = &self.scene.bodies;
+        let mut arrived_at = None;
+        if let Some(ref mut warp) = self.warp_animation {
+            warp.progress += delta_time / warp.duration;
+
+            if warp.progress >= 1.0 {
+                arrived_at = Some(warp.target_body_index.and_then(|index| bodies.get(index)).map_or("Spaceship", |body| body.name));
+                // Al terminar el warp, centrar en el objetivo final
+                if let Some(body_index) = warp.target_body_index {
+                    if body_index < bodies.len() {
+                        self.camera.center = bodies[body_index].position;
+                    }
+                } else {
+                    self.camera.center = warp.to;
+                }
+                self.warp_animation = None;
+            } else {
+                // Durante el warp, seguir directamente al objetivo
+                if let Some(body_index) = warp.target_body_index {
+                    if body_index < bodies.len() {
+                        // Seguir la posición actual del planeta
+                        let target_pos = bodies[body_index].position;
+
+                        // Interpolación suave hacia el objetivo móvil
+                        let t = warp.progress;
+                        let smooth_t = t * t * (3.0 - 2.0 * t);
+
+                        self.camera.center = warp.from + (target_pos - warp.from) * smooth_t;
+                    }
+                } else {
+                    // Si no hay objetivo específico (nave), usar posición fija
+                    let t = warp.progress;
+                    let smooth_t = t * t * (3.0 - 2.0 * t);
+                    self.camera.center = warp.from + (warp.to - warp.from) * smooth_t;
+                }
+
+                // Zoom out durante el warp
+                let zoom_factor = 1.0 + (warp.progress * (1.0 - warp.progress) * 4.0) * 5.0;
+                let direction = (self.camera.center - self.camera.eye).normalize();
+                let base_distance = 8.0;
+                self.camera.eye = self.camera.center - direction * base_distance * zoom_factor;
+            }
+        }
+
+        if let Some(target) = arrived_at {
+            self.run_script(ScriptEvent::WarpComplete(target));
+        }
+    }
+
+    fn take_screenshot(&mut self) {
+        let path = screenshot::next_screenshot_path();
+        match screenshot::save(self, &path) {
+            Ok(state) => {
+                info!("Screenshot saved to {} (state in {})", path.display(), state.display());
+                self.toasts.info(tr_with("toast.screenshot_saved", &[("path", &path.display())]));
+            }
+            Err(e) => self.toasts.warning(tr_with("toast.screenshot_failed", &[("error", &e)])),
+        }
+    }
+
+    // La escena con los cambios del editor de órbitas, para cargarla con `--scene`
+    fn export_scene(&mut self) {
+        let path = PathBuf::from(EXPORT_PATH);
+        match storage::write(&path, &scene_file::export(&self.scene, "Edited scene")) {
+            Ok(()) => {
+                info!("Scene exported to {}", path.display());
+                self.toasts.info(tr_with("toast.scene_exported", &[("path", &path.display())]));
+            }
+            Err(e) => self.toasts.warning(tr_with("toast.scene_export_failed", &[("error", &e)])),
+        }
+    }
+
+    // El encendido planificado, de una vez: cambia la velocidad de la nave, gasta el combustible
+    // que llevaría lograrlo con el empuje normal y cierra el planificador
+    fn execute_burn(&mut self) {
+        let Some(maneuver) = &self.maneuver else {
+            return;
+        };
+        if maneuver.magnitude() <= 0.0 {
+            self.toasts.warning(tr("toast.burn_empty"));
+            return;
+        }
+        let velocity = self.reference_frame.relative_velocity(&self.scene, self.scene.spaceship.velocity);
+        let delta_v = maneuver.delta_v(&self.scene, velocity);
+        self.scene.spaceship.velocity += delta_v;
+        self.missions.burn(delta_v.magnitude() / self.settings.controls.ship_thrust.max(f32::EPSILON));
+        self.toasts.info(tr_with("toast.burn_executed", &[("delta_v", &format!("{:.2}", delta_v.magnitude()))]));
+        self.maneuver = None;
+        self.trajectory.clear();
+    }
+
+    // Los últimos segundos grabados, codificados a GIF en otro hilo; el aviso de que terminó
+    // llega en un frame posterior
+    fn save_clip(&mut self) {
+        let path = screenshot::next_clip_path();
+        let seconds = format!("{:.0}", self.clip.duration());
+        if self.clip.save(path.clone()) {
+            self.toasts.info(tr_with("toast.clip_saving", &[("seconds", &seconds), ("path", &path.display())]));
+        } else {
+            self.toasts.warning(tr("toast.clip_empty"));
+        }
+    }
+
+    // Viaje animado de la cámara orbital hasta un cuerpo (teclas 1-4, Tab, la búsqueda y `warp_to`
+    // de los scripts)
+    fn warp_to_body(&mut self, index: usize) {
+        self.current_body_index = index;
+        self.start_warp(self.scene.bodies[index].position, Some(index));
+        self.camera.set_mode(CameraMode::Orbital);
+        self.toasts.info(tr_with("toast.warping_to", &[("body", &self.scene.bodies[index].name)]));
+    }
+
+    // Entrega un evento al script del escenario y aplica lo que pida. Si el script falla se
+    // detiene (con aviso) y la simulación sigue sin él.
+    fn run_script(&mut self, event: ScriptEvent) {
+        if self.script.is_none() {
+            return;
+        }
+        let _span = info_span!("script", event = event.name()).entered();
+        let (focus_name, _) = self.focus_info();
+        let Some(script) = self.script.as_mut() else {
+            return;
+        };
+        match script.handle(event, &self.scene, &self.camera, focus_name) {
+            Ok(commands) => commands.into_iter().for_each(|command| self.apply_script_command(command)),
+            Err(e) => {
+                warn!("Script stopped: {}", e);
+                self.toasts.warning(tr_with("toast.script_stopped", &[("error", &e)]));
+                self.script = None;
+            }
+        }
+    }
+
+    // Los espectadores de `--sync-join` reciben la nave y el tiempo del anfitrión
+    fn is_sync_viewer(&self) -> bool {
+        self.sync.as_ref().is_some_and(NetSync::is_viewer)
+    }
+
+    // Intercambio con las otras instancias: el anfitrión manda su estado y el espectador aplica
+    // el último recibido antes de simular el frame (entre estados sigue simulando solo)
+    fn sync_network(&mut self, frame_time: f32) {
+        let Some(sync) = &mut self.sync else {
+            return;
+        };
+        let _span = info_span!("network_sync").entered();
+        let (scene, time_scale, orbits_running) = (&self.scene, self.settings.time_scale, self.orbits_running);
+        let events = sync.update(frame_time, || SyncState::capture(scene, time_scale, orbits_running));
+        for event in events {
+            match event {
+                SyncEvent::ViewerJoined(address) => self.toasts.info(tr_with("toast.viewer_joined", &[("address", &address)])),
+                SyncEvent::ViewerLeft(address) => self.toasts.info(tr_with("toast.viewer_left", &[("address", &address)])),
+                SyncEvent::Connected(address) => self.toasts.info(tr_with("toast.sync_connected", &[("address", &address)])),
+                SyncEvent::Lost => self.toasts.warning(tr("toast.sync_lost")),
+                // La velocidad del anfitrión se acota como la local: un datagrama no puede dejarla
+                // fuera del rango de los controles
+                SyncEvent::State(state) => {
+                    if state.apply(&mut self.scene) {
+                        self.settings.time_scale = state.time_scale.clamp(MIN_TIME_SCALE, MAX_TIME_SCALE);
+                        self.orbits_running = state.orbits_running;
+                    }
+                }
+            }
+        }
+    }
+
+    fn apply_script_command(&mut self, command: ScriptCommand) {
+        match command {
+            ScriptCommand::Toast(text) => self.toasts.info(text),
+            ScriptCommand::Warning(text) => self.toasts.warning(text),
+            ScriptCommand::Objective(objective) => self.objective = objective,
+            ScriptCommand::WarpTo(name) => match self.scene.bodies.iter().position(|body| body.name == name) {
+                Some(index) => self.warp_to_body(index),
+                None => warn!("Script asked to warp to unknown body `{}`", name),
+            },
+            ScriptCommand::TimeScale(scale) => self.settings.time_scale = scale.clamp(MIN_TIME_SCALE, MAX_TIME_SCALE),
+            ScriptCommand::LogEvent(text) => self.record_event(EventKind::Script, text),
+            ScriptCommand::AddMission(spec) => self.missions.add(&spec, &self.scene),
+            ScriptCommand::ShipPart(name, transform) => {
+                if !self.scene.spaceship.set_part_transform(&name, transform) {
+                    warn!("Script asked to move unknown ship part `{}`", name);
+                }
+            }
+        }
+    }
+
+    // Búsqueda de cuerpos abierta: las teclas escriben el nombre; Enter viaja al elegido y Esc (o
+    // la tecla de la búsqueda) la cierra
+    fn handle_body_search(&mut self, input: &InputState) {
+        let Some(search) = self.body_search.as_mut() else {
+            return;
+        };
+        let command = if self.keymap.is_pressed(input, Action::SearchBodies) {
+            Some(SearchCommand::Close)
+        } else {
+            input.typed_keys().into_iter().find_map(|key| search.handle_key(key, &self.scene))
+        };
+        match command {
+            Some(SearchCommand::Close) => self.body_search = None,
+            Some(SearchCommand::WarpTo(body)) => {
+                match body {
+                    Some(index) => self.warp_to_body(index),
+                    None => self.toasts.warning(tr("toast.search_no_match")),
+                }
+                self.body_search = None;
+            }
+            None => {}
+        }
+    }
+
+    // Reasignación de teclas en curso: cada tecla presionada se asigna a la acción actual
+    fn handle_rebinding(&mut self, input: &InputState) {
+        let Some(session) = self.rebinding.as_mut() else {
+            return;
+        };
+
+        for key in input.pressed_keys() {
+            match session.handle_key(&mut self.keymap, key) {
+                RebindStep::Waiting => {}
+                RebindStep::Finished => {
+                    match self.keymap.save(KEYBINDINGS_FILE) {
+                        Ok(()) => self.toasts.info(tr_with("toast.bindings_saved", &[("file", &KEYBINDINGS_FILE)])),
+                        Err(e) => self.toasts.warning(tr_with("toast.bindings_failed", &[("error", &e)])),
+                    }
+                    self.rebinding = None;
+                    return;
+                }
+                RebindStep::Cancelled => {
+                    // Descartar cambios volviendo a leer la configuración guardada
+                    self.keymap = KeyMap::load(KEYBINDINGS_FILE);
+                    self.rebinding = None;
+                    self.toasts.info(tr("toast.rebind_cancelled"));
+                    return;
+                }
+            }
+        }
+    }
+
+    // Teclas del menú de pausa; los cambios de ajustes se aplican aquí y lo que afecta al bucle
+    // principal (salir, cargar escena) se devuelve
+    fn handle_menu(&mut self, input: &InputState) -> Option<MenuCommand> {
+        let menu = self.menu.as_mut()?;
+        let cursor = menu.cursor();
+        let command = input
+            .typed_keys()
+            .into_iter()
+            .find_map(|key| menu.handle_key(key));
+        if command.is_some() || menu.cursor() != cursor {
+            self.audio.play(Sound::Blip);
+        }
+        let command = command?;
+
+        match command {
+            MenuCommand::Resume => self.menu = None,
+            MenuCommand::Adjust(setting, steps) => {
+                match setting {
+                    Setting::Resolution => {
+                        self.settings.cycle_resolution(steps);
+                        self.apply_resolution();
+                    }
+                    Setting::LodQuality => self.settings.cycle_lod_quality(steps),
+                    Setting::Tessellation => {
+                        self.settings.cycle_sphere_tessellation(steps);
+                        self.apply_tessellation();
+                    }
+                    Setting::AntiAliasing => self.settings.cycle_anti_aliasing(steps),
+                    Setting::TemporalUpscaling => self.settings.temporal_upscaling = !self.settings.temporal_upscaling,
+                    Setting::FrameLimit => self.settings.cycle_frame_limit(steps),
+                    Setting::OrbitLines => self.settings.show_orbits = !self.settings.show_orbits,
+                    Setting::SimSpeed => self.settings.change_time_scale(steps),
+                    Setting::Hud => self.show_hud = !self.show_hud,
+                    Setting::BodyLabels => self.show_body_labels = !self.show_body_labels,
+                    Setting::PerfOverlay => self.show_perf_overlay = !self.show_perf_overlay,
+                    Setting::Volume => {
+                        self.settings.change_volume(steps);
+                        self.audio.set_volume(self.settings.master_volume);
+                    }
+                    Setting::ShotOverlays => self.settings.annotate_screenshots = !self.settings.annotate_screenshots,
+                    Setting::Pipelining => {
+                        self.settings.pipelined_rendering = !self.settings.pipelined_rendering;
+                        self.apply_pipelining();
+                    }
+                    Setting::Language => {
+                        self.settings.cycle_language(steps);
+                        i18n::set_language(self.settings.language);
+                    }
+                    Setting::PowerSaving => self.settings.power_saving = !self.settings.power_saving,
+                    Setting::Exposure => self.settings.change_exposure(steps),
+                    Setting::AutoExposure => {
+                        self.settings.auto_exposure = !self.settings.auto_exposure;
+                        self.auto_exposure.reset();
+                    }
+                    Setting::Brightness => self.settings.change_brightness(steps),
+                    Setting::Contrast => self.settings.change_contrast(steps),
+                    Setting::ColorVision => self.settings.cycle_color_vision(steps),
+                    Setting::SkyExposure => self.settings.change_sky_exposure(steps),
+                    Setting::ShipGravity => self.settings.ship_gravity = !self.settings.ship_gravity,
+                    Setting::SolarWind => self.settings.solar_wind = !self.settings.solar_wind,
+                    Setting::FixedTick => {
+                        self.settings.fixed_tick = !self.settings.fixed_tick;
+                        self.fixed_step.reset();
+                    }
+                    Setting::AdaptiveQuality => {
+                        self.settings.adaptive_quality = !self.settings.adaptive_quality;
+                        self.frame_budget.reset();
+                    }
+                    Setting::FocusOrder => self.settings.cycle_focus_order(steps),
+                }
+                if let Err(e) = self.settings.save(SETTINGS_FILE) {
+                    self.toasts.warning(tr_with("toast.settings_failed", &[("error", &e)]));
+                }
+            }
+            MenuCommand::LoadScene(_) | MenuCommand::Quit => {}
+        }
+        Some(command)
+    }
+
+    // `delta_time` es el tiempo real del frame: los controles de la nave no dependen de los FPS
+    fn handle_input(&mut self, input: &InputState, delta_time: f32) {
+        if self.rebinding.is_some() {
+            self.handle_rebinding(input);
+            return;
+        }
+        if self.body_search.is_some() {
+            self.handle_body_search(input);
+            return;
+        }
+
+        // Siguiendo a otra instancia el tiempo y la nave son los del anfitrión; la cámara es propia
+        let follows_host = self.is_sync_viewer();
+        // Sensibilidades de settings.toml (`[controls]`): las de la cámara están por frame a 60 Hz
+        // y se escalan con el tiempo real del frame, como los controles de la nave
+        let controls = self.settings.controls;
+        let frame_scale = delta_time * CAMERA_REFERENCE_FPS;
+        let rotation_speed = controls.camera_orbit_degrees.to_radians() * frame_scale;
+        let zoom_speed = controls.camera_zoom_step * frame_scale;
+        let move_speed = controls.camera_move_step * frame_scale;
+
+        // Camera controls - solo si no estamos en modo primera persona (vista de nave)
+        if !self.camera.mode.follows_ship() {
+            // Camera orbit
+            if self.keymap.is_down(input, Action::OrbitLeft) {
+                self.camera.orbit(rotation_speed, 0.0);
+            }
+            if self.keymap.is_down(input, Action::OrbitRight) {
+                self.camera.orbit(-rotation_speed, 0.0);
+            }
+            if self.keymap.is_down(input, Action::OrbitUp) {
+                self.camera.orbit(0.0, -rotation_speed);
+            }
+            if self.keymap.is_down(input, Action::OrbitDown) {
+                self.camera.orbit(0.0, rotation_speed);
+            }
+            // Stick derecho del mando: órbita proporcional a cuánto se inclina
+            let (stick_x, stick_y) = (input.axis(GamepadAxis::RightStickX), input.axis(GamepadAxis::RightStickY));
+            if stick_x != 0.0 || stick_y != 0.0 {
+                self.camera.orbit(-stick_x * rotation_speed, -stick_y * rotation_speed);
+            }
+
+            // Camera zoom (más suave y con throttling)
+            if self.keymap.is_down(input, Action::ZoomIn) {
+                self.camera.zoom(zoom_speed);
+            }
+            if self.keymap.is_down(input, Action::ZoomOut) {
+                self.camera.zoom(-zoom_speed);
+            }
+
+            // 3D Movement - Up/Down (más suave)
+            if self.keymap.is_down(input, Action::MoveUp) {
+                self.camera.move_up_down(move_speed);
+            }
+            if self.keymap.is_down(input, Action::MoveDown) {
+                self.camera.move_up_down(-move_speed);
+            }
+
+            // Rueda del mouse: un paso de zoom por evento, sin importar la escala de la plataforma
+            // ni la duración del frame
+            let scroll = input.scroll();
+            if scroll != 0.0 {
+                self.camera.zoom(controls.camera_zoom_step * scroll.signum());
+            }
+        }
+
+        // Toggle cámara de persecución (alternativa suave a la vista de primera persona)
+        if self.keymap.is_pressed(input, Action::ToggleChaseCamera) {
+            if matches!(self.camera.mode, CameraMode::Chase) {
+                self.camera.set_mode(CameraMode::Orbital);
+                self.start_warp(self.scene.bodies[self.current_body_index].position, Some(self.current_body_index));
+                self.toasts.info(tr("toast.camera_orbital"));
+            } else {
+                self.warp_animation = None;
+                self.camera.set_mode(CameraMode::Chase);
+                self.toasts.info(tr("toast.camera_chase"));
+            }
+        }
+
+        // Focus with warp animation
+        for index in 0..WARP_TARGETS.min(self.scene.bodies.len()) {
+            if self.keymap.is_pressed(input, Action::WarpTo(index)) {
+                self.warp_to_body(index);
+            }
+        }
+        // Recorrer los cuerpos con Tab; con el editor o el planificador abiertos Tab es de ellos
+        if self.orbit_editor.is_none() && self.maneuver.is_none() {
+            let current = (!self.camera.mode.follows_ship()).then_some(self.current_body_index);
+            for (action, steps) in [(Action::FocusNext, 1), (Action::FocusPrevious, -1)] {
+                if self.keymap.is_pressed(input, action) {
+                    if let Some(index) = body_search::cycle_focus(&self.scene, self.settings.focus_order, current, steps) {
+                        self.warp_to_body(index);
+                    }
+                }
+            }
+        }
+        if self.keymap.is_pressed(input, Action::SearchBodies) {
+            self.body_search = Some(BodySearch::default());
+        }
+        if self.keymap.is_pressed(input, Action::WarpToShip) {
+            self.start_warp(self.scene.spaceship.position, None); // None porque la nave se controla manualmente
+            // Activar modo primera persona (vista tercera persona de la nave)
+            self.camera.set_mode(CameraMode::FirstPerson);
+            self.toasts.info(tr("toast.warping_to_ship"));
+        }
+
+        // Toggle orbit animation
+        if !follows_host && self.keymap.is_pressed(input, Action::ToggleOrbitAnimation) {
+            self.orbits_running = !self.orbits_running;
+            self.toasts.info(tr_with("toast.orbit_animation", &[("state", &on_off(self.orbits_running))]));
+        }
+
+        // Toggle orbit lines visibility
+        if self.keymap.is_pressed(input, Action::ToggleOrbitLines) {
+            self.settings.show_orbits = !self.settings.show_orbits;
+            self.toasts.info(tr(if self.settings.show_orbits { "toast.orbit_lines_visible" } else { "toast.orbit_lines_hidden" }));
+        }
+
+        if !follows_host {
+            // Velocidad de simulación (afecta órbitas y rotaciones, no a la nave)
+            if self.keymap.is_pressed(input, Action::SpeedUp) {
+                self.settings.change_time_scale(1);
+                self.toasts.info(tr_with("toast.sim_speed", &[("scale", &self.settings.time_scale)]));
+            }
+            if self.keymap.is_pressed(input, Action::SlowDown) {
+                self.settings.change_time_scale(-1);
+                self.toasts.info(tr_with("toast.sim_speed", &[("scale", &self.settings.time_scale)]));
+            }
+
+            // Saltos de tiempo: las órbitas se recalculan desde el tiempo absoluto en el siguiente update
+            for (action, days) in [(Action::TimeJumpForward, TIME_JUMP_DAYS), (Action::TimeJumpBackward, -TIME_JUMP_DAYS)] {
+                if self.keymap.is_pressed(input, action) {
+                    self.scene.clock.jump_days(days);
+                    self.toasts.info(tr_with("toast.time_jump", &[("days", &format!("{:+}", days)), ("date", &format_date(self.scene.clock.days()))]));
+                }
+            }
+        }
+
+        // Toggle HUD
+        if self.keymap.is_pressed(input, Action::ToggleHud) {
+            self.show_hud = !self.show_hud;
+            self.toasts.info(tr_with("toast.hud", &[("state", &on_off(self.show_hud))]));
+        }
+
+        if self.keymap.is_pressed(input, Action::TogglePipelineStats) {
+            self.show_pipeline_stats = !self.show_pipeline_stats;
+            self.toasts.info(tr_with("toast.pipeline_stats", &[("state", &on_off(self.show_pipeline_stats))]));
+        }
+
+        if self.keymap.is_pressed(input, Action::TogglePerfOverlay) {
+            self.show_perf_overlay = !self.show_perf_overlay;
+            self.toasts.info(tr_with("toast.perf_overlay", &[("state", &on_off(self.show_perf_overlay))]));
+        }
+
+        if self.keymap.is_pressed(input, Action::ToggleVelocityIndicators) {
+            self.show_velocity_indicators = !self.show_velocity_indicators;
+            self.toasts.info(tr_with("toast.velocity_indicators", &[("state", &on_off(self.show_velocity_indicators))]));
+        }
+
+        if self.keymap.is_pressed(input, Action::ToggleHelp) {
+            self.show_help = !self.show_help;
+        }
+
+        if self.keymap.is_pressed(input, Action::ToggleBodyLabels) {
+            self.show_body_labels = !self.show_body_labels;
+            self.toasts.info(tr_with("toast.body_labels", &[("state", &on_off(self.show_body_labels))]));
+        }
+
+        if self.keymap.is_pressed(input, Action::ToggleEventLog) {
+            self.show_event_log = !self.show_event_log;
+            self.toasts.info(tr_with("toast.event_log", &[("state", &on_off(self.show_event_log))]));
+        }
+
+        if self.keymap.is_pressed(input, Action::ToggleRuler) {
+            self.ruler = match self.ruler {
+                Some(_) => None,
+                None => Some(Ruler::default()),
+            };
+            self.toasts.info(tr_with("toast.ruler", &[("state", &on_off(self.ruler.is_some()))]));
+        }
+        // Clic izquierdo con la regla: el cuerpo o la nave bajo el cursor es el siguiente extremo
+        if let (Some(ruler), Some((x, y))) = (&mut self.ruler, input.mouse_position.filter(|_| input.was_clicked(0))) {
+            let framebuffer = &self.renderer.framebuffer;
+            let scale_x = framebuffer.width as f32 / self.window_size.0.max(1) as f32;
+            let scale_y = framebuffer.height as f32 / self.window_size.1.max(1) as f32;
+            let projection = self.renderer.screen_projection(&self.camera);
+            if let Some(end) = ruler::pick_target(&projection, &self.scene, (x * scale_x, y * scale_y)) {
+                ruler.pick(end);
+            }
+        }
+
+        if self.keymap.is_pressed(input, Action::ToggleReferenceFrame) {
+            self.reference_frame.local = !self.reference_frame.local;
+            self.reference_frame.update(&self.scene);
+            let frame = self.reference_frame.body_name(&self.scene).unwrap_or(tr("hud.inertial_frame"));
+            self.toasts.info(tr_with("toast.reference_frame", &[("frame", &frame)]));
+        }
+
+        if self.keymap.is_pressed(input, Action::ToggleOrbitEditor) {
+            self.orbit_editor = match self.orbit_editor {
+                Some(_) => None,
+                None => Some(OrbitEditor::default()),
+            };
+            // Comparten las teclas de edición: abrir uno cierra el otro
+            self.maneuver = None;
+            self.toasts.info(tr_with("toast.orbit_editor", &[("state", &on_off(self.orbit_editor.is_some()))]));
+        }
+        if let Some(editor) = &mut self.orbit_editor {
+            if self.keymap.is_pressed(input, Action::EditNextParameter) {
+                editor.next_parameter();
+            }
+            // Solo el cuerpo enfocado (teclas 1-4); siguiendo a la nave no hay qué editar
+            let focused = (!self.camera.mode.follows_ship()).then_some(self.current_body_index);
+            for (action, steps) in [(Action::EditIncrease, 1), (Action::EditDecrease, -1)] {
+                if let Some(body) = focused.and_then(|index| self.scene.bodies.get_mut(index)).filter(|_| self.keymap.is_pressed(input, action)) {
+                    if !editor.adjust(body, steps) {
+                        self.toasts.warning(tr_with("toast.editor_no_orbit", &[("body", &body.name)]));
+                    }
+                }
+            }
+            if self.keymap.is_pressed(input, Action::ExportScene) {
+                self.export_scene();
+            }
+        }
+
+        // Planificar una maniobra: solo quien pilotea la nave
+        if !follows_host && self.keymap.is_pressed(input, Action::ToggleManeuver) {
+            self.maneuver = match self.maneuver {
+                Some(_) => None,
+                None => Some(Maneuver::default()),
+            };
+            self.orbit_editor = None;
+            self.toasts.info(tr_with("toast.maneuver", &[("state", &on_off(self.maneuver.is_some()))]));
+        }
+        if let Some(maneuver) = &mut self.maneuver {
+            if self.keymap.is_pressed(input, Action::EditNextParameter) {
+                maneuver.next_axis();
+            }
+            for (action, steps) in [(Action::EditIncrease, 1), (Action::EditDecrease, -1)] {
+                if self.keymap.is_pressed(input, action) {
+                    maneuver.adjust(steps);
+                }
+            }
+            if self.keymap.is_pressed(input, Action::ExecuteBurn) {
+                self.execute_burn();
+            }
+        }
+
+        // Se guarda al final del frame, con la imagen ya completa
+        if self.keymap.is_pressed(input, Action::Screenshot) {
+            self.screenshot_requested = true;
+        }
+
+        if self.keymap.is_pressed(input, Action::SaveClip) {
+            self.save_clip();
+        }
+
+        if self.keymap.is_pressed(input, Action::ToggleMute) {
+            self.audio.toggle_mute();
+            if !self.audio.is_available() {
+                self.toasts.info(tr("toast.audio_unavailable"));
+            } else {
+                self.toasts.info(tr(if self.audio.is_muted() { "toast.audio_muted" } else { "toast.audio_on" }));
+            }
+        }
+
+        // Ajustes de imagen de la escena (los planetas lejanos se pierden en algunas pantallas)
+        for (action, steps) in [(Action::ExposureUp, 1), (Action::ExposureDown, -1)] {
+            if self.keymap.is_pressed(input, action) {
+                self.settings.change_exposure(steps);
+                self.toasts.info(tr_with("toast.exposure", &[("value", &self.settings.grading().exposure_text())]));
+            }
+        }
+        for (action, steps) in [(Action::BrightnessUp, 1), (Action::BrightnessDown, -1)] {
+            if self.keymap.is_pressed(input, action) {
+                self.settings.change_brightness(steps);
+                self.toasts.info(tr_with("toast.brightness", &[("value", &self.settings.grading().brightness_text())]));
+            }
+        }
+        for (action, steps) in [(Action::ContrastUp, 1), (Action::ContrastDown, -1)] {
+            if self.keymap.is_pressed(input, action) {
+                self.settings.change_contrast(steps);
+                self.toasts.info(tr_with("toast.contrast", &[("value", &self.settings.grading().contrast_text())]));
+            }
+        }
+        if self.keymap.is_pressed(input, Action::ResetPicture) {
+            self.settings.reset_grading();
+            self.toasts.info(tr("toast.picture_reset"));
+        }
+
+        // Spaceship controls (pilotear, disparar y saltar solo en el anfitrión si hay sincronización,
+        // y no mientras se planifica una maniobra)
+        let spaceship = &mut self.scene.spaceship;
+
+        if !follows_host && self.maneuver.is_none() {
+            if self.keymap.is_down(input, Action::RotateLeft) {
+                spaceship.rotate(-controls.ship_turn_rate * delta_time);
+            }
+            if self.keymap.is_down(input, Action::RotateRight) {
+                spaceship.rotate(controls.ship_turn_rate * delta_time);
+            }
+            // Stick izquierdo del mando: giro proporcional a cuánto se inclina
+            let turn = input.axis(GamepadAxis::LeftStickX);
+            if turn != 0.0 {
+                spaceship.rotate(turn * controls.ship_turn_rate * delta_time);
+            }
+            if self.keymap.is_down(input, Action::ThrustForward) {
+                spaceship.apply_thrust(controls.ship_thrust * delta_time);
+                self.missions.burn(delta_time);
+            }
+
+            // Disparar desde la nariz de la nave (mantener para disparo continuo)
+            if self.keymap.is_down(input, Action::Fire) {
+                let forward = spaceship.forward();
+                let nose = spaceship.position + forward * spaceship.bounding_radius();
+                self.scene.weapons.try_fire(nose, forward, spaceship.velocity);
+            }
+
+            // Warp drive: mantener la tecla para cargar, soltar para saltar
+            if self.keymap.is_down(input, Action::ChargeWarpDrive) {
+                spaceship.charge_warp(delta_time);
+            } else if self.keymap.is_released(input, Action::ChargeWarpDrive) {
+                spaceship.release_warp();
+                if matches!(spaceship.warp_drive, WarpDrive::Jumping { .. }) {
+                    self.audio.play(Sound::Warp);
+                }
+            }
+        }
+
+        // Cambiar el modelo de la nave
+        if self.keymap.is_pressed(input, Action::CycleShipModel) {
+            spaceship.cycle_model(&mut self.assets);
+            self.toasts.info(tr_with("toast.ship_model", &[("model", &spaceship.get_model_name())]));
+        }
+
+        // Cambiar precisión de la colisión de la nave
+        if self.keymap.is_pressed(input, Action::CycleCollisionShape) {
+            spaceship.collision_mode = spaceship.collision_mode.next();
+            self.toasts.info(tr_with("toast.collision_shape", &[("shape", &spaceship.collision_mode.name())]));
+        }
+
+        // Comportamiento en el límite de la zona de juego
+        if self.keymap.is_pressed(input, Action::CycleBoundaryMode) {
+            let play_area = &mut spaceship.play_area;
+            play_area.mode = play_area.mode.next();
+            self.toasts.info(tr_with("toast.boundary_mode", &[("mode", &play_area.mode.name())]));
+        }
+
+        // Sombreado suave (normales promediadas) o facetado de los planetas
+        if self.keymap.is_pressed(input, Action::ToggleSmoothShading) {
+            self.scene.smooth_shading = !self.scene.smooth_shading;
+            self.scene.rebuild_planet_meshes();
+            self.toasts.info(tr(if self.scene.smooth_shading { "toast.shading_smooth" } else { "toast.shading_faceted" }));
+        }
+
+        if self.keymap.is_pressed(input, Action::CycleSphereMesh) {
+            let scene = &mut self.scene;
+            scene.sphere_mesh = scene.sphere_mesh.next();
+            scene.rebuild_planet_meshes();
+            let vertices = format!("{:?}", scene.meshes.get(scene.planet_mesh).vertex_counts());
+            self.toasts.info(tr_with("toast.planet_mesh", &[("mesh", &scene.sphere_mesh.name()), ("vertices", &vertices)]));
+        }
+
+        // Recargar modelos y texturas desde el disco sin reiniciar
+        if self.keymap.is_pressed(input, Action::ReloadAssets) {
+            let reloaded = self.assets.reload_all();
+            self.scene.spaceship.reload_model(&mut self.assets);
+            self.scene.refresh_sky(&self.assets);
+            let (models, textures) = self.assets.loaded_counts();
+            self.toasts.info(tr_with("toast.assets_reloaded", &[("count", &reloaded), ("models", &models), ("textures", &textures)]));
+        }
+
+        // Iniciar la reasignación de teclas (Esc cancela, Backspace conserva la tecla actual)
+        if self.keymap.is_pressed(input, Action::RebindKeys) {
+            self.rebinding = Some(RebindSession::new(&self.keymap));
+            self.toasts.info(tr("toast.rebinding"));
+        }
+    }
+}
+
+// Lista de controles con las teclas asignadas actualmente (las del archivo de configuración)
+fn render_controls_help(framebuffer: &mut Framebuffer, keymap: &KeyMap) {
+    let entries: Vec<(String, String)> = Action::all()
+        .into_iter()
+        .map(|action| (keymap.key_names(action), action.description()))
+        .collect();
+    let footer = tr_with("hud.controls_footer", &[
+        ("help", &keymap.key_names(Action::ToggleHelp)),
+        ("rebind", &keymap.key_names(Action::RebindKeys)),
+        ("file", &KEYBINDINGS_FILE),
+    ]);
+    render_help_overlay(framebuffer, tr("hud.controls_title"), &footer, &entries);
+}
@@ -0,0 +1,38 @@
+use std::fmt;
+use std::io;
+
+// Lo que puede fallar al arrancar o presentar frames. El binario lo muestra como un mensaje
+// legible y sale con código 1; quien embebe el engine decide qué hacer.
+#[derive(Debug)]
+pub enum Error {
+    Window(String),   // No se pudo abrir la ventana con el backend pedido
+    Present(String),  // La ventana dejó de aceptar frames
+    Scene(String),    // Archivo de escena ilegible o inválido
+    Script(String),   // Script de escenario ilegible o que no compila
+    Sync(io::Error),  // Socket de la sincronización por red
+    Shot(String),     // Estado de `--shot` ilegible o captura que no se pudo guardar
+    Render(String),   // Buffer de destino de `Renderer::render_scene` que no coincide con el tamaño pedido
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Window(e) => write!(f, "could not open the window: {}", e),
+            Error::Present(e) => write!(f, "could not present the frame: {}", e),
+            Error::Scene(e) => write!(f, "could not load the scene: {}", e),
+            Error::Script(e) => write!(f, "could not load the script: {}", e),
+            Error::Sync(e) => write!(f, "network sync: {}", e),
+            Error::Shot(e) => write!(f, "shot: {}", e),
+            Error::Render(e) => write!(f, "could not render: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Sync(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::{HashSet, VecDeque};
+use nalgebra_glm::Vec3;
+use crate::celestial_body::ShaderType;
+use crate::framebuffer::Framebuffer;
+use crate::i18n::{tr, tr_with};
+use crate::ruler::format_distance;
+use crate::scene::Scene;
+use crate::sim_clock::format_date;
+use crate::text::{draw_text, text_width, GLYPH_HEIGHT};
+
+const MAX_ENTRIES: usize = 64;
+const VISIBLE_ENTRIES: usize = 8;  // Las más recientes en el panel
+// Dos planetas están en conjunción si el ángulo entre ellos visto desde la estrella baja de
+// esto; tienen que separarse el doble antes de poder anunciar otra
+const CONJUNCTION_DEGREES: f32 = 2.0;
+// Solo se anuncia el acercamiento máximo de la nave a un cuerpo si pasó a menos de esta
+// cantidad de radios de su superficie
+const APPROACH_RADII: f32 = 3.0;
+// Cambios de distancia menores no cuentan como acercarse o alejarse (nave quieta, órbitas pausadas)
+const APPROACH_EPSILON: f32 = 1e-4;
+// Por debajo de esta velocidad relativa el contacto con un cuerpo es un aterrizaje, no un choque
+pub const TOUCHDOWN_SPEED: f32 = 1.0;
+
+const PANEL_COLOR: u32 = 0x000000;
+const TITLE_COLOR: u32 = 0x7FD4FF;
+const DATE_COLOR: u32 = 0x9AA8B8;
+const TEXT_COLOR: u32 = 0xE0F0FF;
+const MARGIN: usize = 6;
+const PADDING: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Conjunction,
+    ClosestApproach,
+    Touchdown,
+    Impact,
+    Mission,  // Una misión empezó o terminó, o se cumplió uno de sus objetivos
+    Script,   // Lo agregó el script del escenario con `log_event`
+}
+
+impl EventKind {
+    // Los choques ya tienen su propio aviso; el resto se anuncia con un toast al registrarse
+    pub fn is_announced(self) -> bool {
+        self != EventKind::Impact
+    }
+}
+
+// Entrada del registro: fecha de la simulación y texto ya traducido
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimEvent {
+    pub days: f64,
+    pub kind: EventKind,
+    pub text: String,
+}
+
+// Registro de sucesos de la simulación (tecla T lo muestra). Cualquier parte del engine puede
+// agregar entradas con `push`; `EventWatch` agrega las que detecta solo.
+#[derive(Debug, Default)]
+pub struct EventLog {
+    entries: VecDeque<SimEvent>,
+}
+
+impl EventLog {
+    pub fn push(&mut self, days: f64, kind: EventKind, text: String) {
+        if self.entries.len() == MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(SimEvent { days, kind, text });
+    }
+
+    // De la más vieja a la más nueva
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &SimEvent> {
+        self.entries.iter()
+    }
+}
+
+// Acercamiento de la nave a un cuerpo: distancia a la superficie en el paso anterior y si venía bajando
+#[derive(Debug, Clone, Copy)]
+struct Approach {
+    clearance: f32,
+    closing: bool,
+}
+
+// Detecta sucesos comparando cada paso de la simulación con el anterior: conjunciones entre
+// planetas y acercamientos máximos de la nave. Los eclipses quedan para cuando los cuerpos
+// proyecten sombra.
+#[derive(Debug, Default)]
+pub struct EventWatch {
+    aligned: HashSet<(usize, usize)>,
+    approaches: Vec<Option<Approach>>,  // Por cuerpo; None hasta el primer paso
+    primed: bool,  // El primer paso solo toma el estado: lo que ya estaba al cargar no se anuncia
+}
+
+impl EventWatch {
+    pub fn observe(&mut self, scene: &Scene) -> Vec<(EventKind, String)> {
+        let mut events = Vec::new();
+        self.observe_conjunctions(scene, &mut events);
+        self.observe_approaches(scene, &mut events);
+        if !self.primed {
+            self.primed = true;
+            events.clear();
+        }
+        events
+    }
+
+    // Cuerpos que orbitan el mismo centro (los planetas de la estrella, las lunas de un planeta)
+    // alineados vistos desde él
+    fn observe_conjunctions(&mut self, scene: &Scene, events: &mut Vec<(EventKind, String)>) {
+        let planets: Vec<usize> = scene.bodies
+            .iter()
+            .enumerate()
+            .filter(|(_, body)| body.orbit_radius > 0.0 && body.shader_type != ShaderType::Sun)
+            .map(|(index, _)| index)
+            .collect();
+        for (i, &a) in planets.iter().enumerate() {
+            let parent = scene.bodies[a].parent;
+            for &b in planets[i + 1..].iter().filter(|&&b| scene.bodies[b].parent == parent) {
+                let center = parent.map_or_else(Vec3::zeros, |index| scene.bodies[index].position);
+                let (from_a, from_b) = (scene.bodies[a].position - center, scene.bodies[b].position - center);
+                let Some(angle) = angle_between(from_a, from_b) else {
+                    continue;
+                };
+                if angle < CONJUNCTION_DEGREES.to_radians() {
+                    if self.aligned.insert((a, b)) {
+                        let text = tr_with("event.conjunction", &[("a", &scene.bodies[a].name), ("b", &scene.bodies[b].name)]);
+                        events.push((EventKind::Conjunction, text));
+                    }
+                } else if angle > 2.0 * CONJUNCTION_DEGREES.to_radians() {
+                    self.aligned.remove(&(a, b));
+                }
+            }
+        }
+    }
+
+    // Se anuncia al empezar a alejarse, con la menor distancia a la superficie
+    fn observe_approaches(&mut self, scene: &Scene, events: &mut Vec<(EventKind, String)>) {
+        self.approaches.resize(scene.bodies.len(), None);
+        let ship = scene.spaceship.position;
+        for (body, slot) in scene.bodies.iter().zip(&mut self.approaches) {
+            let clearance = (ship - body.position).magnitude() - body.scale;
+            let Some(approach) = slot else {
+                *slot = Some(Approach { clearance, closing: false });
+                continue;
+            };
+            let change = clearance - approach.clearance;
+            if change.abs() < APPROACH_EPSILON {
+                continue;
+            }
+            let closing = change < 0.0;
+            if approach.closing && !closing && approach.clearance > 0.0 && approach.clearance < body.scale * APPROACH_RADII {
+                let distance = format_distance(approach.clearance, scene.unit_km);
+                events.push((EventKind::ClosestApproach, tr_with("event.closest_approach", &[("body", &body.name), ("distance", &distance)])));
+            }
+            *approach = Approach { clearance, closing };
+        }
+    }
+
+    // La nave tocó el cuerpo: ese acercamiento terminó en contacto (ya registrado), no en un paso cercano
+    pub fn touched(&mut self, body: usize) {
+        if let Some(approach) = self.approaches.get_mut(body) {
+            *approach = None;
+        }
+    }
+}
+
+// Contacto de la nave con un cuerpo: aterrizaje si llegó despacio, choque si no
+pub fn contact_event(body: &str, relative_speed: f32) -> (EventKind, String) {
+    if relative_speed < TOUCHDOWN_SPEED {
+        (EventKind::Touchdown, tr_with("event.touchdown", &[("body", &body)]))
+    } else {
+        let speed = format!("{:.1}", relative_speed);
+        (EventKind::Impact, tr_with("event.impact", &[("body", &body), ("speed", &speed)]))
+    }
+}
+
+fn angle_between(a: Vec3, b: Vec3) -> Option<f32> {
+    let lengths = a.magnitude() * b.magnitude();
+    (lengths > f32::EPSILON).then(|| (a.dot(&b) / lengths).clamp(-1.0, 1.0).acos())
+}
+
+// Panel del registro, apilado debajo de los otros paneles de la esquina superior derecha: las
+// entradas más recientes abajo, cada una con su fecha. Devuelve la y donde termina.
+pub fn render_event_log(framebuffer: &mut Framebuffer, log: &EventLog, panel_y: usize) -> usize {
+    let line = GLYPH_HEIGHT + 3;
+    let mut rows: Vec<(String, &str)> = log.entries()
+        .rev()
+        .take(VISIBLE_ENTRIES)
+        .map(|event| (format_date(event.days), event.text.as_str()))
+        .collect();
+    rows.reverse();
+    let empty = tr("hud.event_log_empty");
+    let date_width = rows.iter().map(|(date, _)| text_width(date)).max().unwrap_or(0);
+    let text_column = if rows.is_empty() { 0 } else { date_width + 2 * text_width(" ") };
+    let content_width = rows
+        .iter()
+        .map(|(_, text)| text_column + text_width(text))
+        .chain([text_width(tr("hud.event_log_title")), text_width(empty)])
+        .max()
+        .unwrap_or(0);
+    let panel_width = (content_width + 2 * PADDING).min(framebuffer.width);
+    let panel_height = (rows.len().max(1) + 1) * line + 2 * PADDING;
+    let panel_x = framebuffer.width.saturating_sub(panel_width + MARGIN);
+    framebuffer.blend_rect(panel_x, panel_y, panel_width, panel_height, PANEL_COLOR, 0.6);
+
+    let left = (panel_x + PADDING) as i32;
+    let mut y = (panel_y + PADDING) as i32;
+    draw_text(framebuffer, left, y, tr("hud.event_log_title"), TITLE_COLOR);
+    y += line as i32;
+    if rows.is_empty() {
+        draw_text(framebuffer, left, y, empty, DATE_COLOR);
+    }
+    for (date, text) in &rows {
+        draw_text(framebuffer, left, y, date, DATE_COLOR);
+        draw_text(framebuffer, left + text_column as i32, y, text, TEXT_COLOR);
+        y += line as i32;
+    }
+    panel_y + panel_height + MARGIN
+}
@@ -8,6 +8,7 @@ pub struct Fragment {
    pub normal: Vec3,
    pub vertex_position: Vec3,
    pub intensity: f32,
+   pub tex_coords: Vec2,
 }
 
 impl Fragment {
@@ -19,6 +20,7 @@ impl Fragment {
          normal: Vec3::new(0.0, 0.0, 0.0),
          vertex_position: Vec3::new(0.0, 0.0, 0.0),
          intensity: 1.0,
+         tex_coords: Vec2::new(0.0, 0.0),
       }
    }
 
@@ -38,6 +40,7 @@ impl Fragment {
          normal,
          vertex_position,
          intensity,
+         tex_coords: Vec2::new(0.0, 0.0),
       }
    }
 }
\ No newline at end of file
@@ -0,0 +1,166 @@
+use std::time::Duration;
+
+// Frames seguidos por encima del presupuesto antes de bajar un escalón (un tirón suelto no cuenta)
+const OVER_FRAMES: u32 = 3;
+// Frames seguidos con holgura antes de subir un escalón; más que para bajar, para no oscilar
+const CALM_FRAMES: u32 = 90;
+// Fracción del presupuesto por debajo de la cual hay holgura para volver a subir
+const HEADROOM: f32 = 0.5;
+// Presupuesto sin límite de FPS
+pub const DEFAULT_BUDGET: Duration = Duration::from_micros(16_667);
+
+// Escalones de calidad, de la completa a la más barata. Cada uno incluye los recortes de los anteriores.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Quality {
+    #[default]
+    Full,
+    LowLod,        // Cambio de LOD de las esferas a la mitad de tamaño en pantalla
+    NoSkybox,      // Sin nebulosa, estrellas ni imagen de cielo (queda el color de fondo)
+    FewParticles,  // Un tercio de las chispas por impacto
+}
+
+impl Quality {
+    const ALL: [Quality; 4] = [Quality::Full, Quality::LowLod, Quality::NoSkybox, Quality::FewParticles];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Quality::Full => "Full",
+            Quality::LowLod => "Low LOD",
+            Quality::NoSkybox => "No sky",
+            Quality::FewParticles => "Few FX",
+        }
+    }
+
+    // Multiplica el `lod_bias` de `lod_quality`
+    pub fn lod_factor(self) -> f32 {
+        if self >= Quality::LowLod { 0.5 } else { 1.0 }
+    }
+
+    pub fn draws_skybox(self) -> bool {
+        self < Quality::NoSkybox
+    }
+
+    // Fracción de las chispas de cada impacto que se crean
+    pub fn particle_scale(self) -> f32 {
+        if self >= Quality::FewParticles { 0.35 } else { 1.0 }
+    }
+
+    fn step(self, delta: isize) -> Quality {
+        let index = (self as isize + delta).clamp(0, Quality::ALL.len() as isize - 1);
+        Quality::ALL[index as usize]
+    }
+}
+
+// Presupuesto de tiempo por frame con degradación gradual (ajuste `adaptive_quality`): si el
+// trabajo de un frame (entrada, simulación y dibujo, sin la espera del límite de FPS) pasa del
+// presupuesto varias veces seguidas, los frames siguientes se dibujan un escalón más barato; con
+// holgura sostenida se recupera. Así la latencia de la entrada se mantiene estable aunque la
+// escena se ponga pesada.
+#[derive(Debug, Default)]
+pub struct FrameBudget {
+    quality: Quality,
+    over_frames: u32,
+    calm_frames: u32,
+}
+
+impl FrameBudget {
+    pub fn quality(&self) -> Quality {
+        self.quality
+    }
+
+    // Registra lo que tardó el último frame; devuelve la calidad nueva si cambió
+    pub fn observe(&mut self, work: Duration, budget: Duration) -> Option<Quality> {
+        let load = work.as_secs_f32() / budget.as_secs_f32().max(f32::EPSILON);
+        if load > 1.0 {
+            self.over_frames += 1;
+            self.calm_frames = 0;
+        } else if load < HEADROOM {
+            self.calm_frames += 1;
+            self.over_frames = 0;
+        } else {
+            self.over_frames = 0;
+            self.calm_frames = 0;
+        }
+
+        let next = if self.over_frames >= OVER_FRAMES {
+            self.quality.step(1)
+        } else if self.calm_frames >= CALM_FRAMES {
+            self.quality.step(-1)
+        } else {
+            return None;
+        };
+        self.over_frames = 0;
+        self.calm_frames = 0;
+        (next != self.quality).then(|| {
+            self.quality = next;
+            next
+        })
+    }
+
+    // Vuelve a calidad completa (al apagar el ajuste)
+    pub fn reset(&mut self) {
+        *self = FrameBudget::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUDGET: Duration = Duration::from_millis(16);
+    const OVER: Duration = Duration::from_millis(20);
+    const CALM: Duration = Duration::from_millis(4);
+    const BUSY: Duration = Duration::from_millis(12);
+
+    fn observe(budget: &mut FrameBudget, work: Duration, frames: u32) -> Option<Quality> {
+        (0..frames).filter_map(|_| budget.observe(work, BUDGET)).last()
+    }
+
+    #[test]
+    fn sustained_overrun_steps_down_one_level() {
+        let mut budget = FrameBudget::default();
+        assert_eq!(observe(&mut budget, OVER, OVER_FRAMES - 1), None);
+        assert_eq!(budget.observe(OVER, BUDGET), Some(Quality::LowLod));
+        assert_eq!(budget.quality(), Quality::LowLod);
+    }
+
+    #[test]
+    fn isolated_spikes_do_not_count() {
+        let mut budget = FrameBudget::default();
+        for _ in 0..10 {
+            assert_eq!(observe(&mut budget, OVER, OVER_FRAMES - 1), None);
+            assert_eq!(budget.observe(BUSY, BUDGET), None);
+        }
+        assert_eq!(budget.quality(), Quality::Full);
+    }
+
+    #[test]
+    fn headroom_recovers_slowly() {
+        let mut budget = FrameBudget::default();
+        observe(&mut budget, OVER, 2 * OVER_FRAMES);
+        assert_eq!(budget.quality(), Quality::NoSkybox);
+        assert_eq!(observe(&mut budget, CALM, CALM_FRAMES - 1), None);
+        assert_eq!(budget.observe(CALM, BUDGET), Some(Quality::LowLod));
+        // Entre la mitad y el presupuesto no se sube ni se baja
+        assert_eq!(observe(&mut budget, BUSY, 2 * CALM_FRAMES), None);
+    }
+
+    #[test]
+    fn levels_stop_at_both_ends() {
+        let mut budget = FrameBudget::default();
+        assert_eq!(observe(&mut budget, CALM, 2 * CALM_FRAMES), None);
+        observe(&mut budget, OVER, 10 * OVER_FRAMES);
+        assert_eq!(budget.quality(), Quality::FewParticles);
+        assert_eq!(observe(&mut budget, OVER, OVER_FRAMES), None);
+        budget.reset();
+        assert_eq!(budget.quality(), Quality::Full);
+    }
+
+    #[test]
+    fn cheaper_levels_keep_the_earlier_cuts() {
+        assert_eq!(Quality::Full.lod_factor(), 1.0);
+        assert!(Quality::NoSkybox.lod_factor() < 1.0 && !Quality::NoSkybox.draws_skybox());
+        assert!(Quality::FewParticles.particle_scale() < 1.0 && !Quality::FewParticles.draws_skybox());
+        assert_eq!(Quality::LowLod.particle_scale(), 1.0);
+    }
+}
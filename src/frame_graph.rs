@@ -0,0 +1,168 @@
+use tracing::debug_span;
+use crate::camera::Camera;
+use crate::frame_budget::Quality;
+use crate::frame_stats::FrameStats;
+use crate::frame_pipeline::ShadeQueue;
+use crate::framebuffer::Framebuffer;
+use crate::renderable::Renderable;
+use crate::renderer::SceneFrame;
+use crate::scene::Scene;
+use crate::settings::Settings;
+
+// Nombres de las pasadas que registra `Renderer::new`, en el orden en que corren. Sirven de
+// ancla para `insert_before` / `insert_after`. Hasta `RESOLVE` las mallas solo dejan profundidad
+// y fragmentos en cola; lo que necesite su color va después.
+pub const CLEAR: &str = "clear";
+pub const SKYBOX: &str = "skybox";
+pub const OPAQUE: &str = "opaque";
+pub const LABELS: &str = "labels";
+pub const TRANSPARENT: &str = "transparent";
+pub const RESOLVE: &str = "resolve";
+pub const UPSCALE: &str = "upscale";
+pub const PARTICLES: &str = "particles";
+pub const POST: &str = "post";
+pub const UI: &str = "ui";
+
+// Buffers del framebuffer que una pasada lee o escribe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Color,  // framebuffer.buffer
+    Depth,  // framebuffer.zbuffer
+}
+
+impl Target {
+    pub fn name(self) -> &'static str {
+        match self {
+            Target::Color => "color",
+            Target::Depth => "depth",
+        }
+    }
+}
+
+// Dibujo de la interfaz que pone quien llama al renderer (HUD, menús, overlays); corre en la
+// pasada `UI`, con la proyección y las etiquetas del frame
+pub type UiHook<'a> = dyn FnMut(&mut Framebuffer, &mut FrameStats, &SceneFrame) + 'a;
+
+// Lo que recibe cada pasada: el framebuffer, los contadores y todo lo que se dibuja en el frame
+pub struct PassContext<'a> {
+    pub framebuffer: &'a mut Framebuffer,
+    pub queue: &'a mut ShadeQueue,          // Fragmentos de las mallas hasta `RESOLVE`
+    pub stats: &'a mut FrameStats,
+    pub scene: &'a Scene,
+    pub camera: &'a Camera,
+    pub settings: &'a Settings,
+    pub objects: &'a [&'a dyn Renderable],  // `scene.renderables()`, una sola vez por frame
+    pub frame: &'a mut SceneFrame,          // Proyección del frame; `LABELS` llena las etiquetas
+    pub with_labels: bool,
+    pub focus: Option<usize>,
+    pub quality: Quality,                   // Escalón de `frame_budget` con que se dibuja este frame
+    pub ui: &'a mut UiHook<'a>,
+}
+
+// Una etapa del frame. `reads` y `writes` declaran qué buffers usa, para que el grafo rechace
+// una pasada que lee algo que nada escribió antes (p. ej. una prueba de profundidad antes de
+// `OPAQUE`).
+pub trait FramePass {
+    fn name(&self) -> &str;
+
+    fn reads(&self) -> &[Target] {
+        &[]
+    }
+
+    fn writes(&self) -> &[Target];
+
+    fn run(&mut self, ctx: &mut PassContext);
+}
+
+// Las pasadas del frame, en orden. Se insertan nuevas (sombras, un minimapa 3D...) antes o
+// después de una existente sin tocar el resto.
+#[derive(Default)]
+pub struct FrameGraph {
+    passes: Vec<Box<dyn FramePass>>,
+}
+
+impl FrameGraph {
+    pub fn new() -> Self {
+        FrameGraph::default()
+    }
+
+    pub fn push(&mut self, pass: Box<dyn FramePass>) -> Result<(), String> {
+        self.insert(self.passes.len(), pass)
+    }
+
+    pub fn insert_before(&mut self, anchor: &str, pass: Box<dyn FramePass>) -> Result<(), String> {
+        let index = self.position(anchor)?;
+        self.insert(index, pass)
+    }
+
+    pub fn insert_after(&mut self, anchor: &str, pass: Box<dyn FramePass>) -> Result<(), String> {
+        let index = self.position(anchor)?;
+        self.insert(index + 1, pass)
+    }
+
+    // Quitar una pasada puede dejar a otra leyendo algo que ya nadie escribe; en ese caso no se quita
+    pub fn remove(&mut self, name: &str) -> Result<Box<dyn FramePass>, String> {
+        let index = self.position(name)?;
+        let pass = self.passes.remove(index);
+        if let Err(e) = self.validate() {
+            self.passes.insert(index, pass);
+            return Err(e);
+        }
+        Ok(pass)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.passes.iter().map(|pass| pass.name())
+    }
+
+    pub fn run(&mut self, ctx: &mut PassContext) {
+        self.run_range(0..self.passes.len(), ctx);
+    }
+
+    // Las pasadas antes de `name` (todas si no existe) y las que van después: el renderer
+    // con pipeline corre `RESOLVE` en otro hilo entre las dos mitades
+    pub fn run_before(&mut self, name: &str, ctx: &mut PassContext) {
+        let end = self.position(name).unwrap_or(self.passes.len());
+        self.run_range(0..end, ctx);
+    }
+
+    pub fn run_after(&mut self, name: &str, ctx: &mut PassContext) {
+        let start = self.position(name).map_or(self.passes.len(), |index| index + 1);
+        self.run_range(start..self.passes.len(), ctx);
+    }
+
+    fn run_range(&mut self, range: std::ops::Range<usize>, ctx: &mut PassContext) {
+        for pass in &mut self.passes[range] {
+            let _span = debug_span!("pass", name = pass.name()).entered();
+            pass.run(ctx);
+        }
+    }
+
+    fn position(&self, name: &str) -> Result<usize, String> {
+        self.passes.iter().position(|pass| pass.name() == name).ok_or_else(|| format!("no pass named `{}`", name))
+    }
+
+    fn insert(&mut self, index: usize, pass: Box<dyn FramePass>) -> Result<(), String> {
+        if self.position(pass.name()).is_ok() {
+            return Err(format!("a pass named `{}` already exists", pass.name()));
+        }
+        self.passes.insert(index, pass);
+        if let Err(e) = self.validate() {
+            self.passes.remove(index);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    // Cada buffer que se lee tiene que haberlo escrito una pasada anterior
+    fn validate(&self) -> Result<(), String> {
+        let mut written: Vec<Target> = Vec::new();
+        for pass in &self.passes {
+            if let Some(target) = pass.reads().iter().find(|target| !written.contains(target)) {
+                return Err(format!("pass `{}` reads the {} buffer before any pass writes it", pass.name(), target.name()));
+            }
+            written.extend(pass.writes());
+        }
+        Ok(())
+    }
+}
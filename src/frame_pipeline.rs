@@ -0,0 +1,176 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use log::warn;
+use tracing::debug_span;
+use web_time::Instant;
+use crate::celestial_body::ShaderType;
+use crate::fragment::Fragment;
+use crate::framebuffer::Framebuffer;
+use crate::renderer::SceneFrame;
+use crate::rings::RingBand;
+use crate::shaders::{fragment_shader, Uniforms};
+
+const NO_FRAGMENT: u32 = u32::MAX;
+
+// Cómo se colorea un lote: lo mismo que `renderable::Shading` pero sin préstamos (el material
+// de la nave ya va dentro de los uniforms), para que el lote pueda pasar a otro hilo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BatchShading {
+    Shader(ShaderType),
+    Flat(u32),
+    Ring(RingBand),
+}
+
+struct ShadeBatch {
+    uniforms: Uniforms,
+    shading: BatchShading,
+}
+
+// Fragmentos rasterizados que esperan al fragment shader. La prueba de profundidad se hace al
+// rasterizar (y deja el z-buffer listo para las etiquetas y las órbitas), así que `resolve`
+// sombrea un solo fragmento por pixel: el que quedó más cerca.
+#[derive(Default)]
+pub struct ShadeQueue {
+    batches: Vec<ShadeBatch>,
+    fragments: Vec<(u32, Fragment)>,  // (lote, fragmento)
+    visible: Vec<u32>,                // Por pixel: índice en `fragments` del visible (o NO_FRAGMENT)
+}
+
+impl ShadeQueue {
+    pub fn new() -> Self {
+        ShadeQueue::default()
+    }
+
+    // Los fragmentos que se agreguen después se sombrean con estos uniforms
+    pub fn begin_batch(&mut self, uniforms: Uniforms, shading: BatchShading) {
+        self.batches.push(ShadeBatch { uniforms, shading });
+    }
+
+    // Prueba de profundidad de un fragmento del último lote; false si quedó detrás de lo que ya había
+    pub fn push(&mut self, framebuffer: &mut Framebuffer, fragment: Fragment) -> bool {
+        let (x, y) = (fragment.position.x as usize, fragment.position.y as usize);
+        if !framebuffer.depth_test(x, y, fragment.depth) {
+            return false;
+        }
+        let pixels = framebuffer.width * framebuffer.height;
+        if self.visible.len() != pixels {
+            self.visible.clear();
+            self.visible.resize(pixels, NO_FRAGMENT);
+        }
+        self.visible[y * framebuffer.width + x] = self.fragments.len() as u32;
+        self.fragments.push((self.batches.len() as u32 - 1, fragment));
+        true
+    }
+
+    // Corre el fragment shader sobre los fragmentos visibles, escribe su color y vacía la cola.
+    // Devuelve cuántos sombreó.
+    pub fn resolve(&mut self, framebuffer: &mut Framebuffer) -> u32 {
+        let mut shaded = 0;
+        for (pixel, slot) in self.visible.iter_mut().enumerate() {
+            let index = std::mem::replace(slot, NO_FRAGMENT);
+            let Some((batch, fragment)) = self.fragments.get(index as usize) else {
+                continue;
+            };
+            let batch = &self.batches[*batch as usize];
+            framebuffer.buffer[pixel] = match batch.shading {
+                BatchShading::Shader(shader_type) => fragment_shader(fragment, &batch.uniforms, &shader_type).to_hex(),
+                BatchShading::Flat(color) => color,
+                BatchShading::Ring(band) => band.shade(fragment, &batch.uniforms).to_hex(),
+            };
+            shaded += 1;
+        }
+        self.batches.clear();
+        self.fragments.clear();
+        shaded
+    }
+}
+
+// Un frame con la geometría ya rasterizada, esperando el sombreado
+pub struct RecordedFrame {
+    pub framebuffer: Framebuffer,
+    pub queue: ShadeQueue,
+    pub frame: SceneFrame,
+}
+
+// Lo que devuelve el hilo de sombreado
+pub struct ShadedFrame {
+    pub recorded: RecordedFrame,
+    pub fragments_shaded: u32,
+    pub shade_time: Duration,
+}
+
+// Sombreado en un hilo aparte, un frame por detrás: mientras el hilo principal simula y
+// rasteriza el frame N+1, este sombrea el N. Se alternan dos framebuffers: el que se presenta
+// (y en el que se graba el frame siguiente) y el que está en el hilo.
+pub struct FramePipeline {
+    jobs: Option<Sender<RecordedFrame>>,
+    results: Receiver<ShadedFrame>,
+    worker: Option<JoinHandle<()>>,
+    in_flight: bool,
+    spare: Option<Framebuffer>,  // El segundo framebuffer mientras no hay un frame en el hilo
+}
+
+impl FramePipeline {
+    // None si no se pueden crear hilos (el build web); el renderer sigue sombreando en el frame
+    pub fn new() -> Option<Self> {
+        let (jobs, inbox) = mpsc::channel::<RecordedFrame>();
+        let (outbox, results) = mpsc::channel();
+        let worker = thread::Builder::new()
+            .name("fragment-shading".to_string())
+            .spawn(move || {
+                for mut recorded in inbox {
+                    let _span = debug_span!("fragment").entered();
+                    let start = Instant::now();
+                    let fragments_shaded = recorded.queue.resolve(&mut recorded.framebuffer);
+                    let shaded = ShadedFrame { recorded, fragments_shaded, shade_time: start.elapsed() };
+                    if outbox.send(shaded).is_err() {
+                        break;
+                    }
+                }
+            });
+        match worker {
+            Ok(worker) => Some(FramePipeline { jobs: Some(jobs), results, worker: Some(worker), in_flight: false, spare: None }),
+            Err(e) => {
+                warn!("Pipelined rendering unavailable ({}); shading on the main thread", e);
+                None
+            }
+        }
+    }
+
+    // Framebuffer donde grabar el frame cuando no hay ninguno en el hilo: el de repuesto, así el
+    // que se presenta conserva la imagen anterior
+    pub fn take_spare(&mut self, like: &Framebuffer) -> Option<Framebuffer> {
+        if self.in_flight {
+            return None;
+        }
+        let spare = self.spare.take().filter(|spare| (spare.width, spare.height) == (like.width, like.height));
+        Some(spare.unwrap_or_else(|| like.clone()))
+    }
+
+    // Entrega el frame recién grabado y devuelve el anterior ya sombreado (None al arrancar,
+    // cuando todavía no había ninguno en el hilo)
+    pub fn exchange(&mut self, recorded: RecordedFrame) -> Option<ShadedFrame> {
+        let previous = if self.in_flight { self.results.recv().ok() } else { None };
+        self.in_flight = self.jobs.as_ref().is_some_and(|jobs| jobs.send(recorded).is_ok());
+        previous
+    }
+
+    // Espera el frame en curso y se queda con su framebuffer (al desactivar el pipeline o cambiar
+    // la resolución no se presenta)
+    pub fn flush(&mut self) {
+        if std::mem::take(&mut self.in_flight) {
+            self.spare = self.results.recv().ok().map(|shaded| shaded.recorded.framebuffer);
+        }
+    }
+}
+
+impl Drop for FramePipeline {
+    fn drop(&mut self) {
+        // Sin emisor el hilo termina su bucle
+        self.jobs = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
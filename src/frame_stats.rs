@@ -0,0 +1,247 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+use tracing::debug_span;
+use web_time::Instant;  // std::time::Instant entra en pánico en wasm32; en escritorio es el mismo
+use crate::frame_budget::Quality;
+use crate::framebuffer::Framebuffer;
+use crate::profiling;
+use crate::text::{draw_text, draw_text_styled, Align, TextStyle, GLYPH_HEIGHT};
+
+const HISTORY_LEN: usize = 120;  // Frames que muestra la gráfica
+const GRAPH_HEIGHT: usize = 48;
+const GRAPH_MAX_MS: f32 = 50.0;  // Tope de la escala vertical (20 FPS)
+const OVERLAY_MARGIN: usize = 6;
+
+const PANEL_COLOR: u32 = 0x000000;
+const LABEL_COLOR: u32 = 0x7FD4FF;
+const TEXT_COLOR: u32 = 0xE0F0FF;
+const GOOD_COLOR: u32 = 0x40C040;
+const SLOW_COLOR: u32 = 0xE0C040;
+const BAD_COLOR: u32 = 0xFF4040;
+
+// Etapas del pipeline que se miden por separado
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stage {
+    Vertex,
+    Raster,
+    Fragment,
+    Present,
+}
+
+impl Stage {
+    const ALL: [Stage; 4] = [Stage::Vertex, Stage::Raster, Stage::Fragment, Stage::Present];
+
+    fn label(self) -> &'static str {
+        match self {
+            Stage::Vertex => "VTX ",
+            Stage::Raster => "RAST",
+            Stage::Fragment => "FRAG",
+            Stage::Present => "PRES",
+        }
+    }
+}
+
+// Conteos de trabajo del pipeline en un frame, para comprobar en vivo el efecto de
+// optimizaciones como el culling
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PipelineCounters {
+    pub bodies_drawn: u32,
+    pub bodies_culled: u32,
+    pub triangles_submitted: u32,
+    pub triangles_rasterized: u32,  // Los que quedan tras descartar degenerados y fuera de pantalla
+    pub triangles_invalid: u32,     // Descartados por tener vértices NaN/infinitos (w≈0 al dividir)
+    pub triangles_degenerate: u32,  // Descartados por no tener área (los tres vértices casi alineados)
+    // Pixeles escritos: el shader corre una sola vez por pixel visible, después de resolver la
+    // profundidad, así que no es "fragmentos menos rechazados". Con el pipeline de frames llega
+    // con el frame sombreado (0 en el primero).
+    pub fragments_shaded: u32,
+    // Fragmentos rasterizados que perdieron la prueba de profundidad al encolarse; suelen ser más
+    // que los pixeles escritos
+    pub depth_rejected: u32,
+}
+
+// Tiempos del frame en curso por etapa (se suman entre todas las llamadas de dibujo)
+// e historial de la duración de los últimos frames
+pub struct FrameStats {
+    stage_time: [Duration; 4],
+    last_stage_ms: [f32; 4],
+    frame_times: VecDeque<f32>,  // Milisegundos, el más reciente al final
+    pub counters: PipelineCounters,  // Frame en curso; se van sumando durante el dibujo
+    last_counters: PipelineCounters,
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        FrameStats::new()
+    }
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        FrameStats {
+            stage_time: [Duration::ZERO; 4],
+            last_stage_ms: [0.0; 4],
+            frame_times: VecDeque::with_capacity(HISTORY_LEN),
+            counters: PipelineCounters::default(),
+            last_counters: PipelineCounters::default(),
+        }
+    }
+
+    // Ejecuta `f` sumando lo que tarda a la etapa indicada
+    pub fn time<R>(&mut self, stage: Stage, f: impl FnOnce() -> R) -> R {
+        // Un span por etapa (el nombre de un span tiene que ser fijo en cada llamada)
+        let _span = match stage {
+            Stage::Vertex => debug_span!("vertex"),
+            Stage::Raster => debug_span!("raster"),
+            Stage::Fragment => debug_span!("fragment"),
+            Stage::Present => debug_span!("present"),
+        }
+        .entered();
+        let start = Instant::now();
+        let result = f();
+        self.record(stage, start.elapsed());
+        result
+    }
+
+    // Suma un tiempo medido en otro lado (el sombreado del hilo del pipeline)
+    pub fn record(&mut self, stage: Stage, duration: Duration) {
+        self.stage_time[stage as usize] += duration;
+    }
+
+    // Cierra el frame: guarda los tiempos por etapa y la duración total (en segundos)
+    pub fn end_frame(&mut self, frame_time: f32) {
+        for (last, accumulated) in self.last_stage_ms.iter_mut().zip(&mut self.stage_time) {
+            *last = accumulated.as_secs_f32() * 1000.0;
+            *accumulated = Duration::ZERO;
+        }
+        if self.frame_times.len() == HISTORY_LEN {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time * 1000.0);
+        self.last_counters = std::mem::take(&mut self.counters);
+        profiling::finish_frame();
+    }
+
+    // Conteos del último frame completo
+    pub fn last_counters(&self) -> PipelineCounters {
+        self.last_counters
+    }
+
+    pub fn current_fps(&self) -> f32 {
+        self.frame_times.back().map_or(0.0, |&ms| fps(ms))
+    }
+
+    // Promedio sobre todo el historial (frames totales / tiempo total, no promedio de FPS)
+    pub fn average_fps(&self) -> f32 {
+        let total: f32 = self.frame_times.iter().sum();
+        if total > 0.0 {
+            self.frame_times.len() as f32 * 1000.0 / total
+        } else {
+            0.0
+        }
+    }
+}
+
+fn fps(frame_ms: f32) -> f32 {
+    if frame_ms > 0.0 { 1000.0 / frame_ms } else { 0.0 }
+}
+
+fn frame_color(frame_ms: f32) -> u32 {
+    if frame_ms <= 1000.0 / 60.0 + 0.5 {
+        GOOD_COLOR
+    } else if frame_ms <= 1000.0 / 30.0 + 0.5 {
+        SLOW_COLOR
+    } else {
+        BAD_COLOR
+    }
+}
+
+// Panel en la esquina superior derecha: FPS, tiempo por etapa y gráfica de frame time.
+// Con `quality` (ajuste `adaptive_quality`) agrega el escalón del presupuesto de frame.
+// Devuelve la y donde termina, para apilar otro panel debajo.
+pub fn render_overlay(framebuffer: &mut Framebuffer, stats: &FrameStats, quality: Option<Quality>, panel_y: usize) -> usize {
+    let panel_width = HISTORY_LEN + 10;
+    let line = GLYPH_HEIGHT + 3;
+    let quality_rows = usize::from(quality.is_some());
+    let panel_height = (2 + quality_rows) * line + 2 * line + GRAPH_HEIGHT + 16;
+    let panel_x = framebuffer.width.saturating_sub(panel_width + OVERLAY_MARGIN);
+    framebuffer.blend_rect(panel_x, panel_y, panel_width, panel_height, PANEL_COLOR, 0.6);
+
+    let left = (panel_x + 5) as i32;
+    let right = (panel_x + panel_width - 5) as i32;
+    let mut y = (panel_y + 5) as i32;
+
+    let last_ms = stats.frame_times.back().copied().unwrap_or(0.0);
+    draw_text(framebuffer, left, y, "FPS", LABEL_COLOR);
+    let fps_text = format!("{:.0} ({:.1} ms)", stats.current_fps(), last_ms);
+    draw_text_styled(framebuffer, right, y, &fps_text, &TextStyle::new(frame_color(last_ms)).with_align(Align::Right));
+    y += line as i32;
+    draw_text(framebuffer, left, y, "AVG", LABEL_COLOR);
+    let average = format!("{:.0}", stats.average_fps());
+    draw_text_styled(framebuffer, right, y, &average, &TextStyle::new(TEXT_COLOR).with_align(Align::Right));
+    y += line as i32;
+    if let Some(quality) = quality {
+        draw_text(framebuffer, left, y, "QUAL", LABEL_COLOR);
+        let color = if quality == Quality::Full { GOOD_COLOR } else { SLOW_COLOR };
+        draw_text_styled(framebuffer, right, y, quality.name(), &TextStyle::new(color).with_align(Align::Right));
+        y += line as i32;
+    }
+
+    // Dos columnas de etapas: VTX/RAST arriba, FRAG/PRES abajo
+    for (i, stage) in Stage::ALL.iter().enumerate() {
+        let column_x = if i % 2 == 0 { left } else { left + (panel_width / 2) as i32 };
+        let row_y = y + (i / 2 * line) as i32;
+        draw_text(framebuffer, column_x, row_y, stage.label(), LABEL_COLOR);
+        let value = format!("{:>5.1}", stats.last_stage_ms[*stage as usize]);
+        draw_text(framebuffer, column_x + 32, row_y, &value, TEXT_COLOR);
+    }
+    y += (2 * line) as i32 + 4;
+
+    // Gráfica: una columna por frame, de más viejo (izquierda) a más reciente (derecha)
+    let graph_x = panel_x + 5;
+    let graph_y = y as usize;
+    let graph_bottom = graph_y + GRAPH_HEIGHT;
+    let to_height = |ms: f32| ((ms / GRAPH_MAX_MS).min(1.0) * GRAPH_HEIGHT as f32) as usize;
+    for target_ms in [1000.0 / 60.0, 1000.0 / 30.0] {
+        let guide_y = graph_bottom - to_height(target_ms);
+        framebuffer.blend_rect(graph_x, guide_y, HISTORY_LEN, 1, TEXT_COLOR, 0.3);
+    }
+    let offset = HISTORY_LEN - stats.frame_times.len();
+    for (i, &ms) in stats.frame_times.iter().enumerate() {
+        let bar = to_height(ms).max(1);
+        framebuffer.fill_rect(graph_x + offset + i, graph_bottom - bar, 1, bar, frame_color(ms));
+    }
+
+    panel_y + panel_height + OVERLAY_MARGIN
+}
+
+// Conteos del pipeline del último frame, en la misma columna que el panel de FPS. Devuelve la y
+// donde termina, como `render_overlay`.
+pub fn render_pipeline_overlay(framebuffer: &mut Framebuffer, stats: &FrameStats, panel_y: usize) -> usize {
+    let counters = stats.last_counters();
+    let rows = [
+        ("BODIES", format!("{} / {}", counters.bodies_drawn, counters.bodies_drawn + counters.bodies_culled)),
+        ("TRIS", format!("{}", counters.triangles_submitted)),
+        ("RASTER", format!("{}", counters.triangles_rasterized)),
+        ("INVALID", format!("{}", counters.triangles_invalid)),
+        ("DEGEN", format!("{}", counters.triangles_degenerate)),
+        ("Z-REJ", format!("{}", counters.depth_rejected)),
+        ("WRITTEN", format!("{}", counters.fragments_shaded)),
+    ];
+
+    let panel_width = HISTORY_LEN + 10;
+    let line = GLYPH_HEIGHT + 3;
+    let panel_height = rows.len() * line + 8;
+    let panel_x = framebuffer.width.saturating_sub(panel_width + OVERLAY_MARGIN);
+    framebuffer.blend_rect(panel_x, panel_y, panel_width, panel_height, PANEL_COLOR, 0.6);
+
+    let left = (panel_x + 5) as i32;
+    let right = (panel_x + panel_width - 5) as i32;
+    let value_style = TextStyle::new(TEXT_COLOR).with_align(Align::Right);
+    for (i, (label, value)) in rows.iter().enumerate() {
+        let y = (panel_y + 5 + i * line) as i32;
+        draw_text(framebuffer, left, y, label, LABEL_COLOR);
+        draw_text_styled(framebuffer, right, y, value, &value_style);
+    }
+    panel_y + panel_height + OVERLAY_MARGIN
+}
@@ -1,10 +1,14 @@
 // framebuffer.rs
 
+use std::path::Path;
+
+#[derive(Clone)]
 pub struct Framebuffer {
    pub width: usize,
    pub height: usize,
    pub buffer: Vec<u32>,
    pub zbuffer: Vec<f32>,
+   pub interleave: Option<(usize, usize)>,  // Reescalado temporal: solo se rasteriza este pixel de cada bloque de 2x2
    background_color: u32,
    current_color: u32,
 }
@@ -16,6 +20,7 @@ impl Framebuffer {
          height,
          buffer: vec![0; width * height],
          zbuffer: vec![f32::INFINITY; width * height],
+         interleave: None,
          background_color: 0x000000,
          current_color: 0xFFFFFF,
       }
@@ -30,12 +35,168 @@ impl Framebuffer {
       }
    }
 
-   pub fn point(&mut self, x: usize, y: usize, depth: f32) {
+   // Devuelve si el pixel pasó la prueba de profundidad y se escribió
+   pub fn point(&mut self, x: usize, y: usize, depth: f32) -> bool {
       if x < self.width && y < self.height {
          let index = y * self.width + x;
          if self.zbuffer[index] > depth {
                self.buffer[index] = self.current_color;
                self.zbuffer[index] = depth;
+               return true;
+         }
+      }
+      false
+   }
+
+   // Si el rasterizador dibuja en (x, y) en este frame (siempre, salvo con el reescalado temporal)
+   pub fn is_sampled(&self, x: usize, y: usize) -> bool {
+      self.interleave.is_none_or(|(phase_x, phase_y)| x % 2 == phase_x && y % 2 == phase_y)
+   }
+
+   // Profundidad en (x, y) para pruebas de oclusión antes de reconstruir la imagen: con el
+   // reescalado temporal se lee el pixel rasterizado de su bloque
+   pub fn sampled_depth(&self, x: usize, y: usize) -> f32 {
+      let snap = |value: usize, phase: usize, limit: usize| {
+         let snapped = value - value % 2 + phase;
+         if snapped < limit { snapped } else { snapped.saturating_sub(2) }
+      };
+      let (x, y) = match self.interleave {
+         Some((phase_x, phase_y)) => (snap(x, phase_x, self.width), snap(y, phase_y, self.height)),
+         None => (x, y),
+      };
+      self.zbuffer[y * self.width + x]
+   }
+
+   // Solo la prueba de profundidad: actualiza el z-buffer sin tocar el color (el color lo pone
+   // después `ShadeQueue::resolve`)
+   pub fn depth_test(&mut self, x: usize, y: usize, depth: f32) -> bool {
+      if x < self.width && y < self.height {
+         let index = y * self.width + x;
+         if self.zbuffer[index] > depth {
+            self.zbuffer[index] = depth;
+            return true;
+         }
+      }
+      false
+   }
+
+   // Escribe un pixel ignorando el z-buffer (para HUD y overlays 2D)
+   pub fn set_pixel(&mut self, x: usize, y: usize, color: u32) {
+      if x < self.width && y < self.height {
+         self.buffer[y * self.width + x] = color;
+      }
+   }
+
+   // Igual que set_pixel pero acepta coordenadas negativas (se descartan)
+   pub fn set_pixel_i32(&mut self, x: i32, y: i32, color: u32) {
+      if x >= 0 && y >= 0 {
+         self.set_pixel(x as usize, y as usize, color);
+      }
+   }
+
+   // Mezcla un color sobre el pixel existente con la opacidad dada (0.0 a 1.0)
+   pub fn blend_pixel(&mut self, x: usize, y: usize, color: u32, alpha: f32) {
+      if x < self.width && y < self.height {
+         let index = y * self.width + x;
+         let alpha = alpha.clamp(0.0, 1.0);
+         let dst = self.buffer[index];
+         let mix = |shift: u32| {
+            let a = ((dst >> shift) & 0xFF) as f32;
+            let b = ((color >> shift) & 0xFF) as f32;
+            ((a * (1.0 - alpha) + b * alpha) as u32) << shift
+         };
+         self.buffer[index] = mix(16) | mix(8) | mix(0);
+      }
+   }
+
+   pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: u32) {
+      for py in y..(y + height).min(self.height) {
+         for px in x..(x + width).min(self.width) {
+            self.buffer[py * self.width + px] = color;
+         }
+      }
+   }
+
+   pub fn blend_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: u32, alpha: f32) {
+      for py in y..(y + height).min(self.height) {
+         for px in x..(x + width).min(self.width) {
+            self.blend_pixel(px, py, color, alpha);
+         }
+      }
+   }
+
+   pub fn draw_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: u32) {
+      if width == 0 || height == 0 {
+         return;
+      }
+      for px in x..x + width {
+         self.set_pixel(px, y, color);
+         self.set_pixel(px, y + height - 1, color);
+      }
+      for py in y..y + height {
+         self.set_pixel(x, py, color);
+         self.set_pixel(x + width - 1, py, color);
+      }
+   }
+
+   // Línea 2D (Bresenham) mezclada sobre la imagen, recortada a la pantalla
+   pub fn blend_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: u32, alpha: f32) {
+      bresenham((x0, y0), (x1, y1), self.screen_rect(), |x, y, _| {
+         self.blend_pixel(x as usize, y as usize, color, alpha);
+      });
+   }
+
+   // Igual que blend_line pero recortada a un rectángulo (x, y, ancho, alto), para paneles del HUD
+   pub fn blend_line_in_rect(&mut self, rect: (i32, i32, i32, i32), from: (i32, i32), to: (i32, i32), color: u32, alpha: f32) {
+      let (left, top, width, height) = rect;
+      let (_, _, right, bottom) = self.screen_rect();
+      let clip = (left.max(0), top.max(0), left.saturating_add(width).min(right), top.saturating_add(height).min(bottom));
+      bresenham(from, to, clip, |x, y, _| {
+         self.blend_pixel(x as usize, y as usize, color, alpha);
+      });
+   }
+
+   // Línea 2D opaca sin prueba de profundidad (HUD, marcadores)
+   pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: u32) {
+      bresenham((x0, y0), (x1, y1), self.screen_rect(), |x, y, _| {
+         self.set_pixel(x as usize, y as usize, color);
+      });
+   }
+
+   // Línea con profundidad interpolada y prueba contra el z-buffer (líneas en el mundo 3D)
+   pub fn draw_depth_line(&mut self, from: (i32, i32, f32), to: (i32, i32, f32), color: u32) {
+      let previous_color = self.current_color;
+      self.current_color = color;
+      bresenham((from.0, from.1), (to.0, to.1), self.screen_rect(), |x, y, t| {
+         let depth = from.2 + (to.2 - from.2) * t;
+         self.point(x as usize, y as usize, depth);
+      });
+      self.current_color = previous_color;
+   }
+
+   // (izquierda, arriba, derecha, abajo) de la pantalla, con derecha y abajo excluidos
+   fn screen_rect(&self) -> (i32, i32, i32, i32) {
+      (0, 0, self.width.min(i32::MAX as usize) as i32, self.height.min(i32::MAX as usize) as i32)
+   }
+
+   // Circunferencia (algoritmo del punto medio)
+   pub fn draw_circle(&mut self, cx: i32, cy: i32, radius: i32, color: u32) {
+      let mut x = radius;
+      let mut y = 0;
+      let mut err = 1 - radius;
+      while x >= y {
+         for (px, py) in [(x, y), (y, x), (-y, x), (-x, y), (-x, -y), (-y, -x), (y, -x), (x, -y)] {
+            let (sx, sy) = (cx + px, cy + py);
+            if sx >= 0 && sy >= 0 {
+               self.set_pixel(sx as usize, sy as usize, color);
+            }
+         }
+         y += 1;
+         if err < 0 {
+            err += 2 * y + 1;
+         } else {
+            x -= 1;
+            err += 2 * (y - x) + 1;
          }
       }
    }
@@ -47,4 +208,97 @@ impl Framebuffer {
    pub fn set_current_color(&mut self, color: u32) {
       self.current_color = color;
    }
+
+   // Guarda la imagen como PNG (0RGB -> RGB)
+   pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), image::ImageError> {
+      let pixels = self.buffer.iter().flat_map(|&pixel| [(pixel >> 16) as u8, (pixel >> 8) as u8, pixel as u8]).collect();
+      let image = image::RgbImage::from_raw(self.width as u32, self.height as u32, pixels)
+         .expect("the buffer has width * height pixels");
+      image.save(path)
+   }
+
+   // Lee un PNG guardado con save_png (RGB -> 0RGB); el z-buffer queda vacío
+   pub fn load_png<P: AsRef<Path>>(path: P) -> Result<Self, image::ImageError> {
+      let image = image::open(path)?.to_rgb8();
+      let mut framebuffer = Framebuffer::new(image.width() as usize, image.height() as usize);
+      for (pixel, rgb) in framebuffer.buffer.iter_mut().zip(image.pixels()) {
+         *pixel = (rgb[0] as u32) << 16 | (rgb[1] as u32) << 8 | rgb[2] as u32;
+      }
+      Ok(framebuffer)
+   }
+}
+
+// Extremo de una línea recortada: (x, y, t en la línea original)
+type ClippedEnd = (i32, i32, f64);
+
+// Recorta el segmento al rectángulo (izquierda, arriba, derecha, abajo), con derecha y abajo
+// excluidos (Liang-Barsky en f64, así los extremos saturados de una proyección lejana no
+// desbordan). Devuelve los extremos recortados y su t en el segmento original.
+fn clip_line(from: (i32, i32), to: (i32, i32), clip: (i32, i32, i32, i32)) -> Option<(ClippedEnd, ClippedEnd)> {
+   let (left, top, right, bottom) = clip;
+   if right <= left || bottom <= top {
+      return None;
+   }
+   let (x0, y0) = (from.0 as f64, from.1 as f64);
+   let (dx, dy) = (to.0 as f64 - x0, to.1 as f64 - y0);
+   let (mut t0, mut t1) = (0.0f64, 1.0f64);
+   let edges = [
+      (-dx, x0 - left as f64),
+      (dx, (right - 1) as f64 - x0),
+      (-dy, y0 - top as f64),
+      (dy, (bottom - 1) as f64 - y0),
+   ];
+   for (p, q) in edges {
+      if p == 0.0 {
+         if q < 0.0 {
+            return None;
+         }
+      } else if p < 0.0 {
+         t0 = t0.max(q / p);
+      } else {
+         t1 = t1.min(q / p);
+      }
+   }
+   if t0 > t1 {
+      return None;
+   }
+   let point = |t: f64| {
+      let x = (x0 + dx * t).round().clamp(left as f64, (right - 1) as f64) as i32;
+      let y = (y0 + dy * t).round().clamp(top as f64, (bottom - 1) as f64) as i32;
+      (x, y, t)
+   };
+   Some((point(t0), point(t1)))
+}
+
+// Recorre los pixeles de una línea recortada a `clip` (ver clip_line); `plot` recibe (x, y, t)
+// con t en [0, 1] a lo largo de la línea original, y solo pixeles dentro del rectángulo
+fn bresenham(from: (i32, i32), to: (i32, i32), clip: (i32, i32, i32, i32), mut plot: impl FnMut(i32, i32, f32)) {
+   let Some(((x0, y0, t0), (x1, y1, t1))) = clip_line(from, to, clip) else {
+      return;
+   };
+   let dx = (x1 - x0).abs();
+   let dy = -(y1 - y0).abs();
+   let sx = if x0 < x1 { 1 } else { -1 };
+   let sy = if y0 < y1 { 1 } else { -1 };
+   let steps = dx.max(-dy).max(1) as f64;
+   let mut err = dx + dy;
+   let (mut x, mut y) = (x0, y0);
+   let mut step = 0.0;
+
+   loop {
+      plot(x, y, (t0 + (t1 - t0) * step / steps) as f32);
+      if x == x1 && y == y1 {
+         break;
+      }
+      let e2 = 2 * err;
+      if e2 >= dy {
+         err += dy;
+         x += sx;
+      }
+      if e2 <= dx {
+         err += dx;
+         y += sy;
+      }
+      step += 1.0;
+   }
 }
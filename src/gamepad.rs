@@ -0,0 +1,128 @@
+use crate::input::InputState;
+
+// Mandos conectados: cada frame agrega sus botones y sticks a `InputState`, después de la ventana.
+// Sin `--features gamepad`, o si no se pueden leer los mandos, no hace nada.
+pub struct Gamepads {
+    source: Option<pads::Source>,
+}
+
+impl Gamepads {
+    pub fn new() -> Self {
+        Gamepads { source: pads::Source::open() }
+    }
+
+    pub fn poll(&mut self, input: &mut InputState) {
+        if let Some(source) = &mut self.source {
+            source.poll(input);
+        }
+    }
+}
+
+impl Default for Gamepads {
+    fn default() -> Self {
+        Gamepads::new()
+    }
+}
+
+#[cfg(feature = "gamepad")]
+mod pads {
+    use gilrs::{Axis, Button, EventType, Gilrs};
+    use log::{info, warn};
+    use crate::input::{GamepadAxis, GamepadButton, InputEvent, InputState};
+
+    pub struct Source {
+        gilrs: Gilrs,
+    }
+
+    impl Source {
+        pub fn open() -> Option<Self> {
+            match Gilrs::new() {
+                Ok(gilrs) => {
+                    for (_, pad) in gilrs.gamepads() {
+                        info!("Gamepad connected: {}", pad.name());
+                    }
+                    Some(Source { gilrs })
+                }
+                Err(e) => {
+                    warn!("Gamepads disabled: {}", e);
+                    None
+                }
+            }
+        }
+
+        pub fn poll(&mut self, input: &mut InputState) {
+            while let Some(event) = self.gilrs.next_event() {
+                match event.event {
+                    EventType::ButtonPressed(button, _) => {
+                        if let Some(button) = button_from(button) {
+                            input.push(InputEvent::GamepadButton { button, down: true });
+                        }
+                    }
+                    EventType::ButtonReleased(button, _) => {
+                        if let Some(button) = button_from(button) {
+                            input.push(InputEvent::GamepadButton { button, down: false });
+                        }
+                    }
+                    EventType::AxisChanged(axis, value, _) => {
+                        if let Some(axis) = axis_from(axis) {
+                            input.push(InputEvent::GamepadAxis { axis, value });
+                        }
+                    }
+                    EventType::Connected => info!("Gamepad connected: {}", self.gilrs.gamepad(event.id).name()),
+                    EventType::Disconnected => info!("Gamepad disconnected"),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn button_from(button: Button) -> Option<GamepadButton> {
+        Some(match button {
+            Button::South => GamepadButton::South,
+            Button::East => GamepadButton::East,
+            Button::North => GamepadButton::North,
+            Button::West => GamepadButton::West,
+            Button::LeftTrigger => GamepadButton::LeftBumper,
+            Button::RightTrigger => GamepadButton::RightBumper,
+            Button::LeftTrigger2 => GamepadButton::LeftTrigger,
+            Button::RightTrigger2 => GamepadButton::RightTrigger,
+            Button::Select => GamepadButton::Select,
+            Button::Start => GamepadButton::Start,
+            Button::LeftThumb => GamepadButton::LeftThumb,
+            Button::RightThumb => GamepadButton::RightThumb,
+            Button::DPadUp => GamepadButton::DPadUp,
+            Button::DPadDown => GamepadButton::DPadDown,
+            Button::DPadLeft => GamepadButton::DPadLeft,
+            Button::DPadRight => GamepadButton::DPadRight,
+            _ => return None,
+        })
+    }
+
+    fn axis_from(axis: Axis) -> Option<GamepadAxis> {
+        Some(match axis {
+            Axis::LeftStickX => GamepadAxis::LeftStickX,
+            Axis::LeftStickY => GamepadAxis::LeftStickY,
+            Axis::RightStickX => GamepadAxis::RightStickX,
+            Axis::RightStickY => GamepadAxis::RightStickY,
+            _ => return None,
+        })
+    }
+}
+
+// Sin `--features gamepad` no hay mandos: `open` nunca devuelve un lector
+#[cfg(not(feature = "gamepad"))]
+mod pads {
+    use crate::input::InputState;
+
+    pub enum Source {}
+
+    impl Source {
+        pub fn open() -> Option<Self> {
+            None
+        }
+
+        pub fn poll(&mut self, _input: &mut InputState) {
+            match *self {}
+        }
+    }
+}
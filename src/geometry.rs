@@ -0,0 +1,185 @@
+use nalgebra_glm::{Vec2, Vec3};
+use std::f32::consts::PI;
+use crate::vertex::Vertex;
+use crate::normals;
+
+// Malla indexada: vértices únicos y 3 índices por triángulo (lo que consume `render_indexed`)
+#[derive(Debug, Clone, Default)]
+pub struct Mesh {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+}
+
+impl Mesh {
+    // Desde una lista de triángulos sin índices (cada 3 vértices seguidos forman uno)
+    pub fn from_triangle_list(vertices: Vec<Vertex>) -> Self {
+        let indices = (0..vertices.len() as u32).collect();
+        Mesh { vertices, indices }
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    pub fn compute_tangents(&mut self) {
+        normals::compute_tangents(&mut self.vertices, &self.indices);
+    }
+
+    fn push_vertex(&mut self, position: Vec3, normal: Vec3, tex_coords: Vec2) -> u32 {
+        self.vertices.push(Vertex::new(position, normal, tex_coords));
+        (self.vertices.len() - 1) as u32
+    }
+
+    fn push_quad(&mut self, a: u32, b: u32, c: u32, d: u32) {
+        self.indices.extend_from_slice(&[a, b, c, a, c, d]);
+    }
+}
+
+// Todas las primitivas quedan centradas en el origen con el eje principal en +Y y sus
+// triángulos en sentido antihorario vistos desde fuera.
+// Los segmentos de las superficies de revolución repiten la primera columna de vértices
+// al final para que la u vaya de 0 a 1 sin saltar en la costura.
+
+// Cubo de lado `size`, con normales planas (4 vértices propios por cara)
+pub fn cube(size: f32) -> Mesh {
+    let half = size / 2.0;
+    let faces = [
+        (Vec3::x(), Vec3::z(), Vec3::y()),
+        (-Vec3::x(), -Vec3::z(), Vec3::y()),
+        (Vec3::y(), Vec3::x(), Vec3::z()),
+        (-Vec3::y(), Vec3::x(), -Vec3::z()),
+        (Vec3::z(), -Vec3::x(), Vec3::y()),
+        (-Vec3::z(), Vec3::x(), Vec3::y()),
+    ];
+    let mut mesh = Mesh::default();
+
+    for (normal, u_axis, v_axis) in faces {
+        let corner = |u: f32, v: f32| (normal + u_axis * (2.0 * u - 1.0) + v_axis * (2.0 * v - 1.0)) * half;
+        let a = mesh.push_vertex(corner(0.0, 0.0), normal, Vec2::new(0.0, 0.0));
+        let b = mesh.push_vertex(corner(1.0, 0.0), normal, Vec2::new(1.0, 0.0));
+        let c = mesh.push_vertex(corner(1.0, 1.0), normal, Vec2::new(1.0, 1.0));
+        let d = mesh.push_vertex(corner(0.0, 1.0), normal, Vec2::new(0.0, 1.0));
+        mesh.push_quad(a, d, c, b);
+    }
+
+    mesh
+}
+
+// Cilindro con tapas; el lateral tiene normales suaves y las tapas planas
+pub fn cylinder(radius: f32, height: f32, segments: usize) -> Mesh {
+    let segments = segments.max(3);
+    let half = height / 2.0;
+    let mut mesh = Mesh::default();
+
+    let first = mesh.vertices.len() as u32;
+    for i in 0..=segments {
+        let (u, direction) = around(i, segments);
+        mesh.push_vertex(direction * radius - Vec3::y() * half, direction, Vec2::new(u, 0.0));
+        mesh.push_vertex(direction * radius + Vec3::y() * half, direction, Vec2::new(u, 1.0));
+    }
+    for i in 0..segments as u32 {
+        let (bottom, top) = (first + i * 2, first + i * 2 + 1);
+        mesh.push_quad(bottom, top, top + 2, bottom + 2);
+    }
+
+    cap(&mut mesh, radius, half, Vec3::y(), segments);
+    cap(&mut mesh, radius, -half, -Vec3::y(), segments);
+    mesh
+}
+
+// Cono con la punta en +Y y base cerrada. La punta se repite por segmento para que cada
+// cara lateral tenga su propia normal inclinada.
+pub fn cone(radius: f32, height: f32, segments: usize) -> Mesh {
+    let segments = segments.max(3);
+    let half = height / 2.0;
+    let slope = radius / height;  // Cuánto se inclina la normal lateral hacia arriba
+    let mut mesh = Mesh::default();
+
+    let first = mesh.vertices.len() as u32;
+    for i in 0..=segments {
+        let (u, direction) = around(i, segments);
+        let normal = (direction + Vec3::y() * slope).normalize();
+        mesh.push_vertex(direction * radius - Vec3::y() * half, normal, Vec2::new(u, 0.0));
+        mesh.push_vertex(Vec3::y() * half, normal, Vec2::new(u, 1.0));
+    }
+    for i in 0..segments as u32 {
+        let (base, apex) = (first + i * 2, first + i * 2 + 1);
+        mesh.indices.extend_from_slice(&[base, apex, base + 2]);
+    }
+
+    cap(&mut mesh, radius, -half, -Vec3::y(), segments);
+    mesh
+}
+
+// Toro acostado en el plano XZ: `major_radius` hasta el centro del tubo, `minor_radius` del tubo
+pub fn torus(major_radius: f32, minor_radius: f32, major_segments: usize, minor_segments: usize) -> Mesh {
+    let (major_segments, minor_segments) = (major_segments.max(3), minor_segments.max(3));
+    let mut mesh = Mesh::default();
+
+    for i in 0..=major_segments {
+        let (u, direction) = around(i, major_segments);
+        for j in 0..=minor_segments {
+            let v = j as f32 / minor_segments as f32;
+            let angle = v * 2.0 * PI;
+            let normal = direction * angle.cos() + Vec3::y() * angle.sin();
+            let position = direction * major_radius + normal * minor_radius;
+            mesh.push_vertex(position, normal, Vec2::new(u, v));
+        }
+    }
+
+    let stride = (minor_segments + 1) as u32;
+    for i in 0..major_segments as u32 {
+        for j in 0..minor_segments as u32 {
+            let a = i * stride + j;
+            let b = (i + 1) * stride + j;
+            mesh.push_quad(a, a + 1, b + 1, b);
+        }
+    }
+
+    mesh
+}
+
+// Anillo plano en el plano XZ mirando a +Y (anillos planetarios, marcadores en el suelo).
+// u da la vuelta y v va del borde interior (0) al exterior (1).
+pub fn annulus(inner_radius: f32, outer_radius: f32, segments: usize) -> Mesh {
+    let segments = segments.max(3);
+    let mut mesh = Mesh::default();
+
+    for i in 0..=segments {
+        let (u, direction) = around(i, segments);
+        mesh.push_vertex(direction * inner_radius, Vec3::y(), Vec2::new(u, 0.0));
+        mesh.push_vertex(direction * outer_radius, Vec3::y(), Vec2::new(u, 1.0));
+    }
+    for i in 0..segments as u32 {
+        let (inner, outer) = (i * 2, i * 2 + 1);
+        mesh.push_quad(inner, inner + 2, outer + 2, outer);
+    }
+
+    mesh
+}
+
+// Tapa circular a la altura `y` (abanico alrededor de un vértice central)
+fn cap(mesh: &mut Mesh, radius: f32, y: f32, normal: Vec3, segments: usize) {
+    let center = mesh.push_vertex(Vec3::y() * y, normal, Vec2::new(0.5, 0.5));
+    let first = mesh.vertices.len() as u32;
+    for i in 0..=segments {
+        let (_, direction) = around(i, segments);
+        let uv = Vec2::new(0.5 + direction.x * 0.5, 0.5 + direction.z * 0.5);
+        mesh.push_vertex(direction * radius + Vec3::y() * y, normal, uv);
+    }
+    for i in 0..segments as u32 {
+        let (a, b) = (first + i, first + i + 1);
+        if normal.y > 0.0 {
+            mesh.indices.extend_from_slice(&[center, b, a]);
+        } else {
+            mesh.indices.extend_from_slice(&[center, a, b]);
+        }
+    }
+}
+
+// Fracción de vuelta y dirección horizontal del paso `i` de `segments`
+fn around(i: usize, segments: usize) -> (f32, Vec3) {
+    let u = i as f32 / segments as f32;
+    let angle = u * 2.0 * PI;
+    (u, Vec3::new(angle.cos(), 0.0, angle.sin()))
+}
@@ -0,0 +1,178 @@
+use std::path::{Path, PathBuf};
+use crate::camera::CameraMode;
+use crate::engine::{Engine, LaunchOptions};
+use crate::framebuffer::Framebuffer;
+use crate::input::InputState;
+use crate::scene::SceneSource;
+use crate::settings::{Resolution, Settings};
+
+// Carpeta de las imágenes de referencia (relativa a la raíz del crate, donde corre `cargo test`)
+pub const GOLDEN_DIR: &str = "tests/golden";
+
+// Paso fijo y semilla de todas las corridas de referencia
+const FRAME_TIME: f32 = 1.0 / 60.0;
+const SEED: u64 = 7;
+
+// Distancia "redmean" máxima posible entre dos colores RGB de 8 bits (negro contra blanco)
+const MAX_REDMEAN_DISTANCE: f32 = 764.83;
+
+// Cuánto puede cambiar una imagen antes de considerarse distinta a su referencia
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub pixel: f32,      // Distancia de color (0..1) a partir de la que un pixel cuenta como distinto
+    pub fraction: f32,   // Fracción de pixeles distintos que se acepta
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance { pixel: 0.05, fraction: 0.001 }
+    }
+}
+
+// Resultado de comparar un frame con su referencia, con una imagen de las diferencias: la
+// referencia atenuada en gris y los pixeles distintos en rojo
+pub struct Comparison {
+    pub differing: usize,
+    pub total: usize,
+    pub max_distance: f32,
+    pub diff: Framebuffer,
+}
+
+impl Comparison {
+    pub fn differing_fraction(&self) -> f32 {
+        self.differing as f32 / self.total.max(1) as f32
+    }
+
+    pub fn passes(&self, tolerance: &Tolerance) -> bool {
+        self.differing_fraction() <= tolerance.fraction
+    }
+}
+
+// Compara pixel a pixel con una distancia de color ponderada como la percibe el ojo, así los
+// cambios de redondeo no cuentan pero un cuerpo movido o un shader distinto sí
+pub fn compare(actual: &Framebuffer, reference: &Framebuffer, tolerance: &Tolerance) -> Result<Comparison, String> {
+    if (actual.width, actual.height) != (reference.width, reference.height) {
+        return Err(format!(
+            "size {}x{} does not match the reference's {}x{}",
+            actual.width, actual.height, reference.width, reference.height,
+        ));
+    }
+    let mut diff = Framebuffer::new(reference.width, reference.height);
+    let mut differing = 0;
+    let mut max_distance: f32 = 0.0;
+    for (index, (&a, &b)) in actual.buffer.iter().zip(&reference.buffer).enumerate() {
+        let distance = color_distance(a, b);
+        max_distance = max_distance.max(distance);
+        diff.buffer[index] = if distance > tolerance.pixel {
+            differing += 1;
+            0xFF0000
+        } else {
+            let luma = (((b >> 16) & 0xFF) * 3 + ((b >> 8) & 0xFF) * 6 + (b & 0xFF)) / 40;
+            luma << 16 | luma << 8 | luma
+        };
+    }
+    Ok(Comparison { differing, total: actual.buffer.len(), max_distance, diff })
+}
+
+// Distancia "redmean" normalizada a 0..1
+fn color_distance(a: u32, b: u32) -> f32 {
+    let channel = |color: u32, shift: u32| ((color >> shift) & 0xFF) as f32;
+    let red_mean = (channel(a, 16) + channel(b, 16)) * 0.5;
+    let (dr, dg, db) = (channel(a, 16) - channel(b, 16), channel(a, 8) - channel(b, 8), channel(a, 0) - channel(b, 0));
+    let squared = (2.0 + red_mean / 256.0) * dr * dr + 4.0 * dg * dg + (2.0 + (255.0 - red_mean) / 256.0) * db * db;
+    squared.sqrt() / MAX_REDMEAN_DISTANCE
+}
+
+// Qué pasó con un caso al revisarlo
+pub enum GoldenOutcome {
+    Created,  // No había referencia y se guardó el frame como tal
+    Updated,
+    Matched(Comparison),
+    Mismatch(Comparison),  // Junto a la referencia quedan `<caso>.actual.png` y `<caso>.diff.png`
+}
+
+// Una imagen de referencia: escena, cámara, tamaño y cuántos frames se simulan antes de guardarla
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoldenCase {
+    pub name: &'static str,
+    pub scene: SceneSource,
+    pub camera_mode: CameraMode,
+    pub frames: u32,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl GoldenCase {
+    fn new(name: &'static str, camera_mode: CameraMode, frames: u32) -> Self {
+        GoldenCase { name, scene: SceneSource::SolarSystem, camera_mode, frames, width: 320, height: 240 }
+    }
+
+    // Los casos de `tests/golden`: el sistema solar desde cada cámara, con HUD y etiquetas
+    pub fn defaults() -> Vec<GoldenCase> {
+        vec![
+            GoldenCase::new("orbital", CameraMode::Orbital, 30),
+            GoldenCase::new("chase", CameraMode::Chase, 90),
+            GoldenCase::new("first_person", CameraMode::FirstPerson, 60),
+        ]
+    }
+
+    // Siempre la misma imagen: ajustes por defecto (sin leer settings.toml) a resolución completa,
+    // semilla fija, sin sonido ni entrada y pasos de 1/60 s
+    pub fn render(&self, asset_root: Option<&Path>) -> Result<Framebuffer, String> {
+        let settings = Settings { resolution: Resolution::Full, ..Settings::default() };
+        let mut launch = LaunchOptions::default()
+            .with_scene(self.scene.clone())
+            .with_seed(SEED)
+            .with_camera_mode(self.camera_mode)
+            .with_audio(false);
+        if let Some(root) = asset_root {
+            launch = launch.with_asset_root(root.to_path_buf());
+        }
+        let mut engine = Engine::launch(self.width, self.height, settings, launch).map_err(|e| e.to_string())?;
+        let input = InputState::new();
+        for _ in 0..self.frames.max(1) {
+            engine.frame(&input, FRAME_TIME);
+        }
+        Ok(engine.renderer.framebuffer.clone())
+    }
+
+    pub fn reference_path(&self, dir: &Path) -> PathBuf {
+        dir.join(format!("{}.png", self.name))
+    }
+
+    // Dibuja el caso y lo compara con su referencia en `dir`; con `update` (o si todavía no
+    // existe) la referencia se reemplaza por el frame nuevo
+    pub fn check(&self, dir: &Path, update: bool, tolerance: &Tolerance, asset_root: Option<&Path>) -> Result<GoldenOutcome, String> {
+        let frame = self.render(asset_root)?;
+        let reference_path = self.reference_path(dir);
+        let actual_path = dir.join(format!("{}.actual.png", self.name));
+        let diff_path = dir.join(format!("{}.diff.png", self.name));
+        let save = |image: &Framebuffer, path: &Path| image.save_png(path).map_err(|e| format!("{}: {}", path.display(), e));
+
+        let existed = reference_path.exists();
+        if update || !existed {
+            std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+            save(&frame, &reference_path)?;
+            remove_stale(&[&actual_path, &diff_path]);
+            return Ok(if existed { GoldenOutcome::Updated } else { GoldenOutcome::Created });
+        }
+
+        let reference = Framebuffer::load_png(&reference_path).map_err(|e| format!("{}: {}", reference_path.display(), e))?;
+        let comparison = compare(&frame, &reference, tolerance).map_err(|e| format!("{}: {}", self.name, e))?;
+        if comparison.passes(tolerance) {
+            remove_stale(&[&actual_path, &diff_path]);
+            Ok(GoldenOutcome::Matched(comparison))
+        } else {
+            save(&frame, &actual_path)?;
+            save(&comparison.diff, &diff_path)?;
+            Ok(GoldenOutcome::Mismatch(comparison))
+        }
+    }
+}
+
+// Los `.actual.png` / `.diff.png` de una revisión anterior que falló
+fn remove_stale(paths: &[&Path]) {
+    for path in paths {
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,83 @@
+use nalgebra_glm::Vec3;
+use crate::scene::Scene;
+use crate::vehicle::drag_factor;
+
+// Parámetro gravitacional por unidad de volumen: GM = GRAVITY_STRENGTH * radio³ (todos los
+// cuerpos con la misma densidad). Con esto la atracción en la superficie del gigante gaseoso
+// se siente con el empuje normal de la nave.
+pub const GRAVITY_STRENGTH: f32 = 2.5;
+// Segundos hacia adelante que muestra la predicción y paso de integración
+pub const PREDICTION_SECONDS: f32 = 8.0;
+const PREDICTION_STEP: f32 = 1.0 / 30.0;
+// Cada cuántos pasos se guarda un punto de la polilínea
+const STEPS_PER_POINT: usize = 2;
+// Cada cuánto se vuelve a calcular (unos pocos frames)
+const UPDATE_INTERVAL: f32 = 0.1;
+
+// Aceleración sobre un punto por la atracción de las esferas (centro, radio). Dentro de un
+// cuerpo la distancia se toma como su radio, para que no se dispare cerca del centro.
+pub fn acceleration(position: Vec3, bodies: &[(Vec3, f32)]) -> Vec3 {
+    bodies.iter().fold(Vec3::zeros(), |total, &(center, radius)| {
+        let offset = center - position;
+        let distance = offset.magnitude().max(radius).max(f32::EPSILON);
+        total + offset / distance * (GRAVITY_STRENGTH * radius.powi(3) / (distance * distance))
+    })
+}
+
+// Trayectoria futura de la nave con la gravedad de los cuerpos (que siguen moviéndose en sus
+// órbitas), el roce y sin empuje: termina antes si el centro de la nave entra en un cuerpo.
+// Parte de la posición actual con `velocity` (la de la nave, o la que tendría tras un encendido);
+// sin `gravity` es la línea recta frenada por el roce. Con `frame` cada punto se lleva al
+// sistema de ese cuerpo (como si se quedara donde está ahora).
+pub fn predict(scene: &Scene, velocity: Vec3, time_scale: f32, orbits_running: bool, frame: Option<usize>, gravity: bool) -> Vec<Vec3> {
+    let (mut position, mut velocity) = (scene.spaceship.position, velocity);
+    let mut seconds = scene.clock.seconds;
+    let time_step = if orbits_running { (PREDICTION_STEP * time_scale) as f64 } else { 0.0 };
+
+    let mut points = vec![position];
+    let steps = (PREDICTION_SECONDS / PREDICTION_STEP) as usize;
+    for step in 1..=steps {
+        seconds += time_step;
+        let bodies = scene.body_spheres_at(seconds);
+        if gravity {
+            velocity += acceleration(position, &bodies) * PREDICTION_STEP;
+        }
+        position += velocity * PREDICTION_STEP;
+        velocity *= drag_factor(PREDICTION_STEP);
+
+        let hit = bodies.iter().any(|&(center, radius)| (position - center).magnitude() < radius);
+        if hit || step % STEPS_PER_POINT == 0 {
+            let offset = frame.map_or_else(Vec3::zeros, |index| scene.bodies[index].position - bodies[index].0);
+            points.push(position + offset);
+        }
+        if hit {
+            break;
+        }
+    }
+    points
+}
+
+// La última predicción, recalculada cada `UPDATE_INTERVAL` segundos
+#[derive(Default)]
+pub struct TrajectoryPredictor {
+    points: Vec<Vec3>,
+    since_update: f32,
+}
+
+impl TrajectoryPredictor {
+    pub fn update(&mut self, scene: &Scene, delta_time: f32, time_scale: f32, orbits_running: bool, frame: Option<usize>) {
+        self.since_update += delta_time;
+        if self.points.is_empty() || self.since_update >= UPDATE_INTERVAL {
+            self.points = predict(scene, scene.spaceship.velocity, time_scale, orbits_running, frame, true);
+            self.since_update = 0.0;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    pub fn points(&self) -> &[Vec3] {
+        &self.points
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(not(target_arch = "wasm32"))]
+use log::warn;
+#[cfg(not(target_arch = "wasm32"))]
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::mpsc::{self, Receiver};
+
+// Vigila una carpeta de assets y acumula las rutas modificadas hasta que se consultan.
+// Los eventos llegan desde el hilo de `notify`; el bucle principal los recoge una vez por frame.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct AssetWatcher {
+    _watcher: RecommendedWatcher,  // Mientras viva sigue enviando eventos
+    events: Receiver<notify::Result<Event>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl AssetWatcher {
+    pub fn new(root: &Path) -> notify::Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        // Ruta absoluta para que los eventos se puedan comparar con las rutas de los assets
+        let root = root.canonicalize().map_err(notify::Error::io)?;
+        watcher.watch(&root, RecursiveMode::Recursive)?;
+        Ok(AssetWatcher { _watcher: watcher, events })
+    }
+
+    // Rutas (canónicas) creadas o modificadas desde la última llamada, sin repetir.
+    // Un guardado suele generar varios eventos seguidos; aquí se funden en uno.
+    pub fn changed_paths(&self) -> HashSet<PathBuf> {
+        let mut changed = HashSet::new();
+        for event in self.events.try_iter() {
+            match event {
+                Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+                    changed.extend(event.paths.iter().map(|path| canonical(path)));
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Asset watcher error: {}", e),
+            }
+        }
+        changed
+    }
+}
+
+// En el navegador no hay carpeta que vigilar: los assets vienen embebidos en el binario
+#[cfg(target_arch = "wasm32")]
+pub struct AssetWatcher;
+
+#[cfg(target_arch = "wasm32")]
+impl AssetWatcher {
+    pub fn new(_root: &Path) -> Result<Self, &'static str> {
+        Err("there is no file system to watch in the browser")
+    }
+
+    pub fn changed_paths(&self) -> HashSet<PathBuf> {
+        HashSet::new()
+    }
+}
+
+// Canónica si el archivo existe (puede no existir un instante si el editor guarda renombrando)
+pub fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
@@ -0,0 +1,390 @@
+use nalgebra_glm::Vec3;
+use crate::framebuffer::Framebuffer;
+use crate::i18n::{tr, tr_with};
+use crate::missions::MissionStatus;
+use crate::palette::Palette;
+use crate::vehicle::WarpDrive;
+use crate::collision::CollisionMode;
+use crate::proximity::{ProximityWarning, WarningLevel};
+use crate::play_area::{BoundaryMode, PlayArea};
+use crate::sim_clock::{format_date, format_duration, seconds_to_days};
+use crate::text::{draw_text, draw_text_styled, measure_text, text_width, Align, TextStyle, GLYPH_HEIGHT};
+
+// Distancia (superficie a superficie) a partir de la cual la barra de proximidad empieza a llenarse
+pub const PROXIMITY_WARNING_DISTANCE: f32 = 2.0;
+
+const PANEL_COLOR: u32 = 0x000000;
+const PANEL_ALPHA: f32 = 0.55;
+const TEXT_COLOR: u32 = 0xC8D2DC;
+const LABEL_COLOR: u32 = 0x7FA0C0;
+const OBJECTIVE_COLOR: u32 = 0xFFE070;
+const LINE_SPACING: usize = GLYPH_HEIGHT + 3;
+
+// Datos que el HUD necesita de la simulación en cada frame
+pub struct HudData<'a> {
+    pub ship_model: &'a str,
+    pub velocity: Vec3,               // Respecto al sistema de referencia
+    pub frame_name: Option<&'a str>,  // Cuerpo del sistema local (None = inercial)
+    pub position: Vec3,
+    pub focus_name: &'a str,
+    pub focus_distance: f32,
+    pub nearest_name: &'a str,
+    pub nearest_clearance: f32,
+    pub time_scale: f32,
+    pub sim_days: f64,
+    pub orbits_paused: bool,
+    pub collision_flash: f32,
+    pub warp_drive: WarpDrive,
+    pub collision_mode: CollisionMode,
+    pub ammo: u32,
+    pub max_ammo: u32,
+    pub weapon_cooldown: f32,
+    pub proximity_warning: ProximityWarning,
+    pub play_area: PlayArea,
+    pub palette: &'a Palette,  // Colores de avisos y de la barra de proximidad
+}
+
+impl HudData<'_> {
+    // 0.0 = lejos de todo, 1.0 = tocando la superficie del cuerpo más cercano
+    pub fn proximity(&self) -> f32 {
+        (1.0 - self.nearest_clearance / PROXIMITY_WARNING_DISTANCE).clamp(0.0, 1.0)
+    }
+}
+
+// Ficha del cuerpo enfocado por la cámara
+pub struct BodyCard<'a> {
+    pub name: &'a str,
+    pub kind: &'a str,
+    pub description: &'a str,
+    pub radius: f32,             // Unidades de la escena
+    pub radius_km: Option<f32>,
+    pub parent_name: &'a str,    // Alrededor de qué orbita
+    pub orbit_radius: f32,
+    pub sphere_of_influence: Option<f32>,
+    pub orbital_period: Option<f32>,
+    pub rotation_period: Option<f32>,
+    pub camera_distance: f32,
+    pub ship_distance: f32,
+}
+
+// Devuelve la coordenada y donde termina el panel, para apilar otros debajo
+pub fn render_hud(framebuffer: &mut Framebuffer, data: &HudData) -> usize {
+    let speed = data.velocity.magnitude();
+    let sim_speed = if data.orbits_paused {
+        tr("hud.paused").to_string()
+    } else {
+        format!("x{:.2}", data.time_scale)
+    };
+
+    let lines = [
+        (tr("hud.ship"), format!("{} [{}]", data.ship_model, data.collision_mode.name())),
+        (tr("hud.speed"), match data.frame_name {
+            Some(body) => tr_with("hud.speed_in_frame", &[("speed", &format!("{:.2}", speed)), ("body", &body)]),
+            None => format!("{:.2} u/s", speed),
+        }),
+        (tr("hud.velocity"), format!("{:+.1} {:+.1} {:+.1}", data.velocity.x, data.velocity.y, data.velocity.z)),
+        (tr("hud.position"), format!("{:+.1} {:+.1} {:+.1}", data.position.x, data.position.y, data.position.z)),
+        (tr("hud.focus"), format!("{} {:.2} u", data.focus_name, data.focus_distance)),
+        (tr("hud.nearest"), format!("{} {:.2} u", data.nearest_name, data.nearest_clearance.max(0.0))),
+        (tr("hud.sim"), sim_speed),
+        (tr("hud.date"), format_date(data.sim_days)),
+        (tr("hud.warp"), warp_status(data.warp_drive)),
+        (tr("hud.time_to_impact"), match data.proximity_warning.time_to_impact {
+            Some(time) if data.proximity_warning.level != WarningLevel::Clear => {
+                format!("{:.1}s {}", time, data.proximity_warning.body_name)
+            }
+            _ => "-".to_string(),
+        }),
+        (tr("hud.ammo"), format!("{}/{}{}", data.ammo, data.max_ammo, if data.weapon_cooldown > 0.0 { " *" } else { "" })),
+    ];
+
+    let margin = 6;
+    let panel_width = 200;
+    let panel_height = lines.len() * LINE_SPACING + LINE_SPACING + 10;
+    framebuffer.blend_rect(margin, margin, panel_width, panel_height, PANEL_COLOR, PANEL_ALPHA);
+
+    let text_x = (margin + 5) as i32;
+    let mut y = margin + 5;
+    for (label, value) in &lines {
+        draw_text(framebuffer, text_x, y as i32, label, LABEL_COLOR);
+        draw_text(framebuffer, text_x + 48, y as i32, value, TEXT_COLOR);
+        y += LINE_SPACING;
+    }
+
+    // Barra de proximidad de colisión: verde -> amarillo -> rojo (o los de la paleta elegida)
+    let proximity = data.proximity();
+    let bar_x = margin + 5 + 48;
+    let bar_width = panel_width - 58;
+    let bar_height = GLYPH_HEIGHT;
+    draw_text(framebuffer, text_x, y as i32, tr("hud.proximity"), LABEL_COLOR);
+    framebuffer.draw_rect(bar_x, y, bar_width, bar_height, LABEL_COLOR);
+    let filled = ((bar_width - 2) as f32 * proximity) as usize;
+    framebuffer.fill_rect(bar_x + 1, y + 1, filled, bar_height - 2, proximity_color(data.palette, proximity));
+
+    // Aviso al acercarse al límite de la zona de juego
+    if let Some(remaining) = data.play_area.leaving_warning(data.position) {
+        let detail = if remaining > 0.0 {
+            tr_with("hud.boundary_in", &[("distance", &format!("{:.1}", remaining))])
+        } else {
+            match data.play_area.mode {
+                BoundaryMode::SoftPush => tr("hud.returning").to_string(),
+                BoundaryMode::WrapAround => tr("hud.wrapping").to_string(),
+            }
+        };
+        let center = (framebuffer.width / 2) as i32;
+        draw_text_styled(framebuffer, center, 24, tr("hud.leaving_system"), &TextStyle::new(data.palette.caution).with_align(Align::Center));
+        draw_text_styled(framebuffer, center, 24 + LINE_SPACING as i32, &detail, &TextStyle::new(TEXT_COLOR).with_align(Align::Center));
+    }
+
+    // Destello rojo en el borde de la pantalla tras una colisión
+    if data.collision_flash > 0.0 {
+        let alpha = data.collision_flash.clamp(0.0, 1.0) * 0.6;
+        let thickness = 6;
+        let (width, height) = (framebuffer.width, framebuffer.height);
+        let color = data.palette.danger;
+        framebuffer.blend_rect(0, 0, width, thickness, color, alpha);
+        framebuffer.blend_rect(0, height.saturating_sub(thickness), width, thickness, color, alpha);
+        framebuffer.blend_rect(0, 0, thickness, height, color, alpha);
+        framebuffer.blend_rect(width.saturating_sub(thickness), 0, thickness, height, color, alpha);
+
+        let style = TextStyle::new(color).with_scale(2).with_align(Align::Center).with_shadow(0x000000);
+        draw_text_styled(framebuffer, (width / 2) as i32, (height / 2) as i32 - 48, tr("hud.collision"), &style);
+    }
+
+    margin + panel_height
+}
+
+// Se dibuja a la izquierda, empezando en `panel_y` (debajo del HUD si está visible)
+pub fn render_body_card(framebuffer: &mut Framebuffer, card: &BodyCard, panel_y: usize, palette: &Palette) {
+    let period = |period: Option<f32>| match period {
+        Some(seconds) => format_duration(seconds_to_days(seconds as f64)),
+        None => "-".to_string(),
+    };
+    let radius = match card.radius_km {
+        Some(km) => format!("{:.2} u ({:.0} km)", card.radius, km),
+        None => format!("{:.2} u", card.radius),
+    };
+    let orbit = if card.orbital_period.is_some() {
+        tr_with("hud.orbit_around", &[("radius", &format!("{:.2}", card.orbit_radius)), ("parent", &card.parent_name)])
+    } else {
+        "-".to_string()
+    };
+
+    let mut lines = vec![
+        (tr("hud.card_type"), card.kind.to_string()),
+        (tr("hud.card_radius"), radius),
+        (tr("hud.card_orbit"), orbit),
+    ];
+    // Solo los cuerpos con padre tienen una esfera de influencia con borde
+    if let Some(radius) = card.sphere_of_influence {
+        lines.push((tr("hud.card_soi"), format!("{:.2} u", radius)));
+    }
+    lines.extend([
+        (tr("hud.card_year"), period(card.orbital_period)),
+        (tr("hud.card_day"), period(card.rotation_period)),
+        (tr("hud.card_camera"), format!("{:.2} u", card.camera_distance)),
+        (tr("hud.card_ship"), format!("{:.2} u", card.ship_distance)),
+    ]);
+
+    let margin = 6;
+    let panel_width = 200;
+    let header_lines = if card.description.is_empty() { 1 } else { 2 };
+    let panel_height = (lines.len() + header_lines) * LINE_SPACING + 10;
+    if panel_y + panel_height + margin > framebuffer.height {
+        return;
+    }
+    framebuffer.blend_rect(margin, panel_y, panel_width, panel_height, PANEL_COLOR, PANEL_ALPHA);
+
+    let text_x = (margin + 5) as i32;
+    let mut y = panel_y + 5;
+    draw_text(framebuffer, text_x, y as i32, card.name, palette.caution);
+    y += LINE_SPACING;
+    if !card.description.is_empty() {
+        draw_text(framebuffer, text_x, y as i32, card.description, LABEL_COLOR);
+        y += LINE_SPACING;
+    }
+    for (label, value) in &lines {
+        draw_text(framebuffer, text_x, y as i32, label, LABEL_COLOR);
+        draw_text(framebuffer, text_x + 48, y as i32, value, TEXT_COLOR);
+        y += LINE_SPACING;
+    }
+}
+
+// Alerta de colisión inminente: borde y mensaje parpadeando, más rápido y rojo al acercarse.
+// Se dibuja aunque el HUD esté oculto.
+pub fn render_proximity_alert(framebuffer: &mut Framebuffer, warning: &ProximityWarning, time: f32, palette: &Palette) {
+    if !warning.is_flash_on(time) {
+        return;
+    }
+
+    let (color, message, alpha) = match warning.level {
+        WarningLevel::Clear => return,
+        WarningLevel::Caution => (palette.caution, tr("hud.caution"), 0.2),
+        WarningLevel::Warning => (palette.danger, tr("hud.warning"), 0.35),
+        WarningLevel::Critical => (palette.danger, tr("hud.critical"), 0.5),
+    };
+
+    let (width, height) = (framebuffer.width, framebuffer.height);
+    let thickness = if warning.level == WarningLevel::Critical { 8 } else { 4 };
+    framebuffer.blend_rect(0, 0, width, thickness, color, alpha);
+    framebuffer.blend_rect(0, height.saturating_sub(thickness), width, thickness, color, alpha);
+    framebuffer.blend_rect(0, 0, thickness, height, color, alpha);
+    framebuffer.blend_rect(width.saturating_sub(thickness), 0, thickness, height, color, alpha);
+
+    let detail = match warning.time_to_impact {
+        Some(time) => tr_with("hud.impact_in", &[("body", &warning.body_name), ("time", &format!("{:.1}", time))]),
+        None => String::new(),
+    };
+    let y = height.saturating_sub(48);
+    let x = (width / 2).saturating_sub(text_width(message) / 2);
+    framebuffer.blend_rect(x.saturating_sub(4), y.saturating_sub(4), text_width(message) + 8, 2 * LINE_SPACING + 6, PANEL_COLOR, PANEL_ALPHA);
+    let (x, y) = (x as i32, y as i32);
+    draw_text(framebuffer, x, y, message, color);
+    let detail_style = TextStyle::new(TEXT_COLOR).with_align(Align::Center);
+    draw_text_styled(framebuffer, (width / 2) as i32, y + LINE_SPACING as i32, &detail, &detail_style);
+}
+
+// Objetivo actual del escenario (lo fija su script), centrado arriba
+pub fn render_objective(framebuffer: &mut Framebuffer, objective: &str) -> usize {
+    let width = text_width(objective);
+    let x = (framebuffer.width / 2).saturating_sub(width / 2);
+    let y = 8;
+    framebuffer.blend_rect(x.saturating_sub(4), y - 4, width + 8, LINE_SPACING + 4, PANEL_COLOR, PANEL_ALPHA);
+    draw_text(framebuffer, x as i32, y as i32, objective, OBJECTIVE_COLOR);
+    y + LINE_SPACING
+}
+
+// Misión en curso, centrada arriba (debajo del objetivo del script si hay uno): nombre y
+// objetivos cumplidos, el siguiente pendiente con su avance y lo que queda de tiempo y combustible
+pub fn render_mission(framebuffer: &mut Framebuffer, status: &MissionStatus, y: usize) {
+    let header = if status.waiting {
+        tr_with("hud.mission_next", &[("mission", &status.name)])
+    } else {
+        tr_with("hud.mission", &[("mission", &status.name), ("done", &status.done), ("total", &status.total)])
+    };
+    let objective = format!("> {}", status.objective);
+    let mut limits = Vec::new();
+    if let Some(time) = status.time_left {
+        limits.push(tr_with("hud.mission_time", &[("time", &format!("{:.0}", time.ceil()))]));
+    }
+    if let Some(fuel) = status.fuel_left {
+        limits.push(tr_with("hud.mission_fuel", &[("fuel", &format!("{:.1}", fuel))]));
+    }
+    let limits = limits.join("   ");
+    let lines = if limits.is_empty() { 2 } else { 3 };
+
+    let bar_width = 40;
+    let show_bar = status.progress > 0.0 && status.progress < 1.0;
+    let objective_width = text_width(&objective) + if show_bar { bar_width + 6 } else { 0 };
+    let width = text_width(&header).max(objective_width).max(text_width(&limits));
+    let x = (framebuffer.width / 2).saturating_sub(width / 2);
+    framebuffer.blend_rect(x.saturating_sub(4), y.saturating_sub(4), width + 8, lines * LINE_SPACING + 4, PANEL_COLOR, PANEL_ALPHA);
+
+    draw_text(framebuffer, x as i32, y as i32, &header, LABEL_COLOR);
+    let objective_y = y + LINE_SPACING;
+    draw_text(framebuffer, x as i32, objective_y as i32, &objective, OBJECTIVE_COLOR);
+    if show_bar {
+        let bar_x = x + text_width(&objective) + 6;
+        framebuffer.draw_rect(bar_x, objective_y, bar_width, GLYPH_HEIGHT, LABEL_COLOR);
+        let filled = ((bar_width - 2) as f32 * status.progress) as usize;
+        framebuffer.fill_rect(bar_x + 1, objective_y + 1, filled, GLYPH_HEIGHT - 2, OBJECTIVE_COLOR);
+    }
+    if !limits.is_empty() {
+        draw_text(framebuffer, x as i32, (objective_y + LINE_SPACING) as i32, &limits, TEXT_COLOR);
+    }
+}
+
+fn warp_status(warp_drive: WarpDrive) -> String {
+    match warp_drive {
+        WarpDrive::Idle => tr("hud.warp_ready").to_string(),
+        WarpDrive::Charging { charge } => tr_with("hud.warp_charging", &[("percent", &format!("{:>3.0}", charge * 100.0))]),
+        WarpDrive::Jumping { .. } => tr("hud.warp_jump").to_string(),
+        WarpDrive::Cooldown { remaining } => tr_with("hud.warp_cooldown", &[("time", &format!("{:.1}", remaining))]),
+    }
+}
+
+fn proximity_color(palette: &Palette, proximity: f32) -> u32 {
+    let [far, near, touching] = palette.proximity;
+    if proximity < 0.5 {
+        far
+    } else if proximity < 0.8 {
+        near
+    } else {
+        touching
+    }
+}
+
+// Indicación del flujo de reasignación de teclas, centrada en pantalla
+// `conflict` avisa que la última tecla ya la usa otra acción
+pub fn render_rebind_prompt(framebuffer: &mut Framebuffer, action: &str, current_keys: &str, cancel_keys: &str, conflict: Option<&str>, progress: (usize, usize)) {
+    let title = tr_with("hud.rebind_title", &[("current", &progress.0), ("total", &progress.1)]);
+    let mut body = tr_with("hud.rebind_body", &[("action", &action), ("keys", &current_keys), ("cancel", &cancel_keys)]);
+    if let Some(conflict) = conflict {
+        body = format!("{}\n\n{}", body, conflict);
+    }
+
+    let style = TextStyle::new(TEXT_COLOR);
+    let (text_w, text_h) = measure_text(&format!("{}\n{}", title, body), &style);
+    let width = text_w + 20;
+    let height = text_h + 16;
+    let x = framebuffer.width.saturating_sub(width) / 2;
+    let y = framebuffer.height.saturating_sub(height) / 2;
+
+    framebuffer.blend_rect(x, y, width, height, PANEL_COLOR, 0.8);
+    framebuffer.draw_rect(x, y, width, height, LABEL_COLOR);
+
+    let (text_x, text_y) = ((x + 10) as i32, (y + 8) as i32);
+    draw_text(framebuffer, text_x, text_y, &title, LABEL_COLOR);
+    draw_text_styled(framebuffer, text_x, text_y + style.line_height() as i32, &body, &style);
+}
+
+// Tabla de controles (teclas, descripción) en un panel centrado. Si no cabe en una sola
+// columna a lo alto de la pantalla se reparte en varias.
+pub fn render_help_overlay(framebuffer: &mut Framebuffer, title: &str, footer: &str, entries: &[(String, String)]) {
+    const PADDING: usize = 10;
+    const KEY_GAP: usize = 12;
+    const COLUMN_GAP: usize = 24;
+
+    let line = LINE_SPACING - 1;
+    let header = 2 * line;
+    let available = framebuffer.height.saturating_sub(2 * PADDING + 2 * header + 8);
+    let column_count = entries.len().div_ceil((available / line).max(1)).max(1);
+    let rows = entries.len().div_ceil(column_count).max(1);
+    let columns: Vec<&[(String, String)]> = entries.chunks(rows).collect();
+
+    let column_widths: Vec<(usize, usize)> = columns
+        .iter()
+        .map(|column| {
+            let keys = column.iter().map(|(keys, _)| text_width(keys)).max().unwrap_or(0);
+            let descriptions = column.iter().map(|(_, description)| text_width(description)).max().unwrap_or(0);
+            (keys, descriptions)
+        })
+        .collect();
+    let content_width: usize = column_widths.iter().map(|(keys, descriptions)| keys + KEY_GAP + descriptions).sum::<usize>()
+        + COLUMN_GAP * columns.len().saturating_sub(1);
+    let width = (content_width.max(text_width(title)).max(text_width(footer)) + 2 * PADDING).min(framebuffer.width);
+    let height = (rows.min(entries.len()) * line + 2 * header + 2 * PADDING).min(framebuffer.height);
+    let x = framebuffer.width.saturating_sub(width) / 2;
+    let y = framebuffer.height.saturating_sub(height) / 2;
+
+    framebuffer.blend_rect(x, y, width, height, PANEL_COLOR, 0.85);
+    framebuffer.draw_rect(x, y, width, height, LABEL_COLOR);
+
+    let center_x = (x + width / 2) as i32;
+    let title_style = TextStyle::new(TEXT_COLOR).with_align(Align::Center).with_shadow(0x000000);
+    draw_text_styled(framebuffer, center_x, (y + PADDING) as i32, title, &title_style);
+
+    let mut column_x = x + PADDING;
+    let top = y + PADDING + header;
+    for (column, (key_width, description_width)) in columns.iter().zip(&column_widths) {
+        for (row, (keys, description)) in column.iter().enumerate() {
+            let row_y = (top + row * line) as i32;
+            draw_text(framebuffer, column_x as i32, row_y, keys, LABEL_COLOR);
+            draw_text(framebuffer, (column_x + key_width + KEY_GAP) as i32, row_y, description, TEXT_COLOR);
+        }
+        column_x += key_width + KEY_GAP + description_width + COLUMN_GAP;
+    }
+
+    let footer_style = TextStyle::new(LABEL_COLOR).with_align(Align::Center);
+    draw_text_styled(framebuffer, center_x, (y + height - PADDING - GLYPH_HEIGHT) as i32, footer, &footer_style);
+}
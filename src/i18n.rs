@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use crate::embedded;
+
+// Idioma de los textos en pantalla (HUD, menús, avisos) y de la lista de controles por consola.
+// Los logs siguen en inglés.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "es")]
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Spanish];
+
+    // Código de settings.toml y `--lang`
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+        }
+    }
+
+    // Nombre en el propio idioma, para el menú
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Español",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Language> {
+        Language::ALL.into_iter().find(|language| language.code().eq_ignore_ascii_case(code))
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Language::English => embedded::STRINGS_EN,
+            Language::Spanish => embedded::STRINGS_ES,
+        }
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+// Tablas de textos por idioma (en el orden de `Language::ALL`), cargadas al primer uso
+fn bundles() -> &'static [HashMap<String, String>] {
+    static BUNDLES: OnceLock<Vec<HashMap<String, String>>> = OnceLock::new();
+    BUNDLES.get_or_init(|| Language::ALL.iter().map(|&language| parse_bundle(language)).collect())
+}
+
+// Las secciones del TOML forman el prefijo del ID: `[hud] ship = ...` es "hud.ship"
+fn parse_bundle(language: Language) -> HashMap<String, String> {
+    let mut strings = HashMap::new();
+    match toml::from_str::<toml::Table>(language.source()) {
+        Ok(table) => flatten("", &table, &mut strings),
+        Err(e) => warn!("Invalid {} string table: {}", language.code(), e),
+    }
+    strings
+}
+
+fn flatten(prefix: &str, table: &toml::Table, strings: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let id = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match value {
+            toml::Value::String(text) => {
+                strings.insert(id, text.clone());
+            }
+            toml::Value::Table(table) => flatten(&id, table, strings),
+            _ => warn!("String table entry `{}` is not text", id),
+        }
+    }
+}
+
+pub fn set_language(language: Language) {
+    CURRENT.store(language as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    Language::ALL[CURRENT.load(Ordering::Relaxed) as usize]
+}
+
+// Texto de un mensaje en el idioma actual; si falta se usa el inglés y, si tampoco está, el ID
+pub fn tr(id: &'static str) -> &'static str {
+    let bundles = bundles();
+    [language(), Language::English]
+        .into_iter()
+        .find_map(|language| bundles[language as usize].get(id))
+        .map_or(id, String::as_str)
+}
+
+// Igual que `tr`, reemplazando cada `{nombre}` del texto por su valor
+pub fn tr_with(id: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter().fold(tr(id).to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+// "ON" / "OFF" de los toasts y el menú
+pub fn on_off(on: bool) -> &'static str {
+    if on { tr("common.on") } else { tr("common.off") }
+}
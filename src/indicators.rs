@@ -0,0 +1,122 @@
+use nalgebra_glm::Vec3;
+use crate::camera::ScreenProjection;
+use crate::framebuffer::Framebuffer;
+use crate::palette::Palette;
+
+const MARKER_RADIUS: i32 = 7;
+// Por debajo de esta velocidad la dirección no es significativa y no se dibuja nada
+const MIN_SPEED: f32 = 0.05;
+// Segundos de movimiento que representa la línea de velocidad
+const VELOCITY_LINE_SECONDS: f32 = 0.5;
+// Distancia a la que se proyectan las direcciones prograde/retrograde
+const MARKER_DISTANCE: f32 = 100.0;
+
+// Trayectoria predicha (puntos en el mundo) como polilínea con profundidad que se apaga hacia
+// el final, así lo próximo se lee más fuerte que lo lejano en el tiempo
+pub fn render_trajectory(framebuffer: &mut Framebuffer, projection: &ScreenProjection, points: &[Vec3], color: u32, dashed: bool) {
+    let segments = points.len().saturating_sub(1);
+    for (index, pair) in points.windows(2).enumerate() {
+        // Punteada: dos tramos sí y dos no
+        if dashed && index % 4 >= 2 {
+            continue;
+        }
+        let Some((from, to)) = projection.project_segment(pair[0], pair[1]) else {
+            continue;
+        };
+        let fade = 1.0 - index as f32 / segments as f32;
+        let channel = |shift: u32| ((((color >> shift) & 0xFF) as f32 * fade) as u32) << shift;
+        let bias = 1.001;
+        framebuffer.draw_depth_line(
+            (from.x as i32, from.y as i32, from.z * bias),
+            (to.x as i32, to.y as i32, to.z * bias),
+            channel(16) | channel(8) | channel(0),
+        );
+    }
+}
+
+// Línea de velocidad en el mundo (con profundidad) y marcadores prograde/retrograde en pantalla
+pub fn render_velocity_indicators(
+    framebuffer: &mut Framebuffer,
+    projection: &ScreenProjection,
+    ship_position: Vec3,
+    velocity: Vec3,
+    palette: &Palette,
+) {
+    let speed = velocity.magnitude();
+    if speed < MIN_SPEED {
+        return;
+    }
+    let direction = velocity / speed;
+
+    let tip = ship_position + velocity * VELOCITY_LINE_SECONDS;
+    if let Some((from, to)) = projection.project_segment(ship_position, tip) {
+        // Un poco hacia la cámara para que la línea no pelee con el casco de la nave (la
+        // profundidad es -near/distancia: la escala acerca un 0.1% a cualquier distancia)
+        let bias = 1.001;
+        framebuffer.draw_depth_line(
+            (from.x as i32, from.y as i32, from.z * bias),
+            (to.x as i32, to.y as i32, to.z * bias),
+            palette.velocity,
+        );
+    }
+
+    if let Some(prograde) = projection.project(ship_position + direction * MARKER_DISTANCE) {
+        draw_prograde_marker(framebuffer, prograde.x as i32, prograde.y as i32, palette.prograde);
+    }
+    if let Some(retrograde) = projection.project(ship_position - direction * MARKER_DISTANCE) {
+        draw_retrograde_marker(framebuffer, retrograde.x as i32, retrograde.y as i32, palette.retrograde);
+    }
+}
+
+// Círculo con tres aletas (arriba, izquierda y derecha), como en KSP
+fn draw_prograde_marker(framebuffer: &mut Framebuffer, x: i32, y: i32, color: u32) {
+    let r = MARKER_RADIUS;
+    framebuffer.draw_circle(x, y, r, color);
+    framebuffer.set_pixel_i32(x, y, color);
+    framebuffer.draw_line(x - r - 5, y, x - r, y, color);
+    framebuffer.draw_line(x + r, y, x + r + 5, y, color);
+    framebuffer.draw_line(x, y - r - 5, x, y - r, color);
+}
+
+// Círculo con una cruz interior
+fn draw_retrograde_marker(framebuffer: &mut Framebuffer, x: i32, y: i32, color: u32) {
+    let r = MARKER_RADIUS;
+    let d = (r as f32 * 0.7) as i32;
+    framebuffer.draw_circle(x, y, r, color);
+    framebuffer.draw_line(x - d, y - d, x + d, y + d, color);
+    framebuffer.draw_line(x - d, y + d, x + d, y - d, color);
+    framebuffer.draw_line(x, y + r, x, y + r + 5, color);
+}
+
+const RETICLE_COLOR: u32 = 0xC8FFC8;
+const RETICLE_GAP: i32 = 4;
+const RETICLE_ARM: i32 = 7;
+const LEAD_SIZE: i32 = 6;
+
+// Mira en el centro de la pantalla (modo nave, mientras hay disparos en vuelo)
+pub fn render_reticle(framebuffer: &mut Framebuffer) {
+    let (x, y) = ((framebuffer.width / 2) as i32, (framebuffer.height / 2) as i32);
+    let (gap, arm) = (RETICLE_GAP, RETICLE_GAP + RETICLE_ARM);
+    framebuffer.blend_line(x - arm, y, x - gap, y, RETICLE_COLOR, 0.8);
+    framebuffer.blend_line(x + gap, y, x + arm, y, RETICLE_COLOR, 0.8);
+    framebuffer.blend_line(x, y - arm, x, y - gap, RETICLE_COLOR, 0.8);
+    framebuffer.blend_line(x, y + gap, x, y + arm, RETICLE_COLOR, 0.8);
+    framebuffer.set_pixel_i32(x, y, RETICLE_COLOR);
+}
+
+// Rombo donde hay que apuntar para acertarle al blanco, unido al blanco con una línea tenue
+pub fn render_lead_indicator(framebuffer: &mut Framebuffer, projection: &ScreenProjection, target: Vec3, lead: Vec3, palette: &Palette) {
+    let (Some(target), Some(lead)) = (projection.project(target), projection.project(lead)) else {
+        return;
+    };
+    let (tx, ty) = (target.x as i32, target.y as i32);
+    let (x, y) = (lead.x as i32, lead.y as i32);
+    framebuffer.blend_line(tx, ty, x, y, palette.lead, 0.35);
+
+    let color = palette.lead;
+    let s = LEAD_SIZE;
+    for (from, to) in [((x, y - s), (x + s, y)), ((x + s, y), (x, y + s)), ((x, y + s), (x - s, y)), ((x - s, y), (x, y - s))] {
+        framebuffer.draw_line(from.0, from.1, to.0, to.1, color);
+    }
+    framebuffer.set_pixel_i32(x, y, color);
+}
@@ -0,0 +1,194 @@
+use minifb::Key;
+use std::collections::{HashMap, HashSet};
+
+// Los sticks no vuelven exactamente a cero: por debajo de esto se toman como soltados
+const STICK_DEAD_ZONE: f32 = 0.15;
+
+// Botones de un mando por su posición, con la disposición de Xbox: South = A, East = B, los
+// bumpers son LB/RB y los gatillos LT/RT
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+// Ejes de los sticks, de -1.0 a 1.0 (Y positivo hacia arriba)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+}
+
+// Lo que pasó con la entrada desde el frame anterior, en el orden en que llegó
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputEvent {
+    KeyPressed(Key),
+    KeyRepeated(Key),  // Repetición automática de una tecla mantenida (menús)
+    KeyReleased(Key),
+    MouseMoved { x: f32, y: f32 },
+    MouseButton { button: usize, down: bool },  // 0 izquierdo, 1 medio, 2 derecho
+    Scroll { x: f32, y: f32 },
+    GamepadButton { button: GamepadButton, down: bool },
+    GamepadAxis { axis: GamepadAxis, value: f32 },
+}
+
+// Estado de la entrada de un frame: teclas mantenidas más la cola de eventos del frame.
+// El engine solo ve esto (vía `KeyMap` para traducir teclas a acciones); quién lo llena
+// -un `Backend`, el mando (`gamepad.rs`), una prueba o una repetición grabada- le da igual.
+// Las teclas se nombran con `minifb::Key` en todo el simulador; los demás backends traducen las suyas.
+#[derive(Debug, Clone, Default)]
+pub struct InputState {
+    held: HashSet<Key>,
+    events: Vec<InputEvent>,
+    pub mouse_position: Option<(f32, f32)>,
+    pub mouse_down: [bool; 3],
+    pad_held: HashSet<GamepadButton>,
+    axes: HashMap<GamepadAxis, f32>,
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        InputState::default()
+    }
+
+    // Empieza un frame nuevo: se vacía la cola, las teclas mantenidas siguen
+    pub fn begin_frame(&mut self) {
+        self.events.clear();
+    }
+
+    pub fn push(&mut self, event: InputEvent) {
+        match event {
+            InputEvent::KeyPressed(key) => {
+                self.held.insert(key);
+            }
+            InputEvent::KeyReleased(key) => {
+                self.held.remove(&key);
+            }
+            InputEvent::MouseMoved { x, y } => self.mouse_position = Some((x, y)),
+            InputEvent::MouseButton { button, down } => {
+                if let Some(state) = self.mouse_down.get_mut(button) {
+                    *state = down;
+                }
+            }
+            InputEvent::GamepadButton { button, down: true } => {
+                self.pad_held.insert(button);
+            }
+            InputEvent::GamepadButton { button, down: false } => {
+                self.pad_held.remove(&button);
+            }
+            InputEvent::GamepadAxis { axis, value } => {
+                self.axes.insert(axis, value.clamp(-1.0, 1.0));
+            }
+            InputEvent::KeyRepeated(_) | InputEvent::Scroll { .. } => {}
+        }
+        self.events.push(event);
+    }
+
+    // Reemplaza las teclas mantenidas por las que informa la ventana
+    pub fn set_held(&mut self, keys: impl IntoIterator<Item = Key>) {
+        self.held = keys.into_iter().collect();
+    }
+
+    pub fn events(&self) -> &[InputEvent] {
+        &self.events
+    }
+
+    // Nada pasó en este frame y no hay teclas, botones ni sticks mantenidos
+    pub fn is_quiet(&self) -> bool {
+        self.events.is_empty()
+            && self.held.is_empty()
+            && !self.mouse_down.contains(&true)
+            && self.pad_held.is_empty()
+            && self.axes.keys().all(|&axis| self.axis(axis) == 0.0)
+    }
+
+    // La tecla está mantenida
+    pub fn is_held(&self, key: Key) -> bool {
+        self.held.contains(&key)
+    }
+
+    // La tecla se presionó en este frame (sin repetición)
+    pub fn was_pressed(&self, key: Key) -> bool {
+        self.events.contains(&InputEvent::KeyPressed(key))
+    }
+
+    pub fn was_released(&self, key: Key) -> bool {
+        self.events.contains(&InputEvent::KeyReleased(key))
+    }
+
+    pub fn is_pad_held(&self, button: GamepadButton) -> bool {
+        self.pad_held.contains(&button)
+    }
+
+    pub fn was_pad_pressed(&self, button: GamepadButton) -> bool {
+        self.events.contains(&InputEvent::GamepadButton { button, down: true })
+    }
+
+    pub fn was_pad_released(&self, button: GamepadButton) -> bool {
+        self.events.contains(&InputEvent::GamepadButton { button, down: false })
+    }
+
+    // Posición del stick, 0.0 dentro de la zona muerta (y reescalada para no saltar al salir)
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        let value = self.axes.get(&axis).copied().unwrap_or(0.0);
+        if value.abs() <= STICK_DEAD_ZONE {
+            0.0
+        } else {
+            value.signum() * (value.abs() - STICK_DEAD_ZONE) / (1.0 - STICK_DEAD_ZONE)
+        }
+    }
+
+    // El botón del mouse se presionó en este frame
+    pub fn was_clicked(&self, button: usize) -> bool {
+        self.events.contains(&InputEvent::MouseButton { button, down: true })
+    }
+
+    // Teclas presionadas en este frame, en orden (para asignarlas al reasignar teclas)
+    pub fn pressed_keys(&self) -> Vec<Key> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                InputEvent::KeyPressed(key) => Some(*key),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Como `pressed_keys` pero con la repetición automática (navegar menús manteniendo la tecla)
+    pub fn typed_keys(&self) -> Vec<Key> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                InputEvent::KeyPressed(key) | InputEvent::KeyRepeated(key) => Some(*key),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Desplazamiento vertical de la rueda acumulado en este frame
+    pub fn scroll(&self) -> f32 {
+        self.events
+            .iter()
+            .map(|event| match event {
+                InputEvent::Scroll { y, .. } => *y,
+                _ => 0.0,
+            })
+            .sum()
+    }
+}
@@ -0,0 +1,177 @@
+use std::f32::consts::PI;
+use nalgebra_glm::Vec3;
+use crate::camera::Camera;
+use crate::scene::Scene;
+
+// Paso fijo de la simulación con el ajuste `fixed_tick`
+pub const TICK_RATE: f32 = 30.0;
+pub const TICK: f32 = 1.0 / TICK_RATE;
+// Tope de pasos por frame: tras un tirón (cargar una escena, arrastrar la ventana) se descarta el
+// tiempo que sobra en vez de encadenar decenas de pasos
+const MAX_TICKS_PER_FRAME: u32 = 4;
+
+// Posición y rotación de lo que se mueve en la simulación: cuerpos, nave y la cámara si sigue a la nave
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    bodies: Vec<(Vec3, Vec3)>,
+    ship: (Vec3, Vec3),
+    camera: Option<(Vec3, Vec3)>,
+}
+
+impl Snapshot {
+    pub fn capture(scene: &Scene, camera: &Camera) -> Self {
+        Snapshot {
+            bodies: scene.bodies.iter().map(|body| (body.position, body.rotation)).collect(),
+            ship: (scene.spaceship.position, scene.spaceship.rotation),
+            // La cámara libre se mueve con la entrada de cada frame, no con la simulación
+            camera: camera.mode.follows_ship().then_some((camera.eye, camera.center)),
+        }
+    }
+
+    // `alpha` = 0.0 es `self`, 1.0 es `next`
+    pub fn blend(&self, next: &Snapshot, alpha: f32) -> Snapshot {
+        let transform = |(position, rotation): (Vec3, Vec3), (next_position, next_rotation): (Vec3, Vec3)| {
+            (position.lerp(&next_position, alpha), lerp_angles(rotation, next_rotation, alpha))
+        };
+        Snapshot {
+            bodies: self.bodies.iter().zip(&next.bodies).map(|(&a, &b)| transform(a, b)).collect(),
+            ship: transform(self.ship, next.ship),
+            camera: self.camera.zip(next.camera).map(|((eye, center), (next_eye, next_center))| {
+                (eye.lerp(&next_eye, alpha), center.lerp(&next_center, alpha))
+            }),
+        }
+    }
+
+    pub fn apply(&self, scene: &mut Scene, camera: &mut Camera) {
+        for (body, &(position, rotation)) in scene.bodies.iter_mut().zip(&self.bodies) {
+            body.position = position;
+            body.rotation = rotation;
+        }
+        (scene.spaceship.position, scene.spaceship.rotation) = self.ship;
+        if let Some((eye, center)) = self.camera {
+            camera.eye = eye;
+            camera.center = center;
+            camera.has_changed = true;
+        }
+    }
+}
+
+// Simulación a paso fijo con interpolación al presentar: la simulación avanza en pasos de
+// `TICK` y cada frame se dibuja mezclando los dos últimos estados según el tiempo que sobró, así
+// la ventana puede presentar a 60+ FPS con movimiento suave aunque la simulación vaya a 30 Hz.
+// El dibujo va un paso por detrás de la simulación.
+#[derive(Debug, Default)]
+pub struct FixedStep {
+    accumulator: f32,
+    previous: Option<Snapshot>,  // Estado antes del último paso
+}
+
+impl FixedStep {
+    // Suma el tiempo del frame y devuelve cuántos pasos de `TICK` hay que simular
+    pub fn advance(&mut self, delta_time: f32) -> u32 {
+        self.accumulator += delta_time;
+        let ticks = (self.accumulator / TICK) as u32;
+        self.accumulator -= ticks as f32 * TICK;
+        if ticks > MAX_TICKS_PER_FRAME {
+            self.accumulator = 0.0;
+        }
+        ticks.min(MAX_TICKS_PER_FRAME)
+    }
+
+    // Se llama justo antes de cada paso
+    pub fn begin_tick(&mut self, scene: &Scene, camera: &Camera) {
+        self.previous = Some(Snapshot::capture(scene, camera));
+    }
+
+    // Fracción del paso siguiente ya transcurrida (0.0..1.0)
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / TICK).clamp(0.0, 1.0)
+    }
+
+    // Deja en la escena el estado interpolado para dibujar y devuelve el real, que hay que
+    // volver a aplicar después de dibujar (None si todavía no hubo un paso)
+    pub fn present(&self, scene: &mut Scene, camera: &mut Camera) -> Option<Snapshot> {
+        let previous = self.previous.as_ref()?;
+        let current = Snapshot::capture(scene, camera);
+        // Una escena recargada (o un cambio de cámara) no se mezcla con el estado anterior
+        if previous.bodies.len() != current.bodies.len() || previous.camera.is_some() != current.camera.is_some() {
+            return None;
+        }
+        previous.blend(&current, self.alpha()).apply(scene, camera);
+        Some(current)
+    }
+
+    pub fn reset(&mut self) {
+        *self = FixedStep::default();
+    }
+}
+
+// Interpola cada ángulo por el camino corto (la nave guarda su rumbo en 0..2π)
+fn lerp_angles(from: Vec3, to: Vec3, alpha: f32) -> Vec3 {
+    from.zip_map(&to, |a, b| {
+        let delta = (b - a + PI).rem_euclid(2.0 * PI) - PI;
+        a + delta * alpha
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(position: Vec3, rotation: Vec3) -> Snapshot {
+        Snapshot { bodies: vec![(position, rotation)], ship: (position, rotation), camera: Some((position, Vec3::zeros())) }
+    }
+
+    #[test]
+    fn advance_counts_whole_ticks_and_keeps_the_rest() {
+        let mut step = FixedStep::default();
+        assert_eq!(step.advance(TICK * 0.5), 0);
+        assert!((step.alpha() - 0.5).abs() < 1e-4);
+        assert_eq!(step.advance(TICK * 1.75), 2);
+        assert!((step.alpha() - 0.25).abs() < 1e-4);
+    }
+
+    #[test]
+    fn long_frame_is_capped_and_drops_the_backlog() {
+        let mut step = FixedStep::default();
+        assert_eq!(step.advance(TICK * 10.5), MAX_TICKS_PER_FRAME);
+        assert_eq!(step.alpha(), 0.0);
+        assert_eq!(step.advance(TICK * 0.5), 0);
+    }
+
+    #[test]
+    fn reset_forgets_the_accumulated_time() {
+        let mut step = FixedStep::default();
+        step.advance(TICK * 0.9);
+        step.reset();
+        assert_eq!(step.alpha(), 0.0);
+    }
+
+    #[test]
+    fn blend_mixes_positions_and_camera() {
+        let from = snapshot(Vec3::zeros(), Vec3::zeros());
+        let to = snapshot(Vec3::new(4.0, 0.0, -2.0), Vec3::new(0.0, 1.0, 0.0));
+        let blended = from.blend(&to, 0.25);
+        assert_eq!(blended.ship.0, Vec3::new(1.0, 0.0, -0.5));
+        assert!((blended.ship.1.y - 0.25).abs() < 1e-6);
+        assert_eq!(blended.bodies, vec![blended.ship]);
+        assert_eq!(blended.camera, Some((Vec3::new(1.0, 0.0, -0.5), Vec3::zeros())));
+        assert_eq!(from.blend(&to, 1.0).ship.0, to.ship.0);
+    }
+
+    #[test]
+    fn angles_take_the_short_way_across_the_wrap() {
+        // De 350° a 10°: 20° hacia adelante, no 340° hacia atrás
+        let from = Vec3::new(0.0, 350f32.to_radians(), 0.0);
+        let to = Vec3::new(0.0, 10f32.to_radians(), 0.0);
+        let half = lerp_angles(from, to, 0.5);
+        assert!((half.y - 360f32.to_radians()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn free_camera_is_not_blended() {
+        let mut to = snapshot(Vec3::new(1.0, 0.0, 0.0), Vec3::zeros());
+        to.camera = None;
+        assert_eq!(snapshot(Vec3::zeros(), Vec3::zeros()).blend(&to, 0.5).camera, None);
+    }
+}
@@ -0,0 +1,697 @@
+use minifb::Key;
+use std::collections::HashMap;
+use std::path::Path;
+use log::{info, warn};
+use crate::i18n::{tr, tr_with};
+use crate::input::{GamepadButton, InputState};
+use crate::storage;
+
+pub const KEYBINDINGS_FILE: &str = "keybindings.cfg";
+
+// Cantidad de cuerpos con tecla de warp directa (1-4)
+pub const WARP_TARGETS: usize = 4;
+
+// Acciones del simulador; las teclas se asignan a acciones, nunca se consultan directamente
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    OrbitLeft,
+    OrbitRight,
+    OrbitUp,
+    OrbitDown,
+    ZoomIn,
+    ZoomOut,
+    MoveUp,
+    MoveDown,
+    ToggleChaseCamera,
+    WarpTo(usize),
+    WarpToShip,
+    FocusNext,
+    FocusPrevious,
+    SearchBodies,
+    ToggleOrbitAnimation,
+    ToggleOrbitLines,
+    SpeedUp,
+    SlowDown,
+    TimeJumpForward,
+    TimeJumpBackward,
+    ToggleHud,
+    ToggleHelp,
+    TogglePerfOverlay,
+    TogglePipelineStats,
+    ToggleVelocityIndicators,
+    ToggleBodyLabels,
+    ToggleRuler,
+    ToggleEventLog,
+    ToggleReferenceFrame,
+    ToggleOrbitEditor,
+    EditNextParameter,
+    EditIncrease,
+    EditDecrease,
+    ExportScene,
+    ToggleManeuver,
+    ExecuteBurn,
+    RotateLeft,
+    RotateRight,
+    ThrustForward,
+    Fire,
+    ChargeWarpDrive,
+    CycleCollisionShape,
+    CycleShipModel,
+    CycleBoundaryMode,
+    ReloadAssets,
+    ToggleSmoothShading,
+    CycleSphereMesh,
+    ToggleMute,
+    ExposureUp,
+    ExposureDown,
+    BrightnessUp,
+    BrightnessDown,
+    ContrastUp,
+    ContrastDown,
+    ResetPicture,
+    Screenshot,
+    SaveClip,
+    RebindKeys,
+    PauseMenu,
+}
+
+impl Action {
+    // Todas las acciones en el orden en que se muestran y se reasignan
+    pub fn all() -> Vec<Action> {
+        let mut actions = vec![
+            Action::OrbitLeft,
+            Action::OrbitRight,
+            Action::OrbitUp,
+            Action::OrbitDown,
+            Action::ZoomIn,
+            Action::ZoomOut,
+            Action::MoveUp,
+            Action::MoveDown,
+            Action::ToggleChaseCamera,
+        ];
+        actions.extend((0..WARP_TARGETS).map(Action::WarpTo));
+        actions.extend([
+            Action::WarpToShip,
+            Action::FocusNext,
+            Action::FocusPrevious,
+            Action::SearchBodies,
+            Action::ToggleOrbitAnimation,
+            Action::ToggleOrbitLines,
+            Action::SpeedUp,
+            Action::SlowDown,
+            Action::TimeJumpForward,
+            Action::TimeJumpBackward,
+            Action::ToggleHud,
+            Action::ToggleHelp,
+            Action::TogglePerfOverlay,
+            Action::TogglePipelineStats,
+            Action::ToggleVelocityIndicators,
+            Action::ToggleBodyLabels,
+            Action::ToggleRuler,
+            Action::ToggleEventLog,
+            Action::ToggleReferenceFrame,
+            Action::ToggleOrbitEditor,
+            Action::EditNextParameter,
+            Action::EditIncrease,
+            Action::EditDecrease,
+            Action::ExportScene,
+            Action::ToggleManeuver,
+            Action::ExecuteBurn,
+            Action::RotateLeft,
+            Action::RotateRight,
+            Action::ThrustForward,
+            Action::Fire,
+            Action::ChargeWarpDrive,
+            Action::CycleCollisionShape,
+            Action::CycleShipModel,
+            Action::CycleBoundaryMode,
+            Action::ReloadAssets,
+            Action::ToggleSmoothShading,
+            Action::CycleSphereMesh,
+            Action::ToggleMute,
+            Action::ExposureUp,
+            Action::ExposureDown,
+            Action::BrightnessUp,
+            Action::BrightnessDown,
+            Action::ContrastUp,
+            Action::ContrastDown,
+            Action::ResetPicture,
+            Action::Screenshot,
+            Action::SaveClip,
+            Action::RebindKeys,
+            Action::PauseMenu,
+        ]);
+        actions
+    }
+
+    // Nombre usado en el archivo de configuración
+    pub fn name(&self) -> String {
+        match self {
+            Action::OrbitLeft => "orbit_left".into(),
+            Action::OrbitRight => "orbit_right".into(),
+            Action::OrbitUp => "orbit_up".into(),
+            Action::OrbitDown => "orbit_down".into(),
+            Action::ZoomIn => "zoom_in".into(),
+            Action::ZoomOut => "zoom_out".into(),
+            Action::MoveUp => "move_up".into(),
+            Action::MoveDown => "move_down".into(),
+            Action::ToggleChaseCamera => "toggle_chase_camera".into(),
+            Action::WarpTo(index) => format!("warp_to_{}", index + 1),
+            Action::WarpToShip => "warp_to_ship".into(),
+            Action::FocusNext => "focus_next".into(),
+            Action::FocusPrevious => "focus_previous".into(),
+            Action::SearchBodies => "search_bodies".into(),
+            Action::ToggleOrbitAnimation => "toggle_orbit_animation".into(),
+            Action::ToggleOrbitLines => "toggle_orbit_lines".into(),
+            Action::SpeedUp => "speed_up".into(),
+            Action::SlowDown => "slow_down".into(),
+            Action::TimeJumpForward => "time_jump_forward".into(),
+            Action::TimeJumpBackward => "time_jump_backward".into(),
+            Action::ToggleHud => "toggle_hud".into(),
+            Action::ToggleHelp => "toggle_help".into(),
+            Action::TogglePerfOverlay => "toggle_perf_overlay".into(),
+            Action::TogglePipelineStats => "toggle_pipeline_stats".into(),
+            Action::ToggleVelocityIndicators => "toggle_velocity_indicators".into(),
+            Action::ToggleBodyLabels => "toggle_body_labels".into(),
+            Action::ToggleRuler => "toggle_ruler".into(),
+            Action::ToggleEventLog => "toggle_event_log".into(),
+            Action::ToggleReferenceFrame => "toggle_reference_frame".into(),
+            Action::ToggleOrbitEditor => "toggle_orbit_editor".into(),
+            Action::EditNextParameter => "edit_next_parameter".into(),
+            Action::EditIncrease => "edit_increase".into(),
+            Action::EditDecrease => "edit_decrease".into(),
+            Action::ExportScene => "export_scene".into(),
+            Action::ToggleManeuver => "toggle_maneuver".into(),
+            Action::ExecuteBurn => "execute_burn".into(),
+            Action::RotateLeft => "rotate_left".into(),
+            Action::RotateRight => "rotate_right".into(),
+            Action::ThrustForward => "thrust_forward".into(),
+            Action::Fire => "fire".into(),
+            Action::ChargeWarpDrive => "charge_warp_drive".into(),
+            Action::CycleCollisionShape => "cycle_collision_shape".into(),
+            Action::CycleShipModel => "cycle_ship_model".into(),
+            Action::CycleBoundaryMode => "cycle_boundary_mode".into(),
+            Action::ReloadAssets => "reload_assets".into(),
+            Action::ToggleSmoothShading => "toggle_smooth_shading".into(),
+            Action::CycleSphereMesh => "cycle_sphere_mesh".into(),
+            Action::ToggleMute => "toggle_mute".into(),
+            Action::ExposureUp => "exposure_up".into(),
+            Action::ExposureDown => "exposure_down".into(),
+            Action::BrightnessUp => "brightness_up".into(),
+            Action::BrightnessDown => "brightness_down".into(),
+            Action::ContrastUp => "contrast_up".into(),
+            Action::ContrastDown => "contrast_down".into(),
+            Action::ResetPicture => "reset_picture".into(),
+            Action::Screenshot => "screenshot".into(),
+            Action::SaveClip => "save_clip".into(),
+            Action::RebindKeys => "rebind_keys".into(),
+            Action::PauseMenu => "pause_menu".into(),
+        }
+    }
+
+    // Descripción legible (en el idioma de la interfaz) para la ayuda y el flujo de reasignación
+    pub fn description(&self) -> String {
+        match self {
+            Action::OrbitLeft => tr("action.orbit_left").into(),
+            Action::OrbitRight => tr("action.orbit_right").into(),
+            Action::OrbitUp => tr("action.orbit_up").into(),
+            Action::OrbitDown => tr("action.orbit_down").into(),
+            Action::ZoomIn => tr("action.zoom_in").into(),
+            Action::ZoomOut => tr("action.zoom_out").into(),
+            Action::MoveUp => tr("action.move_up").into(),
+            Action::MoveDown => tr("action.move_down").into(),
+            Action::ToggleChaseCamera => tr("action.toggle_chase_camera").into(),
+            Action::WarpTo(index) => tr_with("action.warp_to", &[("number", &(index + 1))]),
+            Action::WarpToShip => tr("action.warp_to_ship").into(),
+            Action::FocusNext => tr("action.focus_next").into(),
+            Action::FocusPrevious => tr("action.focus_previous").into(),
+            Action::SearchBodies => tr("action.search_bodies").into(),
+            Action::ToggleOrbitAnimation => tr("action.toggle_orbit_animation").into(),
+            Action::ToggleOrbitLines => tr("action.toggle_orbit_lines").into(),
+            Action::SpeedUp => tr("action.speed_up").into(),
+            Action::SlowDown => tr("action.slow_down").into(),
+            Action::TimeJumpForward => tr("action.time_jump_forward").into(),
+            Action::TimeJumpBackward => tr("action.time_jump_backward").into(),
+            Action::ToggleHud => tr("action.toggle_hud").into(),
+            Action::ToggleHelp => tr("action.toggle_help").into(),
+            Action::TogglePerfOverlay => tr("action.toggle_perf_overlay").into(),
+            Action::TogglePipelineStats => tr("action.toggle_pipeline_stats").into(),
+            Action::ToggleVelocityIndicators => tr("action.toggle_velocity_indicators").into(),
+            Action::ToggleBodyLabels => tr("action.toggle_body_labels").into(),
+            Action::ToggleRuler => tr("action.toggle_ruler").into(),
+            Action::ToggleEventLog => tr("action.toggle_event_log").into(),
+            Action::ToggleReferenceFrame => tr("action.toggle_reference_frame").into(),
+            Action::ToggleOrbitEditor => tr("action.toggle_orbit_editor").into(),
+            Action::EditNextParameter => tr("action.edit_next_parameter").into(),
+            Action::EditIncrease => tr("action.edit_increase").into(),
+            Action::EditDecrease => tr("action.edit_decrease").into(),
+            Action::ExportScene => tr("action.export_scene").into(),
+            Action::ToggleManeuver => tr("action.toggle_maneuver").into(),
+            Action::ExecuteBurn => tr("action.execute_burn").into(),
+            Action::RotateLeft => tr("action.rotate_left").into(),
+            Action::RotateRight => tr("action.rotate_right").into(),
+            Action::ThrustForward => tr("action.thrust_forward").into(),
+            Action::Fire => tr("action.fire").into(),
+            Action::ChargeWarpDrive => tr("action.charge_warp_drive").into(),
+            Action::CycleCollisionShape => tr("action.cycle_collision_shape").into(),
+            Action::CycleShipModel => tr("action.cycle_ship_model").into(),
+            Action::CycleBoundaryMode => tr("action.cycle_boundary_mode").into(),
+            Action::ReloadAssets => tr("action.reload_assets").into(),
+            Action::ToggleSmoothShading => tr("action.toggle_smooth_shading").into(),
+            Action::CycleSphereMesh => tr("action.cycle_sphere_mesh").into(),
+            Action::ToggleMute => tr("action.toggle_mute").into(),
+            Action::ExposureUp => tr("action.exposure_up").into(),
+            Action::ExposureDown => tr("action.exposure_down").into(),
+            Action::BrightnessUp => tr("action.brightness_up").into(),
+            Action::BrightnessDown => tr("action.brightness_down").into(),
+            Action::ContrastUp => tr("action.contrast_up").into(),
+            Action::ContrastDown => tr("action.contrast_down").into(),
+            Action::ResetPicture => tr("action.reset_picture").into(),
+            Action::Screenshot => tr("action.screenshot").into(),
+            Action::SaveClip => tr("action.save_clip").into(),
+            Action::RebindKeys => tr("action.rebind_keys").into(),
+            Action::PauseMenu => tr("action.pause_menu").into(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        // "exit" es el nombre que tenía antes la tecla que ahora abre el menú de pausa
+        let name = if name == "exit" { "pause_menu" } else { name };
+        Action::all().into_iter().find(|action| action.name() == name)
+    }
+}
+
+pub struct KeyMap {
+    bindings: HashMap<Action, Vec<Key>>,
+    // Botones del mando: disposición fija, keybindings.cfg solo cambia las teclas
+    pad_bindings: HashMap<Action, Vec<GamepadButton>>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let mut bindings = HashMap::new();
+        bindings.insert(Action::OrbitLeft, vec![Key::Left]);
+        bindings.insert(Action::OrbitRight, vec![Key::Right]);
+        bindings.insert(Action::OrbitUp, vec![Key::Up]);
+        bindings.insert(Action::OrbitDown, vec![Key::Down]);
+        bindings.insert(Action::ZoomIn, vec![Key::W]);
+        bindings.insert(Action::ZoomOut, vec![Key::S]);
+        bindings.insert(Action::MoveUp, vec![Key::Q]);
+        bindings.insert(Action::MoveDown, vec![Key::E]);
+        bindings.insert(Action::ToggleChaseCamera, vec![Key::C]);
+        bindings.insert(Action::WarpTo(0), vec![Key::Key1]);
+        bindings.insert(Action::WarpTo(1), vec![Key::Key2]);
+        bindings.insert(Action::WarpTo(2), vec![Key::Key3]);
+        bindings.insert(Action::WarpTo(3), vec![Key::Key4]);
+        bindings.insert(Action::WarpToShip, vec![Key::Key5]);
+        // Shift ya acelera la nave: el foco hacia atrás va en la tecla de al lado de Tab
+        bindings.insert(Action::FocusNext, vec![Key::Tab]);
+        bindings.insert(Action::FocusPrevious, vec![Key::Backquote]);
+        bindings.insert(Action::SearchBodies, vec![Key::F7]);
+        bindings.insert(Action::ToggleOrbitAnimation, vec![Key::Space]);
+        bindings.insert(Action::ToggleOrbitLines, vec![Key::O]);
+        bindings.insert(Action::SpeedUp, vec![Key::Equal]);
+        bindings.insert(Action::SlowDown, vec![Key::Minus]);
+        bindings.insert(Action::TimeJumpForward, vec![Key::RightBracket]);
+        bindings.insert(Action::TimeJumpBackward, vec![Key::LeftBracket]);
+        bindings.insert(Action::ToggleHud, vec![Key::F1]);
+        bindings.insert(Action::ToggleHelp, vec![Key::H]);
+        bindings.insert(Action::TogglePerfOverlay, vec![Key::F3]);
+        bindings.insert(Action::TogglePipelineStats, vec![Key::F4]);
+        bindings.insert(Action::ToggleVelocityIndicators, vec![Key::V]);
+        bindings.insert(Action::ToggleBodyLabels, vec![Key::L]);
+        bindings.insert(Action::ToggleRuler, vec![Key::R]);
+        bindings.insert(Action::ToggleEventLog, vec![Key::T]);
+        bindings.insert(Action::ToggleReferenceFrame, vec![Key::P]);
+        bindings.insert(Action::ToggleOrbitEditor, vec![Key::I]);
+        bindings.insert(Action::EditNextParameter, vec![Key::Tab]);
+        bindings.insert(Action::EditIncrease, vec![Key::PageUp]);
+        bindings.insert(Action::EditDecrease, vec![Key::PageDown]);
+        bindings.insert(Action::ExportScene, vec![Key::F6]);
+        bindings.insert(Action::ToggleManeuver, vec![Key::X]);
+        bindings.insert(Action::ExecuteBurn, vec![Key::Enter]);
+        bindings.insert(Action::RotateLeft, vec![Key::A]);
+        bindings.insert(Action::RotateRight, vec![Key::D]);
+        bindings.insert(Action::ThrustForward, vec![Key::LeftShift, Key::RightShift]);
+        bindings.insert(Action::Fire, vec![Key::F]);
+        bindings.insert(Action::ChargeWarpDrive, vec![Key::J]);
+        bindings.insert(Action::CycleCollisionShape, vec![Key::K]);
+        bindings.insert(Action::CycleShipModel, vec![Key::M]);
+        bindings.insert(Action::CycleBoundaryMode, vec![Key::B]);
+        bindings.insert(Action::ReloadAssets, vec![Key::F5]);
+        bindings.insert(Action::ToggleSmoothShading, vec![Key::N]);
+        bindings.insert(Action::CycleSphereMesh, vec![Key::G]);
+        bindings.insert(Action::ToggleMute, vec![Key::U]);
+        bindings.insert(Action::ExposureUp, vec![Key::Period]);
+        bindings.insert(Action::ExposureDown, vec![Key::Comma]);
+        bindings.insert(Action::BrightnessUp, vec![Key::Apostrophe]);
+        bindings.insert(Action::BrightnessDown, vec![Key::Semicolon]);
+        bindings.insert(Action::ContrastUp, vec![Key::Key0]);
+        bindings.insert(Action::ContrastDown, vec![Key::Key9]);
+        bindings.insert(Action::ResetPicture, vec![Key::Slash]);
+        bindings.insert(Action::Screenshot, vec![Key::F12]);
+        bindings.insert(Action::SaveClip, vec![Key::F11]);
+        bindings.insert(Action::RebindKeys, vec![Key::F2]);
+        bindings.insert(Action::PauseMenu, vec![Key::Escape]);
+
+        // Los sticks van aparte (nave y cámara, ver `Engine::handle_input`)
+        let mut pad_bindings = HashMap::new();
+        pad_bindings.insert(Action::ThrustForward, vec![GamepadButton::RightTrigger]);
+        pad_bindings.insert(Action::Fire, vec![GamepadButton::South]);
+        pad_bindings.insert(Action::ChargeWarpDrive, vec![GamepadButton::West]);
+        pad_bindings.insert(Action::ToggleChaseCamera, vec![GamepadButton::East]);
+        pad_bindings.insert(Action::WarpToShip, vec![GamepadButton::North]);
+        pad_bindings.insert(Action::FocusNext, vec![GamepadButton::RightBumper]);
+        pad_bindings.insert(Action::FocusPrevious, vec![GamepadButton::LeftBumper]);
+        pad_bindings.insert(Action::RotateLeft, vec![GamepadButton::DPadLeft]);
+        pad_bindings.insert(Action::RotateRight, vec![GamepadButton::DPadRight]);
+        pad_bindings.insert(Action::ZoomIn, vec![GamepadButton::DPadUp]);
+        pad_bindings.insert(Action::ZoomOut, vec![GamepadButton::DPadDown]);
+        pad_bindings.insert(Action::ToggleHud, vec![GamepadButton::Select]);
+        KeyMap { bindings, pad_bindings }
+    }
+}
+
+impl KeyMap {
+    // Parte de las teclas por defecto y aplica encima las del archivo (si existe)
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        match storage::read_to_string(path) {
+            Ok(contents) => Self::parse(&contents, path),
+            Err(_) => KeyMap::default(),
+        }
+    }
+
+    // `path` solo se usa en los mensajes
+    fn parse(contents: &str, path: &Path) -> Self {
+        let mut keymap = KeyMap::default();
+        for (line_number, raw_line) in contents.lines().enumerate() {
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let parsed = line.split_once('=').and_then(|(name, keys)| {
+                let action = Action::from_name(name.trim())?;
+                let keys: Option<Vec<Key>> = keys
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|k| !k.is_empty())
+                    .map(key_from_name)
+                    .collect();
+                Some((action, keys?))
+            });
+
+            match parsed {
+                Some((action, keys)) => {
+                    keymap.bindings.insert(action, keys);
+                }
+                None => warn!("{}:{}: ignoring invalid binding `{}`", path.display(), line_number + 1, line),
+            }
+        }
+
+        info!("Key bindings loaded from {}", path.display());
+        keymap
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut contents = String::from("# SpaceTravel key bindings: action = Key[, Key...]\n");
+        for action in Action::all() {
+            contents.push_str(&format!("{} = {}\n", action.name(), self.key_names(action)));
+        }
+        storage::write(path.as_ref(), &contents)
+    }
+
+    pub fn keys(&self, action: Action) -> &[Key] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn key_names(&self, action: Action) -> String {
+        let names: Vec<String> = self.keys(action).iter().map(|key| format!("{:?}", key)).collect();
+        names.join(", ")
+    }
+
+    pub fn bind(&mut self, action: Action, keys: Vec<Key>) {
+        self.bindings.insert(action, keys);
+    }
+
+    // Primera acción (en el orden de `Action::all`) distinta de `except` que usa la tecla
+    pub fn action_for(&self, key: Key, except: Action) -> Option<Action> {
+        Action::all().into_iter().find(|&action| action != except && self.keys(action).contains(&key))
+    }
+
+    pub fn pad_buttons(&self, action: Action) -> &[GamepadButton] {
+        self.pad_bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    // La acción se mantiene (alguna de sus teclas o botones del mando está presionado)
+    pub fn is_down(&self, input: &InputState, action: Action) -> bool {
+        self.keys(action).iter().any(|&key| input.is_held(key))
+            || self.pad_buttons(action).iter().any(|&button| input.is_pad_held(button))
+    }
+
+    // La acción se activó en este frame (sin repetición)
+    pub fn is_pressed(&self, input: &InputState, action: Action) -> bool {
+        self.keys(action).iter().any(|&key| input.was_pressed(key))
+            || self.pad_buttons(action).iter().any(|&button| input.was_pad_pressed(button))
+    }
+
+    pub fn is_released(&self, input: &InputState, action: Action) -> bool {
+        self.keys(action).iter().any(|&key| input.was_released(key))
+            || self.pad_buttons(action).iter().any(|&button| input.was_pad_released(button))
+    }
+}
+
+// Flujo de reasignación en tiempo de ejecución: se recorre cada acción y se espera una tecla
+pub struct RebindSession {
+    actions: Vec<Action>,
+    current: usize,
+    // Las teclas de RebindKeys al empezar: cierran la sesión, así Escape se puede asignar como
+    // cualquier otra (por defecto es la de la pausa)
+    cancel_keys: Vec<Key>,
+    // Tecla ya usada por otra acción, esperando que se confirme el intercambio
+    conflict: Option<(Key, Action)>,
+}
+
+pub enum RebindStep {
+    Waiting,
+    Finished,
+    Cancelled,
+}
+
+impl RebindSession {
+    pub fn new(keymap: &KeyMap) -> Self {
+        RebindSession {
+            actions: Action::all(),
+            current: 0,
+            cancel_keys: keymap.keys(Action::RebindKeys).to_vec(),
+            conflict: None,
+        }
+    }
+
+    pub fn current_action(&self) -> Option<Action> {
+        self.actions.get(self.current).copied()
+    }
+
+    pub fn progress(&self) -> (usize, usize) {
+        (self.current + 1, self.actions.len())
+    }
+
+    pub fn cancel_keys(&self) -> &[Key] {
+        &self.cancel_keys
+    }
+
+    // (tecla, acción que ya la usa) si la última tecla está pendiente de confirmar
+    pub fn conflict(&self) -> Option<(Key, Action)> {
+        self.conflict
+    }
+
+    // Las teclas de RebindKeys cancelan, Backspace conserva la tecla actual y cualquier otra se
+    // asigna. Si otra acción ya la usa se avisa, y repetirla intercambia las teclas de las dos.
+    pub fn handle_key(&mut self, keymap: &mut KeyMap, key: Key) -> RebindStep {
+        let Some(action) = self.current_action() else {
+            return RebindStep::Finished;
+        };
+
+        if self.cancel_keys.contains(&key) {
+            return RebindStep::Cancelled;
+        }
+        match key {
+            Key::Backspace => {}
+            _ if self.conflict.is_some_and(|(pending, _)| pending == key) => {
+                if let Some((_, other)) = self.conflict {
+                    // La otra acción pierde la tecla y se queda con las que tenía esta
+                    let mut swapped: Vec<Key> = keymap.keys(other).iter().copied().filter(|&k| k != key).collect();
+                    for previous in keymap.keys(action).to_vec() {
+                        if !swapped.contains(&previous) {
+                            swapped.push(previous);
+                        }
+                    }
+                    keymap.bind(other, swapped);
+                }
+                keymap.bind(action, vec![key]);
+            }
+            _ => {
+                // Una tecla que la acción ya tiene no choca (p. ej. Tab, compartida con el editor)
+                let other = (!keymap.keys(action).contains(&key))
+                    .then(|| keymap.action_for(key, action))
+                    .flatten();
+                if let Some(other) = other {
+                    self.conflict = Some((key, other));
+                    return RebindStep::Waiting;
+                }
+                keymap.bind(action, vec![key]);
+            }
+        }
+
+        self.conflict = None;
+        self.current += 1;
+        if self.current >= self.actions.len() {
+            RebindStep::Finished
+        } else {
+            RebindStep::Waiting
+        }
+    }
+}
+
+fn key_from_name(name: &str) -> Option<Key> {
+    ALL_KEYS.iter().copied().find(|key| format!("{:?}", key).eq_ignore_ascii_case(name))
+}
+
+const ALL_KEYS: [Key; 106] = [
+    Key::Key0, Key::Key1, Key::Key2, Key::Key3, Key::Key4,
+    Key::Key5, Key::Key6, Key::Key7, Key::Key8, Key::Key9,
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I,
+    Key::J, Key::K, Key::L, Key::M, Key::N, Key::O, Key::P, Key::Q, Key::R,
+    Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+    Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7, Key::F8,
+    Key::F9, Key::F10, Key::F11, Key::F12, Key::F13, Key::F14, Key::F15,
+    Key::Down, Key::Left, Key::Right, Key::Up,
+    Key::Apostrophe, Key::Backquote, Key::Backslash, Key::Comma, Key::Equal,
+    Key::LeftBracket, Key::Minus, Key::Period, Key::RightBracket, Key::Semicolon,
+    Key::Slash, Key::Backspace, Key::Delete, Key::End, Key::Enter, Key::Escape,
+    Key::Home, Key::Insert, Key::Menu, Key::PageDown, Key::PageUp, Key::Pause,
+    Key::Space, Key::Tab, Key::NumLock, Key::CapsLock, Key::ScrollLock,
+    Key::LeftShift, Key::RightShift, Key::LeftCtrl, Key::RightCtrl,
+    Key::NumPad0, Key::NumPad1, Key::NumPad2, Key::NumPad3, Key::NumPad4,
+    Key::NumPad5, Key::NumPad6, Key::NumPad7, Key::NumPad8, Key::NumPad9,
+    Key::NumPadDot, Key::NumPadSlash, Key::NumPadAsterisk, Key::NumPadMinus,
+    Key::NumPadPlus, Key::NumPadEnter,
+    Key::LeftAlt, Key::RightAlt, Key::LeftSuper, Key::RightSuper,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(contents: &str) -> KeyMap {
+        KeyMap::parse(contents, Path::new("keybindings.cfg"))
+    }
+
+    // Sesión parada en `action`, como si se hubieran saltado las anteriores
+    fn session_at(keymap: &KeyMap, action: Action) -> RebindSession {
+        let mut session = RebindSession::new(keymap);
+        session.current = session.actions.iter().position(|&other| other == action).unwrap();
+        session
+    }
+
+    #[test]
+    fn config_overrides_defaults() {
+        let keymap = parse("# comment\nfire = G, h  # trailing comment\n\nthrust_forward = LeftShift\n");
+        assert_eq!(keymap.keys(Action::Fire), &[Key::G, Key::H]);
+        assert_eq!(keymap.keys(Action::ThrustForward), &[Key::LeftShift]);
+        assert_eq!(keymap.keys(Action::ZoomIn), &[Key::W]);
+    }
+
+    #[test]
+    fn invalid_lines_keep_the_default_binding() {
+        let keymap = parse("zoom_in = NotAKey\nno_such_action = A\nzoom_out\n");
+        assert_eq!(keymap.keys(Action::ZoomIn), &[Key::W]);
+        assert_eq!(keymap.keys(Action::ZoomOut), &[Key::S]);
+    }
+
+    #[test]
+    fn old_exit_name_binds_the_pause_menu() {
+        assert_eq!(parse("exit = F10").keys(Action::PauseMenu), &[Key::F10]);
+    }
+
+    #[test]
+    fn free_key_is_bound_and_advances() {
+        let mut keymap = KeyMap::default();
+        let mut session = RebindSession::new(&keymap);
+        let first = session.current_action().unwrap();
+        assert!(matches!(session.handle_key(&mut keymap, Key::Z), RebindStep::Waiting));
+        assert_eq!(keymap.keys(first), &[Key::Z]);
+        assert_eq!(session.progress().0, 2);
+    }
+
+    #[test]
+    fn rebind_key_cancels_and_escape_does_not() {
+        let mut keymap = KeyMap::default();
+        let mut session = RebindSession::new(&keymap);
+        assert_eq!(session.cancel_keys(), &[Key::F2]);
+        // Escape es de la pausa: choca, pero no cierra la sesión
+        assert!(matches!(session.handle_key(&mut keymap, Key::Escape), RebindStep::Waiting));
+        assert_eq!(session.conflict(), Some((Key::Escape, Action::PauseMenu)));
+        assert!(matches!(session.handle_key(&mut keymap, Key::F2), RebindStep::Cancelled));
+    }
+
+    #[test]
+    fn backspace_keeps_the_current_binding() {
+        let mut keymap = KeyMap::default();
+        let mut session = RebindSession::new(&keymap);
+        let first = session.current_action().unwrap();
+        let before = keymap.keys(first).to_vec();
+        session.handle_key(&mut keymap, Key::Backspace);
+        assert_eq!(keymap.keys(first), before.as_slice());
+        assert_ne!(session.current_action(), Some(first));
+    }
+
+    #[test]
+    fn conflicting_key_warns_then_swaps() {
+        let mut keymap = KeyMap::default();
+        let mut session = session_at(&keymap, Action::ZoomIn);
+        assert!(matches!(session.handle_key(&mut keymap, Key::S), RebindStep::Waiting));
+        assert_eq!(session.conflict(), Some((Key::S, Action::ZoomOut)));
+        assert_eq!(session.current_action(), Some(Action::ZoomIn));
+        assert_eq!(keymap.keys(Action::ZoomIn), &[Key::W]);
+
+        session.handle_key(&mut keymap, Key::S);
+        assert_eq!(keymap.keys(Action::ZoomIn), &[Key::S]);
+        assert_eq!(keymap.keys(Action::ZoomOut), &[Key::W]);
+        assert_eq!(session.conflict(), None);
+        assert_eq!(session.current_action(), Some(Action::ZoomOut));
+    }
+
+    #[test]
+    fn another_key_clears_the_conflict() {
+        let mut keymap = KeyMap::default();
+        let mut session = session_at(&keymap, Action::ZoomIn);
+        session.handle_key(&mut keymap, Key::S);
+        session.handle_key(&mut keymap, Key::Z);
+        assert_eq!(keymap.keys(Action::ZoomIn), &[Key::Z]);
+        assert_eq!(keymap.keys(Action::ZoomOut), &[Key::S]);
+        assert_eq!(session.conflict(), None);
+    }
+
+    #[test]
+    fn key_the_action_already_has_does_not_conflict() {
+        // Tab es de FocusNext y de EditNextParameter
+        let mut keymap = KeyMap::default();
+        let mut session = session_at(&keymap, Action::EditNextParameter);
+        session.handle_key(&mut keymap, Key::Tab);
+        assert_eq!(session.conflict(), None);
+        assert_ne!(session.current_action(), Some(Action::EditNextParameter));
+    }
+
+    #[test]
+    fn finishes_after_the_last_action() {
+        let mut keymap = KeyMap::default();
+        let mut session = RebindSession::new(&keymap);
+        let total = session.progress().1;
+        for _ in 1..total {
+            assert!(matches!(session.handle_key(&mut keymap, Key::Backspace), RebindStep::Waiting));
+        }
+        assert!(matches!(session.handle_key(&mut keymap, Key::Backspace), RebindStep::Finished));
+        assert_eq!(session.current_action(), None);
+    }
+}
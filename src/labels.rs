@@ -0,0 +1,106 @@
+use nalgebra_glm::Vec3;
+use crate::camera::ScreenProjection;
+use crate::celestial_body::CelestialBody;
+use crate::framebuffer::Framebuffer;
+use crate::text::{draw_text_styled, measure_text, TextStyle};
+
+const LABEL_COLOR: u32 = 0xE0F0FF;
+const LEADER_COLOR: u32 = 0x7FD4FF;
+const SHADOW_COLOR: u32 = 0x000000;
+const LEADER_LENGTH: i32 = 8;
+const FOCUS_MARGIN: f32 = 6.0;       // Separación entre el disco del cuerpo y los corchetes
+const FOCUS_MIN_HALF_SIZE: f32 = 8.0;
+
+// Margen sobre el radio para la prueba de oclusión: el relieve y el teselado hacen que la
+// superficie real no coincida exactamente con la esfera
+const OCCLUSION_MARGIN: f32 = 1.15;
+
+// Etiqueta ya proyectada: centro y radio del cuerpo en pixeles
+pub struct BodyLabel {
+    pub name: &'static str,
+    pub x: i32,
+    pub y: i32,
+    pub radius: f32,
+}
+
+// Hay que llamarla con el z-buffer ya lleno (después de dibujar cuerpos y nave) pero antes
+// de las órbitas, para que sus líneas no cuenten como oclusores. Un cuerpo se etiqueta si
+// su centro cae en pantalla y nada más cercano tapa el punto de su superficie que mira a
+// la cámara.
+pub fn visible_labels(
+    framebuffer: &Framebuffer,
+    projection: &ScreenProjection,
+    bodies: &[CelestialBody],
+    camera_eye: Vec3,
+) -> Vec<BodyLabel> {
+    bodies
+        .iter()
+        .filter(|body| body.is_drawn())
+        .filter_map(|body| {
+            let radius = projection.projected_radius(body.position, body.scale);
+            if radius == f32::MAX {
+                return None;  // La cámara está dentro del cuerpo
+            }
+            let center = projection.project(body.position)?;
+            let (x, y) = (center.x.round() as i32, center.y.round() as i32);
+            if x < 0 || y < 0 || x >= framebuffer.width as i32 || y >= framebuffer.height as i32 {
+                return None;
+            }
+
+            let toward_camera = (camera_eye - body.position).normalize();
+            let front = projection.project(body.position + toward_camera * body.scale * OCCLUSION_MARGIN)?;
+            // Lo que se dibuja delante de todo no puede quedar tapado
+            let nearest_depth = framebuffer.sampled_depth(x as usize, y as usize);
+            if !body.flags.always_on_top && nearest_depth < front.z {
+                return None;
+            }
+
+            Some(BodyLabel { name: body.name, x, y, radius })
+        })
+        .collect()
+}
+
+// Nombre arriba a la derecha del disco, unido al borde con una línea corta
+pub fn render_labels(framebuffer: &mut Framebuffer, labels: &[BodyLabel]) {
+    let style = TextStyle::new(LABEL_COLOR).with_shadow(SHADOW_COLOR);
+    for label in labels {
+        let rim = (label.radius * std::f32::consts::FRAC_1_SQRT_2) as i32;
+        let (start_x, start_y) = (label.x + rim, label.y - rim);
+        let (end_x, end_y) = (start_x + LEADER_LENGTH, start_y - LEADER_LENGTH);
+        framebuffer.blend_line(start_x, start_y, end_x, end_y, LEADER_COLOR, 0.7);
+
+        let (width, height) = measure_text(label.name, &style);
+        let text_x = (end_x + 2).min(framebuffer.width as i32 - width as i32 - 2).max(2);
+        let text_y = (end_y - height as i32 / 2).max(2);
+        draw_text_styled(framebuffer, text_x, text_y, label.name, &style);
+    }
+}
+
+// Corchetes en las cuatro esquinas del cuadrado que envuelve al cuerpo enfocado por la
+// cámara, con un pulso suave. Se dibujan aunque algo lo tape para que siempre se sepa qué
+// se está siguiendo; no se dibujan si está fuera de pantalla o llena la vista.
+pub fn render_focus_brackets(framebuffer: &mut Framebuffer, projection: &ScreenProjection, body: &CelestialBody, time: f32, color: u32) {
+    let radius = projection.projected_radius(body.position, body.scale);
+    let Some(center) = projection.project(body.position) else {
+        return;
+    };
+    let half = (radius + FOCUS_MARGIN).max(FOCUS_MIN_HALF_SIZE);
+    let (width, height) = (framebuffer.width as f32, framebuffer.height as f32);
+    if half > width.max(height) / 2.0
+        || center.x + half < 0.0
+        || center.y + half < 0.0
+        || center.x - half >= width
+        || center.y - half >= height
+    {
+        return;
+    }
+
+    let alpha = 0.55 + 0.25 * (time * 3.0).sin();
+    let arm = (half * 0.4).clamp(4.0, 16.0) as i32;
+    let (left, right) = ((center.x - half) as i32, (center.x + half) as i32);
+    let (top, bottom) = ((center.y - half) as i32, (center.y + half) as i32);
+    for (x, y, dx, dy) in [(left, top, 1, 1), (right, top, -1, 1), (left, bottom, 1, -1), (right, bottom, -1, -1)] {
+        framebuffer.blend_line(x, y, x + dx * arm, y, color, alpha);
+        framebuffer.blend_line(x, y, x, y + dy * arm, color, alpha);
+    }
+}
@@ -0,0 +1,100 @@
+// Simulador del sistema solar con un rasterizador por software propio.
+//
+// `Engine` junta todo (escena, renderer, cámara, HUD y menús) y avanza un frame a la vez; el
+// binario solo abre la ventana y presenta el framebuffer. Las piezas sueltas (`Scene`,
+// `Renderer`, `Camera`, los shaders...) también se pueden usar por separado.
+
+pub mod error;
+pub mod framebuffer;
+pub mod triangle;
+pub mod vertex;
+pub mod fragment;
+pub mod color;
+pub mod shaders;
+pub mod camera;
+pub mod celestial_body;
+pub mod obj_loader;
+pub mod mesh_cache;
+pub mod texture;
+pub mod embedded;
+pub mod assets;
+pub mod hot_reload;
+pub mod vehicle;
+pub mod orbit;
+pub mod sphere;
+pub mod geometry;
+pub mod mesh_registry;
+pub mod terrain;
+pub mod rings;
+pub mod normals;
+pub mod collision;
+pub mod ship_material;
+pub mod input;
+pub mod gamepad;
+pub mod backend;
+#[cfg(feature = "winit")]
+pub mod winit_backend;
+#[cfg(feature = "wgpu")]
+pub mod wgpu_presenter;
+pub mod keymap;
+pub mod indicators;
+pub mod projectiles;
+pub mod proximity;
+pub mod play_area;
+pub mod gravity;
+pub mod solar_wind;
+pub mod text;
+pub mod i18n;
+pub mod hud;
+pub mod frame_stats;
+pub mod frame_budget;
+pub mod labels;
+pub mod markers;
+pub mod ruler;
+pub mod events;
+pub mod missions;
+pub mod orbit_editor;
+pub mod reference_frame;
+pub mod maneuver;
+pub mod body_search;
+pub mod docking;
+pub mod menu;
+pub mod settings;
+pub mod storage;
+pub mod logging;
+pub mod profiling;
+pub mod antialias;
+pub mod temporal;
+pub mod color_grading;
+pub mod palette;
+pub mod minimap;
+pub mod toast;
+pub mod audio;
+pub mod sim_clock;
+pub mod interpolation;
+pub mod scene_file;
+pub mod scripting;
+pub mod screenshot;
+pub mod clip;
+pub mod golden;
+pub mod net_sync;
+pub mod sky;
+pub mod scene;
+pub mod renderable;
+pub mod frame_graph;
+pub mod frame_pipeline;
+pub mod renderer;
+pub mod engine;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
+#[cfg(target_arch = "wasm32")]
+pub mod web;
+
+pub use camera::{Camera, CameraMode};
+pub use engine::{Engine, FrameStatus, LaunchOptions};
+pub use error::Error;
+pub use framebuffer::Framebuffer;
+pub use input::InputState;
+pub use renderer::Renderer;
+pub use scene::Scene;
+pub use settings::Settings;
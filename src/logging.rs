@@ -0,0 +1,60 @@
+use log::LevelFilter;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+// Mensajes de diagnóstico con el facade `log`: cada módulo escribe con su ruta como target
+// (p. ej. `space_travel_simulator::vehicle`), así se puede subir o silenciar el ruido por módulo.
+pub const DEFAULT_LEVEL: LevelFilter = LevelFilter::Info;
+
+// Escritorio: env_logger. `RUST_LOG` acepta filtros por módulo
+// (`RUST_LOG=space_travel_simulator::obj_loader=debug`); `level` (`--log-level`) fija el nivel
+// general por encima de lo que diga `RUST_LOG` y `file` (`--log-file`) manda todo a un archivo.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn init(level: Option<LevelFilter>, file: Option<&Path>) -> std::io::Result<()> {
+    let mut builder = env_logger::Builder::new();
+    builder.filter_level(DEFAULT_LEVEL).parse_default_env();
+    if let Some(level) = level {
+        builder.filter_level(level);
+    }
+    if let Some(path) = file {
+        let file = std::fs::File::create(path)?;
+        builder
+            .target(env_logger::Target::Pipe(Box::new(file)))
+            .write_style(env_logger::WriteStyle::Never);
+    }
+    builder.init();
+    Ok(())
+}
+
+// Navegador: a la consola de desarrollo, con el nivel como método (console.warn, console.error...)
+#[cfg(target_arch = "wasm32")]
+pub fn init(level: LevelFilter) {
+    if log::set_logger(&ConsoleLogger).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+struct ConsoleLogger;
+
+#[cfg(target_arch = "wasm32")]
+impl log::Log for ConsoleLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = wasm_bindgen::JsValue::from_str(&format!("[{}] {}", record.target(), record.args()));
+        match record.level() {
+            log::Level::Error => web_sys::console::error_1(&message),
+            log::Level::Warn => web_sys::console::warn_1(&message),
+            log::Level::Info => web_sys::console::info_1(&message),
+            log::Level::Debug | log::Level::Trace => web_sys::console::debug_1(&message),
+        }
+    }
+
+    fn flush(&self) {}
+}
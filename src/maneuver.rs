@@ -0,0 +1,131 @@
+use nalgebra_glm::Vec3;
+use crate::framebuffer::Framebuffer;
+use crate::gravity;
+use crate::i18n::tr;
+use crate::reference_frame::dominant_body;
+use crate::scene::Scene;
+use crate::text::{draw_text, text_width, GLYPH_HEIGHT};
+use crate::vehicle::Vehicle;
+
+// Cambio de velocidad por tecla y tope por eje (unidades por segundo)
+const DELTA_V_STEP: f32 = 0.1;
+const MAX_DELTA_V: f32 = 10.0;
+// Por debajo de esta velocidad no hay dirección de avance: se usa la proa de la nave
+const MIN_SPEED: f32 = 0.05;
+
+const PANEL_COLOR: u32 = 0x000000;
+const TITLE_COLOR: u32 = 0x7FD4FF;
+const LABEL_COLOR: u32 = 0x9AA8B8;
+const TEXT_COLOR: u32 = 0xE0F0FF;
+const SELECTED_COLOR: u32 = 0xFFD060;
+const MARGIN: usize = 6;
+const PADDING: usize = 5;
+
+// Ejes del encendido: prograde (negativo = retrograde) y normal al plano de la órbita
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BurnAxis {
+    Prograde,
+    Normal,
+}
+
+impl BurnAxis {
+    pub const ALL: [BurnAxis; 2] = [BurnAxis::Prograde, BurnAxis::Normal];
+
+    fn label(self) -> &'static str {
+        tr(match self {
+            BurnAxis::Prograde => "hud.maneuver_prograde",
+            BurnAxis::Normal => "hud.maneuver_normal",
+        })
+    }
+}
+
+// Planificador de maniobras (tecla X): con la simulación en pausa se arma un encendido
+// instantáneo por ejes y se ve la trayectoria que dejaría, punteada, junto a la actual. Recién
+// al ejecutarlo cambia la velocidad de la nave.
+#[derive(Debug, Default)]
+pub struct Maneuver {
+    selected: usize,    // Índice en `BurnAxis::ALL`
+    delta_v: [f32; 2],  // Por eje, en el orden de `BurnAxis::ALL`
+    path: Vec<Vec3>,    // Trayectoria tras el encendido
+}
+
+impl Maneuver {
+    pub fn next_axis(&mut self) {
+        self.selected = (self.selected + 1) % BurnAxis::ALL.len();
+    }
+
+    pub fn adjust(&mut self, steps: i32) {
+        let value = &mut self.delta_v[self.selected];
+        *value = (*value + DELTA_V_STEP * steps as f32).clamp(-MAX_DELTA_V, MAX_DELTA_V);
+        // Sin restos de coma flotante al volver a cero
+        if value.abs() < DELTA_V_STEP * 0.5 {
+            *value = 0.0;
+        }
+    }
+
+    // Tamaño del encendido (unidades por segundo)
+    pub fn magnitude(&self) -> f32 {
+        self.delta_v[0].hypot(self.delta_v[1])
+    }
+
+    // El encendido como vector en el mundo. `velocity` es la de la nave en el sistema de
+    // referencia; la normal sale del plano que forman su posición respecto al cuerpo dominante y
+    // esa velocidad (o el eje Y si no hay plano)
+    pub fn delta_v(&self, scene: &Scene, velocity: Vec3) -> Vec3 {
+        let ship = &scene.spaceship;
+        let prograde = if velocity.magnitude() > MIN_SPEED { velocity.normalize() } else { ship.forward() };
+        let center = dominant_body(scene, ship.position).map_or_else(Vec3::zeros, |index| scene.bodies[index].position);
+        let normal = (ship.position - center).cross(&prograde).try_normalize(f32::EPSILON).unwrap_or_else(Vec3::y);
+        prograde * self.delta_v[0] + normal * self.delta_v[1]
+    }
+
+    // Vuelve a calcular la trayectoria tras el encendido (la simulación está en pausa, pero las
+    // órbitas avanzarán con la escala de tiempo actual al reanudarla)
+    pub fn plan(&mut self, scene: &Scene, delta_v: Vec3, time_scale: f32, orbits_running: bool, frame: Option<usize>, gravity: bool) {
+        let velocity = scene.spaceship.velocity + delta_v;
+        self.path = gravity::predict(scene, velocity, time_scale, orbits_running, frame, gravity);
+    }
+
+    pub fn path(&self) -> &[Vec3] {
+        &self.path
+    }
+}
+
+// Panel del planificador, apilado con los otros de la esquina superior derecha: el cambio de
+// velocidad por eje con el elegido resaltado, el total y las teclas (`hint`). Devuelve la y
+// donde termina.
+pub fn render_maneuver(framebuffer: &mut Framebuffer, maneuver: &Maneuver, hint: &str, panel_y: usize) -> usize {
+    let line = GLYPH_HEIGHT + 3;
+    let title = tr("hud.maneuver_title");
+    let mut rows: Vec<(&str, String)> = BurnAxis::ALL
+        .iter()
+        .zip(maneuver.delta_v)
+        .map(|(axis, value)| (axis.label(), format!("{:+.2} u/s", value)))
+        .collect();
+    rows.push((tr("hud.maneuver_total"), format!("{:.2} u/s", maneuver.magnitude())));
+    let label_width = rows.iter().map(|(label, _)| text_width(label)).max().unwrap_or(0);
+    let value_column = label_width + 2 * text_width(" ");
+    let content_width = rows
+        .iter()
+        .map(|(_, value)| value_column + text_width(value))
+        .chain([text_width(title), text_width(hint)])
+        .max()
+        .unwrap_or(0);
+    let panel_width = (content_width + 2 * PADDING).min(framebuffer.width);
+    let panel_height = (rows.len() + 2) * line + 2 * PADDING;
+    let panel_x = framebuffer.width.saturating_sub(panel_width + MARGIN);
+    framebuffer.blend_rect(panel_x, panel_y, panel_width, panel_height, PANEL_COLOR, 0.6);
+
+    let left = (panel_x + PADDING) as i32;
+    let mut y = (panel_y + PADDING) as i32;
+    draw_text(framebuffer, left, y, title, TITLE_COLOR);
+    y += line as i32;
+    for (index, (label, value)) in rows.iter().enumerate() {
+        let selected = index == maneuver.selected;
+        draw_text(framebuffer, left, y, label, if selected { SELECTED_COLOR } else { LABEL_COLOR });
+        draw_text(framebuffer, left + value_column as i32, y, value, if selected { SELECTED_COLOR } else { TEXT_COLOR });
+        y += line as i32;
+    }
+    draw_text(framebuffer, left, y, hint, LABEL_COLOR);
+    panel_y + panel_height + MARGIN
+}
@@ -0,0 +1,109 @@
+use nalgebra_glm::{Vec3, Vec4};
+use crate::camera::ScreenProjection;
+use crate::celestial_body::CelestialBody;
+use crate::framebuffer::Framebuffer;
+use crate::renderable::Renderable;
+use crate::shaders::SUN_DIRECTION;
+use crate::text::{draw_text_styled, measure_text, TextStyle};
+
+pub const SUBSOLAR_LABEL: &str = "Subsolar";
+const SUBSOLAR_COLOR: u32 = 0xFFE066;
+pub const DOCKING_PORT_LABEL: &str = "Dock";
+pub const DOCKING_PORT_COLOR: u32 = 0x60E0FF;
+const SHADOW_COLOR: u32 = 0x000000;
+const DOT_RADIUS: i32 = 3;
+const LABEL_GAP: i32 = 5;
+
+// La prueba de oclusión se hace con el punto levantado sobre la superficie (como las
+// etiquetas de los cuerpos), para que el relieve y el teselado no lo tapen
+const OCCLUSION_LIFT: f32 = 1.1;
+
+// Un punto fijo en la superficie de un cuerpo: gira con él (sitios de aterrizaje, tormentas)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceMarker {
+    pub label: &'static str,
+    pub latitude: f32,   // Grados, -90 (polo sur) a 90 (polo norte), sobre el eje de rotación Y
+    pub longitude: f32,  // Grados hacia el este; 0 = el eje +X local del cuerpo
+    pub color: u32,
+}
+
+impl SurfaceMarker {
+    pub fn new(label: &'static str, latitude: f32, longitude: f32, color: u32) -> Self {
+        SurfaceMarker { label, latitude: latitude.clamp(-90.0, 90.0), longitude, color }
+    }
+
+    // Punto de la esfera unitaria de la malla, antes de escalarla y rotarla
+    pub fn local_direction(&self) -> Vec3 {
+        let (sin_lat, cos_lat) = self.latitude.to_radians().sin_cos();
+        let (sin_lon, cos_lon) = self.longitude.to_radians().sin_cos();
+        Vec3::new(cos_lat * cos_lon, sin_lat, -cos_lat * sin_lon)
+    }
+
+    // Dónde queda en el mundo con la posición y la rotación actuales del cuerpo
+    pub fn world_position(&self, body: &CelestialBody) -> Vec3 {
+        let direction = self.local_direction();
+        let point = body.model_matrix() * Vec4::new(direction.x, direction.y, direction.z, 1.0);
+        point.xyz()
+    }
+}
+
+// Marcador ya proyectado, en pixeles
+pub struct ProjectedMarker {
+    pub label: &'static str,
+    pub x: i32,
+    pub y: i32,
+    pub color: u32,
+}
+
+// Como `labels::visible_labels`, con el z-buffer lleno y antes de las órbitas. Un marcador se
+// muestra si está en el hemisferio que mira a la cámara y nada más cercano lo tapa.
+pub fn visible_markers(
+    framebuffer: &Framebuffer,
+    projection: &ScreenProjection,
+    bodies: &[CelestialBody],
+    camera_eye: Vec3,
+) -> Vec<ProjectedMarker> {
+    let mut visible = Vec::new();
+    for body in bodies.iter().filter(|body| body.is_drawn()) {
+        // El punto subsolar no gira con el cuerpo: siempre mira al sol
+        let subsolar = body.subsolar_marker.then(|| (SUBSOLAR_LABEL, body.position + SUN_DIRECTION * body.scale, SUBSOLAR_COLOR));
+        let pinned = body.markers.iter().chain(&body.docking_port).map(|marker| (marker.label, marker.world_position(body), marker.color));
+
+        for (label, point, color) in subsolar.into_iter().chain(pinned) {
+            let normal = (point - body.position).normalize();
+            if normal.dot(&(camera_eye - point)) <= 0.0 {
+                continue;  // Del otro lado del cuerpo
+            }
+            let Some(screen) = projection.project(point) else {
+                continue;
+            };
+            let (x, y) = (screen.x.round() as i32, screen.y.round() as i32);
+            if x < 0 || y < 0 || x >= framebuffer.width as i32 || y >= framebuffer.height as i32 {
+                continue;
+            }
+            let Some(lifted) = projection.project(body.position + normal * body.scale * OCCLUSION_LIFT) else {
+                continue;
+            };
+            let nearest_depth = framebuffer.sampled_depth(x as usize, y as usize);
+            if !body.flags.always_on_top && nearest_depth < lifted.z {
+                continue;
+            }
+            visible.push(ProjectedMarker { label, x, y, color });
+        }
+    }
+    visible
+}
+
+// Un punto con aro y el nombre a la derecha
+pub fn render_markers(framebuffer: &mut Framebuffer, markers: &[ProjectedMarker]) {
+    for marker in markers {
+        framebuffer.draw_circle(marker.x, marker.y, DOT_RADIUS, marker.color);
+        framebuffer.blend_rect((marker.x - 1).max(0) as usize, (marker.y - 1).max(0) as usize, 3, 3, marker.color, 0.9);
+
+        let style = TextStyle::new(marker.color).with_shadow(SHADOW_COLOR);
+        let (width, height) = measure_text(marker.label, &style);
+        let text_x = (marker.x + LABEL_GAP).min(framebuffer.width as i32 - width as i32 - 2).max(2);
+        let text_y = (marker.y - height as i32 / 2).max(2);
+        draw_text_styled(framebuffer, text_x, text_y, marker.label, &style);
+    }
+}
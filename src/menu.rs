@@ -0,0 +1,280 @@
+use minifb::Key;
+use crate::framebuffer::Framebuffer;
+use crate::i18n::tr;
+use crate::palette::Palette;
+use crate::text::{draw_text_styled, measure_text, Align, TextStyle};
+
+const PANEL_COLOR: u32 = 0x000000;
+const BORDER_COLOR: u32 = 0x7FD4FF;
+const TEXT_COLOR: u32 = 0xC8D2DC;
+const HINT_COLOR: u32 = 0x7FA0C0;
+const ITEM_SPACING: usize = 16;
+
+// Pantallas del menú de pausa
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuScreen {
+    Main,
+    Settings,
+    LoadScene,
+}
+
+// Opciones que se pueden cambiar desde la pantalla de ajustes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Setting {
+    Resolution,
+    LodQuality,
+    Tessellation,
+    AntiAliasing,
+    TemporalUpscaling,
+    FrameLimit,
+    OrbitLines,
+    SimSpeed,
+    Hud,
+    BodyLabels,
+    PerfOverlay,
+    Volume,
+    ShotOverlays,
+    Pipelining,
+    Language,
+    PowerSaving,
+    Exposure,
+    AutoExposure,
+    Brightness,
+    Contrast,
+    ColorVision,
+    SkyExposure,
+    ShipGravity,
+    FixedTick,
+    AdaptiveQuality,
+    SolarWind,
+    FocusOrder,
+}
+
+impl Setting {
+    const ALL: [Setting; 27] = [
+        Setting::Resolution,
+        Setting::LodQuality,
+        Setting::Tessellation,
+        Setting::AntiAliasing,
+        Setting::TemporalUpscaling,
+        Setting::Exposure,
+        Setting::AutoExposure,
+        Setting::Brightness,
+        Setting::Contrast,
+        Setting::SkyExposure,
+        Setting::ColorVision,
+        Setting::FrameLimit,
+        Setting::AdaptiveQuality,
+        Setting::OrbitLines,
+        Setting::SimSpeed,
+        Setting::ShipGravity,
+        Setting::SolarWind,
+        Setting::FixedTick,
+        Setting::Hud,
+        Setting::BodyLabels,
+        Setting::FocusOrder,
+        Setting::PerfOverlay,
+        Setting::Volume,
+        Setting::ShotOverlays,
+        Setting::Pipelining,
+        Setting::Language,
+        Setting::PowerSaving,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Setting::Resolution => tr("menu.resolution"),
+            Setting::LodQuality => tr("menu.lod_quality"),
+            Setting::Tessellation => tr("menu.tessellation"),
+            Setting::AntiAliasing => tr("menu.anti_aliasing"),
+            Setting::TemporalUpscaling => tr("menu.temporal_upscaling"),
+            Setting::FrameLimit => tr("menu.frame_limit"),
+            Setting::SimSpeed => tr("menu.sim_speed"),
+            Setting::Hud => tr("menu.hud"),
+            Setting::OrbitLines => tr("menu.orbit_lines"),
+            Setting::BodyLabels => tr("menu.body_labels"),
+            Setting::PerfOverlay => tr("menu.perf_overlay"),
+            Setting::Volume => tr("menu.volume"),
+            Setting::ShotOverlays => tr("menu.shot_overlays"),
+            Setting::Pipelining => tr("menu.pipelining"),
+            Setting::Language => tr("menu.language"),
+            Setting::PowerSaving => tr("menu.power_saving"),
+            Setting::Exposure => tr("menu.exposure"),
+            Setting::AutoExposure => tr("menu.auto_exposure"),
+            Setting::Brightness => tr("menu.brightness"),
+            Setting::Contrast => tr("menu.contrast"),
+            Setting::ColorVision => tr("menu.color_vision"),
+            Setting::SkyExposure => tr("menu.sky_exposure"),
+            Setting::ShipGravity => tr("menu.ship_gravity"),
+            Setting::FixedTick => tr("menu.fixed_tick"),
+            Setting::SolarWind => tr("menu.solar_wind"),
+            Setting::AdaptiveQuality => tr("menu.adaptive_quality"),
+            Setting::FocusOrder => tr("menu.focus_order"),
+        }
+    }
+}
+
+// Lo que el juego tiene que hacer tras una tecla; la navegación entre pantallas la
+// resuelve el propio menú
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuCommand {
+    Resume,
+    Adjust(Setting, i32),  // Pasos hacia adelante (+) o atrás (-); los on/off solo alternan
+    LoadScene(usize),
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MenuItem {
+    Resume,
+    Open(MenuScreen),
+    Setting(Setting),
+    Scene(usize),
+    Back,
+    Quit,
+}
+
+// Máquina de estados del menú de pausa: pantalla actual y opción resaltada.
+// Flechas para moverse, Enter para elegir, Esc vuelve atrás (o reanuda desde la principal).
+pub struct PauseMenu {
+    screen: MenuScreen,
+    selected: usize,
+    scenes: Vec<String>,
+}
+
+impl PauseMenu {
+    pub fn new(scenes: Vec<String>) -> Self {
+        PauseMenu { screen: MenuScreen::Main, selected: 0, scenes }
+    }
+
+    fn items(&self) -> Vec<MenuItem> {
+        match self.screen {
+            MenuScreen::Main => vec![
+                MenuItem::Resume,
+                MenuItem::Open(MenuScreen::Settings),
+                MenuItem::Open(MenuScreen::LoadScene),
+                MenuItem::Quit,
+            ],
+            MenuScreen::Settings => Setting::ALL
+                .iter()
+                .map(|&setting| MenuItem::Setting(setting))
+                .chain([MenuItem::Back])
+                .collect(),
+            MenuScreen::LoadScene => (0..self.scenes.len())
+                .map(MenuItem::Scene)
+                .chain([MenuItem::Back])
+                .collect(),
+        }
+    }
+
+    // Pantalla y opción resaltada, para saber si una tecla movió algo
+    pub fn cursor(&self) -> (MenuScreen, usize) {
+        (self.screen, self.selected)
+    }
+
+    fn open(&mut self, screen: MenuScreen) {
+        self.screen = screen;
+        self.selected = 0;
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> Option<MenuCommand> {
+        let items = self.items();
+        match key {
+            Key::Up | Key::W => {
+                self.selected = (self.selected + items.len() - 1) % items.len();
+                None
+            }
+            Key::Down | Key::S => {
+                self.selected = (self.selected + 1) % items.len();
+                None
+            }
+            Key::Escape | Key::Backspace => match self.screen {
+                MenuScreen::Main => Some(MenuCommand::Resume),
+                _ => {
+                    self.open(MenuScreen::Main);
+                    None
+                }
+            },
+            // En los ajustes, izquierda/derecha recorren los valores en uno u otro sentido
+            Key::Left | Key::Right => match items[self.selected] {
+                MenuItem::Setting(setting) => Some(MenuCommand::Adjust(setting, if key == Key::Left { -1 } else { 1 })),
+                _ => None,
+            },
+            Key::Enter | Key::NumPadEnter | Key::Space => match items[self.selected] {
+                MenuItem::Resume => Some(MenuCommand::Resume),
+                MenuItem::Open(screen) => {
+                    self.open(screen);
+                    None
+                }
+                MenuItem::Setting(setting) => Some(MenuCommand::Adjust(setting, 1)),
+                MenuItem::Scene(index) => Some(MenuCommand::LoadScene(index)),
+                MenuItem::Back => {
+                    self.open(MenuScreen::Main);
+                    None
+                }
+                MenuItem::Quit => Some(MenuCommand::Quit),
+            },
+            _ => None,
+        }
+    }
+
+    fn item_label(&self, item: MenuItem, setting_value: &impl Fn(Setting) -> String) -> String {
+        match item {
+            MenuItem::Resume => tr("menu.resume").to_string(),
+            MenuItem::Open(MenuScreen::Settings) => tr("menu.open_settings").to_string(),
+            MenuItem::Open(MenuScreen::LoadScene) => tr("menu.load_scene").to_string(),
+            MenuItem::Open(MenuScreen::Main) | MenuItem::Back => tr("menu.back").to_string(),
+            MenuItem::Setting(setting) => format!("{:<15}{:>14}", setting.label(), setting_value(setting)),
+            MenuItem::Scene(index) => self.scenes[index].clone(),
+            MenuItem::Quit => tr("menu.quit").to_string(),
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self.screen {
+            MenuScreen::Main => tr("menu.paused"),
+            MenuScreen::Settings => tr("menu.settings"),
+            MenuScreen::LoadScene => tr("menu.load_scene_title"),
+        }
+    }
+
+    // Panel centrado sobre la escena (que queda oscurecida detrás). `setting_value` da el
+    // texto del valor actual de cada ajuste; la opción elegida va en el color de selección.
+    pub fn render(&self, framebuffer: &mut Framebuffer, palette: &Palette, setting_value: impl Fn(Setting) -> String) {
+        framebuffer.blend_rect(0, 0, framebuffer.width, framebuffer.height, PANEL_COLOR, 0.4);
+
+        let items = self.items();
+        let labels: Vec<String> = items.iter().map(|&item| self.item_label(item, &setting_value)).collect();
+        let title_style = TextStyle::new(BORDER_COLOR).with_scale(2).with_align(Align::Center).with_shadow(0x000000);
+        let item_style = TextStyle::new(TEXT_COLOR).with_align(Align::Center);
+        let selected_style = TextStyle::new(palette.selection).with_align(Align::Center);
+        let hint = match self.screen {
+            MenuScreen::Settings => tr("menu.hint_settings"),
+            _ => tr("menu.hint"),
+        };
+
+        let items_width = labels.iter().map(|label| measure_text(label, &item_style).0).max().unwrap_or(0);
+        let width = items_width.max(measure_text(hint, &item_style).0).max(measure_text(self.title(), &title_style).0) + 48;
+        let height = 40 + items.len() * ITEM_SPACING + 28;
+        let x = framebuffer.width.saturating_sub(width) / 2;
+        let y = framebuffer.height.saturating_sub(height) / 2;
+        framebuffer.blend_rect(x, y, width, height, PANEL_COLOR, 0.85);
+        framebuffer.draw_rect(x, y, width, height, BORDER_COLOR);
+
+        let center_x = (x + width / 2) as i32;
+        draw_text_styled(framebuffer, center_x, (y + 12) as i32, self.title(), &title_style);
+
+        for (i, label) in labels.iter().enumerate() {
+            let item_y = (y + 40 + i * ITEM_SPACING) as i32;
+            if i == self.selected {
+                let marked = format!("> {} <", label);
+                draw_text_styled(framebuffer, center_x, item_y, &marked, &selected_style);
+            } else {
+                draw_text_styled(framebuffer, center_x, item_y, label, &item_style);
+            }
+        }
+
+        let hint_style = TextStyle::new(HINT_COLOR).with_align(Align::Center);
+        draw_text_styled(framebuffer, center_x, (y + height - 16) as i32, hint, &hint_style);
+    }
+}
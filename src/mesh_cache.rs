@@ -0,0 +1,352 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use nalgebra_glm::{Vec2, Vec3};
+use crate::geometry::Mesh;
+use crate::obj_loader::{FaceGroup, MeshOptions, MtlMaterial, ProcessedModel, SubMesh};
+use crate::vertex::Vertex;
+
+// Caché binaria de modelos ya procesados: `<modelo>.meshcache` junto al .obj.
+// Guarda la fecha de modificación y el tamaño de cada archivo fuente (.obj y .mtl) y las
+// opciones con que se procesó; si algo cambió, la caché se ignora y se vuelve a escribir.
+const MAGIC: &[u8; 4] = b"STMC";
+const VERSION: u32 = 4;
+const EXTENSION: &str = "meshcache";
+// Marca de "sin índice" para los campos opcionales
+const NONE: u32 = u32::MAX;
+// Bytes mínimos de cada elemento, para rechazar cantidades que no caben en el archivo
+const VERTEX_SIZE: usize = 14 * 4;
+const MATERIAL_SIZE: usize = 4 + 3 * 4 + 3 * 4 + 4 + 4;
+const RANGE_SIZE: usize = 3 * 4;
+
+pub fn cache_path(model_path: &Path) -> PathBuf {
+    model_path.with_extension(EXTENSION)
+}
+
+// None si no hay caché, está desactualizada, se procesó con otras opciones o no se puede leer
+pub fn load(model_path: &Path, options: MeshOptions) -> Option<ProcessedModel> {
+    decode(&fs::read(cache_path(model_path)).ok()?, options)
+}
+
+pub fn save(model_path: &Path, model: &ProcessedModel) -> io::Result<()> {
+    fs::write(cache_path(model_path), encode(model))
+}
+
+pub fn decode(bytes: &[u8], options: MeshOptions) -> Option<ProcessedModel> {
+    let mut reader = Reader { bytes, offset: 0 };
+    read_model(&mut reader, options).ok().flatten()
+}
+
+pub fn encode(model: &ProcessedModel) -> Vec<u8> {
+    let mut writer = Writer { bytes: Vec::new() };
+    writer.bytes.extend_from_slice(MAGIC);
+    writer.u32(VERSION);
+    writer.u32(model.options.crease_angle.map_or(NONE, f32::to_bits));
+
+    writer.len(model.source_files.len());
+    for source in &model.source_files {
+        writer.str(&source.to_string_lossy());
+        let (modified, size) = file_stamp(source).unwrap_or((0, 0));
+        writer.u64(modified);
+        writer.u64(size);
+    }
+
+    writer.len(model.mesh.vertices.len());
+    for vertex in &model.mesh.vertices {
+        writer.vec3(&vertex.position);
+        writer.vec3(&vertex.normal);
+        writer.f32(vertex.tex_coords.x);
+        writer.f32(vertex.tex_coords.y);
+        writer.vec3(&vertex.tangent);
+        writer.vec3(&vertex.bitangent);
+    }
+    writer.len(model.mesh.indices.len());
+    model.mesh.indices.iter().for_each(|&index| writer.u32(index));
+
+    writer.len(model.materials.len());
+    for material in &model.materials {
+        writer.str(&material.name);
+        writer.vec3(&material.diffuse);
+        writer.vec3(&material.specular);
+        writer.f32(material.shininess);
+        writer.str(&material.diffuse_map.as_ref().map(|map| map.to_string_lossy().into_owned()).unwrap_or_default());
+    }
+
+    writer.len(model.groups.len());
+    for group in &model.groups {
+        writer.index(group.material);
+        writer.len(group.faces.start);
+        writer.len(group.faces.end);
+    }
+
+    writer.len(model.objects.len());
+    for object in &model.objects {
+        writer.str(&object.name);
+        writer.len(object.faces.start);
+        writer.len(object.faces.end);
+    }
+
+    writer.bytes
+}
+
+// (nanosegundos desde la época, tamaño) de un archivo; 0 si no existe (p. ej. un .mtl que falta)
+fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((modified.as_nanos() as u64, metadata.len()))
+}
+
+// Ok(None) = la caché es válida pero está desactualizada
+fn read_model(reader: &mut Reader, options: MeshOptions) -> io::Result<Option<ProcessedModel>> {
+    if reader.take(4)? != MAGIC || reader.u32()? != VERSION {
+        return Ok(None);
+    }
+    if reader.u32()? != options.crease_angle.map_or(NONE, f32::to_bits) {
+        return Ok(None);
+    }
+
+    let mut source_files = Vec::new();
+    for _ in 0..reader.count(4 + 8 + 8)? {
+        let source = PathBuf::from(reader.str()?);
+        let stamp = (reader.u64()?, reader.u64()?);
+        if file_stamp(&source).unwrap_or((0, 0)) != stamp {
+            return Ok(None);
+        }
+        source_files.push(source);
+    }
+
+    let vertices = (0..reader.count(VERTEX_SIZE)?)
+        .map(|_| {
+            let mut vertex = Vertex::new(reader.vec3()?, reader.vec3()?, Vec2::new(reader.f32()?, reader.f32()?));
+            vertex.tangent = reader.vec3()?;
+            vertex.bitangent = reader.vec3()?;
+            Ok(vertex)
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    let indices = (0..reader.count(4)?)
+        .map(|_| match reader.u32()? {
+            index if (index as usize) < vertices.len() => Ok(index),
+            _ => Err(invalid("vertex index out of range")),
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    if indices.len() % 3 != 0 {
+        return Err(invalid("index count is not a multiple of 3"));
+    }
+    let triangle_count = indices.len() / 3;
+
+    let mut materials = Vec::new();
+    for _ in 0..reader.count(MATERIAL_SIZE)? {
+        let name = reader.str()?;
+        let diffuse = reader.vec3()?;
+        let specular = reader.vec3()?;
+        let shininess = reader.f32()?;
+        let map = reader.str()?;
+        materials.push(MtlMaterial {
+            name,
+            diffuse,
+            specular,
+            shininess,
+            diffuse_map: (!map.is_empty()).then(|| PathBuf::from(map)),
+        });
+    }
+
+    let mut groups = Vec::new();
+    for _ in 0..reader.count(RANGE_SIZE)? {
+        let material = reader.index()?;
+        if material.is_some_and(|index| index >= materials.len()) {
+            return Err(invalid("group material out of range"));
+        }
+        groups.push(FaceGroup { material, faces: reader.range(triangle_count)? });
+    }
+
+    let mut objects = Vec::new();
+    for _ in 0..reader.count(RANGE_SIZE)? {
+        let name = reader.str()?;
+        objects.push(SubMesh { name, faces: reader.range(triangle_count)? });
+    }
+
+    Ok(Some(ProcessedModel { mesh: Mesh { vertices, indices }, materials, groups, objects, source_files, options }))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("corrupt mesh cache: {}", message))
+}
+
+// Codificación little-endian sin dependencias extra
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn len(&mut self, value: usize) {
+        self.u32(value as u32);
+    }
+
+    fn index(&mut self, value: Option<usize>) {
+        self.u32(value.map_or(NONE, |index| index as u32));
+    }
+
+    fn vec3(&mut self, value: &Vec3) {
+        self.f32(value.x);
+        self.f32(value.y);
+        self.f32(value.z);
+    }
+
+    fn str(&mut self, value: &str) {
+        self.len(value.len());
+        self.bytes.extend_from_slice(value.as_bytes());
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, count: usize) -> io::Result<&[u8]> {
+        let end = self.offset.checked_add(count).ok_or_else(|| invalid("unexpected end of file"))?;
+        let slice = self.bytes.get(self.offset..end).ok_or_else(|| invalid("unexpected end of file"))?;
+        self.offset = end;
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> io::Result<f32> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn len(&mut self) -> io::Result<usize> {
+        Ok(self.u32()? as usize)
+    }
+
+    // Cantidad de elementos de al menos `element_size` bytes: no puede pasar de lo que queda
+    fn count(&mut self, element_size: usize) -> io::Result<usize> {
+        let count = self.len()?;
+        let remaining = self.bytes.len() - self.offset;
+        if count.checked_mul(element_size).is_none_or(|size| size > remaining) {
+            return Err(invalid("element count exceeds file size"));
+        }
+        Ok(count)
+    }
+
+    // Rango de caras dentro de las `limit` que tiene la malla
+    fn range(&mut self, limit: usize) -> io::Result<std::ops::Range<usize>> {
+        let (start, end) = (self.len()?, self.len()?);
+        if start > end || end > limit {
+            return Err(invalid("face range out of bounds"));
+        }
+        Ok(start..end)
+    }
+
+    fn index(&mut self) -> io::Result<Option<usize>> {
+        let value = self.u32()?;
+        Ok((value != NONE).then_some(value as usize))
+    }
+
+    fn vec3(&mut self) -> io::Result<Vec3> {
+        Ok(Vec3::new(self.f32()?, self.f32()?, self.f32()?))
+    }
+
+    fn str(&mut self) -> io::Result<String> {
+        let len = self.len()?;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|_| invalid("string is not UTF-8"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obj_loader::Model;
+
+    // Dos objetos con UVs y normales: sin archivos fuente, la caché no depende del disco
+    const OBJ: &[u8] = b"v 0 0 0\nv 2 0 0\nv 0 1 0\nv 0 0 1\nvt 0 0\nvt 1 0\nvt 0 1\nvn 0 0 1\n\
+o Hull\nf 1/1/1 2/2/1 3/3/1\no Pod\nf 1/1/1 3/3/1 4/2/1\n";
+
+    fn model(options: MeshOptions) -> ProcessedModel {
+        Model::from_obj_bytes(OBJ, "test.obj").unwrap().process(options)
+    }
+
+    #[test]
+    fn round_trip_keeps_the_processed_model() {
+        let options = MeshOptions { crease_angle: Some(45.0) };
+        let original = model(options);
+        let decoded = decode(&encode(&original), options).expect("a fresh cache decodes");
+        assert_eq!(decoded.options, options);
+        assert_eq!(decoded.mesh.indices, original.mesh.indices);
+        assert_eq!(decoded.mesh.vertices.len(), original.mesh.vertices.len());
+        for (a, b) in decoded.mesh.vertices.iter().zip(&original.mesh.vertices) {
+            assert_eq!((a.position, a.normal, a.tex_coords), (b.position, b.normal, b.tex_coords));
+            assert_eq!((a.tangent, a.bitangent), (b.tangent, b.bitangent));
+        }
+        let faces = |model: &ProcessedModel| model.groups.iter().map(|group| (group.material, group.faces.clone())).collect::<Vec<_>>();
+        assert_eq!(faces(&decoded), faces(&original));
+        let objects = |model: &ProcessedModel| model.objects.iter().map(|object| (object.name.clone(), object.faces.clone())).collect::<Vec<_>>();
+        assert_eq!(objects(&decoded), vec![("Hull".to_string(), 0..1), ("Pod".to_string(), 1..2)]);
+        assert_eq!(objects(&decoded), objects(&original));
+    }
+
+    #[test]
+    fn other_options_make_the_cache_stale() {
+        let bytes = encode(&model(MeshOptions::default()));
+        assert!(decode(&bytes, MeshOptions::default()).is_some());
+        assert!(decode(&bytes, MeshOptions { crease_angle: Some(30.0) }).is_none());
+    }
+
+    #[test]
+    fn other_magic_or_version_is_rejected() {
+        let bytes = encode(&model(MeshOptions::default()));
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] ^= 0xFF;
+        assert!(decode(&wrong_magic, MeshOptions::default()).is_none());
+        let mut wrong_version = bytes;
+        wrong_version[4..8].copy_from_slice(&(VERSION + 1).to_le_bytes());
+        assert!(decode(&wrong_version, MeshOptions::default()).is_none());
+    }
+
+    #[test]
+    fn truncated_cache_is_rejected() {
+        let bytes = encode(&model(MeshOptions::default()));
+        for length in 0..bytes.len() {
+            assert!(decode(&bytes[..length], MeshOptions::default()).is_none(), "truncated at {}", length);
+        }
+    }
+
+    #[test]
+    fn corrupt_bytes_never_panic_or_index_out_of_bounds() {
+        let bytes = encode(&model(MeshOptions::default()));
+        for position in 0..bytes.len() {
+            for value in [0x00, 0x7F, 0xFF] {
+                let mut corrupt = bytes.clone();
+                corrupt[position] = value;
+                if let Some(model) = decode(&corrupt, MeshOptions::default()) {
+                    let vertex_count = model.mesh.vertices.len();
+                    assert!(model.mesh.indices.iter().all(|&index| (index as usize) < vertex_count));
+                    let triangles = model.mesh.indices.len() / 3;
+                    assert!(model.groups.iter().all(|group| group.faces.end <= triangles));
+                    assert!(model.objects.iter().all(|object| object.faces.end <= triangles));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::geometry::Mesh;
+
+// Radio en pantalla (pixeles) a partir del cual se pasa al siguiente nivel de detalle
+const LOD_SCREEN_RADIUS: [f32; 3] = [20.0, 60.0, 160.0];
+
+// Identificador de una malla del registro; los cuerpos guardan esto en vez de la geometría
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MeshId(usize);
+
+// Una malla con uno o más niveles de detalle (el 0 es el más simple). Cada frame se elige
+// el nivel según lo grande que se ve, así los cuerpos lejanos cuestan unas decenas de triángulos.
+pub struct LodMesh {
+    levels: Vec<Mesh>,
+}
+
+impl LodMesh {
+    pub fn new(levels: Vec<Mesh>) -> Self {
+        assert!(!levels.is_empty(), "a LodMesh needs at least one level");
+        LodMesh { levels }
+    }
+
+    pub fn level_for(&self, screen_radius: f32) -> &Mesh {
+        let level = LOD_SCREEN_RADIUS.iter().take_while(|&&threshold| screen_radius >= threshold).count();
+        &self.levels[level.min(self.levels.len() - 1)]
+    }
+
+    // Vértices de cada nivel, de menos a más detallado
+    pub fn vertex_counts(&self) -> Vec<usize> {
+        self.levels.iter().map(|mesh| mesh.vertices.len()).collect()
+    }
+}
+
+impl From<Mesh> for LodMesh {
+    fn from(mesh: Mesh) -> Self {
+        LodMesh::new(vec![mesh])
+    }
+}
+
+// Arena con todas las mallas de la escena. Varias entidades pueden compartir un mismo
+// `MeshId` (todos los planetas lisos usan la misma esfera) y el bucle de render no
+// necesita saber qué tipo de geometría es.
+pub struct MeshRegistry {
+    meshes: Vec<LodMesh>,
+}
+
+impl Default for MeshRegistry {
+    fn default() -> Self {
+        MeshRegistry::new()
+    }
+}
+
+impl MeshRegistry {
+    pub fn new() -> Self {
+        MeshRegistry { meshes: Vec::new() }
+    }
+
+    pub fn add(&mut self, mesh: impl Into<LodMesh>) -> MeshId {
+        self.meshes.push(mesh.into());
+        MeshId(self.meshes.len() - 1)
+    }
+
+    pub fn get(&self, id: MeshId) -> &LodMesh {
+        &self.meshes[id.0]
+    }
+
+    // Cambia la geometría sin invalidar el id (p. ej. al regenerar las esferas)
+    pub fn replace(&mut self, id: MeshId, mesh: impl Into<LodMesh>) {
+        self.meshes[id.0] = mesh.into();
+    }
+}
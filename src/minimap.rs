@@ -0,0 +1,138 @@
+use nalgebra_glm::{Vec2, Vec3};
+use crate::camera::FIELD_OF_VIEW_DEGREES;
+use crate::celestial_body::CelestialBody;
+use crate::framebuffer::Framebuffer;
+use crate::orbit::OrbitRing;
+use crate::palette::Palette;
+
+const MAP_SIZE: usize = 112;
+const MARGIN: usize = 6;
+const PANEL_COLOR: u32 = 0x000000;
+const PANEL_ALPHA: f32 = 0.55;
+const BORDER_COLOR: u32 = 0x7FA0C0;
+const ORBIT_COLOR: u32 = 0x7FA0C0;
+const SHIP_SIZE: f32 = 6.0;
+const FRUSTUM_LENGTH: f32 = 36.0;
+
+// Lo que el minimapa necesita de la simulación en cada frame
+pub struct MinimapData<'a> {
+    pub bodies: &'a [CelestialBody],
+    pub orbits: &'a [OrbitRing],
+    pub ship_position: Vec3,
+    pub ship_heading: Vec3,
+    pub camera_eye: Vec3,
+    pub camera_target: Vec3,
+    pub aspect_ratio: f32,
+    pub extent: f32,  // Radio del mundo (desde el origen) que cubre el mapa
+    pub palette: &'a Palette,
+}
+
+// Vista cenital del plano XZ (x a la derecha, z hacia abajo) en la esquina inferior derecha
+struct MapView {
+    center: Vec2,  // Centro del mapa en pixeles
+    radius: f32,   // Radio útil en pixeles
+    scale: f32,    // Pixeles por unidad del mundo
+    rect: (i32, i32, i32, i32),
+}
+
+impl MapView {
+    fn to_map(&self, point: Vec3) -> Vec2 {
+        self.center + Vec2::new(point.x, point.z) * self.scale
+    }
+
+    // Los puntos fuera del mapa se pegan al borde en su dirección
+    fn to_map_clamped(&self, point: Vec3) -> Vec2 {
+        let offset = Vec2::new(point.x, point.z) * self.scale;
+        let length = offset.magnitude();
+        if length > self.radius {
+            self.center + offset * (self.radius / length)
+        } else {
+            self.center + offset
+        }
+    }
+
+    fn line(&self, framebuffer: &mut Framebuffer, from: Vec2, to: Vec2, color: u32, alpha: f32) {
+        let from = (from.x.round() as i32, from.y.round() as i32);
+        let to = (to.x.round() as i32, to.y.round() as i32);
+        framebuffer.blend_line_in_rect(self.rect, from, to, color, alpha);
+    }
+
+    fn disc(&self, framebuffer: &mut Framebuffer, center: Vec2, radius: f32, color: u32) {
+        let reach = radius.ceil() as i32;
+        let (left, top, width, height) = self.rect;
+        for dy in -reach..=reach {
+            for dx in -reach..=reach {
+                if (dx * dx + dy * dy) as f32 > radius * radius {
+                    continue;
+                }
+                let (x, y) = (center.x.round() as i32 + dx, center.y.round() as i32 + dy);
+                if x >= left && y >= top && x < left + width && y < top + height {
+                    framebuffer.set_pixel(x as usize, y as usize, color);
+                }
+            }
+        }
+    }
+}
+
+pub fn render_minimap(framebuffer: &mut Framebuffer, data: &MinimapData) {
+    if framebuffer.width < MAP_SIZE + 2 * MARGIN || framebuffer.height < MAP_SIZE + 2 * MARGIN {
+        return;
+    }
+    let x = framebuffer.width - MAP_SIZE - MARGIN;
+    let y = framebuffer.height - MAP_SIZE - MARGIN;
+    framebuffer.blend_rect(x, y, MAP_SIZE, MAP_SIZE, PANEL_COLOR, PANEL_ALPHA);
+    framebuffer.draw_rect(x, y, MAP_SIZE, MAP_SIZE, BORDER_COLOR);
+
+    let radius = (MAP_SIZE / 2 - 4) as f32;
+    let view = MapView {
+        center: Vec2::new((x + MAP_SIZE / 2) as f32, (y + MAP_SIZE / 2) as f32),
+        radius,
+        scale: radius / data.extent.max(f32::EPSILON),
+        rect: (x as i32 + 1, y as i32 + 1, MAP_SIZE as i32 - 2, MAP_SIZE as i32 - 2),
+    };
+
+    // Órbitas como polígonos de 48 lados
+    for orbit in data.orbits.iter().filter(|orbit| orbit.is_shown(data.bodies)) {
+        let color = data.palette.orbit_color(orbit.slot, ORBIT_COLOR);
+        let points: Vec<Vec2> = (0..=48)
+            .map(|i| {
+                let angle = i as f32 / 48.0 * std::f32::consts::TAU;
+                view.to_map(orbit.point_at(angle))
+            })
+            .collect();
+        for segment in points.windows(2) {
+            view.line(framebuffer, segment[0], segment[1], color, 0.35);
+        }
+    }
+
+    for body in data.bodies.iter().filter(|body| body.is_drawn()) {
+        let size = (body.scale * view.scale).clamp(1.5, 6.0);
+        view.disc(framebuffer, view.to_map(body.position), size, body.shader_type.base_color());
+    }
+
+    // Cuña del campo de visión horizontal de la cámara
+    let camera = view.to_map_clamped(data.camera_eye);
+    let look = Vec2::new(data.camera_target.x - data.camera_eye.x, data.camera_target.z - data.camera_eye.z);
+    if look.magnitude() > 1e-3 {
+        let half_fov = ((FIELD_OF_VIEW_DEGREES.to_radians() / 2.0).tan() * data.aspect_ratio).atan();
+        let heading = look.y.atan2(look.x);
+        for side in [-1.0, 1.0] {
+            let angle = heading + side * half_fov;
+            let edge = camera + Vec2::new(angle.cos(), angle.sin()) * FRUSTUM_LENGTH;
+            view.line(framebuffer, camera, edge, data.palette.selection, 0.6);
+        }
+    }
+    view.disc(framebuffer, camera, 1.5, data.palette.selection);
+
+    // Nave como flecha en la dirección en que apunta
+    let ship = view.to_map_clamped(data.ship_position);
+    let forward = Vec2::new(data.ship_heading.x, data.ship_heading.z);
+    let forward = if forward.magnitude() > 1e-3 { forward.normalize() } else { Vec2::new(0.0, -1.0) };
+    let side = Vec2::new(-forward.y, forward.x);
+    let tip = ship + forward * SHIP_SIZE;
+    let left = ship - forward * (SHIP_SIZE * 0.5) + side * (SHIP_SIZE * 0.6);
+    let right = ship - forward * (SHIP_SIZE * 0.5) - side * (SHIP_SIZE * 0.6);
+    for (from, to) in [(tip, left), (left, right), (right, tip)] {
+        view.line(framebuffer, from, to, data.palette.ship, 1.0);
+    }
+}
@@ -0,0 +1,294 @@
+use std::f32::consts::{PI, TAU};
+use log::warn;
+use crate::celestial_body::ShaderType;
+use crate::i18n::tr_with;
+use crate::scene::Scene;
+
+// La nave "visita" un cuerpo al pasar a menos de esta cantidad de radios de su superficie
+const VISIT_RADII: f32 = 1.0;
+// Una órbita cuenta mientras la nave se mantiene entre estas distancias del centro (en radios
+// del cuerpo); al salir de la banda la vuelta empieza de cero
+const ORBIT_MIN_RADII: f32 = 1.2;
+const ORBIT_MAX_RADII: f32 = 4.0;
+
+// Condición de un objetivo o del disparador de una misión, como se escribe en la escena o en
+// un script: `visit Ares`, `visit all`, `orbit Zeus`, `touchdown Phobos` (o `dock Phobos`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Visit(String),
+    VisitAll,           // Un `visit` por cada cuerpo que no sea una estrella
+    Orbit(String),      // Una vuelta completa alrededor del cuerpo sin tocarlo
+    Touchdown(String),  // Tocarlo despacio (ver `events::contact_event`)
+}
+
+impl Condition {
+    pub fn parse(text: &str) -> Option<Condition> {
+        let (verb, body) = text.trim().split_once(char::is_whitespace)?;
+        let body = body.trim().to_string();
+        match verb.to_ascii_lowercase().as_str() {
+            "visit" if body.eq_ignore_ascii_case("all") => Some(Condition::VisitAll),
+            "visit" => Some(Condition::Visit(body)),
+            "orbit" => Some(Condition::Orbit(body)),
+            "touchdown" | "dock" => Some(Condition::Touchdown(body)),
+            _ => None,
+        }
+    }
+}
+
+// Tal como se escribe, para exportar la escena
+impl std::fmt::Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Condition::Visit(body) => write!(f, "visit {}", body),
+            Condition::VisitAll => write!(f, "visit all"),
+            Condition::Orbit(body) => write!(f, "orbit {}", body),
+            Condition::Touchdown(body) => write!(f, "touchdown {}", body),
+        }
+    }
+}
+
+// Una misión tal como se declara (sección `[mission]` de la escena o `add_mission` en un script)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MissionSpec {
+    pub name: String,
+    pub objectives: Vec<Condition>,
+    pub trigger: Option<Condition>,  // Empieza cuando se cumple (None = apenas le toca)
+    pub time_limit: Option<f32>,     // Segundos reales desde que empieza
+    pub fuel: Option<f32>,           // Segundos de empuje disponibles
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Goal {
+    Visit,
+    Orbit,
+    Touchdown,
+}
+
+#[derive(Debug, Clone)]
+struct Objective {
+    goal: Goal,
+    body: usize,
+    done: bool,
+    swept: f32,               // Ángulo recorrido alrededor del cuerpo dentro de la banda (órbitas)
+    last_angle: Option<f32>,  // Ángulo de la nave alrededor del cuerpo en el paso anterior
+}
+
+impl Objective {
+    fn new(goal: Goal, body: usize) -> Self {
+        Objective { goal, body, done: false, swept: 0.0, last_angle: None }
+    }
+
+    fn label(&self, scene: &Scene) -> String {
+        let body = scene.bodies.get(self.body).map_or("?", |body| body.name);
+        let id = match self.goal {
+            Goal::Visit => "mission.visit",
+            Goal::Orbit => "mission.orbit",
+            Goal::Touchdown => "mission.touchdown",
+        };
+        tr_with(id, &[("body", &body)])
+    }
+
+    // Fracción hecha: 0 o 1, salvo las órbitas que avanzan con la vuelta
+    fn progress(&self) -> f32 {
+        match (self.done, self.goal) {
+            (true, _) => 1.0,
+            (false, Goal::Orbit) => (self.swept.abs() / TAU).min(1.0),
+            (false, _) => 0.0,
+        }
+    }
+
+    fn check(&mut self, scene: &Scene, touched: &[usize]) {
+        let Some(body) = scene.bodies.get(self.body) else {
+            return;
+        };
+        let offset = scene.spaceship.position - body.position;
+        let distance = offset.magnitude();
+        self.done |= match self.goal {
+            Goal::Visit => distance - body.scale < body.scale * VISIT_RADII,
+            Goal::Touchdown => touched.contains(&self.body),
+            Goal::Orbit => {
+                // La nave se mueve en el plano XZ: se acumula el giro alrededor de Y
+                if !(body.scale * ORBIT_MIN_RADII..=body.scale * ORBIT_MAX_RADII).contains(&distance) {
+                    self.swept = 0.0;
+                    self.last_angle = None;
+                    return;
+                }
+                let angle = offset.z.atan2(offset.x);
+                if let Some(last) = self.last_angle {
+                    self.swept += (angle - last + PI).rem_euclid(TAU) - PI;
+                }
+                self.last_angle = Some(angle);
+                self.swept.abs() >= TAU
+            }
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MissionState {
+    Waiting,  // Espera su disparador
+    Active,
+    Complete,
+    Failed,
+}
+
+#[derive(Debug, Clone)]
+pub struct Mission {
+    pub name: String,
+    pub state: MissionState,
+    objectives: Vec<Objective>,
+    trigger: Option<Objective>,
+    time_limit: Option<f32>,
+    fuel: Option<f32>,
+    elapsed: f32,
+    fuel_used: f32,
+}
+
+// Lo que el HUD muestra de la misión en curso
+pub struct MissionStatus {
+    pub name: String,
+    pub waiting: bool,         // Todavía no empezó: `objective` es su disparador
+    pub done: usize,
+    pub total: usize,
+    pub objective: String,     // El primer objetivo pendiente
+    pub progress: f32,         // De ese objetivo, 0-1
+    pub time_left: Option<f32>,
+    pub fuel_left: Option<f32>,
+}
+
+// Misiones de la escena y de los scripts, de a una por vez en el orden en que se agregaron.
+// Cada una espera su disparador, después cuenta sus objetivos (en cualquier orden) y termina
+// cumplida al hacerlos todos o fallida si se acaba el tiempo o el combustible.
+#[derive(Debug, Default)]
+pub struct Missions {
+    missions: Vec<Mission>,
+    touched: Vec<usize>,  // Cuerpos en los que la nave aterrizó desde el último `update`
+}
+
+impl Missions {
+    // Los nombres de cuerpos se resuelven contra la escena; los que no existen se avisan y se
+    // descartan
+    pub fn add(&mut self, spec: &MissionSpec, scene: &Scene) {
+        let resolve = |condition: &Condition| -> Vec<Objective> {
+            let (goal, name) = match condition {
+                Condition::VisitAll => {
+                    return scene.bodies
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, body)| body.shader_type != ShaderType::Sun)
+                        .map(|(index, _)| Objective::new(Goal::Visit, index))
+                        .collect();
+                }
+                Condition::Visit(name) => (Goal::Visit, name),
+                Condition::Orbit(name) => (Goal::Orbit, name),
+                Condition::Touchdown(name) => (Goal::Touchdown, name),
+            };
+            match scene.bodies.iter().position(|body| body.name.eq_ignore_ascii_case(name)) {
+                Some(index) => vec![Objective::new(goal, index)],
+                None => {
+                    warn!("Mission `{}`: unknown body `{}`", spec.name, name);
+                    Vec::new()
+                }
+            }
+        };
+        let objectives: Vec<Objective> = spec.objectives.iter().flat_map(resolve).collect();
+        if objectives.is_empty() {
+            warn!("Mission `{}` has no objectives; ignoring it", spec.name);
+            return;
+        }
+        let trigger = spec.trigger.as_ref().and_then(|condition| resolve(condition).into_iter().next());
+        self.missions.push(Mission {
+            name: spec.name.clone(),
+            state: MissionState::Waiting,
+            objectives,
+            trigger,
+            time_limit: spec.time_limit,
+            fuel: spec.fuel,
+            elapsed: 0.0,
+            fuel_used: 0.0,
+        });
+    }
+
+    fn current_mut(&mut self) -> Option<&mut Mission> {
+        self.missions.iter_mut().find(|mission| matches!(mission.state, MissionState::Waiting | MissionState::Active))
+    }
+
+    pub fn current(&self) -> Option<&Mission> {
+        self.missions.iter().find(|mission| matches!(mission.state, MissionState::Waiting | MissionState::Active))
+    }
+
+    pub fn touchdown(&mut self, body: usize) {
+        self.touched.push(body);
+    }
+
+    // Empuje de la nave durante `seconds`: gasta combustible de la misión en curso
+    pub fn burn(&mut self, seconds: f32) {
+        if let Some(mission) = self.current_mut().filter(|mission| mission.state == MissionState::Active) {
+            mission.fuel_used += seconds;
+        }
+    }
+
+    // Avanza la misión en curso; devuelve lo que pasó (empezó, objetivo cumplido, terminó) ya
+    // traducido, para el registro de sucesos
+    pub fn update(&mut self, scene: &Scene, delta_time: f32) -> Vec<String> {
+        let touched = std::mem::take(&mut self.touched);
+        let mut notices = Vec::new();
+        let Some(mission) = self.current_mut() else {
+            return notices;
+        };
+
+        if mission.state == MissionState::Waiting {
+            if let Some(trigger) = &mut mission.trigger {
+                trigger.check(scene, &touched);
+                if !trigger.done {
+                    return notices;
+                }
+            }
+            mission.state = MissionState::Active;
+            notices.push(tr_with("event.mission_started", &[("mission", &mission.name)]));
+            return notices;
+        }
+
+        mission.elapsed += delta_time;
+        for objective in &mut mission.objectives {
+            if !objective.done {
+                objective.check(scene, &touched);
+                if objective.done {
+                    notices.push(tr_with("event.objective_done", &[("objective", &objective.label(scene))]));
+                }
+            }
+        }
+        if mission.objectives.iter().all(|objective| objective.done) {
+            mission.state = MissionState::Complete;
+            notices.push(tr_with("event.mission_complete", &[("mission", &mission.name)]));
+        } else if mission.time_limit.is_some_and(|limit| mission.elapsed >= limit) {
+            mission.state = MissionState::Failed;
+            notices.push(tr_with("event.mission_out_of_time", &[("mission", &mission.name)]));
+        } else if mission.fuel.is_some_and(|fuel| mission.fuel_used >= fuel) {
+            mission.state = MissionState::Failed;
+            notices.push(tr_with("event.mission_out_of_fuel", &[("mission", &mission.name)]));
+        }
+        notices
+    }
+
+    pub fn status(&self, scene: &Scene) -> Option<MissionStatus> {
+        let mission = self.current()?;
+        let waiting = mission.state == MissionState::Waiting;
+        let next = if waiting {
+            mission.trigger.as_ref()
+        } else {
+            mission.objectives.iter().find(|objective| !objective.done)
+        };
+        let active = |value: Option<f32>| value.filter(|_| !waiting);
+        Some(MissionStatus {
+            name: mission.name.clone(),
+            waiting,
+            done: mission.objectives.iter().filter(|objective| objective.done).count(),
+            total: mission.objectives.len(),
+            objective: next.map(|objective| objective.label(scene)).unwrap_or_default(),
+            progress: next.map_or(0.0, Objective::progress),
+            time_left: active(mission.time_limit.map(|limit| (limit - mission.elapsed).max(0.0))),
+            fuel_left: active(mission.fuel.map(|fuel| (fuel - mission.fuel_used).max(0.0))),
+        })
+    }
+}
@@ -0,0 +1,397 @@
+use nalgebra_glm::Vec3;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, ErrorKind};
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs, UdpSocket};
+use log::{debug, info, warn};
+use web_time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::scene::Scene;
+
+// Puerto UDP del anfitrión si no se indica otro
+pub const DEFAULT_SYNC_PORT: u16 = 47800;
+
+// Estados por segundo que manda el anfitrión (entre uno y otro cada espectador sigue simulando)
+const SEND_RATE: f32 = 30.0;
+// Cada cuánto saluda un espectador, y cuánto silencio se tolera antes de darlo por perdido
+const HELLO_INTERVAL: Duration = Duration::from_secs(1);
+const PEER_TIMEOUT: Duration = Duration::from_secs(5);
+const HELLO: &[u8] = b"SPACETRAVEL-SYNC-HELLO";
+const MAX_DATAGRAM: usize = 65_507;
+
+// Cómo se sincroniza una instancia con otras (`--sync-host` / `--sync-join`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncMode {
+    Host(String),  // Dirección en la que escucha, "ip:puerto"
+    Join(String),  // Anfitrión al que seguir, "host" o "host:puerto"
+}
+
+// Lo que el anfitrión manda en cada datagrama (JSON). Las posiciones de los cuerpos también
+// saldrían del reloj, pero se mandan para que el espectador vea lo mismo aunque su escena
+// calcule distinto; los disparos y las animaciones de warp no viajan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncState {
+    pub session: u64,   // Distinta en cada arranque del anfitrión
+    pub sequence: u64,  // Creciente: los datagramas viejos o repetidos se descartan
+    pub clock_seconds: f64,
+    pub time: f32,
+    pub time_scale: f32,
+    pub orbits_running: bool,
+    pub ship: ShipSync,
+    pub bodies: Vec<BodySync>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShipSync {
+    pub position: [f32; 3],
+    pub rotation: [f32; 3],
+    pub velocity: [f32; 3],
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BodySync {
+    pub name: String,
+    pub position: [f32; 3],
+    pub rotation: [f32; 3],
+}
+
+impl SyncState {
+    pub fn capture(scene: &Scene, time_scale: f32, orbits_running: bool) -> SyncState {
+        let ship = &scene.spaceship;
+        SyncState {
+            session: 0,
+            sequence: 0,
+            clock_seconds: scene.clock.seconds,
+            time: scene.time,
+            time_scale,
+            orbits_running,
+            ship: ShipSync {
+                position: array(ship.position),
+                rotation: array(ship.rotation),
+                velocity: array(ship.velocity),
+            },
+            bodies: scene.bodies
+                .iter()
+                .map(|body| BodySync { name: body.name.to_string(), position: array(body.position), rotation: array(body.rotation) })
+                .collect(),
+        }
+    }
+
+    // Lleva la escena al estado recibido; los cuerpos se buscan por nombre y los que el
+    // anfitrión no conoce quedan donde los pone el reloj. Un estado con valores no finitos se
+    // descarta entero y devuelve false (serde_json ya rechaza los números fuera de rango, pero
+    // el estado no tiene por qué venir de ahí).
+    pub fn apply(&self, scene: &mut Scene) -> bool {
+        if !self.is_finite() {
+            warn!("Ignoring sync state with non-finite values");
+            return false;
+        }
+        scene.clock.seconds = self.clock_seconds;
+        scene.time = self.time;
+        scene.place_bodies();
+        for state in &self.bodies {
+            if let Some(body) = scene.bodies.iter_mut().find(|body| body.name == state.name) {
+                body.position = vec3(state.position);
+                body.rotation = vec3(state.rotation);
+            }
+        }
+        scene.center_orbits();
+
+        let ship = &mut scene.spaceship;
+        ship.position = vec3(self.ship.position);
+        ship.rotation = vec3(self.ship.rotation);
+        ship.velocity = vec3(self.ship.velocity);
+        true
+    }
+
+    fn is_finite(&self) -> bool {
+        let finite = |values: &[f32]| values.iter().all(|value| value.is_finite());
+        self.clock_seconds.is_finite()
+            && finite(&[self.time, self.time_scale])
+            && [self.ship.position, self.ship.rotation, self.ship.velocity].iter().all(|values| finite(values))
+            && self.bodies.iter().all(|body| finite(&body.position) && finite(&body.rotation))
+    }
+}
+
+// Lo que pasó en la red este frame, para que el engine avise o aplique el estado
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncEvent {
+    ViewerJoined(SocketAddr),
+    ViewerLeft(SocketAddr),
+    Connected(SocketAddr),  // El espectador recibió el primer estado (o volvió a recibir)
+    Lost,                   // El espectador dejó de recibir estados
+    State(Box<SyncState>),  // Estado nuevo del anfitrión
+}
+
+// Sincronización por UDP para ver la misma simulación desde varias instancias (una clase en
+// la que cada alumno orbita el mismo sistema con su propia cámara). El anfitrión simula y
+// manda el estado a los espectadores que lo saludaron hace poco; los espectadores lo aplican
+// y solo controlan su cámara. Pensado para una red local: no hay cifrado ni autenticación.
+pub enum NetSync {
+    Host(SyncHost),
+    Viewer(SyncViewer),
+}
+
+pub struct SyncHost {
+    socket: UdpSocket,
+    session: u64,
+    sequence: u64,
+    viewers: HashMap<SocketAddr, Instant>,  // Último saludo de cada espectador
+    since_send: f32,
+}
+
+pub struct SyncViewer {
+    socket: UdpSocket,
+    host: SocketAddr,
+    last_hello: Option<Instant>,
+    last_state: Option<Instant>,
+    session: u64,
+    sequence: u64,
+    receiving: bool,
+}
+
+impl NetSync {
+    pub fn open(mode: &SyncMode) -> io::Result<NetSync> {
+        match mode {
+            SyncMode::Host(address) => NetSync::host(address),
+            SyncMode::Join(address) => NetSync::join(address),
+        }
+    }
+
+    pub fn host(address: &str) -> io::Result<NetSync> {
+        let socket = UdpSocket::bind(address)?;
+        socket.set_nonblocking(true)?;
+        info!("Sync host listening on {}", socket.local_addr()?);
+        let session = SystemTime::now().duration_since(UNIX_EPOCH).map_or(1, |elapsed| elapsed.as_nanos() as u64);
+        Ok(NetSync::Host(SyncHost { socket, session, sequence: 0, viewers: HashMap::new(), since_send: 0.0 }))
+    }
+
+    pub fn join(address: &str) -> io::Result<NetSync> {
+        let host = resolve(address)?;
+        let socket = UdpSocket::bind(if host.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" })?;
+        socket.connect(host)?;
+        socket.set_nonblocking(true)?;
+        info!("Sync viewer following {}", host);
+        Ok(NetSync::Viewer(SyncViewer {
+            socket,
+            host,
+            last_hello: None,
+            last_state: None,
+            session: 0,
+            sequence: 0,
+            receiving: false,
+        }))
+    }
+
+    // El espectador no controla la nave ni el tiempo: los recibe del anfitrión
+    pub fn is_viewer(&self) -> bool {
+        matches!(self, NetSync::Viewer(_))
+    }
+
+    // Una vez por frame. El anfitrión atiende saludos y, a `SEND_RATE`, manda lo que devuelva
+    // `capture`; el espectador saluda y recoge el estado más nuevo que haya llegado.
+    pub fn update(&mut self, frame_time: f32, capture: impl FnOnce() -> SyncState) -> Vec<SyncEvent> {
+        match self {
+            NetSync::Host(host) => host.update(frame_time, capture),
+            NetSync::Viewer(viewer) => viewer.update(),
+        }
+    }
+}
+
+impl SyncHost {
+    fn update(&mut self, frame_time: f32, capture: impl FnOnce() -> SyncState) -> Vec<SyncEvent> {
+        let mut events = Vec::new();
+        let now = Instant::now();
+        let mut buffer = [0; 64];
+        loop {
+            match self.socket.recv_from(&mut buffer) {
+                Ok((length, from)) if &buffer[..length] == HELLO => {
+                    if self.viewers.insert(from, now).is_none() {
+                        info!("Sync viewer joined from {}", from);
+                        events.push(SyncEvent::ViewerJoined(from));
+                    }
+                }
+                Ok((_, from)) => debug!("Ignoring unknown datagram from {}", from),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                // Windows avisa así que un espectador cerró su puerto
+                Err(e) if e.kind() == ErrorKind::ConnectionReset => continue,
+                Err(e) => {
+                    warn!("Sync host receive failed: {}", e);
+                    break;
+                }
+            }
+        }
+
+        let gone: Vec<SocketAddr> = self.viewers
+            .iter()
+            .filter(|(_, &seen)| now.duration_since(seen) > PEER_TIMEOUT)
+            .map(|(&address, _)| address)
+            .collect();
+        for address in gone {
+            self.viewers.remove(&address);
+            info!("Sync viewer {} left", address);
+            events.push(SyncEvent::ViewerLeft(address));
+        }
+
+        self.since_send += frame_time;
+        if self.viewers.is_empty() || self.since_send < 1.0 / SEND_RATE {
+            return events;
+        }
+        self.since_send = 0.0;
+        self.sequence += 1;
+        let state = SyncState { session: self.session, sequence: self.sequence, ..capture() };
+        let datagram = match serde_json::to_vec(&state) {
+            Ok(datagram) if datagram.len() <= MAX_DATAGRAM => datagram,
+            Ok(datagram) => {
+                warn!("Sync state too large for one datagram ({} bytes)", datagram.len());
+                return events;
+            }
+            Err(e) => {
+                warn!("Could not encode the sync state: {}", e);
+                return events;
+            }
+        };
+        for address in self.viewers.keys() {
+            if let Err(e) = self.socket.send_to(&datagram, address) {
+                debug!("Sync send to {} failed: {}", address, e);
+            }
+        }
+        events
+    }
+}
+
+impl SyncViewer {
+    fn update(&mut self) -> Vec<SyncEvent> {
+        let mut events = Vec::new();
+        let now = Instant::now();
+        if self.last_hello.is_none_or(|hello| now.duration_since(hello) >= HELLO_INTERVAL) {
+            if let Err(e) = self.socket.send(HELLO) {
+                debug!("Sync hello to {} failed: {}", self.host, e);
+            }
+            self.last_hello = Some(now);
+        }
+
+        // Solo importa el último: los anteriores se descartan sin aplicar
+        let mut latest: Option<SyncState> = None;
+        let mut buffer = vec![0; MAX_DATAGRAM];
+        loop {
+            match self.socket.recv(&mut buffer) {
+                Ok(length) => match serde_json::from_slice::<SyncState>(&buffer[..length]) {
+                    Ok(state) if state.session != self.session || state.sequence > self.sequence => {
+                        self.session = state.session;
+                        self.sequence = state.sequence;
+                        latest = Some(state);
+                    }
+                    Ok(_) => {}
+                    Err(e) => debug!("Ignoring invalid sync datagram: {}", e),
+                },
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                // El anfitrión todavía no escucha (o se cerró): se sigue saludando
+                Err(e) if e.kind() == ErrorKind::ConnectionRefused || e.kind() == ErrorKind::ConnectionReset => break,
+                Err(e) => {
+                    warn!("Sync viewer receive failed: {}", e);
+                    break;
+                }
+            }
+        }
+
+        if let Some(state) = latest {
+            self.last_state = Some(now);
+            if !self.receiving {
+                self.receiving = true;
+                info!("Receiving sync state from {}", self.host);
+                events.push(SyncEvent::Connected(self.host));
+            }
+            events.push(SyncEvent::State(Box::new(state)));
+        } else if self.receiving && self.last_state.is_some_and(|last| now.duration_since(last) > PEER_TIMEOUT) {
+            self.receiving = false;
+            warn!("Lost the sync host {}", self.host);
+            events.push(SyncEvent::Lost);
+        }
+        events
+    }
+}
+
+// Sin puerto ("192.168.0.10", "aula-pc", "::1") se usa `DEFAULT_SYNC_PORT`
+fn resolve(address: &str) -> io::Result<SocketAddr> {
+    if let Ok(ip) = address.parse::<IpAddr>() {
+        return Ok(SocketAddr::new(ip, DEFAULT_SYNC_PORT));
+    }
+    let with_port = if address.contains(':') { address.to_string() } else { format!("{}:{}", address, DEFAULT_SYNC_PORT) };
+    // El anfitrión escucha en IPv4 por defecto: "localhost" tiene que ser 127.0.0.1, no ::1
+    let addresses: Vec<SocketAddr> = with_port.to_socket_addrs()?.collect();
+    addresses
+        .iter()
+        .find(|address| address.is_ipv4())
+        .or(addresses.first())
+        .copied()
+        .ok_or_else(|| io::Error::new(ErrorKind::NotFound, format!("{} did not resolve to an address", address)))
+}
+
+fn array(v: Vec3) -> [f32; 3] {
+    [v.x, v.y, v.z]
+}
+
+fn vec3(a: [f32; 3]) -> Vec3 {
+    Vec3::new(a[0], a[1], a[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> SyncState {
+        SyncState {
+            session: 1,
+            sequence: 2,
+            clock_seconds: 3600.0,
+            time: 12.5,
+            time_scale: 2.0,
+            orbits_running: true,
+            ship: ShipSync { position: [1.0, 0.0, 2.0], rotation: [0.0, 1.5, 0.0], velocity: [0.0, 0.0, -1.0] },
+            bodies: vec![BodySync { name: "Sol".to_string(), position: [0.0; 3], rotation: [0.0, 0.3, 0.0] }],
+        }
+    }
+
+    #[test]
+    fn state_survives_the_json_round_trip() {
+        let original = state();
+        let bytes = serde_json::to_vec(&original).unwrap();
+        assert_eq!(serde_json::from_slice::<SyncState>(&bytes).unwrap(), original);
+    }
+
+    #[test]
+    fn finite_state_is_accepted() {
+        assert!(state().is_finite());
+    }
+
+    #[test]
+    fn any_non_finite_value_rejects_the_state() {
+        let corruptions: [fn(&mut SyncState); 7] = [
+            |state| state.clock_seconds = f64::NAN,
+            |state| state.time = f32::INFINITY,
+            |state| state.time_scale = f32::NAN,
+            |state| state.ship.position[1] = f32::NEG_INFINITY,
+            |state| state.ship.rotation[0] = f32::NAN,
+            |state| state.ship.velocity[2] = f32::INFINITY,
+            |state| state.bodies[0].rotation[1] = f32::NAN,
+        ];
+        for (index, corrupt) in corruptions.iter().enumerate() {
+            let mut state = state();
+            corrupt(&mut state);
+            assert!(!state.is_finite(), "corruption {}", index);
+        }
+    }
+
+    #[test]
+    fn out_of_range_json_number_is_not_a_state() {
+        let json = serde_json::to_string(&state()).unwrap().replace("[1.0,0.0,2.0]", "[1e39,0.0,2.0]");
+        assert!(serde_json::from_str::<SyncState>(&json).is_err());
+    }
+
+    #[test]
+    fn addresses_without_port_use_the_default() {
+        assert_eq!(resolve("127.0.0.1").unwrap(), SocketAddr::from(([127, 0, 0, 1], DEFAULT_SYNC_PORT)));
+        assert_eq!(resolve("127.0.0.1:5000").unwrap(), SocketAddr::from(([127, 0, 0, 1], 5000)));
+        assert_eq!(resolve("::1").unwrap().port(), DEFAULT_SYNC_PORT);
+    }
+}
@@ -0,0 +1,122 @@
+use nalgebra_glm::{self as glm, Vec2, Vec3};
+use std::collections::HashMap;
+use crate::vertex::Vertex;
+
+// Ángulo de pliegue por defecto: caras que difieren más que esto mantienen una arista dura
+pub const DEFAULT_CREASE_ANGLE: f32 = 60.0;
+
+// Tamaño de la rejilla para fundir posiciones casi iguales en listas de triángulos sueltos
+const WELD_EPSILON: f32 = 1e-4;
+
+// Normales suavizadas por esquina. Cada esquina promedia las normales de las caras que
+// comparten su posición, ponderadas por área (el producto cruz sin normalizar ya mide
+// el doble del área), pero solo las que forman con su propia cara un ángulo menor que
+// `crease_angle_degrees`. Con 0 grados el resultado es sombreado plano.
+pub fn area_weighted_normals(positions: &[Vec3], triangles: &[[usize; 3]], crease_angle_degrees: f32) -> Vec<[Vec3; 3]> {
+    let face_normals: Vec<Vec3> = triangles
+        .iter()
+        .map(|[a, b, c]| glm::cross(&(positions[*b] - positions[*a]), &(positions[*c] - positions[*a])))
+        .collect();
+
+    let mut faces_at: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (face, triangle) in triangles.iter().enumerate() {
+        for &position in triangle {
+            faces_at.entry(position).or_default().push(face);
+        }
+    }
+
+    let cos_crease = crease_angle_degrees.clamp(0.0, 180.0).to_radians().cos();
+    let unit = |normal: &Vec3| {
+        let length = glm::length(normal);
+        (length > f32::EPSILON).then(|| normal / length)
+    };
+
+    triangles
+        .iter()
+        .enumerate()
+        .map(|(face, triangle)| {
+            let own = unit(&face_normals[face]);
+            triangle.map(|position| {
+                let sum = faces_at[&position]
+                    .iter()
+                    .filter(|&&other| match (own, unit(&face_normals[other])) {
+                        (Some(own), Some(other)) => glm::dot(&own, &other) >= cos_crease - 1e-6,
+                        // Caras degeneradas: aceptan (o aportan) sin comparar ángulos
+                        _ => true,
+                    })
+                    .fold(Vec3::zeros(), |sum, &other| sum + face_normals[other]);
+                unit(&sum).or(own).unwrap_or_else(|| Vec3::new(0.0, 1.0, 0.0))
+            })
+        })
+        .collect()
+}
+
+// Igual que `area_weighted_normals` pero para listas de triángulos sin índices (como la
+// esfera procedural): las esquinas con la misma posición se tratan como un solo vértice
+pub fn smooth_triangle_list(vertices: &mut [Vertex], crease_angle_degrees: f32) {
+    let mut welded: HashMap<[i32; 3], usize> = HashMap::new();
+    let mut positions = Vec::new();
+    let corners: Vec<usize> = vertices
+        .iter()
+        .map(|vertex| {
+            let key = [0, 1, 2].map(|axis| (vertex.position[axis] / WELD_EPSILON).round() as i32);
+            *welded.entry(key).or_insert_with(|| {
+                positions.push(vertex.position);
+                positions.len() - 1
+            })
+        })
+        .collect();
+
+    let triangles: Vec<[usize; 3]> = corners.chunks_exact(3).map(|c| [c[0], c[1], c[2]]).collect();
+    let normals = area_weighted_normals(&positions, &triangles, crease_angle_degrees);
+
+    for (triangle, normals) in vertices.chunks_exact_mut(3).zip(normals) {
+        for (vertex, normal) in triangle.iter_mut().zip(normals) {
+            vertex.normal = normal;
+            vertex.transformed_normal = normal;
+        }
+    }
+}
+
+// Tangente y bitangente por vértice a partir de las UV (método de Lengyel): cada triángulo
+// aporta las direcciones del espacio de modelo en que crecen u y v, se acumulan por vértice
+// y la tangente se ortogonaliza contra la normal (Gram-Schmidt). La bitangente conserva el
+// sentido de v, así que sale invertida en las zonas con UV espejadas.
+pub fn compute_tangents(vertices: &mut [Vertex], indices: &[u32]) {
+    let mut tangents = vec![Vec3::zeros(); vertices.len()];
+    let mut bitangents = vec![Vec3::zeros(); vertices.len()];
+
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|index| index as usize);
+        let edge1 = vertices[b].position - vertices[a].position;
+        let edge2 = vertices[c].position - vertices[a].position;
+        let duv1: Vec2 = vertices[b].tex_coords - vertices[a].tex_coords;
+        let duv2: Vec2 = vertices[c].tex_coords - vertices[a].tex_coords;
+
+        let determinant = duv1.x * duv2.y - duv2.x * duv1.y;
+        if determinant.abs() <= f32::EPSILON {
+            continue;  // UV degeneradas: el triángulo no define direcciones
+        }
+        let r = 1.0 / determinant;
+        let tangent = (edge1 * duv2.y - edge2 * duv1.y) * r;
+        let bitangent = (edge2 * duv1.x - edge1 * duv2.x) * r;
+        for index in [a, b, c] {
+            tangents[index] += tangent;
+            bitangents[index] += bitangent;
+        }
+    }
+
+    for ((vertex, tangent), bitangent) in vertices.iter_mut().zip(tangents).zip(bitangents) {
+        let normal = vertex.normal;
+        let mut orthogonal = tangent - normal * glm::dot(&normal, &tangent);
+        if glm::length(&orthogonal) <= 1e-6 {
+            // Sin UV útiles: cualquier dirección perpendicular a la normal
+            let axis = if normal.x.abs() < 0.9 { Vec3::x() } else { Vec3::y() };
+            orthogonal = glm::cross(&axis, &normal);
+        }
+        vertex.tangent = glm::normalize(&orthogonal);
+
+        let handedness = if glm::dot(&glm::cross(&normal, &vertex.tangent), &bitangent) < 0.0 { -1.0 } else { 1.0 };
+        vertex.bitangent = glm::cross(&normal, &vertex.tangent) * handedness;
+    }
+}
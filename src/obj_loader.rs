@@ -1,23 +1,137 @@
 use std::fs::File;
 use std::io::{self, BufRead};
-use std::path::Path;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use nalgebra_glm as glm;
-use glm::Vec3;
+use log::{debug, info, warn};
+use crate::mesh_cache;
+use crate::normals;
+use crate::vertex::Vertex;
+use crate::geometry::Mesh;
+use std::collections::HashMap;
+use glm::{Vec2, Vec3};
 
+// Una esquina de cara: índice de posición y, si el archivo los trae, de UV y de normal
+#[derive(Debug, Clone, Copy)]
+pub struct FaceVertex {
+    pub position: usize,
+    pub tex_coord: Option<usize>,
+    pub normal: Option<usize>,
+}
+
+// Material de un archivo .mtl (colores en 0.0..1.0)
+#[derive(Debug, Clone)]
+pub struct MtlMaterial {
+    pub name: String,
+    pub diffuse: Vec3,                  // Kd
+    pub specular: Vec3,                 // Ks
+    pub shininess: f32,                 // Ns
+    pub diffuse_map: Option<PathBuf>,   // map_Kd, ya resuelto respecto al .mtl
+}
+
+impl MtlMaterial {
+    fn new(name: &str) -> Self {
+        MtlMaterial {
+            name: name.to_string(),
+            diffuse: Vec3::new(0.8, 0.8, 0.8),
+            specular: Vec3::new(0.0, 0.0, 0.0),
+            shininess: 32.0,
+            diffuse_map: None,
+        }
+    }
+}
+
+// Caras consecutivas que comparten material (`usemtl`); None = sin material asignado
+#[derive(Debug, Clone)]
+pub struct FaceGroup {
+    pub material: Option<usize>,
+    pub faces: Range<usize>,
+}
+
+// Parte con nombre del archivo (`o` / `g`): p. ej. casco, cápsula desacoplable o torretas
+#[derive(Debug, Clone)]
+pub struct SubMesh {
+    pub name: String,
+    pub faces: Range<usize>,
+}
+
+#[derive(Clone)]
 pub struct Model {
     pub vertices: Vec<Vec3>,
-    pub faces: Vec<[usize; 3]>,
+    pub normals: Vec<Vec3>,
+    pub tex_coords: Vec<Vec2>,
+    pub faces: Vec<[FaceVertex; 3]>,
+    pub materials: Vec<MtlMaterial>,
+    pub groups: Vec<FaceGroup>,
+    pub objects: Vec<SubMesh>,
+    // Archivos de los que salió el modelo (.obj y sus .mtl), para invalidar la caché
+    pub source_files: Vec<PathBuf>,
+}
+
+// Cómo se procesa un modelo antes de dibujarlo (sale del .ship); entra en la clave de la caché
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MeshOptions {
+    pub crease_angle: Option<f32>,  // Normales suavizadas con este ángulo de pliegue; None = las del archivo
+}
+
+// Modelo listo para dibujar: malla centrada, de tamaño unitario y con tangentes, más los
+// materiales y grupos para armar las superficies. Es lo que guarda la caché binaria.
+#[derive(Clone)]
+pub struct ProcessedModel {
+    pub mesh: Mesh,
+    pub materials: Vec<MtlMaterial>,
+    pub groups: Vec<FaceGroup>,
+    pub objects: Vec<SubMesh>,
+    pub source_files: Vec<PathBuf>,
+    pub options: MeshOptions,
+}
+
+impl ProcessedModel {
+    // Carga desde la caché binaria junto al .obj si sigue vigente para estas opciones; si no,
+    // parsea, procesa y la regenera
+    pub fn load_cached<P: AsRef<Path>>(path: P, options: MeshOptions) -> io::Result<Self> {
+        let path = path.as_ref();
+        if let Some(model) = mesh_cache::load(path, options) {
+            debug!("Loaded {} from mesh cache", path.display());
+            return Ok(model);
+        }
+
+        let model = Model::load_obj(path)?.process(options);
+        if let Err(e) = mesh_cache::save(path, &model) {
+            warn!("Could not write mesh cache for {}: {}", path.display(), e);
+        }
+        Ok(model)
+    }
 }
 
 impl Model {
     pub fn load_obj<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
         let file = File::open(path)?;
-        let reader = io::BufReader::new(file);
-        
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        Self::parse_obj(io::BufReader::new(file), path, Some(base_dir))
+    }
+
+    // OBJ embebido en el binario (`include_bytes!`): no tiene .mtl ni archivos fuente
+    pub fn from_obj_bytes(bytes: &[u8], name: &str) -> io::Result<Self> {
+        Self::parse_obj(bytes, Path::new(name), None)
+    }
+
+    // `path` solo se usa en los mensajes de error; sin `base_dir` se ignoran los `mtllib`
+    fn parse_obj<R: BufRead>(reader: R, path: &Path, base_dir: Option<&Path>) -> io::Result<Self> {
         let mut vertices = Vec::new();
+        let mut normals = Vec::new();
+        let mut tex_coords = Vec::new();
         let mut faces = Vec::new();
+        let mut materials: Vec<MtlMaterial> = Vec::new();
+        let mut groups: Vec<FaceGroup> = Vec::new();
+        let mut current_material = None;
+        let mut objects: Vec<SubMesh> = Vec::new();
+        let mut object_name = String::from("default");
+        let mut group_name: Option<String> = None;
+        let mut source_files: Vec<PathBuf> = base_dir.map(|_| path.to_path_buf()).into_iter().collect();
         
-        for line in reader.lines() {
+        for (line_number, line) in reader.lines().enumerate() {
             let line = line?;
             let parts: Vec<&str> = line.split_whitespace().collect();
             
@@ -26,31 +140,187 @@ impl Model {
             }
             
             match parts[0] {
-                "v" => {
-                    if parts.len() >= 4 {
-                        let x = parts[1].parse::<f32>().unwrap_or(0.0);
-                        let y = parts[2].parse::<f32>().unwrap_or(0.0);
-                        let z = parts[3].parse::<f32>().unwrap_or(0.0);
-                        vertices.push(Vec3::new(x, y, z));
+                "v" if parts.len() >= 4 => vertices.push(parse_vec3(&parts[1..4])),
+                "vn" if parts.len() >= 4 => normals.push(parse_vec3(&parts[1..4])),
+                "vt" if parts.len() >= 3 => {
+                    // La tercera coordenada (w) es opcional y no se usa
+                    let u = parts[1].parse::<f32>().unwrap_or(0.0);
+                    let v = parts[2].parse::<f32>().unwrap_or(0.0);
+                    tex_coords.push(Vec2::new(u, v));
+                },
+                "mtllib" if parts.len() >= 2 => {
+                    let Some(base_dir) = base_dir else {
+                        continue;
+                    };
+                    // El nombre puede tener espacios: todo lo que sigue a `mtllib`
+                    let mtl_path = base_dir.join(line.trim_start()["mtllib".len()..].trim());
+                    source_files.push(mtl_path.clone());
+                    match load_mtl(&mtl_path) {
+                        Ok(loaded) => {
+                            info!("Loaded {} material(s) from {}", loaded.len(), mtl_path.display());
+                            materials.extend(loaded);
+                        }
+                        Err(e) => warn!("Could not read {}: {}", mtl_path.display(), e),
                     }
                 },
-                "f" => {
-                    if parts.len() >= 4 {
-                        // Los índices en archivos .obj empiezan en 1, así que restamos 1
-                        let v1 = parts[1].split('/').next().unwrap_or("1").parse::<usize>().unwrap_or(1) - 1;
-                        let v2 = parts[2].split('/').next().unwrap_or("1").parse::<usize>().unwrap_or(1) - 1;
-                        let v3 = parts[3].split('/').next().unwrap_or("1").parse::<usize>().unwrap_or(1) - 1;
-                        
-                        faces.push([v1, v2, v3]);
+                "o" => {
+                    object_name = parts[1..].join(" ");
+                    group_name = None;
+                },
+                "g" => {
+                    // `g` sin nombre vuelve al objeto actual; varios nombres se unen
+                    group_name = (parts.len() > 1).then(|| parts[1..].join(" "));
+                },
+                "usemtl" => {
+                    let name = parts.get(1).copied().unwrap_or("");
+                    current_material = materials.iter().position(|material| material.name == name);
+                },
+                "f" if parts.len() >= 4 => {
+                    let counts = [vertices.len(), tex_coords.len(), normals.len()];
+                    let corner = |token: &str| {
+                        parse_face_vertex(token, counts).map_err(|message| {
+                            io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                format!("{}:{}: {}", path.display(), line_number + 1, message),
+                            )
+                        })
+                    };
+                    let corners = parts[1..].iter().map(|token| corner(token)).collect::<io::Result<Vec<_>>>()?;
+                    // Caras de más de 3 esquinas (quads, n-gonos convexos) en abanico desde la primera
+                    let triangles = corners.len() - 2;
+
+                    if groups.last().is_none_or(|group| group.material != current_material) {
+                        groups.push(FaceGroup { material: current_material, faces: faces.len()..faces.len() });
                     }
+                    if let Some(group) = groups.last_mut() {
+                        group.faces.end = faces.len() + triangles;
+                    }
+
+                    // Un grupo dentro de un objeto se nombra "objeto/grupo"
+                    let name = match &group_name {
+                        Some(group) => format!("{}/{}", object_name, group),
+                        None => object_name.clone(),
+                    };
+                    if objects.last().is_none_or(|object| object.name != name) {
+                        objects.push(SubMesh { name, faces: faces.len()..faces.len() });
+                    }
+                    if let Some(object) = objects.last_mut() {
+                        object.faces.end = faces.len() + triangles;
+                    }
+                    faces.extend(corners.windows(2).skip(1).map(|pair| [corners[0], pair[0], pair[1]]));
                 },
                 _ => {}
             }
         }
         
-        Ok(Model { vertices, faces })
+        Ok(Model { vertices, normals, tex_coords, faces, materials, groups, objects, source_files })
+    }
+
+    // Normal de una esquina: la del archivo si existe, si no la normal plana de la cara
+    pub fn corner_normals(&self, face: &[FaceVertex; 3]) -> [Vec3; 3] {
+        let v0 = self.vertices[face[0].position];
+        let v1 = self.vertices[face[1].position];
+        let v2 = self.vertices[face[2].position];
+        let face_normal = glm::normalize(&glm::cross(&(v1 - v0), &(v2 - v0)));
+
+        face.map(|corner| {
+            corner.normal
+                .and_then(|index| self.normals.get(index))
+                .map(glm::normalize)
+                .unwrap_or(face_normal)
+        })
+    }
+
+    // Sustituye las normales del archivo por normales suavizadas (ponderadas por área y con
+    // aristas duras a partir de `crease_angle_degrees`), compartidas por posición
+    pub fn smooth_normals(&mut self, crease_angle_degrees: f32) {
+        let triangles: Vec<[usize; 3]> = self.faces.iter().map(|face| face.map(|corner| corner.position)).collect();
+        let corner_normals = normals::area_weighted_normals(&self.vertices, &triangles, crease_angle_degrees);
+
+        // Las esquinas de una misma posición con la misma normal comparten índice, para que
+        // `to_mesh` las pueda fundir
+        self.normals.clear();
+        let mut unique: HashMap<(usize, [u32; 3]), usize> = HashMap::new();
+        for (face, face_normals) in self.faces.iter_mut().zip(corner_normals) {
+            for (corner, normal) in face.iter_mut().zip(face_normals) {
+                let key = (corner.position, [normal.x.to_bits(), normal.y.to_bits(), normal.z.to_bits()]);
+                let index = *unique.entry(key).or_insert_with(|| {
+                    self.normals.push(normal);
+                    self.normals.len() - 1
+                });
+                corner.normal = Some(index);
+            }
+        }
+    }
+
+    // Vértices únicos + índices (3 por cara, en el orden de `faces`). Las esquinas con la misma
+    // posición, UV y normal (la del archivo o la plana de la cara) se funden en un solo vértice.
+    pub fn to_mesh(&self) -> Mesh {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::with_capacity(self.faces.len() * 3);
+        let mut unique: HashMap<(usize, [u32; 2], [u32; 3]), u32> = HashMap::new();
+
+        for face in &self.faces {
+            let normals = self.corner_normals(face);
+            let tex_coords = self.corner_tex_coords(face);
+            for (i, corner) in face.iter().enumerate() {
+                let (normal, uv) = (normals[i], tex_coords[i]);
+                let key = (corner.position, [uv.x.to_bits(), uv.y.to_bits()], [normal.x.to_bits(), normal.y.to_bits(), normal.z.to_bits()]);
+                let index = *unique.entry(key).or_insert_with(|| {
+                    vertices.push(Vertex::new(self.vertices[corner.position], normal, uv));
+                    (vertices.len() - 1) as u32
+                });
+                indices.push(index);
+            }
+        }
+
+        Mesh { vertices, indices }
+    }
+
+    // UV de cada esquina; sin `vt` se usa el mapeo fijo (0,0) (1,0) (0.5,1) de siempre
+    pub fn corner_tex_coords(&self, face: &[FaceVertex; 3]) -> [Vec2; 3] {
+        let fallback = [Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(0.5, 1.0)];
+        let mut tex_coords = fallback;
+        for (i, corner) in face.iter().enumerate() {
+            if let Some(uv) = corner.tex_coord.and_then(|index| self.tex_coords.get(index)) {
+                tex_coords[i] = *uv;
+            }
+        }
+        tex_coords
     }
     
+    // Normaliza, suaviza las normales si se pidió, funde los vértices y calcula las tangentes
+    pub fn process(self, options: MeshOptions) -> ProcessedModel {
+        let mut model = self.normalized();
+        if let Some(crease_angle) = options.crease_angle {
+            model.smooth_normals(crease_angle);
+        }
+        let mut mesh = model.to_mesh();
+        mesh.compute_tangents();
+        ProcessedModel {
+            mesh,
+            materials: model.materials,
+            groups: model.groups,
+            objects: model.objects,
+            source_files: model.source_files,
+            options,
+        }
+    }
+
+    // Copia del modelo centrada en el centro de su caja envolvente y escalada de forma
+    // uniforme para que su eje más largo mida 1.0 (las normales no cambian)
+    pub fn normalized(mut self) -> Self {
+        let center = self.calculate_center();
+        let size = self.calculate_size();
+        let largest_extent = size.x.max(size.y).max(size.z);
+        let factor = if largest_extent > 0.0 { 1.0 / largest_extent } else { 1.0 };
+
+        for vertex in &mut self.vertices {
+            *vertex = (*vertex - center) * factor;
+        }
+        self
+    }
+
     // Calcular el centro del modelo
     pub fn calculate_center(&self) -> Vec3 {
         let mut min = Vec3::new(f32::MAX, f32::MAX, f32::MAX);
@@ -95,3 +365,156 @@ impl Model {
         )
     }
 }
+
+// Lee un archivo .mtl: solo Kd, Ks, Ns y map_Kd, el resto se ignora
+fn load_mtl(path: &Path) -> io::Result<Vec<MtlMaterial>> {
+    let reader = io::BufReader::new(File::open(path)?);
+    let base_dir = path.parent().unwrap_or(Path::new(""));
+    let mut materials: Vec<MtlMaterial> = Vec::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.is_empty() {
+            continue;
+        }
+
+        if parts[0] == "newmtl" {
+            materials.push(MtlMaterial::new(parts.get(1).copied().unwrap_or("")));
+            continue;
+        }
+        let Some(material) = materials.last_mut() else {
+            continue;
+        };
+        match parts[0] {
+            "Kd" if parts.len() >= 4 => material.diffuse = parse_vec3(&parts[1..4]),
+            "Ks" if parts.len() >= 4 => material.specular = parse_vec3(&parts[1..4]),
+            "Ns" if parts.len() >= 2 => material.shininess = parts[1].parse::<f32>().unwrap_or(32.0),
+            // Las opciones (-s, -o, ...) van antes del nombre; el archivo es el último campo
+            "map_Kd" if parts.len() >= 2 => material.diffuse_map = Some(base_dir.join(parts[parts.len() - 1])),
+            _ => {}
+        }
+    }
+
+    Ok(materials)
+}
+
+fn parse_vec3(parts: &[&str]) -> Vec3 {
+    let x = parts[0].parse::<f32>().unwrap_or(0.0);
+    let y = parts[1].parse::<f32>().unwrap_or(0.0);
+    let z = parts[2].parse::<f32>().unwrap_or(0.0);
+    Vec3::new(x, y, z)
+}
+
+// Esquina de cara en formato v, v/vt, v//vn o v/vt/vn.
+// `counts` = cuántas posiciones, UVs y normales se han leído hasta esta línea.
+fn parse_face_vertex(token: &str, counts: [usize; 3]) -> Result<FaceVertex, String> {
+    const KINDS: [&str; 3] = ["vertex", "texture coordinate", "normal"];
+    let mut resolved = [None; 3];
+
+    for (slot, field) in token.split('/').enumerate() {
+        if slot >= 3 {
+            return Err(format!("face corner `{}` has more than 3 indices", token));
+        }
+        if field.is_empty() {
+            continue;
+        }
+        let index = field
+            .parse::<i64>()
+            .map_err(|_| format!("invalid {} index `{}` in `{}`", KINDS[slot], field, token))?;
+        resolved[slot] = Some(resolve_index(index, counts[slot]).ok_or_else(|| {
+            format!(
+                "{} index {} in `{}` is out of range ({} defined so far)",
+                KINDS[slot], index, token, counts[slot]
+            )
+        })?);
+    }
+
+    let position = resolved[0].ok_or_else(|| format!("face corner `{}` has no vertex index", token))?;
+    Ok(FaceVertex { position, tex_coord: resolved[1], normal: resolved[2] })
+}
+
+// Los índices .obj empiezan en 1; los negativos cuentan hacia atrás desde el último definido
+// (-1 = el más reciente). El 0 no es válido.
+fn resolve_index(index: i64, count: usize) -> Option<usize> {
+    let resolved = if index > 0 {
+        index - 1
+    } else if index < 0 {
+        count as i64 + index
+    } else {
+        return None;
+    };
+    (0..count as i64).contains(&resolved).then_some(resolved as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_positive_and_negative_indices() {
+        assert_eq!(resolve_index(1, 3), Some(0));
+        assert_eq!(resolve_index(3, 3), Some(2));
+        assert_eq!(resolve_index(-1, 3), Some(2));
+        assert_eq!(resolve_index(-3, 3), Some(0));
+    }
+
+    #[test]
+    fn rejects_zero_and_out_of_range_indices() {
+        assert_eq!(resolve_index(0, 3), None);
+        assert_eq!(resolve_index(4, 3), None);
+        assert_eq!(resolve_index(-4, 3), None);
+        assert_eq!(resolve_index(1, 0), None);
+    }
+
+    #[test]
+    fn parses_face_corner_formats() {
+        let counts = [4, 2, 3];
+        let corner = parse_face_vertex("2", counts).unwrap();
+        assert_eq!((corner.position, corner.tex_coord, corner.normal), (1, None, None));
+        let corner = parse_face_vertex("-1/2/-3", counts).unwrap();
+        assert_eq!((corner.position, corner.tex_coord, corner.normal), (3, Some(1), Some(0)));
+        let corner = parse_face_vertex("1//3", counts).unwrap();
+        assert_eq!((corner.position, corner.tex_coord, corner.normal), (0, None, Some(2)));
+    }
+
+    #[test]
+    fn rejects_invalid_face_corners() {
+        let counts = [4, 2, 3];
+        assert!(parse_face_vertex("0", counts).unwrap_err().contains("out of range"));
+        assert!(parse_face_vertex("5", counts).unwrap_err().contains("out of range"));
+        assert!(parse_face_vertex("1/3", counts).unwrap_err().contains("texture coordinate"));
+        assert!(parse_face_vertex("1//-4", counts).unwrap_err().contains("normal"));
+        assert!(parse_face_vertex("/1/1", counts).unwrap_err().contains("no vertex index"));
+        assert!(parse_face_vertex("1/1/1/1", counts).unwrap_err().contains("more than 3"));
+        assert!(parse_face_vertex("a", counts).unwrap_err().contains("invalid vertex index"));
+    }
+
+    #[test]
+    fn relative_indices_count_back_from_the_current_line() {
+        let obj = b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\nv 0 0 1\nf -3 -2 -1\n";
+        let model = Model::from_obj_bytes(obj, "relative.obj").unwrap();
+        let positions: Vec<[usize; 3]> = model.faces.iter().map(|face| face.map(|corner| corner.position)).collect();
+        assert_eq!(positions, vec![[0, 1, 2], [1, 2, 3]]);
+    }
+
+    #[test]
+    fn out_of_range_face_is_an_error_with_its_line() {
+        let obj = b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 4\n";
+        let error = Model::from_obj_bytes(obj, "broken.obj").err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().starts_with("broken.obj:4:"));
+    }
+
+    #[test]
+    fn polygons_are_fan_triangulated() {
+        let obj = b"v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 0 2 0\no Quad\nf 1 2 3 4\no Pentagon\nf 1 2 3 4 5\n";
+        let model = Model::from_obj_bytes(obj, "polygons.obj").unwrap();
+        let positions: Vec<[usize; 3]> = model.faces.iter().map(|face| face.map(|corner| corner.position)).collect();
+        assert_eq!(positions, vec![[0, 1, 2], [0, 2, 3], [0, 1, 2], [0, 2, 3], [0, 3, 4]]);
+        assert_eq!(model.groups.len(), 1);
+        assert_eq!(model.groups[0].faces, 0..5);
+        let objects: Vec<(&str, Range<usize>)> = model.objects.iter().map(|object| (object.name.as_str(), object.faces.clone())).collect();
+        assert_eq!(objects, vec![("Quad", 0..2), ("Pentagon", 2..5)]);
+    }
+}
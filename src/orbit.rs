@@ -1,12 +1,39 @@
 use nalgebra_glm::Vec3;
+use std::borrow::Cow;
+use crate::celestial_body::{orbit_point, CelestialBody};
 use crate::vertex::Vertex;
+use crate::renderable::{DrawItem, Renderable, RenderPass, RenderView, Shading};
+use crate::shaders::sun_phase;
 use std::f32::consts::PI;
+use std::ops::Range;
+
+// Las órbitas se dibujan a la mitad de su color para un look más minimalista y sutil
+pub const DEFAULT_OPACITY: f32 = 0.5;
+// Desde esta distancia (al punto más cercano del anillo) la órbita se apaga hasta
+// `MIN_DISTANCE_FADE` al doble de distancia
+const FADE_START_DISTANCE: f32 = 20.0;
+const MIN_DISTANCE_FADE: f32 = 0.35;
+// Vista de canto: por debajo de esta elevación (seno del ángulo sobre el plano) el anillo se
+// afina y oscurece hasta `MIN_EDGE_FADE`
+const EDGE_ON_ELEVATION: f32 = 0.25;
+const MIN_EDGE_FADE: f32 = 0.4;
+// La órbita del cuerpo enfocado se ve más que las otras
+const FOCUS_BOOST: f32 = 1.6;
+// Segmentos por trazo: cada trazo se ilumina por separado (y en el punteado, cada tercero es hueco)
+const DASH_SEGMENTS: usize = 8;
+// Brillo de un trazo cuyo planeta se vería de noche (el sol detrás) frente a uno a pleno sol
+const NIGHT_SIDE_BRIGHTNESS: f32 = 0.5;
 
 pub struct OrbitRing {
     pub center: Vec3,
-    pub radius: f32,
+    pub radius: f32,          // Semieje mayor; `center` es el foco
+    pub eccentricity: f32,
+    pub inclination: f32,     // Radianes, sobre el eje X
     pub segments: usize,
     pub color: u32,
+    pub slot: usize,  // Posición en la escena: elige el color de la paleta para daltonismo
+    pub body: Option<usize>,  // Índice del cuerpo que la recorre (para resaltarla al enfocarlo)
+    pub opacity: f32,         // Fracción del color con que se dibuja (0.0..=1.0), antes de los fundidos
     pub line_width: f32,
     pub dashed: bool,
 }
@@ -16,59 +43,155 @@ impl OrbitRing {
         OrbitRing {
             center,
             radius,
+            eccentricity: 0.0,
+            inclination: 0.0,
             segments: 200, // Más segmentos para líneas más suaves
             color,
+            slot: 0,
+            body: None,
+            opacity: DEFAULT_OPACITY,
             line_width: 0.015, // Grosor reducido para look minimalista
             dashed: true, // Efecto de línea punteada
         }
     }
 
+    pub fn with_body(mut self, index: usize) -> Self {
+        self.body = Some(index);
+        self
+    }
+
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    // Sin cuerpo asociado se dibuja siempre; si no, según su `draw_orbit`
+    pub fn is_shown(&self, bodies: &[CelestialBody]) -> bool {
+        self.body.and_then(|index| bodies.get(index)).is_none_or(|body| body.flags.draw_orbit)
+    }
+
+    // Modo dinámico, automático para los anillos con cuerpo: toma la forma actual de su órbita
+    // (que el editor puede cambiar) y, si tiene padre, se centra en la posición actual del padre
+    pub fn follow_body(&mut self, bodies: &[CelestialBody]) {
+        let Some(body) = self.body.and_then(|index| bodies.get(index)) else {
+            return;
+        };
+        self.radius = body.orbit_radius;
+        self.eccentricity = body.orbit_eccentricity;
+        self.inclination = body.orbit_inclination;
+        let center = body.parent.and_then(|index| bodies.get(index)).map_or_else(Vec3::zeros, |parent| parent.position);
+        self.center = center + Vec3::new(0.0, body.orbit_height, 0.0);
+    }
+
+    // Qué tanto se ve desde `eye` (1.0 = de frente y cerca): se apaga con la distancia y al
+    // quedar de canto
+    pub fn visibility(&self, eye: Vec3) -> f32 {
+        let offset = eye - self.center;
+        let horizontal = (offset.x * offset.x + offset.z * offset.z).sqrt();
+        let distance = (horizontal - self.radius).hypot(offset.y);
+        let far = ((distance - FADE_START_DISTANCE) / FADE_START_DISTANCE).clamp(0.0, 1.0);
+        let distance_fade = 1.0 + (MIN_DISTANCE_FADE - 1.0) * far;
+
+        let elevation = offset.y.abs() / offset.magnitude().max(f32::EPSILON);
+        let edge = (elevation / EDGE_ON_ELEVATION).clamp(0.0, 1.0);
+        let edge_fade = MIN_EDGE_FADE + (1.0 - MIN_EDGE_FADE) * edge;
+        distance_fade * edge_fade
+    }
+
     pub fn get_vertices(&self) -> Vec<Vertex> {
+        self.vertices_with_width(self.line_width)
+    }
+
+    fn vertices_with_width(&self, line_width: f32) -> Vec<Vertex> {
+        self.dashes()
+            .flat_map(|segments| self.arc_vertices(segments, line_width))
+            .collect()
+    }
+
+    // Rangos de segmentos que se dibujan: trazos de `DASH_SEGMENTS`, saltando uno de cada tres
+    // si la línea es punteada (8 visible, 8 visible, 8 hueco)
+    fn dashes(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        (0..self.segments.div_ceil(DASH_SEGMENTS))
+            .filter(|dash| !(self.dashed && dash % 3 == 2))
+            .map(|dash| dash * DASH_SEGMENTS..((dash + 1) * DASH_SEGMENTS).min(self.segments))
+    }
+
+    fn point(&self, segment: usize) -> Vec3 {
+        self.point_at((segment as f32 / self.segments as f32) * 2.0 * PI)
+    }
+
+    // Punto de la órbita en la anomalía excéntrica `angle` (en un círculo, el ángulo desde +X)
+    pub fn point_at(&self, angle: f32) -> Vec3 {
+        self.center + orbit_point(self.radius, self.eccentricity, self.inclination, angle)
+    }
+
+    // Crear línea con grosor usando quad (dos triángulos por segmento)
+    fn arc_vertices(&self, segments: Range<usize>, line_width: f32) -> Vec<Vertex> {
         let mut vertices = Vec::new();
         let normal = Vec3::new(0.0, 1.0, 0.0);
-        
-        // Crear línea con grosor usando quad (dos triángulos por segmento)
-        for i in 0..self.segments {
-            let angle1 = (i as f32 / self.segments as f32) * 2.0 * PI;
-            let angle2 = ((i + 1) as f32 / self.segments as f32) * 2.0 * PI;
-            
-            // Calcular si este segmento debe ser visible (para efecto punteado)
-            let dash_pattern = (i / 8) % 3; // Patrón: 8 visible, 8 gap, 8 visible, 8 gap más largo
-            if self.dashed && dash_pattern == 2 {
-                continue; // Saltar algunos segmentos para crear efecto punteado
-            }
-            
-            // Puntos en el círculo
-            let x1 = self.center.x + self.radius * angle1.cos();
-            let z1 = self.center.z + self.radius * angle1.sin();
-            let x2 = self.center.x + self.radius * angle2.cos();
-            let z2 = self.center.z + self.radius * angle2.sin();
-            let y = self.center.y;
-            
+        for i in segments {
+            let (p1, p2) = (self.point(i), self.point(i + 1));
+
             // Calcular perpendicular para dar grosor a la línea
-            let dx = x2 - x1;
-            let dz = z2 - z1;
-            let length = (dx * dx + dz * dz).sqrt();
-            let perpx = -dz / length * self.line_width;
-            let perpz = dx / length * self.line_width;
-            
+            let dx = p2.x - p1.x;
+            let dz = p2.z - p1.z;
+            let length = (dx * dx + dz * dz).sqrt().max(f32::EPSILON);
+            let perp = Vec3::new(-dz / length * line_width, 0.0, dx / length * line_width);
+
             // Crear un quad (rectángulo) con dos triángulos
-            let v1 = Vec3::new(x1 + perpx, y, z1 + perpz);
-            let v2 = Vec3::new(x1 - perpx, y, z1 - perpz);
-            let v3 = Vec3::new(x2 - perpx, y, z2 - perpz);
-            let v4 = Vec3::new(x2 + perpx, y, z2 + perpz);
-            
+            let v1 = p1 + perp;
+            let v2 = p1 - perp;
+            let v3 = p2 - perp;
+            let v4 = p2 + perp;
+
             // Primer triángulo del quad
             vertices.push(Vertex::new(v1, normal, nalgebra_glm::Vec2::new(0.0, 0.0)));
             vertices.push(Vertex::new(v2, normal, nalgebra_glm::Vec2::new(1.0, 0.0)));
             vertices.push(Vertex::new(v3, normal, nalgebra_glm::Vec2::new(0.5, 1.0)));
-            
+
             // Segundo triángulo del quad
             vertices.push(Vertex::new(v1, normal, nalgebra_glm::Vec2::new(0.0, 0.0)));
             vertices.push(Vertex::new(v3, normal, nalgebra_glm::Vec2::new(1.0, 0.0)));
             vertices.push(Vertex::new(v4, normal, nalgebra_glm::Vec2::new(0.5, 1.0)));
         }
-        
         vertices
     }
 }
+
+impl Renderable for OrbitRing {
+    fn render_pass(&self) -> RenderPass {
+        RenderPass::Lines
+    }
+
+    fn name(&self) -> &str {
+        "Orbit"
+    }
+
+    fn draw_items<'a>(&'a self, view: &RenderView<'a>) -> Vec<DrawItem<'a>> {
+        let visibility = self.visibility(view.camera_eye);
+        let focused = self.body.is_some() && self.body == view.focus;
+        let boost = if focused { FOCUS_BOOST } else { 1.0 };
+        let brightness = (self.opacity * visibility * boost).min(1.0);
+        // Más fina cuanto menos se ve, sin bajar de la mitad para que no desaparezcan segmentos
+        let line_width = self.line_width * visibility.max(0.5);
+        let color = view.palette.orbit_color(self.slot, self.color);
+        // Cada trazo con la luz del sol: tan claro como se vería un planeta en ese punto de la
+        // órbita desde la cámara (de día del lado opuesto al sol, de noche entre la cámara y el sol)
+        self.dashes()
+            .map(|segments| {
+                let middle = self.point((segments.start + segments.end) / 2);
+                let daylight = sun_phase(middle, view.camera_eye);
+                let shade = brightness * (NIGHT_SIDE_BRIGHTNESS + (1.0 - NIGHT_SIDE_BRIGHTNESS) * daylight);
+                let channel = |shift: u32| ((((color >> shift) & 0xFF) as f32 * shade) as u32) << shift;
+                let vertices = self.arc_vertices(segments, line_width);
+                let indices = (0..vertices.len() as u32).collect();
+                DrawItem {
+                    vertices: Cow::Owned(vertices),
+                    indices: Cow::Owned(indices),
+                    shading: Shading::Flat(channel(16) | channel(8) | channel(0)),
+                    time: view.time,
+                }
+            })
+            .collect()
+    }
+}
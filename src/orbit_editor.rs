@@ -0,0 +1,154 @@
+use crate::celestial_body::{CelestialBody, ShaderType, MAX_ECCENTRICITY};
+use crate::framebuffer::Framebuffer;
+use crate::i18n::{tr, tr_with};
+use crate::text::{draw_text, text_width, GLYPH_HEIGHT};
+
+// Pasos de cada tecla: el radio y el tamaño cambian un porcentaje, el resto una cantidad fija
+const SCALE_STEP: f32 = 1.05;
+const ECCENTRICITY_STEP: f32 = 0.02;
+const INCLINATION_STEP_DEGREES: f32 = 2.0;
+const MIN_ORBIT_RADIUS: f32 = 0.1;
+const MIN_SIZE: f32 = 0.02;
+const MAX_SIZE: f32 = 20.0;
+pub const EXPORT_PATH: &str = "edited.scene";
+
+const PANEL_COLOR: u32 = 0x000000;
+const TITLE_COLOR: u32 = 0x7FD4FF;
+const LABEL_COLOR: u32 = 0x9AA8B8;
+const TEXT_COLOR: u32 = 0xE0F0FF;
+const SELECTED_COLOR: u32 = 0xFFD060;
+const MARGIN: usize = 6;
+const PADDING: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Parameter {
+    Radius,
+    Eccentricity,
+    Inclination,
+    Size,
+    Shader,
+}
+
+impl Parameter {
+    pub const ALL: [Parameter; 5] = [
+        Parameter::Radius,
+        Parameter::Eccentricity,
+        Parameter::Inclination,
+        Parameter::Size,
+        Parameter::Shader,
+    ];
+
+    fn label(self) -> &'static str {
+        tr(match self {
+            Parameter::Radius => "hud.editor_radius",
+            Parameter::Eccentricity => "hud.editor_eccentricity",
+            Parameter::Inclination => "hud.editor_inclination",
+            Parameter::Size => "hud.editor_size",
+            Parameter::Shader => "hud.editor_shader",
+        })
+    }
+
+    // Los de la órbita no se aplican a un cuerpo que no orbita
+    fn applies_to(self, body: &CelestialBody) -> bool {
+        match self {
+            Parameter::Radius | Parameter::Eccentricity | Parameter::Inclination => body.orbit_radius > 0.0,
+            Parameter::Size | Parameter::Shader => true,
+        }
+    }
+
+    fn value(self, body: &CelestialBody) -> String {
+        if !self.applies_to(body) {
+            return "-".to_string();
+        }
+        match self {
+            Parameter::Radius => format!("{:.2} u", body.orbit_radius),
+            Parameter::Eccentricity => format!("{:.2}", body.orbit_eccentricity),
+            Parameter::Inclination => format!("{:.0} deg", body.orbit_inclination.to_degrees()),
+            Parameter::Size => format!("{:.2} u", body.scale),
+            Parameter::Shader => body.shader_type.display_name().to_string(),
+        }
+    }
+}
+
+// Editor de órbitas (tecla I): cambia la órbita, el tamaño y el shader del cuerpo enfocado. Los
+// cuerpos se colocan cada paso a partir de estos parámetros, así que el cambio se ve en la
+// simulación y en su anillo de órbita en el mismo frame. La escena modificada se puede exportar.
+#[derive(Debug, Default)]
+pub struct OrbitEditor {
+    selected: usize,  // Índice en `Parameter::ALL`
+}
+
+impl OrbitEditor {
+    pub fn parameter(&self) -> Parameter {
+        Parameter::ALL[self.selected]
+    }
+
+    pub fn next_parameter(&mut self) {
+        self.selected = (self.selected + 1) % Parameter::ALL.len();
+    }
+
+    // Un paso hacia arriba (`steps` = 1) o hacia abajo (-1) del parámetro elegido; false si no se
+    // aplica a ese cuerpo
+    pub fn adjust(&self, body: &mut CelestialBody, steps: i32) -> bool {
+        let parameter = self.parameter();
+        if !parameter.applies_to(body) {
+            return false;
+        }
+        let direction = steps as f32;
+        match parameter {
+            Parameter::Radius => body.orbit_radius = (body.orbit_radius * SCALE_STEP.powi(steps)).max(MIN_ORBIT_RADIUS),
+            Parameter::Eccentricity => {
+                body.orbit_eccentricity = (body.orbit_eccentricity + ECCENTRICITY_STEP * direction).clamp(0.0, MAX_ECCENTRICITY);
+            }
+            Parameter::Inclination => {
+                let degrees = body.orbit_inclination.to_degrees() + INCLINATION_STEP_DEGREES * direction;
+                body.orbit_inclination = degrees.clamp(-90.0, 90.0).to_radians();
+            }
+            Parameter::Size => body.scale = (body.scale * SCALE_STEP.powi(steps)).clamp(MIN_SIZE, MAX_SIZE),
+            Parameter::Shader => {
+                let shaders = ShaderType::BODIES;
+                let current = shaders.iter().position(|&shader| shader == body.shader_type).unwrap_or(0) as i32;
+                body.shader_type = shaders[(current + steps).rem_euclid(shaders.len() as i32) as usize];
+            }
+        }
+        true
+    }
+}
+
+// Panel del editor, apilado con los otros de la esquina superior derecha: los parámetros del
+// cuerpo con el elegido resaltado y las teclas (`hint`). Devuelve la y donde termina.
+pub fn render_orbit_editor(framebuffer: &mut Framebuffer, editor: &OrbitEditor, body: Option<&CelestialBody>, hint: &str, panel_y: usize) -> usize {
+    let line = GLYPH_HEIGHT + 3;
+    let title = match body {
+        Some(body) => tr_with("hud.editor_title", &[("body", &body.name)]),
+        None => tr("hud.editor_no_body").to_string(),
+    };
+    let rows: Vec<(&str, String)> = body
+        .map(|body| Parameter::ALL.iter().map(|&parameter| (parameter.label(), parameter.value(body))).collect())
+        .unwrap_or_default();
+    let label_width = rows.iter().map(|(label, _)| text_width(label)).max().unwrap_or(0);
+    let value_column = label_width + 2 * text_width(" ");
+    let content_width = rows
+        .iter()
+        .map(|(_, value)| value_column + text_width(value))
+        .chain([text_width(&title), text_width(hint)])
+        .max()
+        .unwrap_or(0);
+    let panel_width = (content_width + 2 * PADDING).min(framebuffer.width);
+    let panel_height = (rows.len() + 2) * line + 2 * PADDING;
+    let panel_x = framebuffer.width.saturating_sub(panel_width + MARGIN);
+    framebuffer.blend_rect(panel_x, panel_y, panel_width, panel_height, PANEL_COLOR, 0.6);
+
+    let left = (panel_x + PADDING) as i32;
+    let mut y = (panel_y + PADDING) as i32;
+    draw_text(framebuffer, left, y, &title, TITLE_COLOR);
+    y += line as i32;
+    for (index, (label, value)) in rows.iter().enumerate() {
+        let selected = index == editor.selected;
+        draw_text(framebuffer, left, y, label, if selected { SELECTED_COLOR } else { LABEL_COLOR });
+        draw_text(framebuffer, left + value_column as i32, y, value, if selected { SELECTED_COLOR } else { TEXT_COLOR });
+        y += line as i32;
+    }
+    draw_text(framebuffer, left, y, hint, LABEL_COLOR);
+    panel_y + panel_height + MARGIN
+}
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+
+// Colores de los anillos de órbita y de los acentos de la interfaz (selección, avisos,
+// indicadores). Los ajustes para daltonismo evitan los pares que cada tipo confunde y se
+// apoyan en los colores de Okabe-Ito.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorVision {
+    #[default]
+    Normal,
+    Deuteranopia,  // Rojo-verde (sin conos M), el más común
+    Protanopia,    // Rojo-verde con los rojos oscurecidos (sin conos L)
+    Tritanopia,    // Azul-amarillo (sin conos S)
+}
+
+impl ColorVision {
+    pub const ALL: [ColorVision; 4] = [ColorVision::Normal, ColorVision::Deuteranopia, ColorVision::Protanopia, ColorVision::Tritanopia];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorVision::Normal => "Normal",
+            ColorVision::Deuteranopia => "Deuteranopia",
+            ColorVision::Protanopia => "Protanopia",
+            ColorVision::Tritanopia => "Tritanopia",
+        }
+    }
+
+    pub fn palette(self) -> &'static Palette {
+        match self {
+            ColorVision::Normal => &NORMAL,
+            ColorVision::Deuteranopia => &DEUTERANOPIA,
+            ColorVision::Protanopia => &PROTANOPIA,
+            ColorVision::Tritanopia => &TRITANOPIA,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    pub orbits: &'static [u32],  // Un color por anillo, en orden; vacío = el color de la escena
+    pub selection: u32,          // Corchetes del cuerpo enfocado, opción del menú, cámara del minimapa
+    pub caution: u32,            // Avisos de precaución, nombre en la ficha del cuerpo
+    pub danger: u32,             // Colisión e impacto inminente
+    pub proximity: [u32; 3],     // Barra de proximidad: lejos, cerca, a punto de tocar
+    pub velocity: u32,           // Línea de velocidad de la nave
+    pub prograde: u32,
+    pub retrograde: u32,
+    pub lead: u32,               // Punto de intercepción de los disparos
+    pub trajectory: u32,         // Trayectoria futura de la nave con gravedad
+    pub ship: u32,               // La nave en el minimapa
+}
+
+impl Palette {
+    // Color de un anillo según su posición en la escena
+    pub fn orbit_color(&self, slot: usize, scene_color: u32) -> u32 {
+        if self.orbits.is_empty() {
+            scene_color
+        } else {
+            self.orbits[slot % self.orbits.len()]
+        }
+    }
+}
+
+const NORMAL: Palette = Palette {
+    orbits: &[],
+    selection: 0xFFE070,
+    caution: 0xFFB030,
+    danger: 0xFF4040,
+    proximity: [0x40C040, 0xE0C040, 0xFF4040],
+    velocity: 0x60FF60,
+    prograde: 0xE8E040,
+    retrograde: 0xE8A040,
+    lead: 0xFF6060,
+    trajectory: 0x70B8FF,
+    ship: 0x40FF80,
+};
+
+// Rojo-verde: los pares van por el eje azul-amarillo y el peligro en bermellón
+const DEUTERANOPIA: Palette = Palette {
+    orbits: &[0x56B4E9, 0xE69F00, 0xF0E442, 0xCC79A7, 0x0072B2, 0xFFFFFF],
+    selection: 0xF0E442,
+    caution: 0xE69F00,
+    danger: 0xD55E00,
+    proximity: [0x0072B2, 0xF0E442, 0xD55E00],
+    velocity: 0xFFFFFF,
+    prograde: 0xF0E442,
+    retrograde: 0x56B4E9,
+    lead: 0xD55E00,
+    trajectory: 0xCC79A7,
+    ship: 0x56B4E9,
+};
+
+// Como la anterior, pero el rojo se ve oscuro: el peligro va en naranja claro
+const PROTANOPIA: Palette = Palette {
+    orbits: &[0x56B4E9, 0xE69F00, 0xF0E442, 0xCC79A7, 0x0072B2, 0xFFFFFF],
+    selection: 0xF0E442,
+    caution: 0xF0E442,
+    danger: 0xE69F00,
+    proximity: [0x0072B2, 0xF0E442, 0xE69F00],
+    velocity: 0xFFFFFF,
+    prograde: 0xF0E442,
+    retrograde: 0x56B4E9,
+    lead: 0xE69F00,
+    trajectory: 0xCC79A7,
+    ship: 0x56B4E9,
+};
+
+// Azul-amarillo: los pares van por el eje rojo-cian
+const TRITANOPIA: Palette = Palette {
+    orbits: &[0xE8384F, 0x00B8C8, 0xFFFFFF, 0xFF9EC4, 0x008C8C, 0xB0B0B0],
+    selection: 0xFFFFFF,
+    caution: 0xFF9EC4,
+    danger: 0xE8384F,
+    proximity: [0x00B8C8, 0xFF9EC4, 0xE8384F],
+    velocity: 0xFFFFFF,
+    prograde: 0x00B8C8,
+    retrograde: 0xFF9EC4,
+    lead: 0xE8384F,
+    trajectory: 0xFF9EC4,
+    ship: 0x00B8C8,
+};
@@ -0,0 +1,161 @@
+use nalgebra_glm::Vec3;
+use crate::celestial_body::CelestialBody;
+
+// Radio de la zona de juego sin cuerpos que la fijen (la nave sola, antes de armar la escena)
+pub const DEFAULT_PLAY_AREA_RADIUS: f32 = 12.0;
+// Sin `play_area_radius` en la escena el límite queda esta fracción más allá de lo más lejano
+// que llega un cuerpo (el afelio de la órbita más externa)
+const PLAY_AREA_MARGIN: f32 = 0.75;
+// Franja antes del límite en la que el HUD ya avisa que se está saliendo del sistema
+const WARNING_MARGIN: f32 = 2.0;
+// Aceleración de retorno por unidad de distancia fuera del límite
+const PUSH_STRENGTH: f32 = 4.0;
+// Al cruzar en modo wrap se reaparece un poco dentro del lado opuesto para no volver a cruzar
+const WRAP_INSET: f32 = 0.98;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoundaryMode {
+    SoftPush,   // Una fuerza suave empuja la nave de vuelta hacia el sistema
+    WrapAround, // La nave reaparece en el punto opuesto de la esfera
+}
+
+impl BoundaryMode {
+    pub fn next(self) -> Self {
+        match self {
+            BoundaryMode::SoftPush => BoundaryMode::WrapAround,
+            BoundaryMode::WrapAround => BoundaryMode::SoftPush,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BoundaryMode::SoftPush => "Soft push",
+            BoundaryMode::WrapAround => "Wrap-around",
+        }
+    }
+}
+
+// Qué pasó al aplicar el límite en este frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoundaryEvent {
+    Inside,
+    Pushed,
+    Wrapped,
+}
+
+// Esfera que delimita la zona de juego
+#[derive(Debug, Clone, Copy)]
+pub struct PlayArea {
+    pub center: Vec3,
+    pub radius: f32,
+    pub mode: BoundaryMode,
+}
+
+impl Default for PlayArea {
+    fn default() -> Self {
+        PlayArea::new(Vec3::new(0.0, 0.0, 0.0), DEFAULT_PLAY_AREA_RADIUS)
+    }
+}
+
+impl PlayArea {
+    pub fn new(center: Vec3, radius: f32) -> Self {
+        PlayArea { center, radius, mode: BoundaryMode::SoftPush }
+    }
+
+    // Radio que abarca todo el sistema con margen: el afelio de cada órbita (sumado al alcance de
+    // su padre) más el radio del cuerpo y sus anillos. Nunca deja la franja de aviso sin lugar.
+    pub fn fit_radius(bodies: &[CelestialBody]) -> f32 {
+        let mut reach: Vec<f32> = Vec::with_capacity(bodies.len());
+        for (index, body) in bodies.iter().enumerate() {
+            let center = if body.orbit_radius > 0.0 {
+                let parent = body.parent.filter(|&parent| parent < index).map_or(0.0, |parent| reach[parent]);
+                parent + body.orbit_radius * (1.0 + body.orbit_eccentricity) + body.orbit_height.abs()
+            } else {
+                body.position.magnitude()
+            };
+            reach.push(center);
+        }
+        let outermost = bodies
+            .iter()
+            .zip(&reach)
+            .map(|(body, center)| center + body.scale * body.rings.iter().map(|band| band.outer).fold(1.0, f32::max))
+            .fold(0.0, f32::max);
+        (outermost * (1.0 + PLAY_AREA_MARGIN)).max(outermost + 2.0 * WARNING_MARGIN)
+    }
+
+    // Distancia restante hasta el límite (negativa si ya se está fuera)
+    pub fn distance_to_edge(&self, position: Vec3) -> f32 {
+        self.radius - (position - self.center).magnitude()
+    }
+
+    // Some(distancia al límite) cuando la nave está dentro de la franja de aviso o fuera
+    pub fn leaving_warning(&self, position: Vec3) -> Option<f32> {
+        let remaining = self.distance_to_edge(position);
+        (remaining < WARNING_MARGIN).then_some(remaining)
+    }
+
+    pub fn apply(&self, position: &mut Vec3, velocity: &mut Vec3, delta_time: f32) -> BoundaryEvent {
+        let offset = *position - self.center;
+        let distance = offset.magnitude();
+        if distance <= self.radius {
+            return BoundaryEvent::Inside;
+        }
+        let outward = offset / distance;
+
+        match self.mode {
+            BoundaryMode::SoftPush => {
+                // Como un resorte: cuanto más lejos del límite, más fuerte el retorno
+                let overshoot = distance - self.radius;
+                *velocity -= outward * overshoot * PUSH_STRENGTH * delta_time;
+                BoundaryEvent::Pushed
+            }
+            BoundaryMode::WrapAround => {
+                *position = self.center - outward * self.radius * WRAP_INSET;
+                BoundaryEvent::Wrapped
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inside_leaves_the_ship_alone() {
+        let area = PlayArea::new(Vec3::zeros(), 10.0);
+        let (mut position, mut velocity) = (Vec3::new(9.0, 0.0, 0.0), Vec3::new(3.0, 0.0, 0.0));
+        assert_eq!(area.apply(&mut position, &mut velocity, 0.1), BoundaryEvent::Inside);
+        assert_eq!((position, velocity), (Vec3::new(9.0, 0.0, 0.0), Vec3::new(3.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn soft_push_grows_with_the_overshoot() {
+        let area = PlayArea::new(Vec3::new(1.0, 0.0, 0.0), 10.0);
+        let (mut position, mut velocity) = (Vec3::new(14.0, 0.0, 0.0), Vec3::zeros());
+        assert_eq!(area.apply(&mut position, &mut velocity, 0.5), BoundaryEvent::Pushed);
+        // 3 unidades fuera: 3 * PUSH_STRENGTH * 0.5 hacia el centro; la posición no cambia
+        assert_eq!(position, Vec3::new(14.0, 0.0, 0.0));
+        assert!((velocity - Vec3::new(-3.0 * PUSH_STRENGTH * 0.5, 0.0, 0.0)).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn wrap_around_reappears_inside_the_opposite_side() {
+        let mut area = PlayArea::new(Vec3::zeros(), 10.0);
+        area.mode = BoundaryMode::WrapAround;
+        let velocity_before = Vec3::new(0.0, 4.0, 0.0);
+        let (mut position, mut velocity) = (Vec3::new(0.0, 11.0, 0.0), velocity_before);
+        assert_eq!(area.apply(&mut position, &mut velocity, 0.1), BoundaryEvent::Wrapped);
+        assert!((position - Vec3::new(0.0, -10.0 * WRAP_INSET, 0.0)).magnitude() < 1e-5);
+        assert_eq!(velocity, velocity_before);
+        assert!(area.distance_to_edge(position) > 0.0);
+    }
+
+    #[test]
+    fn paused_frame_does_not_push() {
+        let area = PlayArea::new(Vec3::zeros(), 10.0);
+        let (mut position, mut velocity) = (Vec3::new(12.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        assert_eq!(area.apply(&mut position, &mut velocity, 0.0), BoundaryEvent::Pushed);
+        assert_eq!(velocity, Vec3::new(1.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,134 @@
+// Perfilado por frame. El engine, la escena y el renderer abren spans de `tracing` (el frame,
+// la simulación y sus fases, cada pasada y cada objeto dibujado, y las etapas del pipeline);
+// sin `--features tracy` o `--features puffin` nadie los escucha y casi no cuestan nada.
+
+// Lo que tiene que seguir vivo mientras se perfila (el servidor de puffin)
+pub struct Profiler {
+    #[cfg(feature = "puffin")]
+    _server: Option<puffin_http::Server>,
+}
+
+// Instala los perfiladores compilados como suscriptores de `tracing`; sin ninguno no hace nada
+pub fn init() -> Profiler {
+    #[cfg(any(feature = "tracy", feature = "puffin"))]
+    {
+        use tracing_subscriber::layer::SubscriberExt;
+        let registry = tracing_subscriber::registry();
+        #[cfg(feature = "tracy")]
+        let registry = registry.with(tracing_tracy::TracyLayer::default());
+        #[cfg(feature = "puffin")]
+        let registry = registry.with(puffin_layer::PuffinLayer);
+        if let Err(e) = tracing::subscriber::set_global_default(registry) {
+            log::warn!("Could not install the profiler: {}", e);
+        }
+    }
+    #[cfg(feature = "tracy")]
+    log::info!("Tracy profiling enabled (connect with the Tracy profiler)");
+
+    Profiler {
+        #[cfg(feature = "puffin")]
+        _server: puffin_layer::start_server(),
+    }
+}
+
+// Fin de un frame para los perfiladores (lo llama `FrameStats::end_frame`)
+pub fn finish_frame() {
+    #[cfg(feature = "tracy")]
+    if let Some(client) = tracing_tracy::client::Client::running() {
+        client.frame_mark();
+    }
+    #[cfg(feature = "puffin")]
+    puffin::GlobalProfiler::lock().new_frame();
+}
+
+// Puffin no lee `tracing`: esta capa abre un scope de puffin por cada span mientras está activo
+#[cfg(feature = "puffin")]
+mod puffin_layer {
+    use log::{info, warn};
+    use puffin::{ScopeId, ThreadProfiler};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::fmt::{Debug, Write};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id};
+    use tracing::Subscriber;
+    use tracing_subscriber::layer::{Context, Layer};
+    use tracing_subscriber::registry::LookupSpan;
+
+    thread_local! {
+        // Scope de puffin de cada callsite (por dirección de su metadata) y los abiertos en este hilo
+        static SCOPES: RefCell<HashMap<usize, ScopeId>> = RefCell::default();
+        static OPEN: RefCell<Vec<usize>> = RefCell::default();
+    }
+
+    pub fn start_server() -> Option<puffin_http::Server> {
+        let address = format!("127.0.0.1:{}", puffin_http::DEFAULT_PORT);
+        match puffin_http::Server::new(&address) {
+            Ok(server) => {
+                puffin::set_scopes_on(true);
+                info!("Puffin profiling on {} (connect with puffin_viewer)", address);
+                Some(server)
+            }
+            Err(e) => {
+                warn!("Could not start the puffin server on {}: {}", address, e);
+                None
+            }
+        }
+    }
+
+    pub struct PuffinLayer;
+
+    // Campos del span ("object=Earth") que se muestran como datos del scope
+    struct SpanData(String);
+
+    impl Visit for SpanData {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.record_debug(field, &format_args!("{}", value));
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            let separator = if self.0.is_empty() { "" } else { " " };
+            let _ = write!(self.0, "{}{}={:?}", separator, field.name(), value);
+        }
+    }
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for PuffinLayer {
+        fn on_new_span(&self, attributes: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+            let mut data = SpanData(String::new());
+            attributes.record(&mut data);
+            if let Some(span) = ctx.span(id) {
+                span.extensions_mut().insert(data);
+            }
+        }
+
+        fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+            let Some(span) = ctx.span(id) else {
+                return;
+            };
+            let metadata = span.metadata();
+            let key = metadata as *const _ as usize;
+            let scope_id = SCOPES.with_borrow_mut(|scopes| {
+                *scopes.entry(key).or_insert_with(|| {
+                    ThreadProfiler::call(|profiler| {
+                        profiler.register_named_scope(
+                            metadata.name(),
+                            metadata.target(),
+                            metadata.file().unwrap_or_default(),
+                            metadata.line().unwrap_or_default(),
+                        )
+                    })
+                })
+            });
+            let extensions = span.extensions();
+            let data = extensions.get::<SpanData>().map_or("", |data| data.0.as_str());
+            let offset = ThreadProfiler::call(|profiler| profiler.begin_scope(scope_id, data));
+            OPEN.with_borrow_mut(|open| open.push(offset));
+        }
+
+        fn on_exit(&self, _id: &Id, _ctx: Context<'_, S>) {
+            if let Some(offset) = OPEN.with_borrow_mut(Vec::pop) {
+                ThreadProfiler::call(|profiler| profiler.end_scope(offset));
+            }
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use nalgebra_glm::Vec3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::camera::ScreenProjection;
+use crate::framebuffer::Framebuffer;
+use crate::renderable::{Renderable, RenderPass};
+
+const PROJECTILE_SPEED: f32 = 25.0;
+const PROJECTILE_LIFETIME: f32 = 2.0;    // Segundos antes de desaparecer sin impactar
+const STREAK_LENGTH: f32 = 0.06;         // Segundos de recorrido que abarca la estela
+const FIRE_COOLDOWN: f32 = 0.15;
+const MAX_AMMO: u32 = 30;
+const AMMO_RECHARGE_TIME: f32 = 0.5;     // Segundos para recuperar un disparo
+const IMPACT_PARTICLES: usize = 14;
+const PARTICLE_LIFETIME: f32 = 0.8;
+
+const STREAK_CORE_COLOR: u32 = 0xFFE8E8;
+const STREAK_GLOW_COLOR: u32 = 0xFF3030;
+
+pub struct Projectile {
+    pub position: Vec3,
+    pub velocity: Vec3,
+    pub life: f32,
+}
+
+pub struct Particle {
+    pub position: Vec3,
+    pub velocity: Vec3,
+    pub life: f32,
+}
+
+// Láseres de la nave: disparos con tiempo de vuelo, impactos con partículas y munición limitada
+pub struct Weapons {
+    pub projectiles: Vec<Projectile>,
+    pub particles: Vec<Particle>,
+    pub ammo: u32,
+    pub max_ammo: u32,
+    pub cooldown: f32,
+    recharge_timer: f32,
+    pub particle_scale: f32,  // Fracción de las chispas por impacto (la baja el presupuesto de frame)
+    rng: StdRng,  // Dispersión de las chispas; con semilla la simulación se puede repetir
+}
+
+impl Default for Weapons {
+    fn default() -> Self {
+        Weapons::new()
+    }
+}
+
+impl Weapons {
+    pub fn new() -> Self {
+        Weapons {
+            projectiles: Vec::new(),
+            particles: Vec::new(),
+            ammo: MAX_AMMO,
+            max_ammo: MAX_AMMO,
+            cooldown: 0.0,
+            recharge_timer: 0.0,
+            particle_scale: 1.0,
+            rng: StdRng::from_rng(&mut rand::rng()),
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    // Dispara desde `origin` hacia `direction`; la nave transfiere su velocidad al proyectil
+    pub fn try_fire(&mut self, origin: Vec3, direction: Vec3, ship_velocity: Vec3) -> bool {
+        if self.cooldown > 0.0 || self.ammo == 0 {
+            return false;
+        }
+        self.projectiles.push(Projectile {
+            position: origin,
+            velocity: direction.normalize() * PROJECTILE_SPEED + ship_velocity,
+            life: PROJECTILE_LIFETIME,
+        });
+        self.ammo -= 1;
+        self.cooldown = FIRE_COOLDOWN;
+        true
+    }
+
+    // Avanza proyectiles y partículas; `obstacles` son esferas (centro, radio) contra las que impactan.
+    // Devuelve la cantidad de impactos de este frame.
+    pub fn update(&mut self, delta_time: f32, obstacles: &[(Vec3, f32)]) -> usize {
+        self.cooldown = (self.cooldown - delta_time).max(0.0);
+
+        // Recarga lenta de munición
+        if self.ammo < self.max_ammo {
+            self.recharge_timer += delta_time;
+            if self.recharge_timer >= AMMO_RECHARGE_TIME {
+                self.recharge_timer -= AMMO_RECHARGE_TIME;
+                self.ammo += 1;
+            }
+        } else {
+            self.recharge_timer = 0.0;
+        }
+
+        let mut impacts = Vec::new();
+        self.projectiles.retain_mut(|projectile| {
+            let start = projectile.position;
+            let end = start + projectile.velocity * delta_time;
+            projectile.position = end;
+            projectile.life -= delta_time;
+
+            // Prueba el segmento recorrido este frame para no atravesar cuerpos a alta velocidad
+            let hit = obstacles
+                .iter()
+                .filter_map(|&(center, radius)| segment_sphere_hit(start, end, center, radius).map(|p| (p, center)))
+                .min_by(|a, b| (a.0 - start).magnitude().total_cmp(&(b.0 - start).magnitude()));
+
+            if let Some((point, center)) = hit {
+                impacts.push((point, (point - center).normalize()));
+                return false;
+            }
+            projectile.life > 0.0
+        });
+
+        for &(point, normal) in &impacts {
+            self.spawn_impact(point, normal);
+        }
+
+        self.particles.retain_mut(|particle| {
+            particle.position += particle.velocity * delta_time;
+            particle.velocity *= 0.96;
+            particle.life -= delta_time;
+            particle.life > 0.0
+        });
+
+        impacts.len()
+    }
+
+    // Chispas que salen rebotadas desde la superficie impactada
+    fn spawn_impact(&mut self, point: Vec3, normal: Vec3) {
+        let rng = &mut self.rng;
+        let count = ((IMPACT_PARTICLES as f32 * self.particle_scale).round() as usize).max(1);
+        for _ in 0..count {
+            let random = Vec3::new(
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+            );
+            let direction = (normal + random * 0.8).normalize();
+            self.particles.push(Particle {
+                position: point,
+                velocity: direction * rng.random_range(0.5..2.5),
+                life: PARTICLE_LIFETIME * rng.random_range(0.5..1.0),
+            });
+        }
+    }
+
+    pub fn render(&self, framebuffer: &mut Framebuffer, projection: &ScreenProjection) {
+        // Estelas brillantes: núcleo claro con un halo rojo a los lados
+        for projectile in &self.projectiles {
+            let tail = projectile.position - projectile.velocity * STREAK_LENGTH;
+            if let Some((from, to)) = projection.project_segment(tail, projectile.position) {
+                let (x0, y0, x1, y1) = (from.x as i32, from.y as i32, to.x as i32, to.y as i32);
+                framebuffer.draw_depth_line((x0, y0 + 1, from.z), (x1, y1 + 1, to.z), STREAK_GLOW_COLOR);
+                framebuffer.draw_depth_line((x0 + 1, y0, from.z), (x1 + 1, y1, to.z), STREAK_GLOW_COLOR);
+                framebuffer.draw_depth_line((x0, y0, from.z), (x1, y1, to.z), STREAK_CORE_COLOR);
+            }
+        }
+
+        // Partículas de impacto: de amarillo a rojo oscuro según se apagan
+        for particle in &self.particles {
+            if let Some(p) = projection.project(particle.position).filter(|p| p.x >= 0.0 && p.y >= 0.0) {
+                let t = (particle.life / PARTICLE_LIFETIME).clamp(0.0, 1.0);
+                let r = 255;
+                let g = (60.0 + 180.0 * t) as u32;
+                let b = (40.0 * t) as u32;
+                framebuffer.set_current_color((r << 16) | (g << 8) | b);
+                framebuffer.point(p.x as usize, p.y as usize, p.z);
+                if t > 0.5 {
+                    framebuffer.point(p.x as usize + 1, p.y as usize, p.z);
+                }
+            }
+        }
+    }
+}
+
+impl Renderable for Weapons {
+    fn render_pass(&self) -> RenderPass {
+        RenderPass::Effects
+    }
+
+    fn name(&self) -> &str {
+        "Weapons"
+    }
+
+    fn draw_direct(&self, framebuffer: &mut Framebuffer, projection: &ScreenProjection) {
+        self.render(framebuffer, projection);
+    }
+}
+
+// Punto al que hay que apuntar para acertar a un blanco que se mueve a velocidad constante.
+// El proyectil sale con la velocidad del tirador más PROJECTILE_SPEED en la dirección de
+// disparo, así que se resuelve en el marco del tirador: |p + v t| = s t, con p y v la
+// posición y velocidad relativas del blanco. None si el blanco se aleja más rápido que el disparo.
+pub fn lead_point(shooter_position: Vec3, shooter_velocity: Vec3, target_position: Vec3, target_velocity: Vec3) -> Option<Vec3> {
+    let p = target_position - shooter_position;
+    let v = target_velocity - shooter_velocity;
+    let a = v.dot(&v) - PROJECTILE_SPEED * PROJECTILE_SPEED;
+    let b = 2.0 * p.dot(&v);
+    let c = p.dot(&p);
+
+    let time = if a.abs() < 1e-6 {
+        // Blanco tan rápido como el disparo: la ecuación queda lineal
+        (b < 0.0).then(|| -c / b)?
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        [(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+            .into_iter()
+            .filter(|&t| t > 0.0)
+            .min_by(f32::total_cmp)?
+    };
+
+    Some(shooter_position + p + v * time)
+}
+
+// Primer punto del segmento que toca la esfera (None si no la toca)
+fn segment_sphere_hit(start: Vec3, end: Vec3, center: Vec3, radius: f32) -> Option<Vec3> {
+    let segment = end - start;
+    let length = segment.magnitude();
+    if length <= f32::EPSILON {
+        return ((start - center).magnitude() <= radius).then_some(start);
+    }
+    let direction = segment / length;
+    let m = start - center;
+    let b = m.dot(&direction);
+    let c = m.dot(&m) - radius * radius;
+    if c > 0.0 && b > 0.0 {
+        return None;
+    }
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let t = (-b - discriminant.sqrt()).max(0.0);
+    (t <= length).then(|| start + direction * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_through_the_sphere_hits_the_near_side() {
+        let hit = segment_sphere_hit(Vec3::new(-5.0, 0.0, 0.0), Vec3::new(5.0, 0.0, 0.0), Vec3::zeros(), 1.0).unwrap();
+        assert!((hit - Vec3::new(-1.0, 0.0, 0.0)).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn segment_ending_before_the_sphere_misses() {
+        assert_eq!(segment_sphere_hit(Vec3::new(-5.0, 0.0, 0.0), Vec3::new(-2.0, 0.0, 0.0), Vec3::zeros(), 1.0), None);
+    }
+
+    #[test]
+    fn segment_passing_beside_the_sphere_misses() {
+        assert_eq!(segment_sphere_hit(Vec3::new(-5.0, 2.0, 0.0), Vec3::new(5.0, 2.0, 0.0), Vec3::zeros(), 1.0), None);
+    }
+
+    #[test]
+    fn segment_leaving_the_sphere_behind_misses() {
+        assert_eq!(segment_sphere_hit(Vec3::new(2.0, 0.0, 0.0), Vec3::new(5.0, 0.0, 0.0), Vec3::zeros(), 1.0), None);
+    }
+
+    #[test]
+    fn segment_starting_inside_hits_at_its_start() {
+        let start = Vec3::new(0.5, 0.0, 0.0);
+        assert_eq!(segment_sphere_hit(start, Vec3::new(5.0, 0.0, 0.0), Vec3::zeros(), 1.0), Some(start));
+    }
+
+    #[test]
+    fn zero_length_segment_hits_only_inside() {
+        let inside = Vec3::new(0.0, 0.5, 0.0);
+        assert_eq!(segment_sphere_hit(inside, inside, Vec3::zeros(), 1.0), Some(inside));
+        let outside = Vec3::new(0.0, 1.5, 0.0);
+        assert_eq!(segment_sphere_hit(outside, outside, Vec3::zeros(), 1.0), None);
+    }
+}
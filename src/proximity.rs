@@ -0,0 +1,225 @@
+use nalgebra_glm::Vec3;
+
+// Umbrales de tiempo hasta el impacto (segundos) para cada nivel de alerta
+const CAUTION_TIME: f32 = 5.0;
+const WARNING_TIME: f32 = 2.5;
+const CRITICAL_TIME: f32 = 1.0;
+// Por debajo de esta velocidad relativa la nave se considera detenida respecto al cuerpo y no
+// hay trayectoria de impacto
+const MIN_SPEED: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WarningLevel {
+    #[default]
+    Clear,
+    Caution,
+    Warning,
+    Critical,
+}
+
+impl WarningLevel {
+    fn from_time_to_impact(time: f32) -> Self {
+        if time < CRITICAL_TIME {
+            WarningLevel::Critical
+        } else if time < WARNING_TIME {
+            WarningLevel::Warning
+        } else if time < CAUTION_TIME {
+            WarningLevel::Caution
+        } else {
+            WarningLevel::Clear
+        }
+    }
+
+    // Parpadeos por segundo de la alerta en pantalla (más rápido cuanto más cerca del impacto)
+    pub fn blink_rate(&self) -> f32 {
+        match self {
+            WarningLevel::Clear => 0.0,
+            WarningLevel::Caution => 1.0,
+            WarningLevel::Warning => 2.5,
+            WarningLevel::Critical => 6.0,
+        }
+    }
+}
+
+// Resultado de la evaluación de un frame: el impacto más próximo en la trayectoria actual
+#[derive(Debug, Clone, Copy)]
+pub struct ProximityWarning {
+    pub level: WarningLevel,
+    pub time_to_impact: Option<f32>,
+    pub body_name: &'static str,
+}
+
+impl ProximityWarning {
+    pub fn clear() -> Self {
+        ProximityWarning { level: WarningLevel::Clear, time_to_impact: None, body_name: "" }
+    }
+
+    // Proyecta la trayectoria rectilínea de la nave respecto a cada cuerpo (centro, velocidad,
+    // radio, nombre), con la velocidad relativa, y busca el primero que tocaría su esfera envolvente
+    pub fn assess(
+        ship_position: Vec3,
+        ship_velocity: Vec3,
+        ship_radius: f32,
+        bodies: &[(Vec3, Vec3, f32, &'static str)],
+    ) -> Self {
+        let nearest = bodies
+            .iter()
+            .filter_map(|&(center, velocity, radius, name)| {
+                let relative = ship_velocity - velocity;
+                let speed = relative.magnitude();
+                if speed < MIN_SPEED {
+                    return None;
+                }
+                time_to_sphere(ship_position, relative / speed, center, radius + ship_radius)
+                    .map(|distance| (distance / speed, name))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+
+        match nearest {
+            Some((time, name)) => ProximityWarning {
+                level: WarningLevel::from_time_to_impact(time),
+                time_to_impact: Some(time),
+                body_name: name,
+            },
+            None => ProximityWarning::clear(),
+        }
+    }
+
+    // La alerta está "encendida" en esta fase del parpadeo
+    pub fn is_flash_on(&self, time: f32) -> bool {
+        let rate = self.level.blink_rate();
+        rate > 0.0 && (time * rate).fract() < 0.5
+    }
+}
+
+// Cuándo suena el pitido de alerta: en cuanto sube el nivel y después al ritmo de su parpadeo
+#[derive(Debug, Default)]
+pub struct WarningBeep {
+    level: WarningLevel,
+    until_next: f32,  // Segundos hasta el próximo pitido
+}
+
+impl WarningBeep {
+    // Devuelve true si hay que sonar este frame
+    pub fn update(&mut self, level: WarningLevel, delta_time: f32) -> bool {
+        let escalated = level > self.level;
+        self.level = level;
+        let rate = level.blink_rate();
+        if rate <= 0.0 {
+            self.until_next = 0.0;
+            return false;
+        }
+        self.until_next -= delta_time;
+        if escalated || self.until_next <= 0.0 {
+            self.until_next = 1.0 / rate;
+            return true;
+        }
+        false
+    }
+}
+
+// Distancia a lo largo del rayo hasta la esfera; 0 si ya se está dentro
+fn time_to_sphere(origin: Vec3, direction: Vec3, center: Vec3, radius: f32) -> Option<f32> {
+    let m = origin - center;
+    let c = m.dot(&m) - radius * radius;
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+    let b = m.dot(&direction);
+    if b > 0.0 {
+        return None;
+    }
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    Some(-b - discriminant.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STILL: Vec3 = Vec3::new(0.0, 0.0, 0.0);
+
+    #[test]
+    fn head_on_approach_reports_the_time_to_impact() {
+        // 9 unidades hasta la superficie a 5 u/s
+        let bodies = [(Vec3::zeros(), STILL, 1.0, "Ares")];
+        let warning = ProximityWarning::assess(Vec3::new(0.0, 0.0, 10.0), Vec3::new(0.0, 0.0, -5.0), 0.0, &bodies);
+        assert_eq!(warning.level, WarningLevel::Warning);
+        assert_eq!(warning.body_name, "Ares");
+        assert!((warning.time_to_impact.unwrap() - 1.8).abs() < 1e-5);
+    }
+
+    #[test]
+    fn ship_radius_counts_toward_contact() {
+        let bodies = [(Vec3::zeros(), STILL, 1.0, "Ares")];
+        let warning = ProximityWarning::assess(Vec3::new(0.0, 0.0, 10.0), Vec3::new(0.0, 0.0, -5.0), 1.0, &bodies);
+        assert!((warning.time_to_impact.unwrap() - 1.6).abs() < 1e-5);
+    }
+
+    #[test]
+    fn moving_away_or_past_is_clear() {
+        let bodies = [(Vec3::zeros(), STILL, 1.0, "Ares")];
+        let away = ProximityWarning::assess(Vec3::new(0.0, 0.0, 10.0), Vec3::new(0.0, 0.0, 5.0), 0.0, &bodies);
+        assert_eq!(away.level, WarningLevel::Clear);
+        let past = ProximityWarning::assess(Vec3::new(3.0, 0.0, 10.0), Vec3::new(0.0, 0.0, -5.0), 0.0, &bodies);
+        assert_eq!(past.level, WarningLevel::Clear);
+        assert_eq!(past.time_to_impact, None);
+    }
+
+    #[test]
+    fn body_flying_alongside_is_clear() {
+        let velocity = Vec3::new(0.0, 0.0, -5.0);
+        let bodies = [(Vec3::zeros(), velocity, 1.0, "Ares")];
+        let warning = ProximityWarning::assess(Vec3::new(0.0, 0.0, 10.0), velocity, 0.0, &bodies);
+        assert_eq!(warning.level, WarningLevel::Clear);
+    }
+
+    #[test]
+    fn body_closing_on_a_parked_ship_warns() {
+        let bodies = [(Vec3::zeros(), Vec3::new(0.0, 0.0, 10.0), 1.0, "Ares")];
+        let warning = ProximityWarning::assess(Vec3::new(0.0, 0.0, 10.0), STILL, 0.0, &bodies);
+        assert_eq!(warning.level, WarningLevel::Critical);
+        assert!((warning.time_to_impact.unwrap() - 0.9).abs() < 1e-5);
+    }
+
+    #[test]
+    fn nearest_impact_wins() {
+        let bodies = [
+            (Vec3::new(0.0, 0.0, -20.0), STILL, 1.0, "Far"),
+            (Vec3::new(0.0, 0.0, -5.0), STILL, 1.0, "Near"),
+        ];
+        let warning = ProximityWarning::assess(Vec3::zeros(), Vec3::new(0.0, 0.0, -2.0), 0.0, &bodies);
+        // 4 unidades a 2 u/s
+        assert_eq!(warning.body_name, "Near");
+        assert_eq!(warning.level, WarningLevel::Warning);
+    }
+
+    #[test]
+    fn inside_the_sphere_is_an_immediate_impact() {
+        let bodies = [(Vec3::zeros(), STILL, 2.0, "Ares")];
+        let warning = ProximityWarning::assess(Vec3::new(1.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.0, &bodies);
+        assert_eq!(warning.time_to_impact, Some(0.0));
+        assert_eq!(warning.level, WarningLevel::Critical);
+    }
+
+    #[test]
+    fn beep_sounds_on_escalation_and_repeats_at_the_blink_rate() {
+        let mut beep = WarningBeep::default();
+        assert!(!beep.update(WarningLevel::Clear, 0.1));
+        assert!(beep.update(WarningLevel::Caution, 0.1));
+        // Caution parpadea a 1 Hz
+        assert!(!beep.update(WarningLevel::Caution, 0.6));
+        assert!(beep.update(WarningLevel::Caution, 0.5));
+        // Subir de nivel suena aunque no haya pasado el período
+        assert!(beep.update(WarningLevel::Critical, 0.01));
+        assert!(!beep.update(WarningLevel::Critical, 0.1));
+        assert!(beep.update(WarningLevel::Critical, 0.1));
+        // Bajar de nivel no suena enseguida
+        assert!(!beep.update(WarningLevel::Warning, 0.01));
+        assert!(!beep.update(WarningLevel::Clear, 1.0));
+        assert!(beep.update(WarningLevel::Caution, 0.01));
+    }
+}
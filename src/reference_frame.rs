@@ -0,0 +1,107 @@
+use nalgebra_glm::Vec3;
+use crate::camera::ScreenProjection;
+use crate::framebuffer::Framebuffer;
+use crate::scene::Scene;
+
+// Exponente de Laplace para la esfera de influencia: r = a (m / M)^(2/5)
+const SOI_EXPONENT: f32 = 0.4;
+const OUTLINE_ALPHA: f32 = 0.5;
+const OUTLINE_SEGMENTS: usize = 64;
+
+// Esfera de influencia del cuerpo `index` alrededor de su padre: donde su gravedad manda sobre la
+// del padre. Las masas salen del volumen, como en gravity.rs. None = sin padre (no tiene límite).
+pub fn sphere_of_influence(scene: &Scene, index: usize) -> Option<f32> {
+    let body = scene.bodies.get(index)?;
+    let parent = scene.bodies.get(body.parent?)?;
+    if body.orbit_radius <= 0.0 {
+        return None;
+    }
+    let mass_ratio = (body.scale / parent.scale).powi(3);
+    Some(body.orbit_radius * mass_ratio.powf(SOI_EXPONENT))
+}
+
+// Cuerpo cuya gravedad manda en `position`: la esfera de influencia más chica que lo contiene
+// (una luna antes que su planeta) o, fuera de todas, el cuerpo sin padre más grande (la estrella)
+pub fn dominant_body(scene: &Scene, position: Vec3) -> Option<usize> {
+    let inside = (0..scene.bodies.len())
+        .filter_map(|index| sphere_of_influence(scene, index).map(|radius| (index, radius)))
+        .filter(|&(index, radius)| (position - scene.bodies[index].position).magnitude() < radius)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index);
+    inside.or_else(|| {
+        scene.bodies
+            .iter()
+            .enumerate()
+            .filter(|(_, body)| body.parent.is_none())
+            .max_by(|a, b| a.1.scale.total_cmp(&b.1.scale))
+            .map(|(index, _)| index)
+    })
+}
+
+// Sistema de referencia de la nave y la cámara (tecla P). Inercial: todo se mide respecto al
+// origen de la escena. Local: respecto al cuerpo dominante donde está la nave, que cambia solo al
+// cruzar una esfera de influencia; la velocidad del HUD, los indicadores de velocidad y la
+// trayectoria predicha son relativos a él, y la cámara libre viaja con él.
+#[derive(Debug, Default)]
+pub struct ReferenceFrame {
+    pub local: bool,
+    body: Option<usize>,        // Cuerpo del sistema local (None en el inercial)
+    last_origin: Option<Vec3>,  // Posición del cuerpo en el paso anterior
+}
+
+impl ReferenceFrame {
+    pub fn body(&self) -> Option<usize> {
+        self.body
+    }
+
+    // Vuelve a elegir el cuerpo tras mover la simulación; devuelve el nuevo si cambió
+    pub fn update(&mut self, scene: &Scene) -> Option<usize> {
+        let body = if self.local { dominant_body(scene, scene.spaceship.position) } else { None };
+        if body == self.body {
+            return None;
+        }
+        self.body = body;
+        self.last_origin = None;
+        body
+    }
+
+    // Cuánto se movió el cuerpo del sistema desde la última llamada (cero al cambiar de cuerpo)
+    pub fn origin_shift(&mut self, scene: &Scene) -> Vec3 {
+        let origin = self.body.and_then(|index| scene.bodies.get(index)).map(|body| body.position);
+        let shift = origin.zip(self.last_origin).map_or_else(Vec3::zeros, |(origin, last)| origin - last);
+        self.last_origin = origin;
+        shift
+    }
+
+    // Velocidad vista desde el sistema: se le resta la del cuerpo
+    pub fn relative_velocity(&self, scene: &Scene, velocity: Vec3) -> Vec3 {
+        let frame_velocity = self.body.and_then(|index| scene.bodies.get(index)).map_or_else(Vec3::zeros, |body| body.velocity);
+        velocity - frame_velocity
+    }
+
+    pub fn body_name(&self, scene: &Scene) -> Option<&'static str> {
+        self.body.and_then(|index| scene.bodies.get(index)).map(|body| body.name)
+    }
+
+    // Borde de la esfera de influencia del cuerpo del sistema, como un círculo en el plano XZ
+    pub fn render(&self, framebuffer: &mut Framebuffer, projection: &ScreenProjection, scene: &Scene, color: u32) {
+        let Some(index) = self.body else {
+            return;
+        };
+        let Some(radius) = sphere_of_influence(scene, index) else {
+            return;
+        };
+        let center = scene.bodies[index].position;
+        let points: Vec<Vec3> = (0..=OUTLINE_SEGMENTS)
+            .map(|i| {
+                let angle = i as f32 / OUTLINE_SEGMENTS as f32 * std::f32::consts::TAU;
+                center + Vec3::new(angle.cos(), 0.0, angle.sin()) * radius
+            })
+            .collect();
+        for pair in points.windows(2) {
+            if let Some((from, to)) = projection.project_segment(pair[0], pair[1]) {
+                framebuffer.blend_line(from.x.round() as i32, from.y.round() as i32, to.x.round() as i32, to.y.round() as i32, color, OUTLINE_ALPHA);
+            }
+        }
+    }
+}
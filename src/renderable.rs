@@ -0,0 +1,109 @@
+use nalgebra_glm::{Mat4, Vec3};
+use std::borrow::Cow;
+use std::sync::Arc;
+use crate::camera::ScreenProjection;
+use crate::celestial_body::ShaderType;
+use crate::framebuffer::Framebuffer;
+use crate::mesh_registry::MeshRegistry;
+use crate::obj_loader::MtlMaterial;
+use crate::palette::Palette;
+use crate::rings::RingBand;
+use crate::ship_material::ShipMaterial;
+use crate::texture::Texture;
+use crate::vertex::Vertex;
+
+// En qué pasada del frame se dibuja un objeto (`frame_graph::OPAQUE`, `TRANSPARENT` o
+// `PARTICLES`). Las etiquetas miran el z-buffer que deja `Opaque`, antes de que las líneas de
+// `Lines` cuenten como oclusores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderPass {
+    Opaque,   // Cuerpos y nave
+    Lines,    // Órbitas
+    Effects,  // Láseres y partículas, directo en pantalla
+}
+
+impl RenderPass {
+    pub const ALL: [RenderPass; 3] = [RenderPass::Opaque, RenderPass::Lines, RenderPass::Effects];
+}
+
+// Cómo se colorean los fragmentos de una malla
+pub enum Shading<'a> {
+    Shader(ShaderType),  // Shader procedural del cuerpo
+    Ship {
+        material: &'a ShipMaterial,
+        surface_material: Option<&'a MtlMaterial>,
+        texture: Option<Arc<Texture>>,
+    },
+    Flat(u32),  // Color fijo, sin iluminación
+    Ring(&'a RingBand),  // Banda de anillos: densidad por ruido y transparencia en trama
+}
+
+// Cómo trata el pipeline a un objeto, para que los especiales (fondo, marcadores, ayudas de
+// depuración) no necesiten excepciones a mano en cada pasada
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderFlags {
+    pub visible: bool,        // false = no se dibuja (tampoco en el minimapa ni con etiqueta)
+    pub cast_shadows: bool,   // Puede tapar el sol a otros cuerpos
+    pub receive_light: bool,  // false = iluminado entero, sin lado oscuro (emisivo)
+    pub draw_orbit: bool,     // Dibuja su anillo de órbita, si la escena tiene uno
+    pub always_on_top: bool,  // Delante de todo lo demás, sin importar la profundidad
+}
+
+impl Default for RenderFlags {
+    fn default() -> Self {
+        RenderFlags { visible: true, cast_shadows: true, receive_light: true, draw_orbit: true, always_on_top: false }
+    }
+}
+
+// Una malla con su sombreado: vértices únicos e índices (3 por triángulo)
+pub struct DrawItem<'a> {
+    pub vertices: Cow<'a, [Vertex]>,
+    pub indices: Cow<'a, [u32]>,
+    pub shading: Shading<'a>,
+    pub time: f32,  // Tiempo que recibe el shader
+}
+
+// Lo que un objeto puede consultar del frame para decidir qué dibuja
+pub struct RenderView<'a> {
+    pub projection: &'a ScreenProjection,
+    pub camera_eye: Vec3,
+    pub lod_bias: f32,  // Escala el radio en pantalla con que se elige el nivel de detalle
+    pub time: f32,
+    pub meshes: &'a MeshRegistry,
+    pub palette: &'a Palette,  // Colores de las órbitas (ajuste de daltonismo)
+    pub focus: Option<usize>,  // Cuerpo que sigue la cámara, si sigue alguno
+}
+
+// Todo lo que aparece en la escena. El renderer recorre una sola lista de `Renderable` por
+// pasada: rasteriza las mallas de `draw_items` con `model_matrix` y luego llama a `draw_direct`.
+pub trait Renderable {
+    fn render_pass(&self) -> RenderPass;
+
+    // Nombre en los spans de perfilado
+    fn name(&self) -> &str;
+
+    fn model_matrix(&self) -> Mat4 {
+        Mat4::identity()
+    }
+
+    fn render_flags(&self) -> RenderFlags {
+        RenderFlags::default()
+    }
+
+    // Esfera envolvente (centro, radio) para descartar por distancia; None = nunca se descarta
+    fn bounds(&self) -> Option<(Vec3, f32)> {
+        None
+    }
+
+    // Anillos (en el espacio del modelo) que hacen sombra sobre el objeto y reciben la suya
+    fn shadow_rings(&self) -> &[RingBand] {
+        &[]
+    }
+
+    fn draw_items<'a>(&'a self, _view: &RenderView<'a>) -> Vec<DrawItem<'a>> {
+        Vec::new()
+    }
+
+    // Para lo que no es una malla (láseres, chispas)
+    fn draw_direct(&self, _framebuffer: &mut Framebuffer, _projection: &ScreenProjection) {}
+}
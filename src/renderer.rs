@@ -0,0 +1,806 @@
+use nalgebra_glm::{Mat4, Vec3};
+use std::f32::consts::PI;
+use std::sync::Arc;
+use tracing::{debug_span, info_span};
+use crate::antialias;
+use crate::camera::{create_perspective_matrix, create_view_matrix, create_viewport_matrix, Camera, CameraMode, ScreenProjection};
+use crate::celestial_body::ShaderType;
+use crate::color_grading;
+use crate::frame_budget::Quality;
+use crate::frame_graph::{self, FrameGraph, FramePass, PassContext, Target, UiHook};
+use crate::frame_pipeline::{BatchShading, FramePipeline, RecordedFrame, ShadeQueue, ShadedFrame};
+use crate::frame_stats::{FrameStats, Stage};
+use crate::framebuffer::Framebuffer;
+use crate::labels::{self, BodyLabel};
+use crate::markers::{self, ProjectedMarker};
+use crate::renderable::{RenderPass, RenderView, Shading};
+use crate::rings::RingBand;
+use crate::scene::Scene;
+use crate::settings::{AntiAliasing, Resolution, Settings};
+use crate::shaders::{vertex_shader, FrameUniforms, Uniforms};
+use crate::sky;
+use crate::temporal::{self, TemporalUpscaler};
+use crate::texture::{Sampler, Texture};
+use crate::triangle::triangle;
+use crate::vertex::Vertex;
+use crate::embedded;
+use crate::error::Error;
+
+pub const BACKGROUND_COLOR: u32 = 0x000011;
+
+// Lo que queda del frame 3D para las capas que se dibujan encima (etiquetas, HUD, indicadores)
+pub struct SceneFrame {
+    pub projection: ScreenProjection,
+    pub uniforms: Arc<FrameUniforms>,  // Matrices y ruido comunes a todos los dibujos del frame
+    pub labels: Vec<BodyLabel>,
+    pub markers: Vec<ProjectedMarker>,  // Marcadores de superficie visibles (con las etiquetas)
+    pub luminance: Option<f32>,  // Brillo medio de la escena antes de los ajustes (con `auto_exposure`)
+}
+
+// Lo que se dibuja en un frame
+pub struct FrameInputs<'a> {
+    pub scene: &'a Scene,
+    pub camera: &'a Camera,
+    pub settings: &'a Settings,
+    pub with_labels: bool,  // Calcular las etiquetas visibles aunque no se muestren (capturas anotadas)
+    pub focus: Option<usize>,  // Cuerpo enfocado por la cámara: su órbita se resalta
+    pub quality: Quality,  // Recortes del presupuesto de frame (`Quality::Full` = ninguno)
+}
+
+// Rasterizador por software: framebuffer con resolución interna propia, contadores del frame,
+// la proyección fija de la ventana y las pasadas con que se arma cada frame
+pub struct Renderer {
+    pub framebuffer: Framebuffer,
+    pub frame_stats: FrameStats,
+    pub projection_matrix: Mat4,
+    pub graph: FrameGraph,
+    pub scene_settings: Settings,  // Ajustes de imagen de `render_scene` (el engine pasa los suyos en cada frame)
+    shade_queue: ShadeQueue,
+    pipeline: Option<FramePipeline>,  // Some = sombreado en otro hilo, un frame por detrás
+    window_size: (usize, usize),  // El framebuffer se dimensiona según esto y la resolución interna
+    frame_index: u64,  // Frames grabados: elige la fase del reescalado temporal
+}
+
+impl Renderer {
+    pub fn new(window_width: usize, window_height: usize, resolution: Resolution) -> Self {
+        let (width, height) = resolution.framebuffer_size(window_width, window_height);
+        let mut framebuffer = Framebuffer::new(width, height);
+        framebuffer.set_background_color(BACKGROUND_COLOR);
+
+        Renderer {
+            framebuffer,
+            frame_stats: FrameStats::new(),
+            projection_matrix: create_perspective_matrix(window_width as f32, window_height as f32),
+            graph: standard_graph(),
+            scene_settings: Settings::default(),
+            shade_queue: ShadeQueue::new(),
+            pipeline: None,
+            window_size: (window_width, window_height),
+            frame_index: 0,
+        }
+    }
+
+    pub fn aspect_ratio(&self) -> f32 {
+        self.window_size.0 as f32 / self.window_size.1 as f32
+    }
+
+    // Otro tamaño de ventana (o de imagen de destino): proyección nueva y el framebuffer que
+    // corresponda con `resolution`; devuelve el tamaño nuevo del framebuffer si cambió
+    pub fn resize(&mut self, window_width: usize, window_height: usize, resolution: Resolution) -> Option<(usize, usize)> {
+        if self.window_size != (window_width, window_height) {
+            self.window_size = (window_width, window_height);
+            self.projection_matrix = create_perspective_matrix(window_width as f32, window_height as f32);
+        }
+        self.apply_resolution(resolution)
+    }
+
+    // Recrea el framebuffer si cambió la resolución interna; devuelve el tamaño nuevo
+    pub fn apply_resolution(&mut self, resolution: Resolution) -> Option<(usize, usize)> {
+        let (width, height) = resolution.framebuffer_size(self.window_size.0, self.window_size.1);
+        if (width, height) == (self.framebuffer.width, self.framebuffer.height) {
+            return None;
+        }
+        // El frame que se estaba sombreando tiene el tamaño anterior: se descarta
+        if let Some(pipeline) = &mut self.pipeline {
+            pipeline.flush();
+        }
+        self.framebuffer = Framebuffer::new(width, height);
+        self.framebuffer.set_background_color(BACKGROUND_COLOR);
+        Some((width, height))
+    }
+
+    // Con el pipeline, el fragment shader de cada frame corre en otro hilo mientras se simula y
+    // rasteriza el siguiente; la imagen presentada va un frame por detrás. Devuelve si quedó
+    // activo (sin hilos, como en el build web, sigue todo en el hilo principal).
+    pub fn set_pipelined(&mut self, enabled: bool) -> bool {
+        if !enabled {
+            self.pipeline = None;
+        } else if self.pipeline.is_none() {
+            self.pipeline = FramePipeline::new();
+        }
+        self.pipeline.is_some()
+    }
+
+    pub fn is_pipelined(&self) -> bool {
+        self.pipeline.is_some()
+    }
+
+    // Corre las pasadas de `graph` en orden; `ui` dibuja la interfaz en la pasada `UI`
+    pub fn render_frame(&mut self, inputs: FrameInputs, ui: &mut UiHook) -> SceneFrame {
+        let _span = info_span!("render_scene").entered();
+        let Some(pipeline) = &mut self.pipeline else {
+            return self.render_immediate(inputs, ui);
+        };
+        let mut frame = scene_frame(inputs.camera, self.projection_matrix, &self.framebuffer);
+        let objects = inputs.scene.renderables();
+
+        // Pipeline: las pasadas hasta `RESOLVE` de este frame se graban ahora y se sombrean en el
+        // hilo; mientras, se terminan (partículas, post, UI) y se presentan las del anterior. Se
+        // graba en el framebuffer ya presentado, o en el de repuesto si no hay nada en el hilo.
+        let mut recording = pipeline
+            .take_spare(&self.framebuffer)
+            .unwrap_or_else(|| std::mem::replace(&mut self.framebuffer, Framebuffer::new(0, 0)));
+        recording.interleave = next_interleave(&mut self.frame_index, inputs.settings);
+        let mut ctx = PassContext {
+            framebuffer: &mut recording,
+            queue: &mut self.shade_queue,
+            stats: &mut self.frame_stats,
+            scene: inputs.scene,
+            camera: inputs.camera,
+            settings: inputs.settings,
+            objects: &objects,
+            frame: &mut frame,
+            with_labels: inputs.with_labels,
+            focus: inputs.focus,
+            quality: inputs.quality,
+            ui: &mut |_, _, _| {},
+        };
+        self.graph.run_before(frame_graph::RESOLVE, &mut ctx);
+
+        let (width, height) = (recording.width, recording.height);
+        let queue = std::mem::take(&mut self.shade_queue);
+        let Some(ShadedFrame { recorded, fragments_shaded, shade_time }) = pipeline.exchange(RecordedFrame { framebuffer: recording, queue, frame }) else {
+            // Todavía no hay un frame sombreado: se vuelve a presentar el anterior (o uno vacío si
+            // el hilo se cayó con el framebuffer)
+            if self.framebuffer.buffer.is_empty() {
+                self.framebuffer = Framebuffer::new(width, height);
+                self.framebuffer.set_background_color(BACKGROUND_COLOR);
+            }
+            return scene_frame(inputs.camera, self.projection_matrix, &self.framebuffer);
+        };
+        self.frame_stats.record(Stage::Fragment, shade_time);
+        self.frame_stats.counters.fragments_shaded += fragments_shaded;
+        self.framebuffer = recorded.framebuffer;
+        self.shade_queue = recorded.queue;
+        let mut frame = recorded.frame;
+
+        // Las etiquetas y la proyección son las del frame sombreado; el HUD ya muestra el estado actual
+        let mut ctx = PassContext {
+            framebuffer: &mut self.framebuffer,
+            queue: &mut self.shade_queue,
+            stats: &mut self.frame_stats,
+            scene: inputs.scene,
+            camera: inputs.camera,
+            settings: inputs.settings,
+            objects: &objects,
+            frame: &mut frame,
+            with_labels: inputs.with_labels,
+            focus: inputs.focus,
+            quality: inputs.quality,
+            ui,
+        };
+        self.graph.run_after(frame_graph::RESOLVE, &mut ctx);
+        frame
+    }
+
+    // Todas las pasadas sobre `framebuffer`, sombreando en este hilo
+    fn render_immediate(&mut self, inputs: FrameInputs, ui: &mut UiHook) -> SceneFrame {
+        self.framebuffer.interleave = next_interleave(&mut self.frame_index, inputs.settings);
+        let mut frame = scene_frame(inputs.camera, self.projection_matrix, &self.framebuffer);
+        let objects = inputs.scene.renderables();
+        let mut ctx = PassContext {
+            framebuffer: &mut self.framebuffer,
+            queue: &mut self.shade_queue,
+            stats: &mut self.frame_stats,
+            scene: inputs.scene,
+            camera: inputs.camera,
+            settings: inputs.settings,
+            objects: &objects,
+            frame: &mut frame,
+            with_labels: inputs.with_labels,
+            focus: inputs.focus,
+            quality: inputs.quality,
+            ui,
+        };
+        self.graph.run(&mut ctx);
+        frame
+    }
+
+    // Proyección con que se dibujaría ahora la cámara, para llevar clics de la pantalla a la escena
+    pub fn screen_projection(&self, camera: &Camera) -> ScreenProjection {
+        screen_projection(camera, self.projection_matrix, &self.framebuffer)
+    }
+
+    // Dibuja la escena 3D (sin HUD ni menús) en un buffer ajeno de `width` x `height` pixeles
+    // 0RGB, sin ventana ni entrada: para usar el simulador como biblioteca desde otra aplicación
+    // (un panel de egui, un servicio de capturas...). Siempre es el frame pedido, aunque el
+    // pipeline esté activo; los ajustes de imagen son los de `scene_settings`.
+    pub fn render_scene(&mut self, scene: &Scene, camera: &Camera, target: &mut [u32], width: usize, height: usize) -> Result<SceneFrame, Error> {
+        if width == 0 || height == 0 || target.len() != width * height {
+            return Err(Error::Render(format!(
+                "a {}x{} image needs {} pixels but the target has {}",
+                width,
+                height,
+                width * height,
+                target.len(),
+            )));
+        }
+        self.resize(width, height, Resolution::Full);
+        let settings = self.scene_settings;
+        let inputs = FrameInputs { scene, camera, settings: &settings, with_labels: true, focus: None, quality: Quality::Full };
+        let _span = info_span!("render_scene").entered();
+        let frame = self.render_immediate(inputs, &mut |_, _, _| {});
+        target.copy_from_slice(&self.framebuffer.buffer);
+        Ok(frame)
+    }
+}
+
+// Fase del reescalado temporal para el frame que se va a grabar (None si está apagado)
+fn next_interleave(frame_index: &mut u64, settings: &Settings) -> Option<(usize, usize)> {
+    *frame_index += 1;
+    settings.temporal_upscaling.then(|| temporal::phase(*frame_index))
+}
+
+// Proyección del frame para el framebuffer donde se dibuja
+fn scene_frame(camera: &Camera, projection_matrix: Mat4, framebuffer: &Framebuffer) -> SceneFrame {
+    let projection = screen_projection(camera, projection_matrix, framebuffer);
+    SceneFrame {
+        uniforms: Arc::new(FrameUniforms::new(&projection)),
+        projection,
+        labels: Vec::new(),
+        markers: Vec::new(),
+        luminance: None,
+    }
+}
+
+fn screen_projection(camera: &Camera, projection_matrix: Mat4, framebuffer: &Framebuffer) -> ScreenProjection {
+    let view_matrix = create_view_matrix(camera);
+    let viewport_matrix = create_viewport_matrix(framebuffer.width as f32, framebuffer.height as f32);
+    ScreenProjection::new(view_matrix, projection_matrix, viewport_matrix)
+}
+
+// clear, skybox, opaque, labels, transparent, resolve, particles, post, ui
+fn standard_graph() -> FrameGraph {
+    let starfield = Texture::from_bytes(embedded::STARFIELD_TEXTURE)
+        .expect("embedded starfield texture must be a valid PNG")
+        .with_sampler(Sampler::Nearest);
+    let passes: [Box<dyn FramePass>; 10] = [
+        Box::new(ClearPass),
+        Box::new(SkyboxPass { starfield }),
+        Box::new(ObjectPass { name: frame_graph::OPAQUE, objects: RenderPass::Opaque }),
+        Box::new(LabelsPass),
+        Box::new(ObjectPass { name: frame_graph::TRANSPARENT, objects: RenderPass::Lines }),
+        Box::new(ResolvePass),
+        Box::new(UpscalePass { upscaler: TemporalUpscaler::default() }),
+        Box::new(ObjectPass { name: frame_graph::PARTICLES, objects: RenderPass::Effects }),
+        Box::new(PostPass),
+        Box::new(UiPass),
+    ];
+    let mut graph = FrameGraph::new();
+    for pass in passes {
+        graph.push(pass).expect("the standard passes are in dependency order");
+    }
+    graph
+}
+
+struct ClearPass;
+
+impl FramePass for ClearPass {
+    fn name(&self) -> &str {
+        frame_graph::CLEAR
+    }
+
+    fn writes(&self) -> &[Target] {
+        &[Target::Color, Target::Depth]
+    }
+
+    fn run(&mut self, ctx: &mut PassContext) {
+        ctx.framebuffer.clear();
+    }
+}
+
+// Nebulosa y estrellas de fondo, o la imagen de cielo de la escena
+struct SkyboxPass {
+    starfield: Texture,
+}
+
+impl FramePass for SkyboxPass {
+    fn name(&self) -> &str {
+        frame_graph::SKYBOX
+    }
+
+    fn writes(&self) -> &[Target] {
+        &[Target::Color]
+    }
+
+    fn run(&mut self, ctx: &mut PassContext) {
+        // En modo tercera persona: fondo negro puro para mejor performance
+        if ctx.quality.draws_skybox() && !matches!(ctx.camera.mode, CameraMode::FirstPerson) {
+            match &ctx.scene.sky {
+                Some(sky) => sky::render_sky(ctx.framebuffer, &sky.image, ctx.camera, ctx.settings.sky_exposure),
+                None => render_starfield(ctx.framebuffer, &self.starfield, ctx.camera, ctx.scene.time),
+            }
+        }
+    }
+}
+
+// Los `Renderable` de la escena de un tipo: cuerpos y nave, órbitas o efectos
+struct ObjectPass {
+    name: &'static str,
+    objects: RenderPass,
+}
+
+impl FramePass for ObjectPass {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn reads(&self) -> &[Target] {
+        &[Target::Depth]
+    }
+
+    fn writes(&self) -> &[Target] {
+        &[Target::Color, Target::Depth]
+    }
+
+    fn run(&mut self, ctx: &mut PassContext) {
+        let first_person = matches!(ctx.camera.mode, CameraMode::FirstPerson);
+        // No renderizar órbitas en modo tercera persona para mejor performance
+        if self.objects == RenderPass::Lines && (!ctx.settings.show_orbits || first_person) {
+            return;
+        }
+        // En modo tercera persona, usar culling más agresivo para mejor performance
+        let max_render_distance = if first_person {
+            20.0  // Distancia reducida en tercera persona
+        } else {
+            50.0  // Distancia normal en modo orbital
+        };
+
+        let projection = &ctx.frame.projection;
+        let view = RenderView {
+            projection,
+            camera_eye: ctx.camera.eye,
+            lod_bias: ctx.settings.lod_quality.bias() * ctx.quality.lod_factor(),
+            time: ctx.scene.time,
+            meshes: &ctx.scene.meshes,
+            palette: ctx.settings.palette(),
+            focus: ctx.focus,
+        };
+        for object in ctx.objects.iter().filter(|object| object.render_pass() == self.objects) {
+            let flags = object.render_flags();
+            if !flags.visible {
+                continue;
+            }
+            // Solo renderizar si está relativamente cerca (culling simple)
+            if let Some((center, radius)) = object.bounds() {
+                if (center - ctx.camera.eye).magnitude() - radius > max_render_distance {
+                    ctx.stats.counters.bodies_culled += 1;
+                    continue;
+                }
+                ctx.stats.counters.bodies_drawn += 1;
+            }
+
+            let _object_span = debug_span!("draw", object = object.name()).entered();
+            let model_matrix = object.model_matrix();
+            for item in object.draw_items(&view) {
+                let uniforms = Uniforms::new(ctx.frame.uniforms.clone(), model_matrix, item.time)
+                    .with_render_flags(flags)
+                    .with_shadow_rings(object.shadow_rings());
+                let uniforms = match &item.shading {
+                    Shading::Ship { material, surface_material, texture } => uniforms
+                        .with_ship_material((*material).clone())
+                        .with_surface_material(surface_material.cloned())
+                        .with_texture(texture.clone()),
+                    Shading::Shader(_) | Shading::Flat(_) | Shading::Ring(_) => uniforms,
+                };
+                render_indexed(ctx.framebuffer, ctx.queue, ctx.stats, uniforms, &item.vertices, &item.indices, &item.shading);
+            }
+            object.draw_direct(ctx.framebuffer, projection);
+        }
+    }
+}
+
+// Visibilidad de las etiquetas con el z-buffer de cuerpos y nave (antes de las órbitas, para
+// que sus líneas no cuenten como oclusores)
+struct LabelsPass;
+
+impl FramePass for LabelsPass {
+    fn name(&self) -> &str {
+        frame_graph::LABELS
+    }
+
+    fn reads(&self) -> &[Target] {
+        &[Target::Depth]
+    }
+
+    fn writes(&self) -> &[Target] {
+        &[]
+    }
+
+    fn run(&mut self, ctx: &mut PassContext) {
+        if ctx.with_labels {
+            ctx.frame.labels = labels::visible_labels(ctx.framebuffer, &ctx.frame.projection, &ctx.scene.bodies, ctx.camera.eye);
+            ctx.frame.markers = markers::visible_markers(ctx.framebuffer, &ctx.frame.projection, &ctx.scene.bodies, ctx.camera.eye);
+        }
+    }
+}
+
+// Fragment shader de lo que quedó visible de cuerpos, nave y órbitas (con el pipeline lo corre
+// el hilo de sombreado y esta pasada no se ejecuta)
+struct ResolvePass;
+
+impl FramePass for ResolvePass {
+    fn name(&self) -> &str {
+        frame_graph::RESOLVE
+    }
+
+    fn reads(&self) -> &[Target] {
+        &[Target::Depth]
+    }
+
+    fn writes(&self) -> &[Target] {
+        &[Target::Color]
+    }
+
+    fn run(&mut self, ctx: &mut PassContext) {
+        let shaded = ctx.stats.time(Stage::Fragment, || ctx.queue.resolve(ctx.framebuffer));
+        ctx.stats.counters.fragments_shaded += shaded;
+    }
+}
+
+// Completa los pixeles que el reescalado temporal no rasterizó en este frame (ver temporal.rs)
+struct UpscalePass {
+    upscaler: TemporalUpscaler,
+}
+
+impl FramePass for UpscalePass {
+    fn name(&self) -> &str {
+        frame_graph::UPSCALE
+    }
+
+    fn reads(&self) -> &[Target] {
+        &[Target::Color, Target::Depth]
+    }
+
+    fn writes(&self) -> &[Target] {
+        &[Target::Color, Target::Depth]
+    }
+
+    fn run(&mut self, ctx: &mut PassContext) {
+        debug_span!("upscale").in_scope(|| self.upscaler.reconstruct(ctx.framebuffer, &ctx.frame.projection));
+    }
+}
+
+// El suavizado va sobre la escena 3D; etiquetas y HUD se dibujan después para que el texto quede nítido
+struct PostPass;
+
+impl FramePass for PostPass {
+    fn name(&self) -> &str {
+        frame_graph::POST
+    }
+
+    fn reads(&self) -> &[Target] {
+        &[Target::Color]
+    }
+
+    fn writes(&self) -> &[Target] {
+        &[Target::Color]
+    }
+
+    fn run(&mut self, ctx: &mut PassContext) {
+        if ctx.settings.anti_aliasing == AntiAliasing::Fxaa {
+            debug_span!("fxaa").in_scope(|| antialias::apply_fxaa(ctx.framebuffer));
+        }
+        // La exposición automática mide la escena sin ajustar; la corrección llega con `settings`
+        if ctx.settings.auto_exposure {
+            ctx.frame.luminance = Some(color_grading::average_luminance(ctx.framebuffer));
+        }
+        debug_span!("grading").in_scope(|| ctx.settings.grading().apply(ctx.framebuffer));
+    }
+}
+
+struct UiPass;
+
+impl FramePass for UiPass {
+    fn name(&self) -> &str {
+        frame_graph::UI
+    }
+
+    fn writes(&self) -> &[Target] {
+        &[Target::Color]
+    }
+
+    fn run(&mut self, ctx: &mut PassContext) {
+        (ctx.ui)(ctx.framebuffer, ctx.stats, ctx.frame);
+    }
+}
+
+pub fn create_model_matrix(translation: Vec3, scale: f32, rotation: Vec3) -> Mat4 {
+    let (sin_x, cos_x) = rotation.x.sin_cos();
+    let (sin_y, cos_y) = rotation.y.sin_cos();
+    let (sin_z, cos_z) = rotation.z.sin_cos();
+
+    let rotation_matrix_x = Mat4::new(
+        1.0,  0.0,    0.0,   0.0,
+        0.0,  cos_x, -sin_x, 0.0,
+        0.0,  sin_x,  cos_x, 0.0,
+        0.0,  0.0,    0.0,   1.0,
+    );
+
+    let rotation_matrix_y = Mat4::new(
+        cos_y,  0.0,  sin_y, 0.0,
+        0.0,    1.0,  0.0,   0.0,
+        -sin_y, 0.0,  cos_y, 0.0,
+        0.0,    0.0,  0.0,   1.0,
+    );
+
+    let rotation_matrix_z = Mat4::new(
+        cos_z, -sin_z, 0.0, 0.0,
+        sin_z,  cos_z, 0.0, 0.0,
+        0.0,    0.0,   1.0, 0.0,
+        0.0,    0.0,   0.0, 1.0,
+    );
+
+    let rotation_matrix = rotation_matrix_z * rotation_matrix_y * rotation_matrix_x;
+
+    let transform_matrix = Mat4::new(
+        scale, 0.0,   0.0,   translation.x,
+        0.0,   scale, 0.0,   translation.y,
+        0.0,   0.0,   scale, translation.z,
+        0.0,   0.0,   0.0,   1.0,
+    );
+
+    transform_matrix * rotation_matrix
+}
+
+// Vértices únicos e índices (3 por triángulo): cada vértice pasa una sola vez por el
+// vertex shader aunque lo compartan varios triángulos. Los fragmentos quedan en `queue` con la
+// prueba de profundidad hecha; el color lo pone la pasada `RESOLVE`.
+pub fn render_indexed(
+    framebuffer: &mut Framebuffer,
+    queue: &mut ShadeQueue,
+    stats: &mut FrameStats,
+    uniforms: Uniforms,
+    vertex_array: &[Vertex],
+    indices: &[u32],
+    shading: &Shading,
+) {
+    // Vertex Shader Stage
+    let transformed_vertices: Vec<Vertex> = stats.time(Stage::Vertex, || {
+        vertex_array
+            .iter()
+            .map(|vertex| vertex_shader(vertex, &uniforms))
+            .collect()
+    });
+
+    // Primitive Assembly Stage
+    let triangles = indices.chunks_exact(3).map(|tri| {
+        [
+            &transformed_vertices[tri[0] as usize],
+            &transformed_vertices[tri[1] as usize],
+            &transformed_vertices[tri[2] as usize],
+        ]
+    });
+
+    let shading = match shading {
+        Shading::Shader(shader_type) => BatchShading::Shader(*shader_type),
+        Shading::Ship { .. } => BatchShading::Shader(ShaderType::Ship),
+        Shading::Flat(color) => BatchShading::Flat(*color),
+        Shading::Ring(band) => BatchShading::Ring(**band),
+    };
+    // Las bandas de anillos descartan fragmentos según su densidad (transparencia en trama)
+    let coverage = match shading {
+        BatchShading::Ring(band) => Some(band),
+        _ => None,
+    };
+    // Lo que va siempre delante se corre a -2..-1, antes que cualquier profundidad normal (-1..0)
+    let depth_offset = if uniforms.always_on_top { -1.0 } else { 0.0 };
+    queue.begin_batch(uniforms, shading);
+    rasterize(framebuffer, queue, stats, triangles, depth_offset, coverage);
+}
+
+// Área (doble, en pixeles²) por debajo de la cual un triángulo se toma como degenerado
+const MIN_TRIANGLE_AREA: f32 = 1e-6;
+
+fn rasterize<'a>(
+    framebuffer: &mut Framebuffer,
+    queue: &mut ShadeQueue,
+    stats: &mut FrameStats,
+    triangles: impl Iterator<Item = [&'a Vertex; 3]>,
+    depth_offset: f32,
+    coverage: Option<RingBand>,
+) {
+    // Rasterization Stage (con la prueba de profundidad; el sombreado queda para `RESOLVE`)
+    let (width, height) = (framebuffer.width as f32, framebuffer.height as f32);
+    let (mut submitted, mut rasterized, mut rejected) = (0, 0, 0);
+    let (mut invalid, mut degenerate) = (0, 0);
+    stats.time(Stage::Raster, || {
+        for tri in triangles {
+            submitted += 1;
+            // Vértices NaN o infinitos (w≈0 en el vertex shader, matrices rotas): las coordenadas
+            // baricéntricas salen basura y el triángulo dejaría rayas por toda la pantalla
+            let [a, b, c] = tri.map(|vertex| vertex.transformed_position);
+            if [a, b, c].iter().any(|position| !position.iter().all(|value| value.is_finite())) {
+                invalid += 1;
+                continue;
+            }
+            // Sin área no hay pixeles, y casi sin área la división por el área dispara los pesos
+            let area = (c.x - a.x) * (b.y - a.y) - (c.y - a.y) * (b.x - a.x);
+            if area.abs() < MIN_TRIANGLE_AREA || !area.is_finite() {
+                degenerate += 1;
+                continue;
+            }
+            // Descarte trivial: con la caja envolvente fuera de la pantalla no generan pixeles.
+            // Un vértice detrás del plano cercano (z fuera de -1..0) se proyecta a coordenadas
+            // enormes o invertidas; sin recorte esos triángulos llenarían la pantalla entera.
+            let off_screen = a.x.max(b.x).max(c.x) < 0.0
+                || a.y.max(b.y).max(c.y) < 0.0
+                || a.x.min(b.x).min(c.x) >= width
+                || a.y.min(b.y).min(c.y) >= height;
+            let outside_depth = [a.z, b.z, c.z].iter().any(|z| !(-1.0..=0.0).contains(z));
+            if off_screen || outside_depth {
+                continue;
+            }
+            rasterized += 1;
+            for mut fragment in triangle(tri[0], tri[1], tri[2]) {
+                fragment.depth += depth_offset;
+                let (x, y) = (fragment.position.x as usize, fragment.position.y as usize);
+                // Con el reescalado temporal los pixeles de otra fase los reconstruye `UPSCALE`
+                if !framebuffer.is_sampled(x, y) || coverage.is_some_and(|band| !band.covers(fragment.tex_coords.y, x, y)) {
+                    continue;
+                }
+                if x < framebuffer.width && y < framebuffer.height && !queue.push(framebuffer, fragment) {
+                    rejected += 1;
+                }
+            }
+        }
+    });
+
+    stats.counters.triangles_submitted += submitted;
+    stats.counters.triangles_rasterized += rasterized;
+    stats.counters.triangles_invalid += invalid;
+    stats.counters.triangles_degenerate += degenerate;
+    stats.counters.depth_rejected += rejected;
+}
+
+// Una capa de estrellas procedurales. Las más lejanas casi no se mueven con la cámara y las
+// más cercanas se desplazan más, lo que da profundidad sin un catálogo de estrellas 3D.
+struct StarLayer {
+    count: u32,
+    seed: u32,
+    parallax: f32,  // Pixeles que se corre la capa por unidad que se traslada la cámara
+    drift: f32,     // Pixeles por segundo que se desplaza sola, como antes el fondo entero
+    brightness: (f32, f32),  // Rango de brillo (0..255) que recorre el parpadeo
+    glow: bool,     // Las más brillantes se ensanchan un pixel a cada lado
+}
+
+const STAR_LAYERS: [StarLayer; 3] = [
+    StarLayer { count: 180, seed: 1, parallax: 0.5, drift: 0.25, brightness: (40.0, 150.0), glow: false },
+    StarLayer { count: 90, seed: 2, parallax: 2.5, drift: 0.5, brightness: (60.0, 210.0), glow: false },
+    StarLayer { count: 40, seed: 3, parallax: 8.0, drift: 1.0, brightness: (120.0, 255.0), glow: true },
+];
+
+// Renderizar campo de estrellas de fondo
+fn render_starfield(framebuffer: &mut Framebuffer, backdrop: &Texture, camera: &Camera, time: f32) {
+    use fastnoise_lite::{FastNoiseLite, NoiseType};
+
+    // Fondo de nebulosa (textura embebida) que se desplaza junto con las estrellas
+    let (width, height) = (framebuffer.width, framebuffer.height);
+    for y in 0..height {
+        let v = 1.0 - (y as f32 + 0.5) / height as f32;
+        for x in 0..width {
+            let u = (x as f32 + time * 0.5) / width as f32;
+            let color = backdrop.sample(nalgebra_glm::Vec2::new(u, v));
+            framebuffer.set_pixel(x, y, color.to_hex());
+        }
+    }
+
+    let mut noise = FastNoiseLite::new();
+    noise.set_noise_type(Some(NoiseType::OpenSimplex2));
+    noise.set_frequency(Some(0.02));
+
+    // Traslación de la cámara en sus propios ejes: al moverse a la derecha las estrellas se
+    // corren a la izquierda, y al subir bajan
+    let forward = camera.center - camera.eye;
+    let (across, along_up) = if forward.magnitude() > 1e-6 {
+        let right = forward.normalize().cross(&camera.up).normalize();
+        let up = right.cross(&forward.normalize());
+        (camera.eye.dot(&right), camera.eye.dot(&up))
+    } else {
+        (0.0, 0.0)
+    };
+
+    let (width_f, height_f) = (width as f32, height as f32);
+    for layer in &STAR_LAYERS {
+        let shift_x = time * layer.drift - across * layer.parallax;
+        let shift_y = along_up * layer.parallax;
+        for i in 0..layer.count {
+            let x = (star_hash(layer.seed, i * 2) * width_f + shift_x).rem_euclid(width_f) as usize;
+            let y = (star_hash(layer.seed, i * 2 + 1) * height_f + shift_y).rem_euclid(height_f) as usize;
+            if x >= width || y >= height {
+                continue;
+            }
+
+            // Usar noise para variar el brillo de las estrellas
+            let (dim, bright) = layer.brightness;
+            let brightness_noise = noise.get_noise_2d((i + layer.seed * 1000) as f32, time * 0.3);
+            let brightness = (dim + (brightness_noise + 1.0) * 0.5 * (bright - dim)) as u8;
+
+            // Algunas estrellas tienen un tinte azulado/rojizo
+            let (r, g, b) = match i % 3 {
+                0 => (brightness, brightness, brightness.saturating_add(30)),  // Azulado
+                1 => (brightness.saturating_add(20), brightness, brightness),  // Rojizo
+                _ => (brightness, brightness, brightness),                     // Blanco
+            };
+            let color = ((r as u32) << 16) | ((g as u32) << 8) | (b as u32);
+
+            framebuffer.set_current_color(color);
+            framebuffer.point(x, y, f32::MAX);  // Profundidad máxima (fondo)
+
+            // Algunas estrellas más brillantes tienen un pequeño glow
+            if layer.glow && brightness > 200 && i % 3 == 0 {
+                if x > 0 {
+                    framebuffer.point(x - 1, y, f32::MAX);
+                }
+                if x < width - 1 {
+                    framebuffer.point(x + 1, y, f32::MAX);
+                }
+            }
+        }
+    }
+}
+
+// Posición pseudoaleatoria fija (0.0..1.0) de la estrella `n` de una capa
+fn star_hash(seed: u32, n: u32) -> f32 {
+    let mut h = n.wrapping_mul(0x9E37_79B9) ^ seed.wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7FEB_352D);
+    h ^= h >> 15;
+    (h & 0xFFFF) as f32 / 65536.0
+}
+
+// Efecto de túnel del warp drive: estelas radiales de estrellas que salen del punto de fuga
+pub fn render_warp_streaks(framebuffer: &mut Framebuffer, center: (f32, f32), intensity: f32, time: f32) {
+    if intensity <= 0.0 {
+        return;
+    }
+
+    let max_radius = (framebuffer.width.max(framebuffer.height)) as f32;
+    let streak_count = (40.0 + 160.0 * intensity) as usize;
+
+    for i in 0..streak_count {
+        // Semillas pseudoaleatorias estables por estela
+        let seed_angle = ((i * 137) % 360) as f32 / 360.0;
+        let seed_speed = ((i * 73) % 100) as f32 / 100.0;
+        let angle = seed_angle * 2.0 * PI + i as f32 * 0.013;
+        let speed = 0.6 + seed_speed * 1.4;
+
+        // Cada estela se desplaza hacia afuera y reaparece en el centro
+        let phase = (time * speed * (0.5 + intensity * 2.0) + seed_speed).fract();
+        let inner = max_radius * phase * phase;
+        let length = max_radius * (0.05 + 0.35 * intensity) * phase;
+
+        let (sin_a, cos_a) = angle.sin_cos();
+        let x0 = center.0 + cos_a * inner;
+        let y0 = center.1 + sin_a * inner;
+        let x1 = center.0 + cos_a * (inner + length);
+        let y1 = center.1 + sin_a * (inner + length);
+
+        let color = if i % 4 == 0 { 0xFFFFFF } else { 0x9CC8FF };
+        framebuffer.blend_line(x0 as i32, y0 as i32, x1 as i32, y1 as i32, color, intensity * phase);
+    }
+
+    // Tinte azulado general mientras dura el salto
+    let (width, height) = (framebuffer.width, framebuffer.height);
+    framebuffer.blend_rect(0, 0, width, height, 0x1A3A80, intensity * 0.15);
+}
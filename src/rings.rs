@@ -0,0 +1,136 @@
+use nalgebra_glm::Vec3;
+use crate::color::Color;
+use crate::fragment::Fragment;
+use crate::geometry::{annulus, Mesh};
+use crate::mesh_registry::MeshId;
+use crate::shaders::Uniforms;
+
+// Segmentos de la corona de cada banda
+const RING_SEGMENTS: usize = 96;
+// Franjas finas de densidad a lo ancho de una banda
+const RINGLETS: f32 = 24.0;
+// Fracción del ancho en que la banda se desvanece hacia cada borde
+const EDGE_SOFTNESS: f32 = 0.08;
+// Medio ancho (en radios del cuerpo) del borde difuso de la sombra del cuerpo sobre los anillos
+const PENUMBRA: f32 = 0.04;
+// Luz que les queda a los anillos en la sombra del cuerpo (la que dispersan las otras partículas)
+const SHADOW_LIGHT: f32 = 0.2;
+// Umbrales de Bayer 4x4: la transparencia se dibuja como una trama fija de pixeles, porque el
+// rasterizador guarda un solo fragmento por pixel y no mezcla colores
+const BAYER: [[f32; 4]; 4] = [
+    [0.5 / 16.0, 8.5 / 16.0, 2.5 / 16.0, 10.5 / 16.0],
+    [12.5 / 16.0, 4.5 / 16.0, 14.5 / 16.0, 6.5 / 16.0],
+    [3.5 / 16.0, 11.5 / 16.0, 1.5 / 16.0, 9.5 / 16.0],
+    [15.5 / 16.0, 7.5 / 16.0, 13.5 / 16.0, 5.5 / 16.0],
+];
+
+// Una banda de un sistema de anillos: una corona en el plano del ecuador del cuerpo. Entre dos
+// bandas queda un hueco (como la división de Cassini).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RingBand {
+    pub inner: f32,  // Radios en radios del cuerpo (1.0 = la superficie)
+    pub outer: f32,
+    pub color: u32,
+    pub opacity: f32,  // 0.0..=1.0, la cobertura en las franjas más densas
+    pub seed: u32,     // Elige el patrón de franjas de densidad
+    pub mesh: Option<MeshId>,  // Corona del registro de la escena (None = no se dibuja)
+}
+
+impl RingBand {
+    pub fn new(inner: f32, outer: f32, color: u32) -> Self {
+        RingBand { inner, outer, color, opacity: 0.8, seed: 0, mesh: None }
+    }
+
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u32) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    // Corona con radio 1 = el del cuerpo (se escala con su matriz de modelo); v va del borde
+    // interior (0) al exterior (1)
+    pub fn create_mesh(&self) -> Mesh {
+        annulus(self.inner, self.outer, RING_SEGMENTS)
+    }
+
+    // Densidad (0.0..=1.0) a la fracción `v` del ancho: ruido de valor en franjas, con los
+    // bordes suavizados
+    pub fn density(&self, v: f32) -> f32 {
+        let v = v.clamp(0.0, 1.0);
+        let coarse = value_noise(self.seed, v * RINGLETS * 0.25);
+        let fine = value_noise(self.seed.wrapping_add(1), v * RINGLETS);
+        let edges = (v / EDGE_SOFTNESS).min((1.0 - v) / EDGE_SOFTNESS).min(1.0);
+        (0.25 + 0.5 * coarse + 0.25 * fine) * edges
+    }
+
+    // Si el fragmento en el pixel (x, y) a la fracción `v` del ancho cubre lo que hay detrás
+    pub fn covers(&self, v: f32, x: usize, y: usize) -> bool {
+        self.opacity * self.density(v) > BAYER[y % 4][x % 4]
+    }
+
+    // Color de un fragmento de la banda: más claro donde es más densa. Las partículas dispersan
+    // la luz, así que no tienen un lado oscuro, pero sí quedan en la sombra del cuerpo si este
+    // la proyecta (`uniforms.shadow_rings` no vacío).
+    pub fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> Color {
+        let density = self.density(fragment.tex_coords.y);
+        let color = Color::from_hex(self.color) * (0.55 + 0.45 * density);
+        if uniforms.shadow_rings.is_empty() {
+            return color;
+        }
+        let shadow = planet_shadow(fragment.vertex_position, uniforms.local_sun);
+        color * (1.0 - (1.0 - SHADOW_LIGHT) * shadow)
+    }
+}
+
+// Sombra del cuerpo (la esfera de radio 1 en el origen) sobre un punto de sus anillos, con `sun`
+// la dirección hacia el sol, ambos en el espacio del modelo: 0.0 = al sol, 1.0 = en sombra. La
+// sombra es un cilindro detrás del cuerpo: el sol está lo bastante lejos para que no se abra.
+pub fn planet_shadow(point: Vec3, sun: Vec3) -> f32 {
+    let along = point.dot(&sun);
+    if along >= 0.0 {
+        return 0.0;
+    }
+    let distance = (point - sun * along).magnitude();  // Al eje de la sombra
+    let t = ((distance - (1.0 - PENUMBRA)) / (2.0 * PENUMBRA)).clamp(0.0, 1.0);
+    1.0 - t * t * (3.0 - 2.0 * t)
+}
+
+// Cuánta luz tapan las bandas `bands` en un punto de la superficie del cuerpo (0.0..=1.0): se
+// sigue el rayo hacia el sol hasta el plano del ecuador y se toma la densidad de la banda que
+// cruza. Con el sol en ese plano (cuerpo sin inclinación) la sombra es una línea y no se ve.
+pub fn ring_shadow(bands: &[RingBand], point: Vec3, sun: Vec3) -> f32 {
+    if sun.y.abs() < f32::EPSILON {
+        return 0.0;
+    }
+    let t = -point.y / sun.y;
+    if t <= 0.0 {
+        return 0.0;
+    }
+    let hit = point + sun * t;
+    let radius = hit.x.hypot(hit.z);
+    bands
+        .iter()
+        .find(|band| (band.inner..=band.outer).contains(&radius))
+        .map_or(0.0, |band| band.opacity * band.density((radius - band.inner) / (band.outer - band.inner)))
+}
+
+// Ruido de valor 1D en 0.0..=1.0, interpolado suavemente entre enteros
+fn value_noise(seed: u32, x: f32) -> f32 {
+    let cell = x.floor();
+    let t = x - cell;
+    let t = t * t * (3.0 - 2.0 * t);
+    let (a, b) = (hash(seed, cell as i32), hash(seed, cell as i32 + 1));
+    a + (b - a) * t
+}
+
+fn hash(seed: u32, n: i32) -> f32 {
+    let mut h = (n as u32).wrapping_mul(0x27D4_EB2D) ^ seed.wrapping_mul(0x1656_67B1);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 13;
+    (h & 0xFFFF) as f32 / 65535.0
+}
@@ -0,0 +1,129 @@
+use nalgebra_glm::Vec3;
+use crate::camera::ScreenProjection;
+use crate::framebuffer::Framebuffer;
+use crate::i18n::tr;
+use crate::scene::Scene;
+use crate::text::{draw_text_styled, measure_text, Align, TextStyle};
+
+pub const AU_KM: f32 = 149_597_870.7;
+// Sin `unit_km` en la escena: Ares orbita a 3 unidades, como Marte a ~1.5 UA
+pub const DEFAULT_UNIT_KM: f32 = AU_KM / 2.0;
+// Distancia en pixeles al centro de un cuerpo pequeño (o de la nave) que todavía cuenta como clic sobre él
+const PICK_RADIUS: f32 = 10.0;
+const END_RADIUS: i32 = 3;
+const SHADOW_COLOR: u32 = 0x000000;
+
+// Un extremo de la medición: se sigue al cuerpo o a la nave, no a un punto fijo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RulerEnd {
+    Body(usize),
+    Ship,
+}
+
+impl RulerEnd {
+    // None si el cuerpo ya no existe (se recargó la escena)
+    fn position(self, scene: &Scene) -> Option<Vec3> {
+        match self {
+            RulerEnd::Body(index) => scene.bodies.get(index).map(|body| body.position),
+            RulerEnd::Ship => Some(scene.spaceship.position),
+        }
+    }
+
+    fn name(self, scene: &Scene) -> &'static str {
+        match self {
+            RulerEnd::Body(index) => scene.bodies.get(index).map_or("?", |body| body.name),
+            RulerEnd::Ship => tr("hud.spaceship"),
+        }
+    }
+}
+
+// Regla (tecla R): con dos clics sobre cuerpos o la nave dibuja la línea entre ambos con la
+// distancia en unidades de la escena y en unidades reales según `unit_km`. Se recalcula cada
+// frame, así que sigue a los cuerpos mientras orbitan. Un tercer clic empieza otra medición.
+#[derive(Debug, Default)]
+pub struct Ruler {
+    first: Option<RulerEnd>,
+    second: Option<RulerEnd>,
+}
+
+impl Ruler {
+    pub fn pick(&mut self, end: RulerEnd) {
+        match (self.first, self.second) {
+            (Some(first), None) if first != end => self.second = Some(end),
+            _ => {
+                self.first = Some(end);
+                self.second = None;
+            }
+        }
+    }
+
+    pub fn render(&self, framebuffer: &mut Framebuffer, projection: &ScreenProjection, scene: &Scene, color: u32) {
+        let style = TextStyle::new(color).with_shadow(SHADOW_COLOR);
+        let (Some(first), Some(second)) = (self.first, self.second) else {
+            // Falta al menos un extremo: se marca el elegido y se indica qué hacer
+            if let Some(center) = self.first.and_then(|end| end.position(scene)).and_then(|point| projection.project(point)) {
+                framebuffer.draw_circle(center.x.round() as i32, center.y.round() as i32, END_RADIUS, color);
+            }
+            let hint = tr(if self.first.is_some() { "hud.ruler_second" } else { "hud.ruler_first" });
+            let y = framebuffer.height as i32 - style.line_height() as i32 - 4;
+            draw_text_styled(framebuffer, framebuffer.width as i32 / 2, y, hint, &style.with_align(Align::Center));
+            return;
+        };
+        let (Some(from), Some(to)) = (first.position(scene), second.position(scene)) else {
+            return;
+        };
+        let Some((a, b)) = projection.project_segment(from, to) else {
+            return;
+        };
+        let (ax, ay, bx, by) = (a.x.round() as i32, a.y.round() as i32, b.x.round() as i32, b.y.round() as i32);
+        framebuffer.blend_line(ax, ay, bx, by, color, 0.8);
+        framebuffer.draw_circle(ax, ay, END_RADIUS, color);
+        framebuffer.draw_circle(bx, by, END_RADIUS, color);
+
+        let text = format!(
+            "{} - {}\n{}",
+            first.name(scene),
+            second.name(scene),
+            format_distance((to - from).magnitude(), scene.unit_km),
+        );
+        // Junto al punto medio de la línea, sin salirse de la pantalla
+        let (width, height) = measure_text(&text, &style);
+        let x = ((ax + bx) / 2 + 6).min(framebuffer.width as i32 - width as i32 - 2).max(2);
+        let y = ((ay + by) / 2 - height as i32 - 4).min(framebuffer.height as i32 - height as i32 - 2).max(2);
+        draw_text_styled(framebuffer, x, y, &text, &style);
+    }
+}
+
+// Cuerpo o nave bajo el pixel `(x, y)` del framebuffer: el más cercano cuyo disco (o un radio
+// mínimo de `PICK_RADIUS`) contiene el punto
+pub fn pick_target(projection: &ScreenProjection, scene: &Scene, (x, y): (f32, f32)) -> Option<RulerEnd> {
+    let bodies = scene.bodies
+        .iter()
+        .enumerate()
+        .filter(|(_, body)| body.is_drawn())
+        .map(|(index, body)| (RulerEnd::Body(index), body.position, projection.projected_radius(body.position, body.scale)));
+    let ship = std::iter::once((RulerEnd::Ship, scene.spaceship.position, 0.0));
+    bodies
+        .chain(ship)
+        .filter(|&(_, _, radius)| radius < f32::MAX)  // La cámara está dentro
+        .filter_map(|(end, position, radius)| {
+            let center = projection.project(position)?;
+            let offset = ((center.x - x).powi(2) + (center.y - y).powi(2)).sqrt();
+            (offset <= radius.max(PICK_RADIUS)).then_some((end, offset))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(end, _)| end)
+}
+
+// "4.20 u = 2.10 AU": unidades astronómicas a partir de 0.1 UA, si no millones de km o km
+pub fn format_distance(units: f32, unit_km: f32) -> String {
+    let km = units * unit_km;
+    let real = if km >= 0.1 * AU_KM {
+        format!("{:.2} AU", km / AU_KM)
+    } else if km >= 1.0e6 {
+        format!("{:.1} M km", km / 1.0e6)
+    } else {
+        format!("{:.0} km", km)
+    };
+    format!("{:.2} u = {}", units, real)
+}
@@ -0,0 +1,377 @@
+use nalgebra_glm::Vec3;
+use std::path::{Path, PathBuf};
+use tracing::{debug_span, info_span};
+use crate::assets::Assets;
+use crate::celestial_body::{CelestialBody, ShaderType};
+use crate::geometry::Mesh;
+use crate::gravity;
+use crate::mesh_registry::{LodMesh, MeshId, MeshRegistry};
+use crate::missions::MissionSpec;
+use crate::normals::{smooth_triangle_list, DEFAULT_CREASE_ANGLE};
+use crate::orbit::OrbitRing;
+use crate::play_area::PlayArea;
+use crate::projectiles::Weapons;
+use crate::ruler::DEFAULT_UNIT_KM;
+use crate::solar_wind::SolarWind;
+use crate::renderable::{RenderFlags, Renderable};
+use crate::scene_file::SceneFile;
+use crate::sim_clock::SimClock;
+use crate::sky::SkyImage;
+use crate::sphere::{SphereMesh, SphereTessellation, LOD_LEVELS};
+use crate::terrain::Terrain;
+use crate::vehicle::{Spaceship, Vehicle, WarpDrive};
+
+// De dónde sale una escena: el sistema solar incorporado o un archivo (`--scene`).
+// "Load Scene" en el menú de pausa ofrece el incorporado más el archivo de la línea de comandos.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum SceneSource {
+    #[default]
+    SolarSystem,
+    File(PathBuf),
+}
+
+impl SceneSource {
+    pub fn name(&self) -> String {
+        match self {
+            SceneSource::SolarSystem => "Solar System".to_string(),
+            SceneSource::File(path) => path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().into_owned()),
+        }
+    }
+}
+
+// Todo lo que se simula: cuerpos, órbitas, nave y disparos, con las mallas con que se dibujan
+pub struct Scene {
+    pub bodies: Vec<CelestialBody>,
+    pub orbits: Vec<OrbitRing>,
+    pub spaceship: Spaceship,
+    pub weapons: Weapons,
+    pub solar_wind: SolarWind,  // Capa de viento solar (ajuste `solar_wind`, lo enciende el engine)
+    pub skybox: CelestialBody,  // DESHABILITADO temporalmente para mejor performance
+    pub meshes: MeshRegistry,
+    pub planet_mesh: MeshId,  // Esfera lisa compartida por los cuerpos sin relieve
+    pub sphere_mesh: SphereMesh,
+    pub sphere_tessellation: SphereTessellation,  // Ajuste `sphere_tessellation`, lo pone el engine
+    pub smooth_shading: bool,
+    pub clock: SimClock,  // Tiempo de la simulación (órbitas y rotaciones)
+    pub time: f32,        // Tiempo real transcurrido sin pausa (animaciones y efectos)
+    pub script: Option<PathBuf>,  // Script del escenario (clave `script` del archivo de escena)
+    pub sky: Option<SkyImage>,    // Fondo equirectangular (None = nebulosa y estrellas procedurales)
+    pub ship_gravity: bool,       // Los cuerpos atraen a la nave (ajuste `ship_gravity`, ver gravity.rs)
+    pub unit_km: f32,             // Kilómetros por unidad de la escena (clave `unit_km`), para la regla
+    pub missions: Vec<MissionSpec>,  // Secciones `[mission]` del archivo de escena
+}
+
+impl Scene {
+    // `seed` fija los números aleatorios de la simulación (chispas de los impactos) para poder
+    // repetir una corrida; sin semilla se elige una al azar
+    pub fn load(source: &SceneSource, assets: &mut Assets, seed: Option<u64>) -> Result<Self, String> {
+        let mut scene = match source {
+            SceneSource::SolarSystem => Scene::solar_system(assets),
+            SceneSource::File(path) => {
+                let file = SceneFile::load(path)?;
+                let mut scene = Scene::from_bodies(file.bodies, file.orbits, assets);
+                scene.script = file.script;
+                scene.unit_km = file.unit_km.unwrap_or(DEFAULT_UNIT_KM);
+                if let Some(radius) = file.play_area_radius {
+                    scene.spaceship.play_area.radius = radius;
+                }
+                scene.missions = file.missions;
+                if let Some(path) = &file.sky {
+                    scene.set_sky(path, assets);
+                }
+                scene
+            }
+        };
+        if let Some(seed) = seed {
+            scene.weapons = Weapons::new().with_seed(seed);
+            scene.solar_wind = SolarWind::new().with_seed(seed.wrapping_add(1));
+        }
+        Ok(scene)
+    }
+
+    pub fn solar_system(assets: &mut Assets) -> Self {
+        let mut bodies = Vec::new();
+        let mut orbits = Vec::new();
+
+        // Sol en el centro
+        bodies.push(
+            CelestialBody::new(
+                Vec3::new(0.0, 0.0, 0.0),
+                1.5,
+                ShaderType::Sun,
+            )
+            .with_name("Sol")
+            .with_physical_radius(696_000.0)
+            .with_description("Yellow dwarf star")
+            .with_rotation_speed(Vec3::new(0.0, 0.1, 0.0))
+            .with_render_flags(RenderFlags { receive_light: false, cast_shadows: false, ..RenderFlags::default() })
+        );
+
+        // Planeta rocoso (tipo Tierra/Marte)
+        bodies.push(
+            CelestialBody::new(
+                Vec3::new(3.0, 0.0, 0.0),
+                0.5,
+                ShaderType::RockyPlanet,
+            )
+            .with_name("Ares")
+            .with_physical_radius(3_390.0)
+            .with_description("Cold desert world")
+            .with_parent(0)
+            .with_orbit(3.0, 0.5)
+            .with_rotation_speed(Vec3::new(0.0, 0.5, 0.0))
+            .with_terrain(Terrain::new(1337, 0.04))
+        );
+        // Órbita del planeta rocoso - Blanco brillante
+        orbits.push(OrbitRing::new(Vec3::new(0.0, 0.0, 0.0), 3.0, 0xFFFFFF).with_body(bodies.len() - 1));
+
+        // Luna del planeta rocoso
+        bodies.push(
+            CelestialBody::new(
+                Vec3::new(3.8, 0.0, 0.0),
+                0.15,
+                ShaderType::Moon,
+            )
+            .with_name("Phobos")
+            .with_physical_radius(1_100.0)
+            .with_description("Cratered moon of Ares")
+            .with_parent(1)
+            .with_orbit(0.8, 1.2)
+            .with_rotation_speed(Vec3::new(0.0, 0.3, 0.0))
+            .with_terrain(Terrain::new(42, 0.06).with_frequency(3.0))
+            .with_docking_port(0.0, 0.0)
+        );
+
+        // Gigante gaseoso (tipo Júpiter)
+        bodies.push(
+            CelestialBody::new(
+                Vec3::new(6.0, 0.0, 0.0),
+                0.8,
+                ShaderType::GasGiant,
+            )
+            .with_name("Zeus")
+            .with_physical_radius(69_900.0)
+            .with_description("Banded gas giant")
+            .with_parent(0)
+            .with_orbit(6.0, 0.25)
+            .with_rotation_speed(Vec3::new(0.0, 0.8, 0.0))
+            .with_magnetic_field()
+        );
+        // Órbita del gigante gaseoso - Blanco brillante
+        orbits.push(OrbitRing::new(Vec3::new(0.0, 0.0, 0.0), 6.0, 0xFFFFFF).with_body(bodies.len() - 1));
+
+        Scene::from_bodies(bodies, orbits, assets)
+    }
+
+    // Escena con estos cuerpos y órbitas, más la nave, el fondo y las mallas de cada cuerpo
+    pub fn from_bodies(mut bodies: Vec<CelestialBody>, mut orbits: Vec<OrbitRing>, assets: &mut Assets) -> Self {
+        let skybox = CelestialBody::new(
+            Vec3::new(0.0, 0.0, 0.0),
+            50.0,
+            ShaderType::Starfield,
+        )
+        .with_render_flags(RenderFlags { visible: false, cast_shadows: false, receive_light: false, draw_orbit: false, always_on_top: false });
+
+        // Los cuerpos lisos comparten una esfera; los que tienen relieve llevan la suya
+        let mut meshes = MeshRegistry::new();
+        let planet_mesh = meshes.add(create_planet_lod(SphereMesh::Icosphere, SphereTessellation::Medium, true, None));
+        for body in &mut bodies {
+            body.mesh = Some(match &body.terrain {
+                Some(terrain) => meshes.add(create_planet_lod(SphereMesh::Icosphere, SphereTessellation::Medium, true, Some(terrain))),
+                None => planet_mesh,
+            });
+            for band in &mut body.rings {
+                band.mesh = Some(meshes.add(band.create_mesh()));
+            }
+        }
+
+        for (slot, orbit) in orbits.iter_mut().enumerate() {
+            orbit.slot = slot;
+        }
+
+        // La zona de juego abarca el sistema; la clave `play_area_radius` la cambia después
+        let mut spaceship = Spaceship::new(assets);
+        spaceship.play_area.radius = PlayArea::fit_radius(&bodies);
+
+        Scene {
+            bodies,
+            orbits,
+            spaceship,
+            weapons: Weapons::new(),
+            solar_wind: SolarWind::new(),
+            skybox,
+            meshes,
+            planet_mesh,
+            sphere_mesh: SphereMesh::Icosphere,
+            sphere_tessellation: SphereTessellation::Medium,
+            smooth_shading: true,
+            clock: SimClock::default(),
+            time: 0.0,
+            script: None,
+            sky: None,
+            ship_gravity: false,
+            unit_km: DEFAULT_UNIT_KM,
+            missions: Vec::new(),
+        }
+    }
+
+    // Fondo desde una imagen equirectangular; si no se puede cargar se conserva el que había
+    pub fn set_sky(&mut self, path: &Path, assets: &mut Assets) {
+        if let Some(sky) = SkyImage::load(path, assets) {
+            self.sky = Some(sky);
+        }
+    }
+
+    // Tras recargar los assets, para que el fondo muestre la imagen nueva
+    pub fn refresh_sky(&mut self, assets: &Assets) {
+        if let Some(sky) = &mut self.sky {
+            sky.refresh(assets);
+        }
+    }
+
+    // Avanza la simulación. `orbits_running` congela órbitas y rotaciones (la nave sigue).
+    // Devuelve los nombres de los cuerpos con los que chocó la nave en este paso.
+    pub fn update(&mut self, delta_time: f32, time_scale: f32, orbits_running: bool) -> Vec<&'static str> {
+        let _span = info_span!("scene_update").entered();
+        // Update bodies (siempre actualizar posiciones para que el warp funcione)
+        self.time += delta_time;
+        let orbits_span = debug_span!("orbits").entered();
+
+        // Las posiciones salen del reloj absoluto: primero se colocan en el instante actual
+        // (que un salto de tiempo pudo haber cambiado) y luego se avanza el reloj
+        self.place_bodies();
+        let previous_positions: Vec<Vec3> = self.bodies.iter().map(|body| body.position).collect();
+
+        if orbits_running {
+            self.clock.advance((delta_time * time_scale) as f64);
+        }
+        self.place_bodies();
+
+        // Velocidad real de cada cuerpo (incluye la escala de tiempo y la luna siguiendo a su planeta)
+        if delta_time > 0.0 {
+            for (body, previous) in self.bodies.iter_mut().zip(previous_positions) {
+                body.velocity = (body.position - previous) / delta_time;
+            }
+        }
+
+        drop(orbits_span);
+
+        // Atracción de los cuerpos sobre la nave (no durante un salto del warp drive)
+        let jumping = matches!(self.spaceship.warp_drive, WarpDrive::Jumping { .. });
+        if self.ship_gravity && !jumping {
+            let pull = gravity::acceleration(self.spaceship.position, &self.obstacles());
+            self.spaceship.velocity += pull * delta_time;
+        }
+
+        // Update spaceship
+        debug_span!("spaceship").in_scope(|| self.spaceship.update(delta_time));
+
+        // Proyectiles: vuelo, impactos y partículas
+        let obstacles = self.obstacles();
+        debug_span!("weapons").in_scope(|| self.weapons.update(delta_time, &obstacles));
+        debug_span!("solar_wind").in_scope(|| self.solar_wind.update(delta_time, &self.bodies));
+
+        // Check collisions
+        let _collisions_span = debug_span!("collisions").entered();
+        let mut collisions = Vec::new();
+        for body in &self.bodies {
+            if self.spaceship.check_collision(body.position, body.scale) {
+                self.spaceship.handle_collision(body.position);
+                collisions.push(body.name);
+            }
+        }
+        collisions
+    }
+
+    // Lo que se dibuja, en el orden de la escena (el renderer los agrupa por pasada)
+    pub fn renderables(&self) -> Vec<&dyn Renderable> {
+        let mut objects: Vec<&dyn Renderable> = Vec::new();
+        objects.extend(self.bodies.iter().filter(|body| body.is_drawn()).map(|body| body as &dyn Renderable));
+        objects.push(&self.spaceship);
+        objects.extend(self.orbits.iter().filter(|orbit| orbit.is_shown(&self.bodies)).map(|orbit| orbit as &dyn Renderable));
+        objects.push(&self.weapons);
+        if self.solar_wind.enabled {
+            objects.push(&self.solar_wind);
+        }
+        objects
+    }
+
+    // Cada cuerpo orbita alrededor de su padre (que va antes en la lista) o del origen
+    pub fn place_bodies(&mut self) {
+        for index in 0..self.bodies.len() {
+            let center = self.bodies[index]
+                .parent
+                .filter(|&parent| parent < index)
+                .map_or(Vec3::zeros(), |parent| self.bodies[parent].position);
+            self.bodies[index].place_at(self.clock.seconds, center);
+        }
+        self.center_orbits();
+    }
+
+    // Los anillos siguen la forma actual de la órbita de su cuerpo, y los de las lunas se mueven
+    // con su planeta: se centran en la posición actual del padre del cuerpo que los recorre
+    pub fn center_orbits(&mut self) {
+        for orbit in &mut self.orbits {
+            orbit.follow_body(&self.bodies);
+        }
+    }
+
+    // Esferas (centro, radio) de los cuerpos para cámara y proyectiles
+    pub fn obstacles(&self) -> Vec<(Vec3, f32)> {
+        self.bodies
+            .iter()
+            .map(|body| (body.position, body.scale))
+            .collect()
+    }
+
+    // Las mismas esferas en otro instante de la simulación (para predecir trayectorias)
+    pub fn body_spheres_at(&self, sim_seconds: f64) -> Vec<(Vec3, f32)> {
+        let mut spheres: Vec<(Vec3, f32)> = Vec::with_capacity(self.bodies.len());
+        for (index, body) in self.bodies.iter().enumerate() {
+            let center = body.parent.filter(|&parent| parent < index).map_or(Vec3::zeros(), |parent| spheres[parent].0);
+            spheres.push((body.position_at(sim_seconds, center), body.scale));
+        }
+        spheres
+    }
+
+    // Cuerpo cuya superficie está más cerca de la nave y la distancia entre superficies
+    pub fn nearest_body(&self) -> (&'static str, f32) {
+        self.bodies
+            .iter()
+            .map(|body| {
+                let clearance = self.spaceship.surface_distance(body.position, body.scale);
+                (body.name, clearance)
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or(("-", f32::MAX))
+    }
+
+    // Regenera las esferas del registro (mismos ids) tras cambiar el tipo de malla, el teselado
+    // o el sombreado
+    pub fn rebuild_planet_meshes(&mut self) {
+        let (sphere, tessellation, smooth) = (self.sphere_mesh, self.sphere_tessellation, self.smooth_shading);
+        self.meshes.replace(self.planet_mesh, create_planet_lod(sphere, tessellation, smooth, None));
+        for body in &self.bodies {
+            if let (Some(terrain), Some(mesh_id)) = (&body.terrain, body.mesh) {
+                self.meshes.replace(mesh_id, create_planet_lod(sphere, tessellation, smooth, Some(terrain)));
+            }
+        }
+    }
+}
+
+// Use optimized procedural sphere instead of loading from file.
+// Icospheres (triángulos parejos, sin polos pellizcados en los shaders de ruido) o cube-spheres
+// (UV equirectangulares continuas, para planetas con textura) en varios niveles de detalle.
+// Las normales se recalculan: promediadas por área (suave) o con ángulo de pliegue 0 (facetado)
+pub fn create_planet_lod(mesh: SphereMesh, tessellation: SphereTessellation, smooth: bool, terrain: Option<&Terrain>) -> LodMesh {
+    LodMesh::new((0..LOD_LEVELS).map(|level| {
+        let mut vertices = mesh.create(level, tessellation);
+        if let Some(terrain) = terrain {
+            terrain.displace(&mut vertices);
+        }
+        smooth_triangle_list(&mut vertices, if smooth { DEFAULT_CREASE_ANGLE } else { 0.0 });
+        let mut mesh = Mesh::from_triangle_list(vertices);
+        mesh.compute_tangents();
+        mesh
+    }).collect())
+}
@@ -0,0 +1,585 @@
+use nalgebra_glm::Vec3;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use log::{info, warn};
+use crate::celestial_body::{CelestialBody, ShaderType, MAX_ECCENTRICITY};
+use crate::markers::SurfaceMarker;
+use crate::missions::{Condition, MissionSpec};
+use crate::orbit::{OrbitRing, DEFAULT_OPACITY};
+use crate::renderable::RenderFlags;
+use crate::rings::RingBand;
+use crate::scene::Scene;
+use crate::sim_clock::DAYS_PER_YEAR;
+use crate::storage;
+use crate::terrain::Terrain;
+
+// Formato de los archivos de escena (`--scene <archivo>`), igual a keybindings.cfg: `nombre = valor`
+// y `#` comenta. Cada `[body]` empieza un cuerpo; los padres van antes que sus lunas.
+//
+//   name = Solar System
+//   script = survey.rhai           # Script del escenario, relativo al archivo de escena
+//   sky = milky_way.jpg            # Fondo equirectangular, relativo al archivo de escena
+//   unit_km = 74798935             # Kilómetros que representa una unidad de la escena (regla)
+//   play_area_radius = 20          # Límite del sistema (sin la clave, el afelio más lejano con margen)
+//   [body]
+//   name = Ares
+//   shader = Rocky Planet          # Sun, Rocky Planet, Gas Giant, Moon, Ringed Planet
+//   scale = 0.5
+//   parent = Sol                   # Nombre de un cuerpo anterior
+//   orbit = 3.0 0.5                # Radio (semieje mayor) y velocidad angular
+//   eccentricity = 0.2             # Elipse con el padre en un foco (0 = círculo, hasta 0.9)
+//   inclination = 10               # Grados de inclinación del plano de la órbita
+//   orbit_ring = FFFFFF            # Dibuja la órbita (centrada en el origen) con ese color
+//   orbit_opacity = 0.5            # Fracción del color del anillo (0-1)
+//   rotation_speed = 0 0.5 0
+//   axial_tilt = 27                # Grados de inclinación del eje de giro y de los anillos
+//   radius_km = 3390
+//   description = Cold desert world
+//   terrain = 1337 0.04 [2.0]      # Semilla, amplitud y frecuencia opcional
+//   position = 3 0 0
+//   ring = 1.2 1.9 D8C8A0 0.8 7    # Banda de anillos: radios interior y exterior (en radios del
+//                                  # cuerpo), color y opcionales opacidad (0-1) y semilla; una
+//                                  # línea por banda, los huecos entre bandas quedan vacíos
+//   docking_port = 0 0             # Puerto de acople: latitud y longitud (guía al acercarse)
+//   visible = true                 # Marcas de dibujo (true/false): visible, cast_shadows,
+//   receive_light = true           # receive_light (false = sin lado oscuro), draw_orbit,
+//   always_on_top = false          # always_on_top (delante de todo)
+//   [mission]                      # Misiones, de a una en el orden del archivo (ver missions.rs)
+//   name = Grand Tour
+//   objective = visit all          # visit, orbit o touchdown (dock) <cuerpo>; una línea por objetivo
+//   trigger = visit Ares           # Empieza al cumplirse (sin disparador, apenas le toca)
+//   time_limit = 300               # Segundos para cumplirla
+//   fuel = 60                      # Segundos de empuje disponibles
+pub struct SceneFile {
+    pub name: String,
+    pub bodies: Vec<CelestialBody>,
+    pub orbits: Vec<OrbitRing>,
+    pub script: Option<PathBuf>,
+    pub sky: Option<PathBuf>,
+    pub unit_km: Option<f32>,
+    pub play_area_radius: Option<f32>,
+    pub missions: Vec<MissionSpec>,
+}
+
+// Un cuerpo mientras se lee: la figura se conoce recién al final de la sección
+#[derive(Default)]
+struct BodyEntry {
+    line_number: usize,
+    name: Option<String>,
+    shader_type: Option<ShaderType>,
+    position: Option<Vec3>,
+    scale: Option<f32>,
+    parent: Option<String>,
+    orbit: Option<(f32, f32)>,
+    eccentricity: Option<f32>,
+    inclination: Option<f32>,      // Grados
+    orbital_period: Option<f64>,   // Días (ver `parse_period`)
+    rotation_period: Option<f64>,
+    orbit_ring: Option<u32>,
+    orbit_opacity: Option<f32>,
+    rotation_speed: Option<Vec3>,
+    axial_tilt: Option<f32>,       // Grados
+    radius_km: Option<f32>,
+    description: Option<String>,
+    terrain: Option<Terrain>,
+    flags: RenderFlags,
+    rings: Vec<RingBand>,
+    markers: Vec<SurfaceMarker>,
+    subsolar_marker: bool,
+    docking_port: Option<(f32, f32)>,  // Latitud y longitud
+    magnetic_field: bool,
+}
+
+impl SceneFile {
+    // Lo que no se entienda se avisa y se ignora, como en los otros archivos de configuración;
+    // un archivo ilegible, sin cuerpos o con un padre desconocido es un error
+    pub fn load(path: &Path) -> Result<SceneFile, String> {
+        let contents = storage::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::parse(&contents, path)
+    }
+
+    // `path` da el nombre por defecto, la carpeta del script y del fondo, y la ubicación en los mensajes
+    fn parse(contents: &str, path: &Path) -> Result<SceneFile, String> {
+        let default_name = path.file_stem().map_or_else(|| "Scene".to_string(), |stem| stem.to_string_lossy().into_owned());
+        let mut name = default_name;
+        let mut script = None;
+        let mut sky = None;
+        let mut unit_km = None;
+        let mut play_area_radius = None;
+        let mut entries: Vec<BodyEntry> = Vec::new();
+        let mut missions: Vec<MissionSpec> = Vec::new();
+        let mut in_mission = false;  // La última sección abierta es un [mission]
+
+        for (line_number, raw_line) in contents.lines().enumerate() {
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line.eq_ignore_ascii_case("[body]") {
+                entries.push(BodyEntry { line_number: line_number + 1, ..BodyEntry::default() });
+                in_mission = false;
+                continue;
+            }
+            if line.eq_ignore_ascii_case("[mission]") {
+                missions.push(MissionSpec { name: format!("Mission {}", missions.len() + 1), ..MissionSpec::default() });
+                in_mission = true;
+                continue;
+            }
+
+            let applied = line.split_once('=').and_then(|(key, value)| {
+                let (key, value) = (key.trim(), value.trim());
+                if let Some(mission) = missions.last_mut().filter(|_| in_mission) {
+                    match key {
+                        "name" => mission.name = value.to_string(),
+                        "objective" => mission.objectives.push(Condition::parse(value)?),
+                        "trigger" => mission.trigger = Some(Condition::parse(value)?),
+                        "time_limit" => mission.time_limit = Some(parse_positive(value)?),
+                        "fuel" => mission.fuel = Some(parse_positive(value)?),
+                        _ => return None,
+                    }
+                    return Some(());
+                }
+                let Some(entry) = entries.last_mut() else {
+                    // Antes del primer [body] solo van el nombre de la escena, su script, su fondo y su escala
+                    if value.is_empty() {
+                        return None;
+                    }
+                    match key {
+                        "name" => name = value.to_string(),
+                        "script" => script = Some(path.parent().unwrap_or(Path::new("")).join(value)),
+                        "sky" => sky = Some(path.parent().unwrap_or(Path::new("")).join(value)),
+                        "unit_km" => unit_km = Some(parse_positive(value)?),
+                        "play_area_radius" => play_area_radius = Some(parse_positive(value)?),
+                        _ => return None,
+                    }
+                    return Some(());
+                };
+                match key {
+                    "name" => entry.name = Some(value.to_string()),
+                    "shader" => entry.shader_type = Some(parse_shader(value)?),
+                    "position" => entry.position = Some(parse_vec3(value)?),
+                    "scale" => entry.scale = Some(parse_positive(value)?),
+                    "parent" => entry.parent = Some(value.to_string()),
+                    "orbit" => entry.orbit = Some(parse_orbit(value)?),
+                    "eccentricity" => entry.eccentricity = Some(value.parse::<f32>().ok().filter(|e| (0.0..=MAX_ECCENTRICITY).contains(e))?),
+                    "inclination" => entry.inclination = Some(value.parse::<f32>().ok().filter(|degrees| (-90.0..=90.0).contains(degrees))?),
+                    "orbital_period" => entry.orbital_period = Some(parse_period(value)?),
+                    "rotation_period" => entry.rotation_period = Some(parse_period(value)?),
+                    "orbit_ring" => entry.orbit_ring = Some(u32::from_str_radix(value.trim_start_matches('#'), 16).ok()?),
+                    "orbit_opacity" => entry.orbit_opacity = Some(value.parse::<f32>().ok().filter(|opacity| (0.0..=1.0).contains(opacity))?),
+                    "rotation_speed" => entry.rotation_speed = Some(parse_vec3(value)?),
+                    "axial_tilt" => entry.axial_tilt = Some(value.parse::<f32>().ok().filter(|degrees| (-90.0..=90.0).contains(degrees))?),
+                    "radius_km" => entry.radius_km = Some(parse_positive(value)?),
+                    "description" => entry.description = Some(value.to_string()),
+                    "terrain" => entry.terrain = Some(parse_terrain(value)?),
+                    "ring" => entry.rings.push(parse_ring(value)?),
+                    "marker" => entry.markers.push(parse_marker(value)?),
+                    "subsolar_marker" => entry.subsolar_marker = parse_bool(value)?,
+                    "magnetic_field" => entry.magnetic_field = parse_bool(value)?,
+                    "docking_port" => entry.docking_port = Some(parse_docking_port(value)?),
+                    "visible" => entry.flags.visible = parse_bool(value)?,
+                    "cast_shadows" => entry.flags.cast_shadows = parse_bool(value)?,
+                    "receive_light" => entry.flags.receive_light = parse_bool(value)?,
+                    "draw_orbit" => entry.flags.draw_orbit = parse_bool(value)?,
+                    "always_on_top" => entry.flags.always_on_top = parse_bool(value)?,
+                    _ => return None,
+                }
+                Some(())
+            });
+
+            if applied.is_none() {
+                warn!("{}:{}: ignoring invalid scene line `{}`", path.display(), line_number + 1, line);
+            }
+        }
+
+        let mut bodies: Vec<CelestialBody> = Vec::new();
+        let mut orbits = Vec::new();
+        for entry in entries {
+            let location = format!("{}:{}", path.display(), entry.line_number);
+            let shader_type = entry.shader_type.ok_or_else(|| format!("{}: body without `shader`", location))?;
+            let mut body = CelestialBody::new(entry.position.unwrap_or_else(Vec3::zeros), entry.scale.unwrap_or(1.0), shader_type);
+            if let Some(name) = &entry.name {
+                body = body.with_name(intern(name));
+            }
+            if let Some(parent) = &entry.parent {
+                let index = bodies
+                    .iter()
+                    .position(|other| other.name == parent.as_str())
+                    .ok_or_else(|| format!("{}: parent `{}` is not defined before this body", location, parent))?;
+                body = body.with_parent(index);
+                // La altura del archivo es absoluta; la órbita se mide desde el padre
+                body.orbit_height -= bodies[index].position.y;
+            }
+            if let Some((radius, speed)) = entry.orbit {
+                body = body.with_orbit(radius, speed)
+                    .with_eccentricity(entry.eccentricity.unwrap_or(0.0))
+                    .with_inclination(entry.inclination.unwrap_or(0.0));
+                if let Some(color) = entry.orbit_ring {
+                    let ring = OrbitRing::new(Vec3::zeros(), radius, color).with_body(bodies.len());
+                    orbits.push(ring.with_opacity(entry.orbit_opacity.unwrap_or(DEFAULT_OPACITY)));
+                }
+            }
+            if let Some(speed) = entry.rotation_speed {
+                body = body.with_rotation_speed(speed);
+            }
+            // Los periodos mandan sobre las velocidades angulares si están los dos
+            if let Some(days) = entry.orbital_period {
+                body = body.with_year_length(days);
+            }
+            if let Some(days) = entry.rotation_period {
+                body = body.with_day_length(days);
+            }
+            if let Some(degrees) = entry.axial_tilt {
+                body = body.with_axial_tilt(degrees);
+            }
+            if let Some(radius_km) = entry.radius_km {
+                body = body.with_physical_radius(radius_km);
+            }
+            if let Some(description) = &entry.description {
+                body = body.with_description(intern(description));
+            }
+            if let Some(terrain) = entry.terrain {
+                body = body.with_terrain(terrain);
+            }
+            for band in entry.rings {
+                body = body.with_ring(band);
+            }
+            for marker in entry.markers {
+                body = body.with_marker(marker);
+            }
+            if entry.subsolar_marker {
+                body = body.with_subsolar_marker();
+            }
+            if let Some((latitude, longitude)) = entry.docking_port {
+                body = body.with_docking_port(latitude, longitude);
+            }
+            if entry.magnetic_field {
+                body = body.with_magnetic_field();
+            }
+            bodies.push(body.with_render_flags(entry.flags));
+        }
+
+        if bodies.is_empty() {
+            return Err(format!("{}: the scene has no [body] sections", path.display()));
+        }
+        info!("Scene `{}` loaded from {} ({} bodies)", name, path.display(), bodies.len());
+        Ok(SceneFile { name, bodies, orbits, script, sky, unit_km, play_area_radius, missions })
+    }
+}
+
+// La escena tal como está ahora, en el formato de `SceneFile::load` (el editor de órbitas la
+// exporta con sus cambios). Las posiciones de los cuerpos que orbitan son las del momento; al
+// cargarla solo cuenta su altura. Las rutas del script y del fondo se escriben como se cargaron.
+pub fn export(scene: &Scene, name: &str) -> String {
+    let mut out = String::new();
+    let mut line = |text: String| {
+        out.push_str(&text);
+        out.push('\n');
+    };
+    line(format!("name = {}", name));
+    if let Some(script) = &scene.script {
+        line(format!("script = {}", script.display()));
+    }
+    if let Some(sky) = &scene.sky {
+        line(format!("sky = {}", sky.path.display()));
+    }
+    line(format!("unit_km = {}", scene.unit_km));
+    line(format!("play_area_radius = {}", scene.spaceship.play_area.radius));
+
+    // Altura absoluta del plano de cada órbita, la que el archivo guarda en `position`
+    let mut heights: Vec<f32> = Vec::with_capacity(scene.bodies.len());
+    for (index, body) in scene.bodies.iter().enumerate() {
+        let parent = body.parent.filter(|&parent| parent < index);
+        let height = if body.orbit_radius > 0.0 {
+            parent.map_or(0.0, |parent| heights[parent]) + body.orbit_height
+        } else {
+            body.position.y
+        };
+        heights.push(height);
+
+        line(String::new());
+        line("[body]".to_string());
+        line(format!("name = {}", body.name));
+        line(format!("shader = {}", body.shader_type.display_name()));
+        line(format!("position = {} {} {}", body.position.x, height, body.position.z));
+        line(format!("scale = {}", body.scale));
+        if let Some(parent) = parent {
+            line(format!("parent = {}", scene.bodies[parent].name));
+        }
+        if body.orbit_radius > 0.0 {
+            line(format!("orbit = {} {}", body.orbit_radius, body.orbit_speed));
+            if body.orbit_eccentricity > 0.0 {
+                line(format!("eccentricity = {}", body.orbit_eccentricity));
+            }
+            if body.orbit_inclination != 0.0 {
+                line(format!("inclination = {:.2}", body.orbit_inclination.to_degrees()));
+            }
+        }
+        if let Some(ring) = scene.orbits.iter().find(|ring| ring.body == Some(index)) {
+            line(format!("orbit_ring = {:06X}", ring.color));
+            line(format!("orbit_opacity = {}", ring.opacity));
+        }
+        let spin = body.rotation_speed;
+        line(format!("rotation_speed = {} {} {}", spin.x, spin.y, spin.z));
+        if body.axial_tilt != 0.0 {
+            line(format!("axial_tilt = {:.2}", body.axial_tilt.to_degrees()));
+        }
+        if let Some(radius_km) = body.radius_km {
+            line(format!("radius_km = {}", radius_km));
+        }
+        if !body.description.is_empty() {
+            line(format!("description = {}", body.description));
+        }
+        if let Some(terrain) = &body.terrain {
+            line(format!("terrain = {} {} {}", terrain.seed, terrain.amplitude, terrain.frequency));
+        }
+        for band in &body.rings {
+            line(format!("ring = {} {} {:06X} {} {}", band.inner, band.outer, band.color, band.opacity, band.seed));
+        }
+        for marker in &body.markers {
+            line(format!("marker = {} {} {:06X} {}", marker.latitude, marker.longitude, marker.color, marker.label));
+        }
+        if body.subsolar_marker {
+            line("subsolar_marker = true".to_string());
+        }
+        if let Some(port) = &body.docking_port {
+            line(format!("docking_port = {} {}", port.latitude, port.longitude));
+        }
+        if body.magnetic_field {
+            line("magnetic_field = true".to_string());
+        }
+        // Solo las marcas que no tienen el valor por defecto
+        let (flags, default) = (body.flags, RenderFlags::default());
+        for (key, value, default) in [
+            ("visible", flags.visible, default.visible),
+            ("cast_shadows", flags.cast_shadows, default.cast_shadows),
+            ("receive_light", flags.receive_light, default.receive_light),
+            ("draw_orbit", flags.draw_orbit, default.draw_orbit),
+            ("always_on_top", flags.always_on_top, default.always_on_top),
+        ] {
+            if value != default {
+                line(format!("{} = {}", key, value));
+            }
+        }
+    }
+
+    for mission in &scene.missions {
+        line(String::new());
+        line("[mission]".to_string());
+        line(format!("name = {}", mission.name));
+        for objective in &mission.objectives {
+            line(format!("objective = {}", objective));
+        }
+        if let Some(trigger) = &mission.trigger {
+            line(format!("trigger = {}", trigger));
+        }
+        if let Some(time_limit) = mission.time_limit {
+            line(format!("time_limit = {}", time_limit));
+        }
+        if let Some(fuel) = mission.fuel {
+            line(format!("fuel = {}", fuel));
+        }
+    }
+    out
+}
+
+// Los nombres y descripciones de los cuerpos son `&'static str` (HUD, alertas, etiquetas);
+// los que vienen de un archivo se guardan una sola vez y se reutilizan al recargar la escena
+fn intern(text: &str) -> &'static str {
+    static INTERNED: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+    let mut interned = INTERNED.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(&existing) = interned.get(text) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(text.to_string().into_boxed_str());
+    interned.insert(leaked);
+    leaked
+}
+
+fn parse_shader(value: &str) -> Option<ShaderType> {
+    ShaderType::BODIES.into_iter().find(|shader| {
+        let name = shader.display_name();
+        name.eq_ignore_ascii_case(value) || name.replace(' ', "_").eq_ignore_ascii_case(value)
+    })
+}
+
+fn parse_floats<const N: usize>(value: &str) -> Option<[f32; N]> {
+    let numbers: Vec<f32> = value.split_whitespace().map(|part| part.parse().ok()).collect::<Option<_>>()?;
+    numbers.try_into().ok()
+}
+
+fn parse_vec3(value: &str) -> Option<Vec3> {
+    let [x, y, z] = parse_floats(value)?;
+    Some(Vec3::new(x, y, z))
+}
+
+fn parse_positive(value: &str) -> Option<f32> {
+    value.parse().ok().filter(|&number: &f32| number > 0.0)
+}
+
+// `<radio> [velocidad angular]`: sin velocidad, la da `orbital_period` (o el cuerpo queda quieto)
+fn parse_orbit(value: &str) -> Option<(f32, f32)> {
+    match value.split_whitespace().count() {
+        1 => Some((value.parse().ok()?, 0.0)),
+        _ => parse_floats(value).map(|[radius, speed]| (radius, speed)),
+    }
+}
+
+// Un periodo en días, con unidad opcional: `24h`, `365d` (o solo `365`), `1.88y`. Negativo =
+// sentido retrógrado
+fn parse_period(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let (number, days_per_unit) = match value.char_indices().last()? {
+        (index, 'h') => (&value[..index], 1.0 / 24.0),
+        (index, 'd') => (&value[..index], 1.0),
+        (index, 'y') => (&value[..index], DAYS_PER_YEAR),
+        _ => (value, 1.0),
+    };
+    let days = number.trim().parse::<f64>().ok()? * days_per_unit;
+    (days.is_finite() && days != 0.0).then_some(days)
+}
+
+fn parse_ring(value: &str) -> Option<RingBand> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let (inner, outer): (f32, f32) = (parts.first()?.parse().ok()?, parts.get(1)?.parse().ok()?);
+    if !(inner > 0.0 && outer > inner && (3..=5).contains(&parts.len())) {
+        return None;
+    }
+    let color = u32::from_str_radix(parts[2].trim_start_matches('#'), 16).ok()?;
+    let mut band = RingBand::new(inner, outer, color);
+    if let Some(opacity) = parts.get(3) {
+        band = band.with_opacity(opacity.parse::<f32>().ok().filter(|opacity| (0.0..=1.0).contains(opacity))?);
+    }
+    if let Some(seed) = parts.get(4) {
+        band = band.with_seed(seed.parse().ok()?);
+    }
+    Some(band)
+}
+
+// `<latitud> <longitud> <color> <nombre>`; el nombre puede tener espacios
+fn parse_marker(value: &str) -> Option<SurfaceMarker> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    if parts.len() < 4 {
+        return None;
+    }
+    let latitude = parts[0].parse::<f32>().ok().filter(|latitude| (-90.0..=90.0).contains(latitude))?;
+    let longitude = parts[1].parse::<f32>().ok().filter(|longitude| longitude.is_finite())?;
+    let color = u32::from_str_radix(parts[2].trim_start_matches('#'), 16).ok()?;
+    Some(SurfaceMarker::new(intern(&parts[3..].join(" ")), latitude, longitude, color))
+}
+
+// `<latitud> <longitud>`, como un marcador sin color ni nombre
+fn parse_docking_port(value: &str) -> Option<(f32, f32)> {
+    let mut parts = value.split_whitespace();
+    let latitude = parts.next()?.parse::<f32>().ok().filter(|latitude| (-90.0..=90.0).contains(latitude))?;
+    let longitude = parts.next()?.parse::<f32>().ok().filter(|longitude| longitude.is_finite())?;
+    parts.next().is_none().then_some((latitude, longitude))
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_terrain(value: &str) -> Option<Terrain> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let (seed, amplitude) = (parts.first()?.parse().ok()?, parts.get(1)?.parse().ok()?);
+    let terrain = Terrain::new(seed, amplitude);
+    match parts.get(2) {
+        Some(frequency) if parts.len() == 3 => Some(terrain.with_frequency(frequency.parse().ok()?)),
+        None => Some(terrain),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(contents: &str) -> SceneFile {
+        SceneFile::parse(contents, Path::new("scenes/test.scene")).unwrap()
+    }
+
+    fn planet() -> CelestialBody {
+        CelestialBody::new(Vec3::zeros(), 1.0, ShaderType::RockyPlanet)
+    }
+
+    #[test]
+    fn periods_accept_units() {
+        assert_eq!(parse_period("365"), Some(365.0));
+        assert_eq!(parse_period("12d"), Some(12.0));
+        assert_eq!(parse_period("36h"), Some(1.5));
+        assert_eq!(parse_period("2y"), Some(2.0 * DAYS_PER_YEAR));
+        assert_eq!(parse_period("1.5 y"), Some(1.5 * DAYS_PER_YEAR));
+        assert_eq!(parse_period("-12h"), Some(-0.5));
+    }
+
+    #[test]
+    fn invalid_periods_are_rejected() {
+        for value in ["", "0", "0h", "d", "ten days", "5m", "inf", "NaN"] {
+            assert_eq!(parse_period(value), None, "`{}`", value);
+        }
+    }
+
+    #[test]
+    fn ring_bands_take_optional_opacity_and_seed() {
+        let band = parse_ring("1.2 1.9 D8C8A0").unwrap();
+        assert_eq!((band.inner, band.outer, band.color), (1.2, 1.9, 0xD8C8A0));
+        let band = parse_ring("1.2 1.9 #D8C8A0 0.5 7").unwrap();
+        assert_eq!((band.color, band.opacity, band.seed), (0xD8C8A0, 0.5, 7));
+    }
+
+    #[test]
+    fn invalid_ring_bands_are_rejected() {
+        for value in ["1.2 1.9", "1.9 1.2 FFFFFF", "0 1 FFFFFF", "1 2 nothex", "1 2 FFFFFF 1.5", "1 2 FFFFFF 0.5 7 9"] {
+            assert!(parse_ring(value).is_none(), "`{}`", value);
+        }
+    }
+
+    #[test]
+    fn periods_set_the_angular_speeds() {
+        let scene = parse("[body]\nshader = Sun\nname = Sol\n[body]\nshader = Rocky Planet\nparent = Sol\n\
+orbit = 3 0.5\norbital_period = 2y\nrotation_period = -12h\n");
+        let body = &scene.bodies[1];
+        // El periodo manda sobre la velocidad de `orbit`; negativo = retrógrado
+        assert_eq!(body.orbit_radius, 3.0);
+        assert_eq!(body.orbit_speed, planet().with_year_length(2.0 * DAYS_PER_YEAR).orbit_speed);
+        assert_eq!(body.rotation_speed.y, planet().with_day_length(-0.5).rotation_speed.y);
+        assert!(body.rotation_speed.y < 0.0);
+    }
+
+    #[test]
+    fn orbit_without_speed_waits_for_a_period() {
+        let scene = parse("[body]\nshader = Sun\nname = Sol\n[body]\nshader = Moon\nparent = Sol\norbit = 2\n");
+        assert_eq!(scene.bodies[1].orbit_speed, 0.0);
+        assert_eq!(scene.bodies[1].orbital_period(), None);
+    }
+
+    #[test]
+    fn each_ring_line_adds_a_band() {
+        let scene = parse("[body]\nshader = Ringed Planet\nring = 1.2 1.5 D8C8A0\nring = 2 1 FFFFFF\nring = 1.7 2.0 C0B090 0.4 3\n");
+        let rings = &scene.bodies[0].rings;
+        // La línea inválida se ignora sin descartar el cuerpo
+        assert_eq!(rings.len(), 2);
+        assert_eq!((rings[0].inner, rings[0].outer), (1.2, 1.5));
+        assert_eq!((rings[1].inner, rings[1].outer, rings[1].opacity, rings[1].seed), (1.7, 2.0, 0.4, 3));
+    }
+
+    #[test]
+    fn scene_keys_come_before_the_first_body() {
+        let scene = parse("name = Test\nplay_area_radius = 20\nunit_km = 1000\n[body]\nshader = Sun\n");
+        assert_eq!(scene.name, "Test");
+        assert_eq!(scene.play_area_radius, Some(20.0));
+        assert_eq!(scene.unit_km, Some(1000.0));
+    }
+
+    #[test]
+    fn unknown_parent_is_an_error() {
+        let error = SceneFile::parse("[body]\nshader = Moon\nparent = Nowhere\n", Path::new("test.scene")).err().unwrap();
+        assert!(error.contains("Nowhere"));
+    }
+}
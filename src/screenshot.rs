@@ -0,0 +1,350 @@
+use nalgebra_glm::Vec3;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use log::warn;
+use web_time::{SystemTime, UNIX_EPOCH};
+use crate::camera::{CameraMode, FIELD_OF_VIEW_DEGREES};
+use crate::engine::{Engine, LaunchOptions};
+use crate::framebuffer::Framebuffer;
+use crate::labels;
+use crate::scene::SceneSource;
+use crate::settings::Settings;
+use crate::sim_clock::format_date;
+use crate::sphere::SphereMesh;
+use crate::storage;
+use crate::text::{draw_text_styled, measure_text, Align, TextStyle};
+use crate::toast::Toasts;
+
+// Carpeta de las capturas de la tecla de captura (relativa al directorio de trabajo)
+pub const SCREENSHOT_DIR: &str = "screenshots";
+
+const PANEL_COLOR: u32 = 0x000000;
+const PANEL_ALPHA: f32 = 0.6;
+const TEXT_COLOR: u32 = 0xC8D2DC;
+const TITLE_COLOR: u32 = 0xFFE070;
+const MARGIN: usize = 6;
+
+// Estado completo de una captura: el JSON que se guarda junto al PNG. Con `--shot` se vuelve
+// a cargar para dibujar exactamente la misma imagen. No se guardan los disparos en vuelo, una
+// animación de warp a medias ni los avisos o el destello de choque que había en pantalla.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Shot {
+    pub image: String,
+    pub taken_at: String,  // UTC
+    pub annotated: bool,
+    pub scene: Option<PathBuf>,  // None = sistema solar incorporado
+    pub seed: Option<u64>,
+    pub sky: Option<PathBuf>,  // Imagen de fondo en uso (None = fondo procedural)
+    pub window_size: [usize; 2],
+    pub framebuffer_size: [usize; 2],
+    pub settings: BTreeMap<String, String>,  // Nombres y valores de `Settings::entries`
+    pub simulation: SimulationState,
+    pub camera: CameraState,
+    pub ship: ShipState,
+    pub overlays: OverlayState,
+    pub objective: Option<String>,
+    pub bodies: Vec<BodyState>,  // Solo de referencia: las posiciones salen del reloj
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationState {
+    pub clock_seconds: f64,
+    pub date: String,
+    pub elapsed: f32,  // Tiempo real de la escena (animaciones de los shaders y el fondo)
+    pub orbits_running: bool,
+    pub smooth_shading: bool,
+    pub sphere_mesh: String,
+    pub focus: String,  // Cuerpo que sigue la cámara orbital
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CameraState {
+    pub mode: String,
+    pub eye: [f32; 3],
+    pub target: [f32; 3],
+    pub up: [f32; 3],
+    pub yaw: f32,
+    pub pitch: f32,
+    pub fov_degrees: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShipState {
+    pub model: String,  // Archivo de assets/models ("" = nave embebida)
+    pub position: [f32; 3],
+    pub rotation: [f32; 3],
+    pub velocity: [f32; 3],
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OverlayState {
+    pub hud: bool,
+    pub body_labels: bool,
+    pub velocity_indicators: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BodyState {
+    pub name: String,
+    pub position: [f32; 3],
+}
+
+impl Shot {
+    pub fn capture(engine: &Engine, image: &Path, annotated: bool) -> Shot {
+        let scene = &engine.scene;
+        let camera = &engine.camera;
+        let ship = &scene.spaceship;
+        let framebuffer = &engine.renderer.framebuffer;
+        let launch = engine.launch_options();
+        let (window_width, window_height) = engine.window_size();
+
+        Shot {
+            image: image.file_name().map_or_else(String::new, |name| name.to_string_lossy().into_owned()),
+            taken_at: utc_timestamp(),
+            annotated,
+            scene: match &launch.scene {
+                SceneSource::SolarSystem => None,
+                SceneSource::File(path) => Some(path.clone()),
+            },
+            seed: launch.seed,
+            sky: scene.sky.as_ref().map(|sky| sky.path.clone()),
+            window_size: [window_width, window_height],
+            framebuffer_size: [framebuffer.width, framebuffer.height],
+            settings: engine.settings.entries().into_iter().map(|(name, value)| (name.to_string(), value)).collect(),
+            simulation: SimulationState {
+                clock_seconds: scene.clock.seconds,
+                date: format_date(scene.clock.days()),
+                elapsed: scene.time,
+                orbits_running: engine.orbits_running,
+                smooth_shading: scene.smooth_shading,
+                sphere_mesh: scene.sphere_mesh.name().to_string(),
+                focus: scene.bodies.get(engine.current_body_index).map_or_else(String::new, |body| body.name.to_string()),
+            },
+            camera: CameraState {
+                mode: camera.mode.name().to_string(),
+                eye: array(camera.eye),
+                target: array(camera.center),
+                up: array(camera.up),
+                yaw: camera.yaw,
+                pitch: camera.pitch,
+                fov_degrees: FIELD_OF_VIEW_DEGREES,
+            },
+            ship: ShipState {
+                model: ship.model_paths
+                    .get(ship.model_index)
+                    .and_then(|path| path.file_name())
+                    .map_or_else(String::new, |name| name.to_string_lossy().into_owned()),
+                position: array(ship.position),
+                rotation: array(ship.rotation),
+                velocity: array(ship.velocity),
+            },
+            overlays: OverlayState {
+                hud: engine.show_hud,
+                body_labels: engine.show_body_labels,
+                velocity_indicators: engine.show_velocity_indicators,
+            },
+            objective: engine.objective.clone(),
+            bodies: scene.bodies
+                .iter()
+                .map(|body| BodyState { name: body.name.to_string(), position: array(body.position) })
+                .collect(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Shot, String> {
+        let contents = storage::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        serde_json::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn window_size(&self) -> (usize, usize) {
+        (self.window_size[0], self.window_size[1])
+    }
+
+    // Los ajustes de la toma encima de `settings` (lo que no se entienda se avisa y se ignora)
+    pub fn settings(&self, mut settings: Settings) -> Settings {
+        for (name, value) in &self.settings {
+            if settings.apply(name, value).is_none() {
+                warn!("Shot {}: ignoring invalid setting `{} = {}`", self.image, name, value);
+            }
+        }
+        settings
+    }
+
+    // Escena, semilla, fondo y cámara de la toma en lugar de las de `launch`
+    pub fn launch_options(&self, launch: LaunchOptions) -> LaunchOptions {
+        let scene = self.scene.clone().map_or(SceneSource::SolarSystem, SceneSource::File);
+        let mut launch = launch.with_scene(scene);
+        if let Some(seed) = self.seed {
+            launch = launch.with_seed(seed);
+        }
+        if let Some(sky) = &self.sky {
+            launch = launch.with_sky(sky.clone());
+        }
+        match camera_mode(&self.camera.mode) {
+            Some(mode) => launch.with_camera_mode(mode),
+            None => launch,
+        }
+    }
+
+    // Deja el engine (lanzado con `launch_options` y `settings`) como estaba al tomar la captura.
+    // El siguiente frame tiene que avanzar 0 s para que la imagen salga igual.
+    pub fn apply(&self, engine: &mut Engine) {
+        let simulation = &self.simulation;
+        let scene = &mut engine.scene;
+        scene.clock.seconds = simulation.clock_seconds;
+        scene.time = simulation.elapsed;
+        scene.place_bodies();
+        engine.orbits_running = simulation.orbits_running;
+        let sphere_mesh = SphereMesh::ALL.into_iter().find(|mesh| mesh.name() == simulation.sphere_mesh);
+        let sphere_mesh = sphere_mesh.unwrap_or(scene.sphere_mesh);
+        if simulation.smooth_shading != scene.smooth_shading || sphere_mesh != scene.sphere_mesh {
+            scene.smooth_shading = simulation.smooth_shading;
+            scene.sphere_mesh = sphere_mesh;
+            scene.rebuild_planet_meshes();
+        }
+        if let Some(index) = scene.bodies.iter().position(|body| body.name == simulation.focus) {
+            engine.current_body_index = index;
+        }
+
+        let ship = &mut scene.spaceship;
+        let model_index = ship.model_paths.iter().position(|path| path.file_name().is_some_and(|name| *name == *self.ship.model));
+        match model_index {
+            Some(index) if index != ship.model_index => ship.select_model(&mut engine.assets, index),
+            Some(_) => {}
+            None if !self.ship.model.is_empty() => warn!("Shot {}: ship model {} not found", self.image, self.ship.model),
+            None => {}
+        }
+        ship.position = vec3(self.ship.position);
+        ship.rotation = vec3(self.ship.rotation);
+        ship.velocity = vec3(self.ship.velocity);
+
+        let camera = &mut engine.camera;
+        if let Some(mode) = camera_mode(&self.camera.mode) {
+            camera.set_mode(mode);
+        }
+        camera.eye = vec3(self.camera.eye);
+        camera.center = vec3(self.camera.target);
+        camera.up = vec3(self.camera.up);
+        camera.yaw = self.camera.yaw;
+        camera.pitch = self.camera.pitch;
+
+        engine.show_hud = self.overlays.hud;
+        engine.show_body_labels = self.overlays.body_labels;
+        engine.show_velocity_indicators = self.overlays.velocity_indicators;
+        engine.objective = self.objective.clone();
+        engine.toasts = Toasts::new();
+    }
+}
+
+// Guarda el último frame del engine en `path` (PNG) y su estado al lado (mismo nombre, .json).
+// Con `settings.annotate_screenshots` la imagen lleva además las etiquetas de todos los
+// cuerpos visibles y un recuadro con la hora, la escena, la semilla y la cámara.
+// Devuelve la ruta del JSON.
+pub fn save(engine: &Engine, path: &Path) -> Result<PathBuf, String> {
+    let annotated = engine.settings.annotate_screenshots;
+    let shot = Shot::capture(engine, path, annotated);
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    let mut image = engine.renderer.framebuffer.clone();
+    if annotated {
+        if !engine.show_body_labels {
+            labels::render_labels(&mut image, engine.body_labels());
+        }
+        render_annotation(&mut image, &shot);
+    }
+    image.save_png(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let json_path = path.with_extension("json");
+    let json = serde_json::to_string_pretty(&shot).map_err(|e| e.to_string())?;
+    std::fs::write(&json_path, json).map_err(|e| format!("{}: {}", json_path.display(), e))?;
+    Ok(json_path)
+}
+
+// `screenshots/shot-AAAAMMDD-HHMMSS.png`, con un sufijo si ya hay una captura en ese segundo
+pub fn next_screenshot_path() -> PathBuf {
+    next_capture_path("shot", "png")
+}
+
+// `screenshots/clip-AAAAMMDD-HHMMSS.gif` para los clips de los últimos segundos
+pub fn next_clip_path() -> PathBuf {
+    next_capture_path("clip", "gif")
+}
+
+fn next_capture_path(prefix: &str, extension: &str) -> PathBuf {
+    let stamp: String = utc_timestamp().chars().filter(|c| c.is_ascii_digit() || *c == ' ').collect();
+    let stamp = stamp.trim().replace(' ', "-");
+    let dir = Path::new(SCREENSHOT_DIR);
+    (1..)
+        .map(|n| match n {
+            1 => dir.join(format!("{}-{}.{}", prefix, stamp, extension)),
+            n => dir.join(format!("{}-{}-{}.{}", prefix, stamp, n, extension)),
+        })
+        .find(|path| !path.exists())
+        .expect("some suffix is free")
+}
+
+// Recuadro arriba a la derecha con los datos de la toma
+fn render_annotation(framebuffer: &mut Framebuffer, shot: &Shot) {
+    let scene_name = shot.scene.as_ref().map_or_else(|| SceneSource::SolarSystem.name(), |path| SceneSource::File(path.clone()).name());
+    let seed = shot.seed.map_or_else(|| "random".to_string(), |seed| seed.to_string());
+    let [eye_x, eye_y, eye_z] = shot.camera.eye;
+    let [target_x, target_y, target_z] = shot.camera.target;
+    let lines = [
+        format!("{}  seed {}", scene_name, seed),
+        format!("{}  time x{}", shot.simulation.date, shot.settings.get("time_scale").map_or("1", String::as_str)),
+        format!("Camera {}  fov {}", shot.camera.mode, shot.camera.fov_degrees),
+        format!("Eye {:.2} {:.2} {:.2}", eye_x, eye_y, eye_z),
+        format!("Target {:.2} {:.2} {:.2}", target_x, target_y, target_z),
+    ];
+
+    let title_style = TextStyle::new(TITLE_COLOR).with_align(Align::Right);
+    let style = TextStyle::new(TEXT_COLOR).with_align(Align::Right);
+    let text = lines.join("\n");
+    let (title_width, title_height) = measure_text(&shot.taken_at, &title_style);
+    let (width, height) = measure_text(&text, &style);
+    let panel_width = width.max(title_width) + 2 * MARGIN;
+    let panel_height = title_height + height + 3 * MARGIN;
+    let x = framebuffer.width.saturating_sub(panel_width + MARGIN);
+    framebuffer.blend_rect(x, MARGIN, panel_width, panel_height, PANEL_COLOR, PANEL_ALPHA);
+
+    let right = (framebuffer.width - 2 * MARGIN) as i32;
+    draw_text_styled(framebuffer, right, (2 * MARGIN) as i32, &shot.taken_at, &title_style);
+    draw_text_styled(framebuffer, right, (3 * MARGIN + title_height) as i32, &text, &style);
+}
+
+fn camera_mode(name: &str) -> Option<CameraMode> {
+    CameraMode::ALL.into_iter().find(|mode| mode.name() == name)
+}
+
+fn array(v: Vec3) -> [f32; 3] {
+    [v.x, v.y, v.z]
+}
+
+fn vec3(a: [f32; 3]) -> Vec3 {
+    Vec3::new(a[0], a[1], a[2])
+}
+
+// "2026-10-16 20:05:30 UTC" sin depender de una biblioteca de fechas
+fn utc_timestamp() -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    let (days, rest) = (seconds / 86_400, seconds % 86_400);
+    let (year, month, day) = civil_from_days(days as i64);
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC", year, month, day, rest / 3600, rest % 3600 / 60, rest % 60)
+}
+
+// Días desde 1970-01-01 a fecha del calendario gregoriano (algoritmo de Howard Hinnant)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
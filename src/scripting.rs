@@ -0,0 +1,243 @@
+use nalgebra_glm::{Mat4, Vec3};
+use rhai::{Array, CallFnOptions, Dynamic, Map, Scope, AST, FLOAT};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use log::{debug, info, warn};
+use crate::camera::Camera;
+use crate::collision::rotation_matrix;
+use crate::missions::{Condition, MissionSpec};
+use crate::scene::Scene;
+use crate::storage;
+use crate::vehicle::Vehicle;
+
+// Operaciones máximas por evento: un bucle infinito corta el evento con error en vez de
+// colgar el simulador
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_EXPRESSION_DEPTH: usize = 128;
+
+// Eventos a los que responde un script (Rhai) definiendo la función con ese nombre. Todas
+// reciben primero `world`, una foto de solo lectura de la simulación:
+//
+//   world.time       días simulados          world.focus    cuerpo que sigue la cámara
+//   world.bodies     [#{name, kind, position, velocity, radius, parent}, ...]
+//   world.ship       #{position, velocity, speed, heading, parts}
+//   world.camera     #{eye, target, mode}
+//
+// Lo que el script guarde en `this` (un mapa) se conserva entre eventos.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScriptEvent {
+    Start,                       // on_start(world)
+    Tick(f32),                   // on_tick(world, dt): cada frame sin pausa, dt en segundos
+    Collision(&'static str),     // on_collision(world, body): al empezar un choque
+    WarpComplete(&'static str),  // on_warp_complete(world, target): cuerpo al que llegó la cámara,
+                                 // o el más cercano al terminar un salto del warp drive
+}
+
+impl ScriptEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScriptEvent::Start => "on_start",
+            ScriptEvent::Tick(_) => "on_tick",
+            ScriptEvent::Collision(_) => "on_collision",
+            ScriptEvent::WarpComplete(_) => "on_warp_complete",
+        }
+    }
+
+    // Argumentos después de `world`
+    fn args(&self) -> Vec<Dynamic> {
+        match *self {
+            ScriptEvent::Start => Vec::new(),
+            ScriptEvent::Tick(delta_time) => vec![Dynamic::from_float(delta_time)],
+            ScriptEvent::Collision(body) | ScriptEvent::WarpComplete(body) => vec![body.into()],
+        }
+    }
+}
+
+// Lo que un script le pide al simulador; el engine lo aplica al terminar cada evento
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptCommand {
+    Toast(String),              // toast(text)
+    Warning(String),            // warn(text)
+    Objective(Option<String>),  // set_objective(text); "" la borra
+    WarpTo(String),             // warp_to(name): la cámara viaja al cuerpo con ese nombre
+    TimeScale(f32),             // set_time_scale(x)
+    LogEvent(String),           // log_event(text): entrada en el registro de sucesos
+    AddMission(MissionSpec),    // add_mission(name, objectives [, #{trigger, time_limit, fuel}])
+    ShipPart(String, Mat4),     // set_ship_part(name, offset, rotation): mueve una parte del modelo
+}
+
+// Un script de escenario cargado (`--script` o la clave `script` del archivo de escena)
+pub struct Script {
+    pub path: PathBuf,
+    engine: rhai::Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    state: Dynamic,  // `this` de los eventos
+    commands: Rc<RefCell<Vec<ScriptCommand>>>,
+}
+
+impl Script {
+    // Compila el archivo y ejecuta lo que tenga fuera de funciones (constantes, etc.)
+    pub fn load(path: &Path) -> Result<Script, String> {
+        let source = storage::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let commands = Rc::new(RefCell::new(Vec::new()));
+        let engine = script_engine(&commands);
+        let ast = engine.compile(&source).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        let mut scope = Scope::new();
+        engine.run_ast_with_scope(&mut scope, &ast).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let hooks: Vec<String> = ast
+            .iter_functions()
+            .filter(|function| function.name.starts_with("on_"))
+            .map(|function| function.name.to_string())
+            .collect();
+        info!("Script {} loaded (events: {})", path.display(), hooks.join(", "));
+
+        Ok(Script {
+            path: path.to_path_buf(),
+            engine,
+            ast,
+            scope,
+            state: Dynamic::from_map(Map::new()),
+            commands,
+        })
+    }
+
+    // Llama al evento si el script lo define y devuelve lo que pidió. Un error (de ejecución o
+    // por pasarse de `MAX_OPERATIONS`) viene con la línea del script.
+    pub fn handle(&mut self, event: ScriptEvent, scene: &Scene, camera: &Camera, focus: &str) -> Result<Vec<ScriptCommand>, String> {
+        let args = event.args();
+        let defined = self.ast.iter_functions().any(|function| function.name == event.name() && function.params.len() == args.len() + 1);
+        if !defined {
+            return Ok(Vec::new());
+        }
+
+        let world = Dynamic::from_map(world_map(scene, camera, focus));
+        let options = CallFnOptions::new().eval_ast(false).rewind_scope(true).bind_this_ptr(&mut self.state);
+        let args: Vec<Dynamic> = std::iter::once(world).chain(args).collect();
+        // El valor que devuelva la función no se usa
+        let _: Dynamic = self.engine
+            .call_fn_with_options(options, &mut self.scope, &self.ast, event.name(), args)
+            .map_err(|e| format!("{} in {}: {}", self.path.display(), event.name(), e))?;
+        Ok(self.commands.borrow_mut().drain(..).collect())
+    }
+}
+
+// Motor de Rhai con el tipo `Vec3` y las funciones que encolan comandos
+fn script_engine(commands: &Rc<RefCell<Vec<ScriptCommand>>>) -> rhai::Engine {
+    let mut engine = rhai::Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    // Los límites por defecto cortan interpolaciones dentro de bucles en funciones de evento
+    engine.set_max_expr_depths(MAX_EXPRESSION_DEPTH, MAX_EXPRESSION_DEPTH);
+    engine.on_print(|text| info!("[script] {}", text));
+    engine.on_debug(|text, _, position| debug!("[script] {} {}", position, text));
+
+    engine
+        .register_type_with_name::<Vec3>("Vec3")
+        .register_fn("vec3", |x: FLOAT, y: FLOAT, z: FLOAT| Vec3::new(x, y, z))
+        .register_get("x", |v: &mut Vec3| v.x)
+        .register_get("y", |v: &mut Vec3| v.y)
+        .register_get("z", |v: &mut Vec3| v.z)
+        .register_fn("+", |a: Vec3, b: Vec3| a + b)
+        .register_fn("-", |a: Vec3, b: Vec3| a - b)
+        .register_fn("*", |a: Vec3, scale: FLOAT| a * scale)
+        .register_fn("length", |v: &mut Vec3| v.magnitude())
+        .register_fn("normalize", |v: &mut Vec3| v.try_normalize(1e-6).unwrap_or_else(Vec3::zeros))
+        .register_fn("dot", |a: Vec3, b: Vec3| a.dot(&b))
+        .register_fn("distance", |a: Vec3, b: Vec3| (a - b).magnitude())
+        .register_fn("to_string", |v: &mut Vec3| format!("({:.2}, {:.2}, {:.2})", v.x, v.y, v.z))
+        .register_fn("to_debug", |v: &mut Vec3| format!("({:.2}, {:.2}, {:.2})", v.x, v.y, v.z));
+
+    let queue = commands.clone();
+    engine.register_fn("toast", move |text: &str| queue.borrow_mut().push(ScriptCommand::Toast(text.to_string())));
+    let queue = commands.clone();
+    engine.register_fn("warn", move |text: &str| queue.borrow_mut().push(ScriptCommand::Warning(text.to_string())));
+    let queue = commands.clone();
+    engine.register_fn("set_objective", move |text: &str| {
+        let objective = (!text.is_empty()).then(|| text.to_string());
+        queue.borrow_mut().push(ScriptCommand::Objective(objective));
+    });
+    let queue = commands.clone();
+    engine.register_fn("warp_to", move |name: &str| queue.borrow_mut().push(ScriptCommand::WarpTo(name.to_string())));
+    let queue = commands.clone();
+    engine.register_fn("set_time_scale", move |scale: FLOAT| queue.borrow_mut().push(ScriptCommand::TimeScale(scale)));
+    let queue = commands.clone();
+    engine.register_fn("log_event", move |text: &str| queue.borrow_mut().push(ScriptCommand::LogEvent(text.to_string())));
+    let queue = commands.clone();
+    engine.register_fn("add_mission", move |name: &str, objectives: Array| {
+        queue.borrow_mut().push(ScriptCommand::AddMission(mission_spec(name, objectives, Map::new())));
+    });
+    let queue = commands.clone();
+    engine.register_fn("add_mission", move |name: &str, objectives: Array, options: Map| {
+        queue.borrow_mut().push(ScriptCommand::AddMission(mission_spec(name, objectives, options)));
+    });
+    // Desplazamiento en unidades del modelo de la nave y rotación en radianes (como la de la nave);
+    // ceros la devuelven a su lugar
+    let queue = commands.clone();
+    engine.register_fn("set_ship_part", move |name: &str, offset: Vec3, rotation: Vec3| {
+        let transform = nalgebra_glm::translation(&offset) * rotation_matrix(rotation).to_homogeneous();
+        queue.borrow_mut().push(ScriptCommand::ShipPart(name.to_string(), transform));
+    });
+    engine
+}
+
+// Los objetivos son textos como en la escena (`"orbit Zeus"`); los que no se entienden se avisan
+// y se ignoran
+fn mission_spec(name: &str, objectives: Array, options: Map) -> MissionSpec {
+    let condition = |value: &Dynamic| {
+        let text = value.to_string();
+        let condition = Condition::parse(&text);
+        if condition.is_none() {
+            warn!("Mission `{}`: ignoring invalid condition `{}`", name, text);
+        }
+        condition
+    };
+    let number = |key: &str| options.get(key).and_then(|value| value.as_float().ok().or_else(|| value.as_int().ok().map(|n| n as FLOAT)));
+    MissionSpec {
+        name: name.to_string(),
+        objectives: objectives.iter().filter_map(condition).collect(),
+        trigger: options.get("trigger").and_then(condition),
+        time_limit: number("time_limit"),
+        fuel: number("fuel"),
+    }
+}
+
+fn world_map(scene: &Scene, camera: &Camera, focus: &str) -> Map {
+    let bodies: Array = scene.bodies
+        .iter()
+        .map(|body| {
+            let parent = body.parent.and_then(|index| scene.bodies.get(index)).map_or("", |parent| parent.name);
+            let mut map = Map::new();
+            map.insert("name".into(), body.name.into());
+            map.insert("kind".into(), body.shader_type.display_name().into());
+            map.insert("position".into(), Dynamic::from(body.position));
+            map.insert("velocity".into(), Dynamic::from(body.velocity));
+            map.insert("radius".into(), Dynamic::from_float(body.scale));
+            map.insert("parent".into(), parent.into());
+            Dynamic::from_map(map)
+        })
+        .collect();
+
+    let spaceship = &scene.spaceship;
+    let mut ship = Map::new();
+    ship.insert("position".into(), Dynamic::from(spaceship.position()));
+    ship.insert("velocity".into(), Dynamic::from(spaceship.velocity()));
+    ship.insert("speed".into(), Dynamic::from_float(spaceship.velocity().magnitude()));
+    ship.insert("heading".into(), Dynamic::from(spaceship.forward()));
+    let parts: Array = spaceship.parts().iter().map(|part| part.name.clone().into()).collect();
+    ship.insert("parts".into(), Dynamic::from_array(parts));
+
+    let mut view = Map::new();
+    view.insert("eye".into(), Dynamic::from(camera.eye));
+    view.insert("target".into(), Dynamic::from(camera.center));
+    view.insert("mode".into(), camera.mode.name().into());
+
+    let mut world = Map::new();
+    world.insert("time".into(), Dynamic::from_float(scene.clock.days() as FLOAT));
+    world.insert("focus".into(), focus.into());
+    world.insert("bodies".into(), Dynamic::from_array(bodies));
+    world.insert("ship".into(), Dynamic::from_map(ship));
+    world.insert("camera".into(), Dynamic::from_map(view));
+    world
+}
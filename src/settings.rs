@@ -0,0 +1,460 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+use log::{info, warn};
+use crate::color_grading::Grading;
+use crate::i18n::Language;
+use crate::palette::{ColorVision, Palette};
+use crate::sphere::SphereTessellation;
+use crate::storage;
+
+pub const SETTINGS_FILE: &str = "settings.toml";
+// Formato de versiones anteriores (`nombre = valor`): si no hay settings.toml se importa una vez
+pub const LEGACY_SETTINGS_FILE: &str = "settings.cfg";
+
+// Tamaño de ventana permitido (los mismos topes que `--width` / `--height`)
+pub const MIN_WINDOW_SIZE: usize = 64;
+pub const MAX_WINDOW_WIDTH: usize = 7680;
+pub const MAX_WINDOW_HEIGHT: usize = 4320;
+
+// Límites de FPS que ofrece el menú (0 = sin límite) y el máximo que se acepta del archivo
+const FRAME_LIMITS: [u32; 5] = [30, 60, 120, 144, 0];
+const MAX_TARGET_FPS: u32 = 1000;
+
+// Límites de la velocidad de simulación (se duplica o divide a la mitad en cada paso)
+pub const MIN_TIME_SCALE: f32 = 0.125;
+pub const MAX_TIME_SCALE: f32 = 64.0;
+
+// Paso del volumen general en el menú
+const VOLUME_STEP: f32 = 0.1;
+
+// Ajustes de imagen (exposición en EV, brillo como desplazamiento, contraste como factor):
+// paso de cada tecla o del menú y límites
+const EXPOSURE_STEP: f32 = 0.5;
+const MIN_EXPOSURE: f32 = -2.0;
+const MAX_EXPOSURE: f32 = 3.0;
+const BRIGHTNESS_STEP: f32 = 0.05;
+const MAX_BRIGHTNESS: f32 = 0.5;
+const CONTRAST_STEP: f32 = 0.1;
+const MIN_CONTRAST: f32 = 0.5;
+const MAX_CONTRAST: f32 = 2.0;
+// Exposición de la imagen de cielo (EV), aparte de la de la escena
+const MIN_SKY_EXPOSURE: f32 = -4.0;
+const MAX_SKY_EXPOSURE: f32 = 2.0;
+
+// Resolución interna del framebuffer como fracción de la ventana; la ventana la estira al presentar
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Resolution {
+    #[serde(rename = "50%")]
+    Half,
+    #[serde(rename = "75%")]
+    ThreeQuarters,
+    #[serde(rename = "100%")]
+    Full,
+}
+
+impl Resolution {
+    pub const ALL: [Resolution; 3] = [Resolution::Half, Resolution::ThreeQuarters, Resolution::Full];
+
+    pub fn scale(self) -> f32 {
+        match self {
+            Resolution::Half => 0.5,
+            Resolution::ThreeQuarters => 0.75,
+            Resolution::Full => 1.0,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Resolution::Half => "50%",
+            Resolution::ThreeQuarters => "75%",
+            Resolution::Full => "100%",
+        }
+    }
+
+    // Tamaño del framebuffer para una ventana dada
+    pub fn framebuffer_size(self, window_width: usize, window_height: usize) -> (usize, usize) {
+        let scale = self.scale();
+        ((window_width as f32 * scale) as usize, (window_height as f32 * scale) as usize)
+    }
+}
+
+// Calidad de las esferas: escala el radio en pantalla con el que se elige el nivel de detalle
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LodQuality {
+    Low,
+    Medium,
+    High,
+}
+
+impl LodQuality {
+    const ALL: [LodQuality; 3] = [LodQuality::Low, LodQuality::Medium, LodQuality::High];
+
+    pub fn bias(self) -> f32 {
+        match self {
+            LodQuality::Low => 0.5,
+            LodQuality::Medium => 1.0,
+            LodQuality::High => 2.0,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LodQuality::Low => "Low",
+            LodQuality::Medium => "Medium",
+            LodQuality::High => "High",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AntiAliasing {
+    Off,
+    #[serde(rename = "FXAA")]
+    Fxaa,  // Suavizado de bordes en post-proceso sobre la escena 3D (antes del HUD)
+}
+
+impl AntiAliasing {
+    const ALL: [AntiAliasing; 2] = [AntiAliasing::Off, AntiAliasing::Fxaa];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AntiAliasing::Off => "Off",
+            AntiAliasing::Fxaa => "FXAA",
+        }
+    }
+}
+
+// Orden en que Tab recorre los cuerpos: el del archivo de escena o del más cercano a la nave al
+// más lejano
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FocusOrder {
+    Scene,
+    Distance,
+}
+
+impl FocusOrder {
+    const ALL: [FocusOrder; 2] = [FocusOrder::Scene, FocusOrder::Distance];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FocusOrder::Scene => "Scene",
+            FocusOrder::Distance => "Distance",
+        }
+    }
+}
+
+// Avanza `steps` posiciones (negativo = hacia atrás) dentro de `all`, dando la vuelta
+fn cycle<T: Copy + PartialEq>(all: &[T], current: T, steps: i32) -> T {
+    let index = all.iter().position(|&value| value == current).unwrap_or(0) as i32;
+    all[(index + steps).rem_euclid(all.len() as i32) as usize]
+}
+
+// Un paso de `step` hacia arriba o abajo, redondeado al paso para que no se acumule error
+fn step_value(value: f32, steps: i32, step: f32, min: f32, max: f32) -> f32 {
+    let value = ((value / step).round() + steps as f32) * step;
+    value.clamp(min, max)
+}
+
+fn parse_named<T: Copy>(all: &[T], name: impl Fn(&T) -> &'static str, value: &str) -> Option<T> {
+    all.iter().copied().find(|option| name(option).eq_ignore_ascii_case(value))
+}
+
+// Sensibilidad de los controles de cámara y nave (solo en settings.toml, sección `[controls]`)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Controls {
+    pub camera_orbit_degrees: f32,  // Giro de la cámara orbital por frame a 60 Hz con las flechas
+    pub camera_zoom_step: f32,      // Zoom por frame a 60 Hz (W/S) o por paso de la rueda
+    pub camera_move_step: f32,      // Subida/bajada de la cámara por frame a 60 Hz (Q/E)
+    pub ship_turn_rate: f32,        // Giro de la nave (radianes por segundo)
+    pub ship_thrust: f32,           // Aceleración de la nave (unidades por segundo²)
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Controls {
+            camera_orbit_degrees: 3.6,
+            camera_zoom_step: 0.3,  // Reducido para zoom más suave
+            camera_move_step: 0.2,  // Reducido para movimiento más suave
+            ship_turn_rate: 2.0,
+            ship_thrust: 5.0,
+        }
+    }
+}
+
+// Configuración de arranque y ajustes del menú, guardados entre sesiones en settings.toml. Lo
+// que falte en el archivo queda con el valor por defecto.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub window_width: usize,   // `--width` / `--height` mandan sobre estos
+    pub window_height: usize,
+    pub target_fps: u32,       // Límite de FPS sin vsync (0 = sin límite)
+    pub resolution: Resolution,
+    pub lod_quality: LodQuality,
+    pub sphere_tessellation: SphereTessellation,  // Triángulos de las esferas (se regeneran al cambiarlo)
+    pub anti_aliasing: AntiAliasing,
+    pub temporal_upscaling: bool,  // Rasterizar un pixel de cada 2x2 por frame y reconstruir el resto (temporal.rs)
+    pub show_orbits: bool,
+    pub time_scale: f32,
+    pub master_volume: f32,  // 0.0..=1.0
+    pub annotate_screenshots: bool,  // Etiquetas y datos de la toma sobre las capturas
+    pub pipelined_rendering: bool,   // Sombrear cada frame en otro hilo mientras se prepara el siguiente
+    pub language: Language,          // Idioma de la interfaz (`--lang` manda sobre este)
+    pub power_saving: bool,          // Dejar de dibujar con la simulación en pausa hasta que llegue entrada
+    pub exposure: f32,               // Ajustes de imagen de la escena 3D (ver color_grading.rs)
+    pub auto_exposure: bool,         // Adaptar la exposición al brillo de la escena, como el ojo
+    pub brightness: f32,
+    pub contrast: f32,
+    pub sky_exposure: f32,           // Solo la imagen de cielo de la escena (sky.rs), en EV
+    pub color_vision: ColorVision,   // Colores de órbitas y acentos del HUD para daltonismo
+    pub ship_gravity: bool,          // Los cuerpos atraen a la nave y se dibuja su trayectoria futura
+    pub adaptive_quality: bool,      // Bajar detalle, cielo y chispas cuando los frames no entran en el presupuesto
+    pub solar_wind: bool,            // Partículas del viento solar desviadas por las magnetosferas
+    pub fixed_tick: bool,            // Simular a 30 Hz e interpolar al dibujar (ver interpolation.rs)
+    pub focus_order: FocusOrder,     // Orden de los cuerpos al cambiar el foco con Tab
+    pub controls: Controls,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            window_width: 800,
+            window_height: 600,
+            target_fps: 60,
+            resolution: Resolution::ThreeQuarters,
+            lod_quality: LodQuality::Medium,
+            sphere_tessellation: SphereTessellation::Medium,
+            anti_aliasing: AntiAliasing::Off,
+            temporal_upscaling: false,
+            show_orbits: true, // Habilitadas por defecto para mejor visualización
+            time_scale: 1.0,
+            master_volume: 0.8,
+            annotate_screenshots: false,
+            pipelined_rendering: false,
+            language: Language::English,
+            power_saving: true,
+            exposure: 0.0,
+            auto_exposure: false,
+            brightness: 0.0,
+            contrast: 1.0,
+            sky_exposure: 0.0,
+            color_vision: ColorVision::Normal,
+            ship_gravity: false,
+            fixed_tick: false,
+            solar_wind: false,
+            adaptive_quality: false,
+            focus_order: FocusOrder::Scene,
+            controls: Controls::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn cycle_resolution(&mut self, steps: i32) {
+        self.resolution = cycle(&Resolution::ALL, self.resolution, steps);
+    }
+
+    pub fn cycle_lod_quality(&mut self, steps: i32) {
+        self.lod_quality = cycle(&LodQuality::ALL, self.lod_quality, steps);
+    }
+
+    pub fn cycle_sphere_tessellation(&mut self, steps: i32) {
+        self.sphere_tessellation = cycle(&SphereTessellation::ALL, self.sphere_tessellation, steps);
+    }
+
+    pub fn cycle_anti_aliasing(&mut self, steps: i32) {
+        self.anti_aliasing = cycle(&AntiAliasing::ALL, self.anti_aliasing, steps);
+    }
+
+    pub fn cycle_language(&mut self, steps: i32) {
+        self.language = cycle(&Language::ALL, self.language, steps);
+    }
+
+    pub fn cycle_color_vision(&mut self, steps: i32) {
+        self.color_vision = cycle(&ColorVision::ALL, self.color_vision, steps);
+    }
+
+    pub fn cycle_focus_order(&mut self, steps: i32) {
+        self.focus_order = cycle(&FocusOrder::ALL, self.focus_order, steps);
+    }
+
+    pub fn palette(&self) -> &'static Palette {
+        self.color_vision.palette()
+    }
+
+    pub fn cycle_frame_limit(&mut self, steps: i32) {
+        self.target_fps = cycle(&FRAME_LIMITS, self.target_fps, steps);
+    }
+
+    // Tiempo mínimo de un frame según `target_fps` (None = sin límite)
+    pub fn frame_budget(&self) -> Option<Duration> {
+        (self.target_fps > 0).then(|| Duration::from_secs_f64(1.0 / self.target_fps as f64))
+    }
+
+    pub fn change_time_scale(&mut self, steps: i32) {
+        self.time_scale = (self.time_scale * 2.0_f32.powi(steps)).clamp(MIN_TIME_SCALE, MAX_TIME_SCALE);
+    }
+
+    // Redondeado a décimas para que los pasos no acumulen error
+    pub fn change_volume(&mut self, steps: i32) {
+        let volume = (self.master_volume + steps as f32 * VOLUME_STEP).clamp(0.0, 1.0);
+        self.master_volume = (volume * 10.0).round() / 10.0;
+    }
+
+    pub fn change_exposure(&mut self, steps: i32) {
+        self.exposure = step_value(self.exposure, steps, EXPOSURE_STEP, MIN_EXPOSURE, MAX_EXPOSURE);
+    }
+
+    pub fn change_brightness(&mut self, steps: i32) {
+        self.brightness = step_value(self.brightness, steps, BRIGHTNESS_STEP, -MAX_BRIGHTNESS, MAX_BRIGHTNESS);
+    }
+
+    pub fn change_contrast(&mut self, steps: i32) {
+        self.contrast = step_value(self.contrast, steps, CONTRAST_STEP, MIN_CONTRAST, MAX_CONTRAST);
+    }
+
+    pub fn change_sky_exposure(&mut self, steps: i32) {
+        self.sky_exposure = step_value(self.sky_exposure, steps, EXPOSURE_STEP, MIN_SKY_EXPOSURE, MAX_SKY_EXPOSURE);
+    }
+
+    pub fn reset_grading(&mut self) {
+        let defaults = Settings::default();
+        (self.exposure, self.brightness, self.contrast) = (defaults.exposure, defaults.brightness, defaults.contrast);
+    }
+
+    pub fn grading(&self) -> Grading {
+        Grading { exposure: self.exposure, brightness: self.brightness, contrast: self.contrast }
+    }
+
+    // Un archivo ilegible o con valores inválidos se avisa y se usan los valores por defecto.
+    // Sin settings.toml se importa el settings.cfg de al lado, si lo hay.
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        let contents = match storage::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(_) => return Settings::import_legacy(path),
+        };
+        match toml::from_str::<Settings>(&contents) {
+            Ok(settings) => {
+                info!("Settings loaded from {}", path.display());
+                settings.clamped()
+            }
+            Err(e) => {
+                warn!("{}: {} (using the default settings)", path.display(), e);
+                Settings::default()
+            }
+        }
+    }
+
+    // settings.cfg (mismo formato que keybindings.cfg: `nombre = valor`, `#` comenta) pasado a
+    // settings.toml; lo que no se entienda queda con el valor por defecto
+    fn import_legacy(path: &Path) -> Self {
+        let mut settings = Settings::default();
+        let legacy_path = path.with_file_name(LEGACY_SETTINGS_FILE);
+        let Ok(contents) = storage::read_to_string(&legacy_path) else {
+            return settings;
+        };
+
+        for (line_number, raw_line) in contents.lines().enumerate() {
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let applied = line.split_once('=').and_then(|(name, value)| settings.apply(name.trim(), value.trim()));
+
+            if applied.is_none() {
+                warn!("{}:{}: ignoring invalid setting `{}`", legacy_path.display(), line_number + 1, line);
+            }
+        }
+
+        match settings.save(path) {
+            Ok(()) => info!("Settings imported from {} into {}", legacy_path.display(), path.display()),
+            Err(e) => warn!("Could not write {}: {}", path.display(), e),
+        }
+        settings
+    }
+
+    // Valores fuera de rango del archivo llevados al más cercano permitido
+    fn clamped(mut self) -> Self {
+        self.window_width = self.window_width.clamp(MIN_WINDOW_SIZE, MAX_WINDOW_WIDTH);
+        self.window_height = self.window_height.clamp(MIN_WINDOW_SIZE, MAX_WINDOW_HEIGHT);
+        self.target_fps = self.target_fps.min(MAX_TARGET_FPS);
+        self.time_scale = self.time_scale.clamp(MIN_TIME_SCALE, MAX_TIME_SCALE);
+        self.master_volume = self.master_volume.clamp(0.0, 1.0);
+        self.exposure = self.exposure.clamp(MIN_EXPOSURE, MAX_EXPOSURE);
+        self.brightness = self.brightness.clamp(-MAX_BRIGHTNESS, MAX_BRIGHTNESS);
+        self.contrast = self.contrast.clamp(MIN_CONTRAST, MAX_CONTRAST);
+        self.sky_exposure = self.sky_exposure.clamp(MIN_SKY_EXPOSURE, MAX_SKY_EXPOSURE);
+        self
+    }
+
+    // Un ajuste de imagen o simulación por nombre, con el valor como texto (el settings.cfg
+    // antiguo y los ajustes que se guardan con las capturas); None si no se entiende
+    pub fn apply(&mut self, name: &str, value: &str) -> Option<()> {
+        match name {
+            "resolution" => self.resolution = parse_named(&Resolution::ALL, Resolution::name, value)?,
+            "lod_quality" => self.lod_quality = parse_named(&LodQuality::ALL, LodQuality::name, value)?,
+            "sphere_tessellation" => self.sphere_tessellation = parse_named(&SphereTessellation::ALL, SphereTessellation::name, value)?,
+            "anti_aliasing" => self.anti_aliasing = parse_named(&AntiAliasing::ALL, AntiAliasing::name, value)?,
+            "temporal_upscaling" => self.temporal_upscaling = value.parse().ok()?,
+            "show_orbits" => self.show_orbits = value.parse().ok()?,
+            "time_scale" => {
+                let scale: f32 = value.parse().ok()?;
+                self.time_scale = scale.clamp(MIN_TIME_SCALE, MAX_TIME_SCALE);
+            }
+            "master_volume" => {
+                let volume: f32 = value.parse().ok()?;
+                self.master_volume = volume.clamp(0.0, 1.0);
+            }
+            "annotate_screenshots" => self.annotate_screenshots = value.parse().ok()?,
+            "pipelined_rendering" => self.pipelined_rendering = value.parse().ok()?,
+            "exposure" => self.exposure = value.parse::<f32>().ok()?.clamp(MIN_EXPOSURE, MAX_EXPOSURE),
+            "auto_exposure" => self.auto_exposure = value.parse().ok()?,
+            "brightness" => self.brightness = value.parse::<f32>().ok()?.clamp(-MAX_BRIGHTNESS, MAX_BRIGHTNESS),
+            "contrast" => self.contrast = value.parse::<f32>().ok()?.clamp(MIN_CONTRAST, MAX_CONTRAST),
+            "sky_exposure" => self.sky_exposure = value.parse::<f32>().ok()?.clamp(MIN_SKY_EXPOSURE, MAX_SKY_EXPOSURE),
+            "color_vision" => self.color_vision = parse_named(&ColorVision::ALL, ColorVision::name, value)?,
+            "ship_gravity" => self.ship_gravity = value.parse().ok()?,
+            "fixed_tick" => self.fixed_tick = value.parse().ok()?,
+            "solar_wind" => self.solar_wind = value.parse().ok()?,
+            "adaptive_quality" => self.adaptive_quality = value.parse().ok()?,
+            "focus_order" => self.focus_order = parse_named(&FocusOrder::ALL, FocusOrder::name, value)?,
+            _ => return None,
+        }
+        Some(())
+    }
+
+    // Los ajustes que acepta `apply`, con su nombre y valor como texto
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("resolution", self.resolution.name().to_string()),
+            ("lod_quality", self.lod_quality.name().to_string()),
+            ("sphere_tessellation", self.sphere_tessellation.name().to_string()),
+            ("anti_aliasing", self.anti_aliasing.name().to_string()),
+            ("temporal_upscaling", self.temporal_upscaling.to_string()),
+            ("show_orbits", self.show_orbits.to_string()),
+            ("time_scale", self.time_scale.to_string()),
+            ("master_volume", self.master_volume.to_string()),
+            ("annotate_screenshots", self.annotate_screenshots.to_string()),
+            ("pipelined_rendering", self.pipelined_rendering.to_string()),
+            ("exposure", self.exposure.to_string()),
+            ("auto_exposure", self.auto_exposure.to_string()),
+            ("brightness", self.brightness.to_string()),
+            ("contrast", self.contrast.to_string()),
+            ("sky_exposure", self.sky_exposure.to_string()),
+            ("color_vision", self.color_vision.name().to_string()),
+            ("ship_gravity", self.ship_gravity.to_string()),
+            ("fixed_tick", self.fixed_tick.to_string()),
+            ("solar_wind", self.solar_wind.to_string()),
+            ("adaptive_quality", self.adaptive_quality.to_string()),
+            ("focus_order", self.focus_order.name().to_string()),
+        ]
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let contents = toml::to_string(self).map_err(std::io::Error::other)?;
+        storage::write(path.as_ref(), &format!("# SpaceTravel settings (rewritten when changed in the pause menu)\n{}", contents))
+    }
+}
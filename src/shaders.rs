@@ -2,40 +2,127 @@ use crate::fragment::Fragment;
 use crate::vertex::Vertex;
 use crate::color::Color;
 use crate::celestial_body::ShaderType;
+use crate::ship_material::ShipMaterial;
+use crate::obj_loader::MtlMaterial;
+use crate::renderable::RenderFlags;
+use crate::rings::{self, RingBand};
+use crate::texture::Texture;
+use crate::camera::ScreenProjection;
+use std::sync::Arc;
 use fastnoise_lite::{FastNoiseLite, NoiseType};
 
-// Estructura de Uniforms actualizada
-pub struct Uniforms {
-   pub model_matrix: nalgebra_glm::Mat4,
-   pub view_matrix: nalgebra_glm::Mat4,
-   pub projection_matrix: nalgebra_glm::Mat4,
+// Dirección (en el mundo) desde la que llega la luz del sol: la misma para los planetas, la
+// nave y las órbitas
+pub const SUN_DIRECTION: nalgebra_glm::Vec3 = nalgebra_glm::Vec3::new(0.0, 0.0, 1.0);
+
+// Fracción iluminada (0.0..=1.0) que se ve desde `eye` de una esfera ubicada en `point`: 1.0 con
+// el sol a la espalda de la cámara, 0.0 con el sol detrás de la esfera
+pub fn sun_phase(point: nalgebra_glm::Vec3, eye: nalgebra_glm::Vec3) -> f32 {
+   let toward_eye = eye - point;
+   if toward_eye.magnitude() < f32::EPSILON {
+      return 1.0;
+   }
+   0.5 + 0.5 * toward_eye.normalize().dot(&SUN_DIRECTION)
+}
+
+// Lo que es igual para todos los dibujos de un frame: se arma una vez por frame y cada
+// `Uniforms` lo comparte (en un Arc, porque los lotes pasan al hilo del pipeline)
+pub struct FrameUniforms {
+   pub view_projection: nalgebra_glm::Mat4,  // projection * view
    pub viewport_matrix: nalgebra_glm::Mat4,
-   pub time: f32,
    pub noise: FastNoiseLite,
 }
 
-impl Uniforms {
-   pub fn new(
-      model_matrix: nalgebra_glm::Mat4,
-      view_matrix: nalgebra_glm::Mat4,
-      projection_matrix: nalgebra_glm::Mat4,
-      viewport_matrix: nalgebra_glm::Mat4,
-      time: f32,
-   ) -> Self {
+impl FrameUniforms {
+   pub fn new(projection: &ScreenProjection) -> Self {
       let mut noise = FastNoiseLite::new();
       noise.set_noise_type(Some(NoiseType::OpenSimplex2));
-      
+
+      FrameUniforms {
+         view_projection: projection.projection_matrix * projection.view_matrix,
+         viewport_matrix: projection.viewport_matrix,
+         noise,
+      }
+   }
+}
+
+// Uniforms de un dibujo: los del frame más la matriz de modelo y lo que sale de ella
+pub struct Uniforms {
+   pub frame: Arc<FrameUniforms>,
+   pub model_matrix: nalgebra_glm::Mat4,
+   pub clip_matrix: nalgebra_glm::Mat4,    // projection * view * model, una vez por dibujo y no por vértice
+   pub normal_matrix: nalgebra_glm::Mat3,  // Inversa transpuesta de la parte 3x3 de `model_matrix`
+   pub time: f32,
+   pub ship_material: Option<ShipMaterial>,
+   pub surface_material: Option<MtlMaterial>,
+   pub texture: Option<Arc<Texture>>,
+   pub receive_light: bool,  // false = `fragment.intensity` se toma como 1 (sin lado oscuro)
+   pub always_on_top: bool,  // Los fragmentos pasan delante de todo lo dibujado sin esta marca
+   pub local_sun: nalgebra_glm::Vec3,  // Hacia el sol en el espacio del modelo (como lo ve la iluminación)
+   pub shadow_rings: Vec<RingBand>,    // Anillos del cuerpo: sombra sobre él y la suya sobre ellos
+}
+
+impl Uniforms {
+   pub fn new(frame: Arc<FrameUniforms>, model_matrix: nalgebra_glm::Mat4, time: f32) -> Self {
+      let model_mat3 = nalgebra_glm::Mat3::new(
+         model_matrix[0], model_matrix[1], model_matrix[2],
+         model_matrix[4], model_matrix[5], model_matrix[6],
+         model_matrix[8], model_matrix[9], model_matrix[10]
+      );
+
+      // Un punto del modelo con normal n está iluminado si dot(normal_matrix * n, SUN_DIRECTION) > 0,
+      // o sea dot(n, normal_matrix^T * SUN_DIRECTION) > 0: esa es la dirección del sol en el modelo
+      let normal_matrix = model_mat3.transpose().try_inverse().unwrap_or(nalgebra_glm::Mat3::identity());
+      let local_sun = (normal_matrix.transpose() * SUN_DIRECTION).try_normalize(f32::EPSILON).unwrap_or(SUN_DIRECTION);
+
       Uniforms {
+         clip_matrix: frame.view_projection * model_matrix,
+         normal_matrix,
+         frame,
          model_matrix,
-         view_matrix,
-         projection_matrix,
-         viewport_matrix,
          time,
-         noise,
+         ship_material: None,
+         surface_material: None,
+         texture: None,
+         receive_light: true,
+         always_on_top: false,
+         local_sun,
+         shadow_rings: Vec::new(),
       }
    }
+
+   pub fn with_ship_material(mut self, material: ShipMaterial) -> Self {
+      self.ship_material = Some(material);
+      self
+   }
+
+   // Material del .mtl para la superficie que se está dibujando (None = paleta procedural)
+   pub fn with_surface_material(mut self, material: Option<MtlMaterial>) -> Self {
+      self.surface_material = material;
+      self
+   }
+
+   pub fn with_texture(mut self, texture: Option<Arc<Texture>>) -> Self {
+      self.texture = texture;
+      self
+   }
+
+   pub fn with_shadow_rings(mut self, bands: &[RingBand]) -> Self {
+      self.shadow_rings = bands.to_vec();
+      self
+   }
+
+   pub fn with_render_flags(mut self, flags: RenderFlags) -> Self {
+      self.receive_light = flags.receive_light;
+      self.always_on_top = flags.always_on_top;
+      self
+   }
 }
 
+// Por debajo de este |w| el vértice está prácticamente en el plano de la cámara: dividir daría
+// coordenadas enormes o infinitas que cruzan la pantalla como rayas
+const MIN_CLIP_W: f32 = 1e-6;
+
 // Vertex shader
 pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
    let position = nalgebra_glm::Vec4::new(
@@ -45,12 +132,13 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
       1.0
    );
 
-   let transformed = uniforms.projection_matrix 
-      * uniforms.view_matrix 
-      * uniforms.model_matrix 
-      * position;
+   let transformed = uniforms.clip_matrix * position;
 
+   // Sin división: queda en NaN y el rasterizador descarta el triángulo (y lo cuenta)
    let w = transformed.w;
+   if w.abs() < MIN_CLIP_W {
+      return Vertex { transformed_position: nalgebra_glm::Vec3::repeat(f32::NAN), ..vertex.clone() };
+   }
    let ndc_position = nalgebra_glm::Vec4::new(
       transformed.x / w,
       transformed.y / w,
@@ -58,16 +146,9 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
       1.0
    );
 
-   let screen_position = uniforms.viewport_matrix * ndc_position;
+   let screen_position = uniforms.frame.viewport_matrix * ndc_position;
 
-   let model_mat3 = nalgebra_glm::Mat3::new(
-      uniforms.model_matrix[0], uniforms.model_matrix[1], uniforms.model_matrix[2],
-      uniforms.model_matrix[4], uniforms.model_matrix[5], uniforms.model_matrix[6],
-      uniforms.model_matrix[8], uniforms.model_matrix[9], uniforms.model_matrix[10]
-   );
-   
-   let normal_matrix = model_mat3.transpose().try_inverse().unwrap_or(nalgebra_glm::Mat3::identity());
-   let transformed_normal = normal_matrix * vertex.normal;
+   let transformed_normal = uniforms.normal_matrix * vertex.normal;
 
    Vertex {
       position: vertex.position,
@@ -80,11 +161,25 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
          screen_position.z
       ),
       transformed_normal,
+      tangent: vertex.tangent,
+      bitangent: vertex.bitangent,
    }
 }
 
 // Fragment shader dispatcher
 pub fn fragment_shader(fragment: &Fragment, uniforms: &Uniforms, shader_type: &ShaderType) -> Color {
+   let adjusted;
+   let fragment = if !uniforms.receive_light {
+      adjusted = Fragment { intensity: 1.0, ..*fragment };
+      &adjusted
+   } else if !uniforms.shadow_rings.is_empty() {
+      // La banda de sombra de los anillos cruza la cara iluminada
+      let shadow = rings::ring_shadow(&uniforms.shadow_rings, fragment.vertex_position, uniforms.local_sun);
+      adjusted = Fragment { intensity: fragment.intensity * (1.0 - shadow), ..*fragment };
+      &adjusted
+   } else {
+      fragment
+   };
    match shader_type {
       ShaderType::Sun => sun_shader(fragment, uniforms),
       ShaderType::RockyPlanet => rocky_planet_shader(fragment, uniforms),
@@ -165,7 +260,7 @@ fn sun_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    // Capa 2: Plasma animado usando noise con más intensidad
    let plasma_zoom = 8.0;
    let plasma_speed = 0.3;
-   let plasma_noise = uniforms.noise.get_noise_3d(
+   let plasma_noise = uniforms.frame.noise.get_noise_3d(
       position.x * plasma_zoom + time * plasma_speed,
       position.y * plasma_zoom,
       position.z * plasma_zoom + time * plasma_speed * 0.5,
@@ -177,7 +272,7 @@ fn sun_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    
    // Capa 3: Manchas solares (áreas más oscuras)
    let spot_zoom = 3.0;
-   let spot_noise = uniforms.noise.get_noise_3d(
+   let spot_noise = uniforms.frame.noise.get_noise_3d(
       position.x * spot_zoom,
       position.y * spot_zoom + time * 0.1,
       position.z * spot_zoom,
@@ -207,7 +302,7 @@ fn rocky_planet_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    
    // Capa 1: Terreno marciano base con más contraste
    let terrain_zoom = 4.0;
-   let terrain_noise = uniforms.noise.get_noise_3d(
+   let terrain_noise = uniforms.frame.noise.get_noise_3d(
       position.x * terrain_zoom,
       position.y * terrain_zoom,
       position.z * terrain_zoom,
@@ -228,7 +323,7 @@ fn rocky_planet_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    
    // Capa 2: Detalles de superficie con más contraste
    let detail_zoom = 8.0;
-   let detail_noise = uniforms.noise.get_noise_3d(
+   let detail_noise = uniforms.frame.noise.get_noise_3d(
       position.x * detail_zoom + 100.0,
       position.y * detail_zoom,
       position.z * detail_zoom,
@@ -244,7 +339,7 @@ fn rocky_planet_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    // Capa 3: Tormentas de polvo más visibles
    let dust_zoom = 6.0;
    let dust_speed = 0.1;
-   let dust_noise = uniforms.noise.get_noise_3d(
+   let dust_noise = uniforms.frame.noise.get_noise_3d(
       position.x * dust_zoom + time * dust_speed,
       position.y * dust_zoom,
       position.z * dust_zoom + time * dust_speed * 0.3,
@@ -292,7 +387,7 @@ fn gas_giant_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    
    // Turbulencias más pronunciadas
    let turbulence_zoom = 6.0;
-   let turbulence_noise = uniforms.noise.get_noise_3d(
+   let turbulence_noise = uniforms.frame.noise.get_noise_3d(
       position.x * turbulence_zoom + time * 0.2,
       position.y * turbulence_zoom * 0.5,
       position.z * turbulence_zoom,
@@ -316,7 +411,7 @@ fn gas_giant_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
                            (position.y - spot_center_y).powi(2)).sqrt();
    
    if distance_to_spot < 0.25 {
-      let spot_noise = uniforms.noise.get_noise_3d(
+      let spot_noise = uniforms.frame.noise.get_noise_3d(
          position.x * 4.0 + time * 0.05,
          position.y * 4.0,
          position.z * 4.0,
@@ -345,7 +440,7 @@ fn moon_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    
    // Capa 2: Variaciones de terreno
    let terrain_zoom = 8.0;
-   let terrain_noise = uniforms.noise.get_noise_3d(
+   let terrain_noise = uniforms.frame.noise.get_noise_3d(
       position.x * terrain_zoom,
       position.y * terrain_zoom,
       position.z * terrain_zoom,
@@ -359,7 +454,7 @@ fn moon_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    
    // Capa 3: Cráteres
    let crater_zoom = 10.0;
-   let crater_noise = uniforms.noise.get_noise_3d(
+   let crater_noise = uniforms.frame.noise.get_noise_3d(
       position.x * crater_zoom + 500.0,
       position.y * crater_zoom,
       position.z * crater_zoom,
@@ -375,7 +470,7 @@ fn moon_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    
    // Capa 4: Detalles de superficie
    let detail_zoom = 25.0;
-   let detail_noise = uniforms.noise.get_noise_3d(
+   let detail_noise = uniforms.frame.noise.get_noise_3d(
       position.x * detail_zoom,
       position.y * detail_zoom,
       position.z * detail_zoom,
@@ -400,7 +495,7 @@ fn rings_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    let distance_from_center = (position.x * position.x + position.z * position.z).sqrt();
    
    // Solo renderizar en las zonas de anillos (no muy cerca del planeta)
-   if distance_from_center < 1.1 || distance_from_center > 1.4 {
+   if !(1.1..=1.4).contains(&distance_from_center) {
       return Color::from_hex(0x000000); // Transparente fuera del rango de anillos
    }
    
@@ -416,7 +511,7 @@ fn rings_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    
    // Capa 1: Partículas de hielo (brillantes)
    let ice_zoom = 50.0;
-   let ice_noise = uniforms.noise.get_noise_3d(
+   let ice_noise = uniforms.frame.noise.get_noise_3d(
       position.x * ice_zoom + time * 0.1,
       position.y * ice_zoom,
       position.z * ice_zoom - time * 0.1,
@@ -424,7 +519,7 @@ fn rings_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    
    // Capa 2: Rocas más grandes (oscuras)
    let rock_zoom = 20.0;
-   let rock_noise = uniforms.noise.get_noise_3d(
+   let rock_noise = uniforms.frame.noise.get_noise_3d(
       position.x * rock_zoom - time * 0.05,
       position.y * rock_zoom,
       position.z * rock_zoom + time * 0.05,
@@ -451,7 +546,7 @@ fn rings_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    
    // Capa 3: Variación de densidad en los anillos
    let density_zoom = 8.0;
-   let density_noise = uniforms.noise.get_noise_2d(
+   let density_noise = uniforms.frame.noise.get_noise_2d(
       distance_from_center * density_zoom,
       time * 0.02,
    );
@@ -480,7 +575,7 @@ fn starfield_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    
    // Usar noise para generar estrellas pseudo-aleatorias
    let star_zoom = 50.0;
-   let star_noise = uniforms.noise.get_noise_3d(
+   let star_noise = uniforms.frame.noise.get_noise_3d(
       dir.x * star_zoom,
       dir.y * star_zoom,
       dir.z * star_zoom,
@@ -499,7 +594,7 @@ fn starfield_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
       ];
       
       // Usar noise adicional para seleccionar color
-      let color_noise = uniforms.noise.get_noise_3d(
+      let color_noise = uniforms.frame.noise.get_noise_3d(
          dir.x * star_zoom * 2.0,
          dir.y * star_zoom * 2.0,
          dir.z * star_zoom * 2.0,
@@ -518,10 +613,16 @@ fn starfield_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
 // ============================================
 // SHIP SHADER - Nave espacial con colores originales
 // ============================================
+// Luz mínima del lado de la nave que no ve el sol
+const SHIP_AMBIENT: f32 = 0.25;
+
 fn ship_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    let position = fragment.vertex_position;
    let time = uniforms.time;
    
+   let default_material = ShipMaterial::default();
+   let material = uniforms.ship_material.as_ref().unwrap_or(&default_material);
+   
    // Helper function for absolute value
    let abs = |x: f32| if x < 0.0 { -x } else { x };
    
@@ -531,18 +632,27 @@ fn ship_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
    let color_compartimentos = Color::new(80, 100, 130); // Dark blue-gray compartments  
    let color_cabina = Color::new(150, 170, 200);       // Grayish blue cockpit
    
-   // Assign colors based on position (using the same logic as your original spaceship)
-   let base_color = 
+   // Con material del .mtl se usa su color difuso; si no, la paleta por posición
+   let base_color =
+      // Textura difusa del material (map_Kd), teñida por Kd como en el formato .mtl
+      if let (Some(texture), Some(surface)) = (&uniforms.texture, &uniforms.surface_material) {
+         let texel = texture.sample(fragment.tex_coords);
+         let tint = Color::from_float(surface.diffuse.x, surface.diffuse.y, surface.diffuse.z);
+         texel.modulate(&tint)
+      }
+      else if let Some(surface) = &uniforms.surface_material {
+         Color::from_float(surface.diffuse.x, surface.diffuse.y, surface.diffuse.z)
+      }
       // Cockpit (upper central part, high Y and center in X)
-      if position.y > 0.35 && abs(position.x) < 0.25 {
+      else if position.y > 0.35 && abs(position.x) < 0.25 {
          color_cabina
       }
       // Side compartments (extreme sides, more restrictive)
       else if abs(position.x) > 0.7 && position.y > -0.1 {
          color_compartimentos
       }
-      // Thrusters (rear lower part, negative Z and low Y)
-      else if position.z < -0.7 || (position.y < -0.2 && abs(position.x) < 0.6) {
+      // Thrusters (región de propulsores del material, o parte inferior central)
+      else if material.in_engine_region(position) || (position.y < -0.2 && abs(position.x) < 0.6) {
          color_propulsores
       }
       // Main body (everything else)
@@ -550,24 +660,37 @@ fn ship_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
          color_cuerpo
       };
    
-   // Simple lighting based on normal for subtle shading
+   // Luz del sol como en los planetas (`fragment.intensity`), con un poco de ambiente para que
+   // el lado en sombra no quede negro
    let normal = fragment.normal.normalize();
-   let light_dir = nalgebra_glm::Vec3::new(0.0, 0.0, 1.0);
-   let dot_product = normal.dot(&light_dir).max(0.3); // Minimum ambient light
-   
-   // Engine glow effect for thrusters (pulsing blue glow)
-   let engine_glow = if position.z < -0.7 {
-      let pulse = ((time * 3.0).sin() + 1.0) * 0.5;
-      let glow_intensity = pulse * 0.4;
-      let engine_blue = Color::new(100, 150, 255);
-      
-      // Blend thruster color with blue glow
-      blend_colors(&base_color, &engine_blue, glow_intensity)
-   } else {
-      base_color
-   };
-   
-   // Apply lighting to final color using the multiplication operator
-   let lighting_factor = 0.4 + 0.6 * dot_product;
-   engine_glow * lighting_factor
+   let lighting_factor = SHIP_AMBIENT + (1.0 - SHIP_AMBIENT) * fragment.intensity;
+   let mut lit = base_color * lighting_factor;
+
+   // Brillo especular del .mtl (Ks, Ns); la cámara mira en la misma dirección que la luz
+   if let Some(surface) = &uniforms.surface_material {
+      let highlight = normal.dot(&SUN_DIRECTION).max(0.0).powf(surface.shininess.max(1.0));
+      let specular = surface.specular * highlight;
+      lit = lit + Color::from_float(specular.x, specular.y, specular.z);
+   }
+
+   // Brillo de los propulsores: emisivo, se ve igual del lado en sombra (pulso con los
+   // parámetros del material)
+   if material.in_engine_region(position) {
+      let pulse = ((time * material.engine_pulse_speed).sin() + 1.0) * 0.5;
+      let glow_intensity = pulse * material.engine_intensity;
+      lit = blend_colors(&lit, &material.engine_color, glow_intensity);
+   }
+
+   // Luces de navegación: emisivas (no dependen de la iluminación) y con parpadeo
+   material.beacons.iter()
+      .filter(|beacon| beacon.is_on(time))
+      .fold(lit, |color, beacon| {
+         let distance = (position - beacon.position).magnitude();
+         if distance < beacon.radius {
+            let falloff = 1.0 - distance / beacon.radius;
+            blend_colors(&color, &beacon.color, falloff.sqrt())
+         } else {
+            color
+         }
+      })
 }
\ No newline at end of file
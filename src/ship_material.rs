@@ -0,0 +1,163 @@
+use nalgebra_glm::Vec3;
+use std::path::Path;
+use log::{info, warn};
+use crate::color::Color;
+use crate::storage;
+use crate::texture::Sampler;
+
+// Luz de navegación que parpadea en un punto del casco (espacio de modelo normalizado)
+#[derive(Debug, Clone)]
+pub struct Beacon {
+    pub position: Vec3,
+    pub radius: f32,
+    pub color: Color,
+    pub period: f32,  // Segundos por ciclo de parpadeo
+    pub duty: f32,    // Fracción del ciclo en la que está encendida (0.0 a 1.0)
+    pub phase: f32,   // Desfase en segundos
+}
+
+impl Beacon {
+    pub fn is_on(&self, time: f32) -> bool {
+        if self.period <= 0.0 {
+            return true;
+        }
+        ((time + self.phase) / self.period).fract() < self.duty
+    }
+}
+
+// Parámetros de material de cada nave, leídos de un archivo `.ship` junto al `.obj`
+#[derive(Debug, Clone)]
+pub struct ShipMaterial {
+    pub engine_min: Vec3,      // Región de los propulsores (caja en espacio de modelo)
+    pub engine_max: Vec3,
+    pub engine_color: Color,
+    pub engine_pulse_speed: f32,
+    pub engine_intensity: f32,
+    pub beacons: Vec<Beacon>,
+    pub texture_sampler: Sampler,  // Filtro de las texturas map_Kd del modelo
+    pub smooth_normals: Option<f32>,  // Ángulo de pliegue en grados; None = normales del archivo
+}
+
+impl Default for ShipMaterial {
+    fn default() -> Self {
+        ShipMaterial {
+            engine_min: Vec3::new(f32::MIN, f32::MIN, f32::MIN),
+            engine_max: Vec3::new(f32::MAX, f32::MAX, -0.45),
+            engine_color: Color::new(100, 150, 255),
+            engine_pulse_speed: 3.0,
+            engine_intensity: 0.4,
+            beacons: Vec::new(),
+            texture_sampler: Sampler::Bilinear,
+            smooth_normals: None,
+        }
+    }
+}
+
+impl ShipMaterial {
+    // Busca `<modelo>.ship` junto al archivo del modelo; si no existe usa los valores por defecto
+    pub fn for_model(model_path: &Path) -> Self {
+        let material_path = model_path.with_extension("ship");
+        match storage::read_to_string(&material_path) {
+            Ok(contents) => {
+                info!("Ship material loaded from {}", material_path.display());
+                Self::parse(&contents)
+            }
+            Err(_) => ShipMaterial::default(),
+        }
+    }
+
+    // Formato: una entrada `clave = valores` por línea, `#` para comentarios.
+    //   engine_min = x y z
+    //   engine_max = x y z
+    //   engine_color = 0xRRGGBB
+    //   engine_pulse = velocidad intensidad
+    //   beacon = x y z radio 0xRRGGBB periodo duty [desfase]
+    //   texture_filter = nearest | bilinear
+    //   smooth_normals = ángulo_de_pliegue | off
+    pub fn parse(contents: &str) -> Self {
+        let mut material = ShipMaterial::default();
+
+        for (line_number, raw_line) in contents.lines().enumerate() {
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                warn!("Ship material line {}: expected `key = value`", line_number + 1);
+                continue;
+            };
+            let values: Vec<&str> = value.split_whitespace().collect();
+
+            let parsed = match key.trim() {
+                "engine_min" => parse_vec3(&values).map(|v| material.engine_min = v),
+                "engine_max" => parse_vec3(&values).map(|v| material.engine_max = v),
+                "engine_color" => values.first().and_then(|v| parse_color(v)).map(|c| material.engine_color = c),
+                "engine_pulse" => parse_floats(&values, 2).map(|v| {
+                    material.engine_pulse_speed = v[0];
+                    material.engine_intensity = v[1];
+                }),
+                "beacon" => parse_beacon(&values).map(|b| material.beacons.push(b)),
+                "texture_filter" => parse_sampler(&values).map(|s| material.texture_sampler = s),
+                "smooth_normals" => parse_crease_angle(&values).map(|a| material.smooth_normals = a),
+                _ => None,
+            };
+
+            if parsed.is_none() {
+                warn!("Ship material line {}: could not parse `{}`", line_number + 1, line);
+            }
+        }
+
+        material
+    }
+
+    pub fn in_engine_region(&self, position: Vec3) -> bool {
+        position.x >= self.engine_min.x && position.x <= self.engine_max.x
+            && position.y >= self.engine_min.y && position.y <= self.engine_max.y
+            && position.z >= self.engine_min.z && position.z <= self.engine_max.z
+    }
+}
+
+fn parse_floats(values: &[&str], count: usize) -> Option<Vec<f32>> {
+    if values.len() < count {
+        return None;
+    }
+    values[..count].iter().map(|v| v.parse::<f32>().ok()).collect()
+}
+
+fn parse_vec3(values: &[&str]) -> Option<Vec3> {
+    parse_floats(values, 3).map(|v| Vec3::new(v[0], v[1], v[2]))
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.trim_start_matches("0x").trim_start_matches('#');
+    u32::from_str_radix(hex, 16).ok().map(Color::from_hex)
+}
+
+fn parse_sampler(values: &[&str]) -> Option<Sampler> {
+    match values.first()?.to_ascii_lowercase().as_str() {
+        "nearest" => Some(Sampler::Nearest),
+        "bilinear" => Some(Sampler::Bilinear),
+        _ => None,
+    }
+}
+
+fn parse_crease_angle(values: &[&str]) -> Option<Option<f32>> {
+    match *values.first()? {
+        "off" => Some(None),
+        value => value.parse::<f32>().ok().map(Some),
+    }
+}
+
+fn parse_beacon(values: &[&str]) -> Option<Beacon> {
+    if values.len() < 7 {
+        return None;
+    }
+    let position = parse_vec3(&values[0..3])?;
+    let radius = values[3].parse().ok()?;
+    let color = parse_color(values[4])?;
+    let period = values[5].parse().ok()?;
+    let duty = values[6].parse::<f32>().ok()?.clamp(0.0, 1.0);
+    let phase = values.get(7).and_then(|v| v.parse().ok()).unwrap_or(0.0);
+
+    Some(Beacon { position, radius, color, period, duty, phase })
+}
@@ -0,0 +1,56 @@
+// Unidades de tiempo de la simulación: un segundo simulado equivale a SIM_DAYS_PER_SECOND
+// días, así la órbita de Ares (la más cercana al Sol) dura más o menos un año.
+pub const SIM_DAYS_PER_SECOND: f64 = 30.0;
+pub const DAYS_PER_YEAR: f64 = 365.25;
+
+// Cuánto salta el reloj con las teclas de avance/retroceso
+pub const TIME_JUMP_DAYS: f64 = 30.0;
+
+pub fn seconds_to_days(seconds: f64) -> f64 {
+    seconds * SIM_DAYS_PER_SECOND
+}
+
+pub fn days_to_seconds(days: f64) -> f64 {
+    days / SIM_DAYS_PER_SECOND
+}
+
+// Tiempo absoluto de la simulación. Las posiciones de los cuerpos se calculan a partir de este
+// valor (no acumulando deltas), por eso se puede saltar hacia adelante o atrás sin deriva.
+// Va en f64 para que los ángulos no pierdan precisión tras muchos años simulados.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimClock {
+    pub seconds: f64,
+}
+
+impl SimClock {
+    pub fn advance(&mut self, seconds: f64) {
+        self.seconds += seconds;
+    }
+
+    // Salto en días; no se puede retroceder más allá del inicio
+    pub fn jump_days(&mut self, days: f64) {
+        self.seconds = (self.seconds + days_to_seconds(days)).max(0.0);
+    }
+
+    pub fn days(&self) -> f64 {
+        seconds_to_days(self.seconds)
+    }
+}
+
+// "Y2 D045.3": años completos y días dentro del año
+pub fn format_date(days: f64) -> String {
+    let years = (days / DAYS_PER_YEAR).floor();
+    format!("Y{} D{:05.1}", years as i64, days - years * DAYS_PER_YEAR)
+}
+
+// Duraciones (periodos orbitales, de rotación): en horas si no llegan a un día, en días, o en
+// años si pasan de uno
+pub fn format_duration(days: f64) -> String {
+    if days >= DAYS_PER_YEAR {
+        format!("{:.2} y", days / DAYS_PER_YEAR)
+    } else if days < 1.0 {
+        format!("{:.1} h", days * 24.0)
+    } else {
+        format!("{:.1} d", days)
+    }
+}
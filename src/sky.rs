@@ -0,0 +1,75 @@
+use nalgebra_glm::{Vec2, Vec3};
+use std::f32::consts::{PI, TAU};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use log::{info, warn};
+use crate::assets::{Assets, Handle};
+use crate::camera::{Camera, FIELD_OF_VIEW_DEGREES};
+use crate::framebuffer::Framebuffer;
+use crate::texture::{Sampler, Texture};
+
+// Fondo de la escena desde una sola imagen equirectangular (longitud en X, latitud en Y, el
+// ecuador en el plano de las órbitas). Reemplaza a la nebulosa y las estrellas procedurales.
+pub struct SkyImage {
+    pub path: PathBuf,
+    handle: Handle<Texture>,
+    pub image: Arc<Texture>,
+}
+
+impl SkyImage {
+    // Si la imagen no se puede leer se avisa y la escena queda con el fondo procedural
+    pub fn load(path: &Path, assets: &mut Assets) -> Option<SkyImage> {
+        match assets.load_texture(path, Sampler::Bilinear) {
+            Ok(handle) => {
+                let image = assets.texture(handle).expect("handle was just acquired");
+                info!("Sky image {} ({}x{})", path.display(), image.width, image.height);
+                Some(SkyImage { path: path.to_path_buf(), handle, image })
+            }
+            Err(e) => {
+                warn!("Could not load sky image {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    // Después de una recarga de assets: la imagen nueva si el archivo cambió
+    pub fn refresh(&mut self, assets: &Assets) {
+        if let Some(image) = assets.texture(self.handle) {
+            self.image = image;
+        }
+    }
+}
+
+// Coordenadas de la imagen para una dirección de vista (normalizada): U da la vuelta alrededor
+// del eje Y, V = 0 es el polo de abajo
+pub fn direction_uv(direction: Vec3) -> Vec2 {
+    let u = 0.5 + direction.z.atan2(direction.x) / TAU;
+    let v = 0.5 + direction.y.clamp(-1.0, 1.0).asin() / PI;
+    Vec2::new(u, v)
+}
+
+// Cada pixel muestrea la imagen en la dirección en que mira la cámara por él. `exposure` (EV)
+// oscurece o aclara solo el fondo, para que un cielo brillante no apague los planetas.
+pub fn render_sky(framebuffer: &mut Framebuffer, image: &Texture, camera: &Camera, exposure: f32) {
+    let forward = camera.center - camera.eye;
+    if forward.magnitude() < 1e-6 {
+        return;
+    }
+    let forward = forward.normalize();
+    let right = forward.cross(&camera.up).normalize();
+    let up = right.cross(&forward);
+
+    let (width, height) = (framebuffer.width, framebuffer.height);
+    let half_height = (FIELD_OF_VIEW_DEGREES.to_radians() / 2.0).tan();
+    let half_width = half_height * width as f32 / height.max(1) as f32;
+    let gain = 2.0_f32.powf(exposure);
+    for y in 0..height {
+        let ny = 1.0 - 2.0 * (y as f32 + 0.5) / height as f32;
+        for x in 0..width {
+            let nx = 2.0 * (x as f32 + 0.5) / width as f32 - 1.0;
+            let direction = (forward + right * (nx * half_width) + up * (ny * half_height)).normalize();
+            let color = image.sample(direction_uv(direction)) * gain;
+            framebuffer.set_pixel(x, y, color.to_hex());
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use nalgebra_glm::Vec3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::camera::ScreenProjection;
+use crate::celestial_body::{CelestialBody, ShaderType};
+use crate::framebuffer::Framebuffer;
+use crate::renderable::{Renderable, RenderPass};
+
+const SPAWN_RATE: f32 = 400.0;     // Partículas por segundo entre todas las estrellas
+const MAX_PARTICLES: usize = 1500;
+const WIND_SPEED: f32 = 2.5;       // Unidades por segundo (tiempo real, no de simulación)
+const SPEED_SPREAD: f32 = 0.3;     // ± fracción de WIND_SPEED entre partículas
+const WIND_EXTENT: f32 = 40.0;     // Distancia a la estrella a la que se pierden
+const STREAK_LENGTH: f32 = 0.12;   // Segundos de recorrido que abarca la estela
+// Radio de la magnetosfera en radios del cuerpo: el viento la rodea en vez de llegar a la superficie
+pub const MAGNETOSPHERE_RADII: f32 = 3.0;
+// Fracción de la velocidad que se conserva al desviarse por la magnetopausa
+const DEFLECTION_DAMPING: f32 = 0.9;
+
+const WIND_COLOR: u32 = 0xFFE8A0;
+const DEFLECTED_COLOR: u32 = 0x80C0FF;  // Las que tocaron una magnetosfera quedan azuladas
+
+struct WindParticle {
+    position: Vec3,
+    velocity: Vec3,
+    source: Vec3,     // Estrella de la que salió (para saber cuándo se alejó demasiado)
+    deflected: bool,
+}
+
+// Viento solar (ajuste `solar_wind`): partículas que salen de cada estrella (ShaderType::Sun) en
+// todas direcciones y se alejan en línea recta. Al entrar en la magnetosfera de un cuerpo con
+// campo magnético se desvían a lo largo de su borde; contra un cuerpo sin campo se pierden en la
+// superficie. Es solo visual: no afecta a la nave ni a los cuerpos.
+pub struct SolarWind {
+    pub enabled: bool,  // Lo pone el engine desde el ajuste
+    particles: Vec<WindParticle>,
+    spawn_debt: f32,    // Fracción de partícula pendiente de los frames anteriores
+    rng: StdRng,
+}
+
+impl Default for SolarWind {
+    fn default() -> Self {
+        SolarWind::new()
+    }
+}
+
+impl SolarWind {
+    pub fn new() -> Self {
+        SolarWind { enabled: false, particles: Vec::new(), spawn_debt: 0.0, rng: StdRng::from_rng(&mut rand::rng()) }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    pub fn update(&mut self, delta_time: f32, bodies: &[CelestialBody]) {
+        if !self.enabled {
+            self.particles.clear();
+            return;
+        }
+        let suns: Vec<&CelestialBody> = bodies.iter().filter(|body| body.shader_type == ShaderType::Sun).collect();
+        if suns.is_empty() {
+            self.particles.clear();
+            return;
+        }
+
+        self.spawn_debt += SPAWN_RATE * delta_time;
+        while self.spawn_debt >= 1.0 {
+            self.spawn_debt -= 1.0;
+            if self.particles.len() >= MAX_PARTICLES {
+                continue;
+            }
+            let sun = suns[self.rng.random_range(0..suns.len())];
+            let direction = random_direction(&mut self.rng);
+            let speed = WIND_SPEED * (1.0 + self.rng.random_range(-SPEED_SPREAD..SPEED_SPREAD));
+            self.particles.push(WindParticle {
+                position: sun.position + direction * sun.scale,
+                velocity: direction * speed,
+                source: sun.position,
+                deflected: false,
+            });
+        }
+
+        let obstacles: Vec<(Vec3, f32, bool)> = bodies
+            .iter()
+            .filter(|body| body.shader_type != ShaderType::Sun)
+            .map(|body| (body.position, body.scale, body.magnetic_field))
+            .collect();
+        self.particles.retain_mut(|particle| {
+            particle.position += particle.velocity * delta_time;
+            for &(center, radius, magnetic_field) in &obstacles {
+                let offset = particle.position - center;
+                let distance = offset.magnitude();
+                if !magnetic_field {
+                    if distance < radius {
+                        return false;
+                    }
+                    continue;
+                }
+                let magnetopause = radius * MAGNETOSPHERE_RADII;
+                if distance >= magnetopause || distance < f32::EPSILON {
+                    continue;
+                }
+                // Se queda sobre el borde y pierde la componente que entra: rodea el cuerpo
+                let normal = offset / distance;
+                let inward = particle.velocity.dot(&normal);
+                if inward < 0.0 {
+                    particle.velocity = (particle.velocity - normal * inward) * DEFLECTION_DAMPING;
+                }
+                particle.position = center + normal * magnetopause;
+                particle.deflected = true;
+            }
+            (particle.position - particle.source).magnitude() < WIND_EXTENT && particle.velocity.magnitude() > 0.05
+        });
+    }
+
+    // Una estela corta por partícula, si su punta no queda detrás de algo más cercano; se
+    // desvanecen con la distancia a la estrella
+    pub fn render(&self, framebuffer: &mut Framebuffer, projection: &ScreenProjection) {
+        for particle in &self.particles {
+            let tail = particle.position - particle.velocity * STREAK_LENGTH;
+            let Some((from, to)) = projection.project_segment(tail, particle.position) else {
+                continue;
+            };
+            let (x, y) = (to.x.round() as i32, to.y.round() as i32);
+            if x < 0 || y < 0 || x >= framebuffer.width as i32 || y >= framebuffer.height as i32 {
+                continue;
+            }
+            if framebuffer.zbuffer[y as usize * framebuffer.width + x as usize] < to.z {
+                continue;
+            }
+            let fade = 1.0 - (particle.position - particle.source).magnitude() / WIND_EXTENT;
+            let color = if particle.deflected { DEFLECTED_COLOR } else { WIND_COLOR };
+            framebuffer.blend_line(from.x.round() as i32, from.y.round() as i32, x, y, color, 0.25 + 0.6 * fade.clamp(0.0, 1.0));
+        }
+    }
+}
+
+impl Renderable for SolarWind {
+    fn render_pass(&self) -> RenderPass {
+        RenderPass::Effects
+    }
+
+    fn name(&self) -> &str {
+        "Solar wind"
+    }
+
+    fn draw_direct(&self, framebuffer: &mut Framebuffer, projection: &ScreenProjection) {
+        self.render(framebuffer, projection);
+    }
+}
+
+// Dirección uniforme sobre la esfera
+fn random_direction(rng: &mut StdRng) -> Vec3 {
+    let z: f32 = rng.random_range(-1.0..1.0);
+    let angle: f32 = rng.random_range(0.0..std::f32::consts::TAU);
+    let ring = (1.0 - z * z).sqrt();
+    Vec3::new(ring * angle.cos(), z, ring * angle.sin())
+}
@@ -1,48 +1,193 @@
-use nalgebra_glm::Vec3;
+use nalgebra_glm::{Vec2, Vec3};
+use serde::{Deserialize, Serialize};
 use crate::vertex::Vertex;
+use std::collections::HashMap;
 use std::f32::consts::PI;
 
-pub fn create_sphere(radius: f32, segments: usize, rings: usize) -> Vec<Vertex> {
-    let mut vertices = Vec::new();
-    
-    for ring in 0..rings {
-        let theta1 = (ring as f32 / rings as f32) * PI;
-        let theta2 = ((ring + 1) as f32 / rings as f32) * PI;
-        
-        for segment in 0..segments {
-            let phi1 = (segment as f32 / segments as f32) * 2.0 * PI;
-            let phi2 = ((segment + 1) as f32 / segments as f32) * 2.0 * PI;
-            
-            // Calcular los 4 vértices del quad
-            let v1 = spherical_to_cartesian(radius, theta1, phi1);
-            let v2 = spherical_to_cartesian(radius, theta1, phi2);
-            let v3 = spherical_to_cartesian(radius, theta2, phi1);
-            let v4 = spherical_to_cartesian(radius, theta2, phi2);
-            
-            // Normales apuntan desde el centro
-            let n1 = v1.normalize();
-            let n2 = v2.normalize();
-            let n3 = v3.normalize();
-            let n4 = v4.normalize();
-            
-            // Primer triángulo del quad
-            vertices.push(Vertex::new(v1, n1, nalgebra_glm::Vec2::new(0.0, 0.0)));
-            vertices.push(Vertex::new(v2, n2, nalgebra_glm::Vec2::new(1.0, 0.0)));
-            vertices.push(Vertex::new(v3, n3, nalgebra_glm::Vec2::new(0.0, 1.0)));
-            
-            // Segundo triángulo del quad
-            vertices.push(Vertex::new(v2, n2, nalgebra_glm::Vec2::new(1.0, 0.0)));
-            vertices.push(Vertex::new(v4, n4, nalgebra_glm::Vec2::new(1.0, 1.0)));
-            vertices.push(Vertex::new(v3, n3, nalgebra_glm::Vec2::new(0.0, 1.0)));
+// Esfera a partir de un icosaedro: cada subdivisión parte cada triángulo en 4 y proyecta los
+// puntos medios a la esfera. Los triángulos quedan casi del mismo tamaño en toda la superficie
+// (20 * 4^subdivisions triángulos), sin el amontonamiento en los polos de la esfera UV.
+pub fn create_icosphere(radius: f32, subdivisions: u32) -> Vec<Vertex> {
+    let t = (1.0 + 5.0_f32.sqrt()) / 2.0;
+    let mut points: Vec<Vec3> = [
+        (-1.0, t, 0.0), (1.0, t, 0.0), (-1.0, -t, 0.0), (1.0, -t, 0.0),
+        (0.0, -1.0, t), (0.0, 1.0, t), (0.0, -1.0, -t), (0.0, 1.0, -t),
+        (t, 0.0, -1.0), (t, 0.0, 1.0), (-t, 0.0, -1.0), (-t, 0.0, 1.0),
+    ]
+    .iter()
+    .map(|&(x, y, z)| Vec3::new(x, y, z).normalize())
+    .collect();
+
+    let mut triangles: Vec<[usize; 3]> = vec![
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+    ];
+
+    for _ in 0..subdivisions {
+        // Los puntos medios se comparten entre los dos triángulos de cada arista
+        let mut midpoints: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut midpoint = |a: usize, b: usize, points: &mut Vec<Vec3>| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                points.push(((points[a] + points[b]) * 0.5).normalize());
+                points.len() - 1
+            })
+        };
+
+        triangles = triangles
+            .iter()
+            .flat_map(|&[a, b, c]| {
+                let ab = midpoint(a, b, &mut points);
+                let bc = midpoint(b, c, &mut points);
+                let ca = midpoint(c, a, &mut points);
+                [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+            })
+            .collect();
+    }
+
+    triangles
+        .iter()
+        .flat_map(|triangle| sphere_triangle(triangle.map(|index| points[index]), radius))
+        .collect()
+}
+
+// Esfera a partir de un cubo: cada cara es una rejilla de `resolution` x `resolution` quads
+// (12 * resolution^2 triángulos) proyectada con el mapeo "spherified cube", que reparte el
+// área de forma más pareja que solo normalizar. Las UV son equirectangulares y continuas.
+pub fn create_cube_sphere(radius: f32, resolution: usize) -> Vec<Vertex> {
+    // (normal de la cara, eje u, eje v)
+    let faces = [
+        (Vec3::x(), Vec3::z(), Vec3::y()),
+        (-Vec3::x(), -Vec3::z(), Vec3::y()),
+        (Vec3::y(), Vec3::x(), Vec3::z()),
+        (-Vec3::y(), Vec3::x(), -Vec3::z()),
+        (Vec3::z(), -Vec3::x(), Vec3::y()),
+        (-Vec3::z(), Vec3::x(), Vec3::y()),
+    ];
+    let resolution = resolution.max(1);
+    let mut vertices = Vec::with_capacity(6 * resolution * resolution * 6);
+
+    for (normal, u_axis, v_axis) in faces {
+        let point = |i: usize, j: usize| {
+            let s = 2.0 * i as f32 / resolution as f32 - 1.0;
+            let t = 2.0 * j as f32 / resolution as f32 - 1.0;
+            spherify(normal + u_axis * s + v_axis * t)
+        };
+        for i in 0..resolution {
+            for j in 0..resolution {
+                let (p00, p10, p01, p11) = (point(i, j), point(i + 1, j), point(i, j + 1), point(i + 1, j + 1));
+                // Antihorario visto desde fuera, para que las normales recalculadas apunten afuera
+                vertices.extend(sphere_triangle([p00, p11, p10], radius));
+                vertices.extend(sphere_triangle([p00, p01, p11], radius));
+            }
         }
     }
-    
+
     vertices
 }
 
-fn spherical_to_cartesian(radius: f32, theta: f32, phi: f32) -> Vec3 {
-    let x = radius * theta.sin() * phi.cos();
-    let y = radius * theta.cos();
-    let z = radius * theta.sin() * phi.sin();
-    Vec3::new(x, y, z)
+// Punto del cubo [-1, 1]^3 llevado a la esfera unitaria
+fn spherify(p: Vec3) -> Vec3 {
+    let (x2, y2, z2) = (p.x * p.x, p.y * p.y, p.z * p.z);
+    Vec3::new(
+        p.x * (1.0 - y2 / 2.0 - z2 / 2.0 + y2 * z2 / 3.0).sqrt(),
+        p.y * (1.0 - z2 / 2.0 - x2 / 2.0 + z2 * x2 / 3.0).sqrt(),
+        p.z * (1.0 - x2 / 2.0 - y2 / 2.0 + x2 * y2 / 3.0).sqrt(),
+    )
+    .normalize()
+}
+
+// Triángulo de la esfera unitaria escalado a `radius`, con UV equirectangulares sin saltos:
+// si el triángulo cruza la costura (u pasa de 1 a 0) sus u se desenrollan por encima de 1,
+// y una esquina en el polo (donde u no está definida) toma la u media de las otras dos
+fn sphere_triangle(directions: [Vec3; 3], radius: f32) -> [Vertex; 3] {
+    let mut uvs = directions.map(spherical_uv);
+
+    let (min_u, max_u) = uvs.iter().fold((f32::MAX, f32::MIN), |(lo, hi), uv| (lo.min(uv.x), hi.max(uv.x)));
+    if max_u - min_u > 0.5 {
+        for uv in uvs.iter_mut().filter(|uv| uv.x < 0.5) {
+            uv.x += 1.0;
+        }
+    }
+
+    for pole in 0..3 {
+        if directions[pole].y.abs() > 1.0 - 1e-5 {
+            let others: Vec<f32> = (0..3).filter(|&i| i != pole).map(|i| uvs[i].x).collect();
+            uvs[pole].x = (others[0] + others[1]) / 2.0;
+        }
+    }
+
+    [0, 1, 2].map(|i| Vertex::new(directions[i] * radius, directions[i], uvs[i]))
+}
+
+// Malla usada para los planetas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SphereMesh {
+    Icosphere,
+    CubeSphere,
+}
+
+impl SphereMesh {
+    pub const ALL: [SphereMesh; 2] = [SphereMesh::Icosphere, SphereMesh::CubeSphere];
+
+    pub fn next(self) -> Self {
+        match self {
+            SphereMesh::Icosphere => SphereMesh::CubeSphere,
+            SphereMesh::CubeSphere => SphereMesh::Icosphere,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SphereMesh::Icosphere => "Icosphere",
+            SphereMesh::CubeSphere => "Cube-sphere",
+        }
+    }
+
+    // Malla unitaria del nivel de detalle `level` (0 = el más simple)
+    pub fn create(self, level: usize, tessellation: SphereTessellation) -> Vec<Vertex> {
+        let index = tessellation as usize;
+        match self {
+            SphereMesh::Icosphere => create_icosphere(1.0, LOD_SUBDIVISIONS[index][level]),
+            SphereMesh::CubeSphere => create_cube_sphere(1.0, LOD_CUBE_RESOLUTION[index][level]),
+        }
+    }
+}
+
+// Cuántos triángulos tienen las esferas de los planetas en cada nivel de detalle. Se elige en el
+// menú: en máquinas lentas las siluetas quedan más poligonales a cambio de frames.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SphereTessellation {
+    Low,
+    Medium,
+    High,
+}
+
+impl SphereTessellation {
+    pub const ALL: [SphereTessellation; 3] = [SphereTessellation::Low, SphereTessellation::Medium, SphereTessellation::High];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SphereTessellation::Low => "Low",
+            SphereTessellation::Medium => "Medium",
+            SphereTessellation::High => "High",
+        }
+    }
+}
+
+// Niveles de detalle de la esfera de los planetas por teselado, con un número de triángulos
+// parecido en ambas mallas. En Medium: icosaedro 80, 320, 1280 y 5120; cubo 48, 192, 1200 y 4800.
+// Low baja a 20..1280 y High sube a 320..20480. La resolución del cubo es par para que los polos
+// caigan en un vértice y ningún triángulo los contenga.
+const LOD_SUBDIVISIONS: [[u32; 4]; 3] = [[0, 1, 2, 3], [1, 2, 3, 4], [2, 3, 4, 5]];
+const LOD_CUBE_RESOLUTION: [[usize; 4]; 3] = [[2, 4, 6, 10], [2, 4, 10, 20], [4, 10, 20, 40]];
+// Cantidad de niveles de detalle que genera `SphereMesh::create`
+pub const LOD_LEVELS: usize = LOD_SUBDIVISIONS[0].len();
+
+// UV equirectangular de una dirección: u alrededor del eje Y, v de polo a polo
+fn spherical_uv(direction: Vec3) -> Vec2 {
+    let u = 0.5 + direction.z.atan2(direction.x) / (2.0 * PI);
+    let v = direction.y.clamp(-1.0, 1.0).acos() / PI;
+    Vec2::new(u, v)
 }
@@ -0,0 +1,44 @@
+use std::io;
+use std::path::Path;
+
+// Lectura y escritura de los archivos de configuración (settings.toml, keybindings.cfg, .ship).
+// En escritorio son archivos; en el navegador no hay sistema de archivos y se guardan en
+// `localStorage` con la ruta como clave, así los ajustes sobreviven a recargar la página.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_to_string(path: &Path) -> io::Result<String> {
+    std::fs::read_to_string(path)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write(path: &Path, contents: &str) -> io::Result<()> {
+    std::fs::write(path, contents)
+}
+
+#[cfg(target_arch = "wasm32")]
+const STORAGE_PREFIX: &str = "space_travel/";
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> io::Result<web_sys::Storage> {
+    web_sys::window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "localStorage is not available"))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn read_to_string(path: &Path) -> io::Result<String> {
+    let key = format!("{}{}", STORAGE_PREFIX, path.display());
+    local_storage()?
+        .get_item(&key)
+        .ok()
+        .flatten()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{} is not in localStorage", key)))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn write(path: &Path, contents: &str) -> io::Result<()> {
+    let key = format!("{}{}", STORAGE_PREFIX, path.display());
+    local_storage()?
+        .set_item(&key, contents)
+        .map_err(|_| io::Error::other(format!("could not write {} to localStorage", key)))
+}
@@ -0,0 +1,218 @@
+use nalgebra_glm::{Mat4, Vec4};
+use crate::camera::ScreenProjection;
+use crate::framebuffer::Framebuffer;
+
+// Pixel de cada bloque de 2x2 que se rasteriza en cada frame: en cuatro frames se pasa por
+// todos. Primero la diagonal, para que dos frames seguidos ya cubran el bloque en ambos ejes.
+const PHASES: [(usize, usize); 4] = [(0, 0), (1, 1), (1, 0), (0, 1)];
+// Diferencia de profundidad (relativa) entre lo que se espera al reproyectar y lo que guardó el
+// historial por encima de la cual el pixel se toma como recién descubierto
+const DEPTH_TOLERANCE: f32 = 0.02;
+
+// Pixel del bloque de 2x2 que se rasteriza en el frame `frame_index`
+pub fn phase(frame_index: u64) -> (usize, usize) {
+    PHASES[(frame_index % PHASES.len() as u64) as usize]
+}
+
+// Reescalado temporal: cada frame se rasteriza y sombrea un pixel de cada bloque de 2x2 (un
+// cuarto de la resolución, con el pixel rotando entre frames) y los otros tres se reconstruyen
+// reproyectando el frame anterior con la profundidad. Si lo reproyectado no coincide (algo se
+// movió o quedó al descubierto) se usa el promedio de los pixeles nuevos de alrededor.
+#[derive(Default)]
+pub struct TemporalUpscaler {
+    history: Vec<u32>,
+    history_depth: Vec<f32>,
+    size: (usize, usize),
+    previous: Option<Mat4>,  // Mundo -> pantalla del frame guardado
+}
+
+impl TemporalUpscaler {
+    // Olvida el historial (el siguiente frame se reconstruye solo con los pixeles nuevos)
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    // Completa los pixeles que no se rasterizaron en `framebuffer` (los que no son de su fase)
+    // y guarda el resultado como historial. Va antes de partículas, post-proceso y HUD.
+    pub fn reconstruct(&mut self, framebuffer: &mut Framebuffer, projection: &ScreenProjection) {
+        let Some((phase_x, phase_y)) = framebuffer.interleave else {
+            self.reset();
+            return;
+        };
+        let (width, height) = (framebuffer.width, framebuffer.height);
+        if self.size != (width, height) {
+            self.size = (width, height);
+            self.history = vec![0; width * height];
+            self.history_depth = vec![f32::INFINITY; width * height];
+            self.previous = None;
+        }
+
+        // Pantalla de este frame -> pantalla del guardado
+        let current = projection.screen_matrix();
+        let reprojection = self.previous.zip(current.try_inverse()).map(|(previous, inverse)| previous * inverse);
+
+        for y in 0..height {
+            for x in 0..width {
+                if x % 2 == phase_x && y % 2 == phase_y {
+                    continue;
+                }
+                // Pixeles nuevos del vecindario de 3x3 (entre 1 y 4): la profundidad más cercana
+                // y el rango de colores de la geometría, para no arrastrar fantasmas del historial
+                let mut nearest = f32::INFINITY;
+                let (mut low, mut high, mut sum, mut count) = ([255u32; 3], [0u32; 3], [0u32; 3], 0);
+                let mut background = false;
+                for sy in y.saturating_sub(1)..(y + 2).min(height) {
+                    for sx in x.saturating_sub(1)..(x + 2).min(width) {
+                        if sx % 2 != phase_x || sy % 2 != phase_y {
+                            continue;
+                        }
+                        let index = sy * width + sx;
+                        let depth = framebuffer.zbuffer[index];
+                        if !is_geometry(depth) {
+                            background = true;
+                            continue;
+                        }
+                        nearest = nearest.min(depth);
+                        let color = framebuffer.buffer[index];
+                        for (channel, shift) in [16, 8, 0].into_iter().enumerate() {
+                            let value = (color >> shift) & 0xFF;
+                            low[channel] = low[channel].min(value);
+                            high[channel] = high[channel].max(value);
+                            sum[channel] += value;
+                        }
+                        count += 1;
+                    }
+                }
+                // Solo fondo alrededor: el cielo ya está dibujado en todos los pixeles
+                if count == 0 {
+                    continue;
+                }
+
+                let index = y * width + x;
+                let history = reprojection.and_then(|matrix| {
+                    let previous = matrix * Vec4::new(x as f32 + 0.5, y as f32 + 0.5, nearest, 1.0);
+                    let (px, py, pz) = (previous.x / previous.w, previous.y / previous.w, previous.z / previous.w);
+                    if !(0.0..width as f32).contains(&px) || !(0.0..height as f32).contains(&py) {
+                        return None;
+                    }
+                    let previous_index = py as usize * width + px as usize;
+                    Some((previous_index, pz))
+                });
+
+                match history {
+                    // En el borde de un objeto el historial dice si el pixel era fondo
+                    Some((previous_index, _)) if background && !is_geometry(self.history_depth[previous_index]) => {}
+                    Some((previous_index, expected)) if (self.history_depth[previous_index] - expected).abs() <= DEPTH_TOLERANCE * expected.abs() => {
+                        let color = self.history[previous_index];
+                        let clamped = |channel: usize, shift: u32| ((color >> shift) & 0xFF).clamp(low[channel], high[channel]) << shift;
+                        framebuffer.buffer[index] = clamped(0, 16) | clamped(1, 8) | clamped(2, 0);
+                        framebuffer.zbuffer[index] = nearest;
+                    }
+                    _ => {
+                        framebuffer.buffer[index] = ((sum[0] / count) << 16) | ((sum[1] / count) << 8) | (sum[2] / count);
+                        framebuffer.zbuffer[index] = nearest;
+                    }
+                }
+            }
+        }
+
+        self.history.copy_from_slice(&framebuffer.buffer);
+        self.history_depth.copy_from_slice(&framebuffer.zbuffer);
+        self.previous = Some(current);
+    }
+}
+
+// Los cuerpos, la nave y las órbitas quedan en -2..=0; el fondo no escribe profundidad (o escribe f32::MAX)
+fn is_geometry(depth: f32) -> bool {
+    depth <= 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: usize = 6;
+
+    // Proyección identidad: la pantalla no se mueve entre frames
+    fn still_projection() -> ScreenProjection {
+        ScreenProjection::new(Mat4::identity(), Mat4::identity(), Mat4::identity())
+    }
+
+    // Frame de la fase `phase` con los pixeles rasterizados a `depth` y del color que diga `color`
+    fn frame(phase: (usize, usize), depth: f32, color: impl Fn(usize, usize) -> u32) -> Framebuffer {
+        let mut framebuffer = Framebuffer::new(SIZE, SIZE);
+        framebuffer.interleave = Some(phase);
+        for y in (phase.1..SIZE).step_by(2) {
+            for x in (phase.0..SIZE).step_by(2) {
+                framebuffer.buffer[y * SIZE + x] = color(x, y);
+                framebuffer.zbuffer[y * SIZE + x] = depth;
+            }
+        }
+        framebuffer
+    }
+
+    // Vecinos nuevos de (2, 2) en la fase (1, 1): negro en una diagonal y blanco en la otra
+    fn checker(x: usize, y: usize) -> u32 {
+        if (x + y).is_multiple_of(4) { 0x000000 } else { 0xFFFFFF }
+    }
+
+    #[test]
+    fn phases_cover_every_block_pixel_in_four_frames() {
+        let phases: Vec<_> = (0..4).map(phase).collect();
+        assert_eq!(phases, vec![(0, 0), (1, 1), (1, 0), (0, 1)]);
+        assert_eq!(phase(4), phase(0));
+    }
+
+    #[test]
+    fn without_interleave_nothing_changes() {
+        let mut framebuffer = frame((0, 0), -1.0, |_, _| 0x404040);
+        framebuffer.interleave = None;
+        let before = framebuffer.buffer.clone();
+        TemporalUpscaler::default().reconstruct(&mut framebuffer, &still_projection());
+        assert_eq!(framebuffer.buffer, before);
+    }
+
+    #[test]
+    fn first_frame_fills_gaps_from_new_neighbors() {
+        let mut framebuffer = frame((0, 0), -1.0, |_, _| 0x404040);
+        TemporalUpscaler::default().reconstruct(&mut framebuffer, &still_projection());
+        assert!(framebuffer.buffer.iter().all(|&pixel| pixel == 0x404040));
+        assert!(framebuffer.zbuffer.iter().all(|&depth| depth == -1.0));
+    }
+
+    #[test]
+    fn background_only_neighborhood_is_left_alone() {
+        let mut framebuffer = frame((0, 0), f32::INFINITY, |_, _| 0x404040);
+        TemporalUpscaler::default().reconstruct(&mut framebuffer, &still_projection());
+        assert_eq!(framebuffer.buffer[SIZE + 1], 0);
+    }
+
+    #[test]
+    fn still_scene_reuses_the_history() {
+        let mut upscaler = TemporalUpscaler::default();
+        upscaler.reconstruct(&mut frame((0, 0), -1.0, |_, _| 0x404040), &still_projection());
+        let mut framebuffer = frame((1, 1), -1.0, checker);
+        upscaler.reconstruct(&mut framebuffer, &still_projection());
+        // El promedio de los vecinos sería 0x7F7F7F
+        assert_eq!(framebuffer.buffer[2 * SIZE + 2], 0x404040);
+    }
+
+    #[test]
+    fn disoccluded_pixel_falls_back_to_the_neighbors() {
+        let mut upscaler = TemporalUpscaler::default();
+        upscaler.reconstruct(&mut frame((0, 0), -1.0, |_, _| 0x404040), &still_projection());
+        let mut framebuffer = frame((1, 1), -0.5, checker);
+        upscaler.reconstruct(&mut framebuffer, &still_projection());
+        assert_eq!(framebuffer.buffer[2 * SIZE + 2], 0x7F7F7F);
+    }
+
+    #[test]
+    fn reset_drops_the_history() {
+        let mut upscaler = TemporalUpscaler::default();
+        upscaler.reconstruct(&mut frame((0, 0), -1.0, |_, _| 0x404040), &still_projection());
+        upscaler.reset();
+        let mut framebuffer = frame((1, 1), -1.0, checker);
+        upscaler.reconstruct(&mut framebuffer, &still_projection());
+        assert_eq!(framebuffer.buffer[2 * SIZE + 2], 0x7F7F7F);
+    }
+}
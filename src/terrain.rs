@@ -0,0 +1,46 @@
+use fastnoise_lite::{FastNoiseLite, FractalType, NoiseType};
+use crate::vertex::Vertex;
+
+const DEFAULT_FREQUENCY: f32 = 1.8;
+const DEFAULT_OCTAVES: i32 = 4;
+
+// Relieve de un planeta: desplaza radialmente los vértices de su esfera con un campo de
+// ruido fractal, para que montañas y cráteres se vean en la silueta y no solo pintados
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Terrain {
+    pub seed: i32,
+    pub amplitude: f32,  // Altura máxima como fracción del radio (0.05 = 5%)
+    pub frequency: f32,  // Sobre la esfera unitaria; más alto = accidentes más pequeños
+    pub octaves: i32,
+}
+
+impl Terrain {
+    pub fn new(seed: i32, amplitude: f32) -> Self {
+        Terrain { seed, amplitude, frequency: DEFAULT_FREQUENCY, octaves: DEFAULT_OCTAVES }
+    }
+
+    pub fn with_frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    // Se aplica al generar la malla: las normales quedan viejas y hay que recalcularlas después
+    pub fn displace(&self, vertices: &mut [Vertex]) {
+        let mut noise = FastNoiseLite::with_seed(self.seed);
+        noise.set_noise_type(Some(NoiseType::OpenSimplex2));
+        noise.set_fractal_type(Some(FractalType::FBm));
+        noise.set_fractal_octaves(Some(self.octaves));
+        noise.set_frequency(Some(self.frequency));
+
+        for vertex in vertices {
+            let radius = vertex.position.magnitude();
+            if radius <= f32::EPSILON {
+                continue;
+            }
+            let direction = vertex.position / radius;
+            let height = noise.get_noise_3d(direction.x, direction.y, direction.z);
+            vertex.position = direction * radius * (1.0 + self.amplitude * height);
+            vertex.transformed_position = vertex.position;
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::framebuffer::Framebuffer;
+use crate::embedded;
+
+// Fuente bitmap 8x8 (dominio público, font8x8 "basic") para los caracteres ASCII 0x20..=0x7E.
+// Cada byte es una fila; el bit menos significativo es el pixel de la izquierda.
+pub const GLYPH_WIDTH: usize = 8;
+pub const GLYPH_HEIGHT: usize = 8;
+
+const FIRST_CHAR: u8 = 0x20;
+
+// 8 bytes por glifo, embebida en el binario
+const FONT: &[u8] = embedded::DEFAULT_FONT;
+
+fn glyph(c: char) -> &'static [u8] {
+   let code = fold_accent(c) as u32;
+   let glyph_count = (FONT.len() / GLYPH_HEIGHT) as u32;
+   let index = if (FIRST_CHAR as u32..FIRST_CHAR as u32 + glyph_count).contains(&code) {
+      (code - FIRST_CHAR as u32) as usize
+   } else {
+      // El resto de los caracteres fuera de ASCII (emojis, otros alfabetos) se muestran como '?'
+      (b'?' - FIRST_CHAR) as usize
+   };
+   &FONT[index * GLYPH_HEIGHT..(index + 1) * GLYPH_HEIGHT]
+}
+
+// Letras acentuadas (los textos en español) dibujadas con su letra base, y los signos de
+// apertura con los de cierre
+fn fold_accent(c: char) -> char {
+   match c {
+      'á' | 'à' | 'ä' | 'â' => 'a',
+      'é' | 'è' | 'ë' | 'ê' => 'e',
+      'í' | 'ì' | 'ï' | 'î' => 'i',
+      'ó' | 'ò' | 'ö' | 'ô' => 'o',
+      'ú' | 'ù' | 'ü' | 'û' => 'u',
+      'ñ' => 'n',
+      'Á' | 'À' | 'Ä' | 'Â' => 'A',
+      'É' | 'È' | 'Ë' | 'Ê' => 'E',
+      'Í' | 'Ì' | 'Ï' | 'Î' => 'I',
+      'Ó' | 'Ò' | 'Ö' | 'Ô' => 'O',
+      'Ú' | 'Ù' | 'Ü' | 'Û' => 'U',
+      'Ñ' => 'N',
+      '¿' => '?',
+      '¡' => '!',
+      c => c,
+   }
+}
+
+// Respecto a qué se toma la x al dibujar: el inicio, el centro o el final de cada línea
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+   Left,
+   Center,
+   Right,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TextStyle {
+   pub color: u32,
+   pub scale: usize,          // Cada pixel del glifo se dibuja como un bloque scale x scale
+   pub align: Align,
+   pub shadow: Option<u32>,   // Sombra desplazada un pixel (escalado) abajo a la derecha
+}
+
+impl TextStyle {
+   pub fn new(color: u32) -> Self {
+      TextStyle { color, scale: 1, align: Align::Left, shadow: None }
+   }
+
+   pub fn with_scale(mut self, scale: usize) -> Self {
+      self.scale = scale.max(1);
+      self
+   }
+
+   pub fn with_align(mut self, align: Align) -> Self {
+      self.align = align;
+      self
+   }
+
+   pub fn with_shadow(mut self, color: u32) -> Self {
+      self.shadow = Some(color);
+      self
+   }
+
+   pub fn line_height(&self) -> usize {
+      (GLYPH_HEIGHT + 2) * self.scale
+   }
+}
+
+// Ancho en pixeles de un texto de una sola línea
+pub fn text_width(text: &str) -> usize {
+   text.chars().count() * GLYPH_WIDTH
+}
+
+// Tamaño (ancho, alto) en pixeles de un texto, con varias líneas separadas por '\n'
+pub fn measure_text(text: &str, style: &TextStyle) -> (usize, usize) {
+   let width = text.lines().map(text_width).max().unwrap_or(0) * style.scale;
+   let lines = text.lines().count().max(1);
+   (width, lines * style.line_height() - 2 * style.scale)
+}
+
+// Dibuja texto directamente en el framebuffer (sin z-buffer), recortando fuera de pantalla
+pub fn draw_text(framebuffer: &mut Framebuffer, x: i32, y: i32, text: &str, color: u32) {
+   draw_text_styled(framebuffer, x, y, text, &TextStyle::new(color));
+}
+
+// Igual que `draw_text` con escala, alineación, sombra y saltos de línea
+pub fn draw_text_styled(framebuffer: &mut Framebuffer, x: i32, y: i32, text: &str, style: &TextStyle) {
+   for (i, line) in text.lines().enumerate() {
+      let width = (text_width(line) * style.scale) as i32;
+      let line_x = match style.align {
+         Align::Left => x,
+         Align::Center => x - width / 2,
+         Align::Right => x - width,
+      };
+      let line_y = y + (i * style.line_height()) as i32;
+
+      if let Some(shadow) = style.shadow {
+         let offset = style.scale as i32;
+         draw_line(framebuffer, line_x + offset, line_y + offset, line, shadow, style.scale);
+      }
+      draw_line(framebuffer, line_x, line_y, line, style.color, style.scale);
+   }
+}
+
+fn draw_line(framebuffer: &mut Framebuffer, x: i32, y: i32, line: &str, color: u32, scale: usize) {
+   let mut cursor_x = x;
+
+   for c in line.chars() {
+      let rows = glyph(c);
+      for (row, bits) in rows.iter().enumerate() {
+         for col in 0..GLYPH_WIDTH {
+            if bits & (1 << col) == 0 {
+               continue;
+            }
+            let px = cursor_x + (col * scale) as i32;
+            let py = y + (row * scale) as i32;
+            for dy in 0..scale as i32 {
+               for dx in 0..scale as i32 {
+                  framebuffer.set_pixel_i32(px + dx, py + dy, color);
+               }
+            }
+         }
+      }
+      cursor_x += (GLYPH_WIDTH * scale) as i32;
+   }
+}
@@ -0,0 +1,81 @@
+use std::path::Path;
+use nalgebra_glm::Vec2;
+use crate::color::Color;
+
+// Cómo se lee un texel entre píxeles de la imagen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sampler {
+    Nearest,
+    Bilinear,
+}
+
+// Imagen RGB en memoria lista para muestrear desde los shaders (UV en 0.0..1.0, se repite fuera)
+#[derive(Debug, Clone)]
+pub struct Texture {
+    pub width: usize,
+    pub height: usize,
+    pub sampler: Sampler,
+    texels: Vec<[f32; 3]>,
+}
+
+impl Texture {
+    // Carga PNG/JPEG (o cualquier formato que soporte `image`) desde un archivo
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, image::ImageError> {
+        let image = image::open(path)?.to_rgb8();
+        Ok(Self::from_rgb8(image))
+    }
+
+    // Decodifica una imagen ya en memoria (p. ej. un asset embebido con `include_bytes!`)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, image::ImageError> {
+        let image = image::load_from_memory(bytes)?.to_rgb8();
+        Ok(Self::from_rgb8(image))
+    }
+
+    fn from_rgb8(image: image::RgbImage) -> Self {
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let texels = image
+            .pixels()
+            .map(|pixel| [pixel[0] as f32, pixel[1] as f32, pixel[2] as f32])
+            .collect();
+        Texture { width, height, sampler: Sampler::Bilinear, texels }
+    }
+
+    pub fn with_sampler(mut self, sampler: Sampler) -> Self {
+        self.sampler = sampler;
+        self
+    }
+
+    // V = 0 es la parte de abajo de la imagen, como en los .obj
+    pub fn sample(&self, uv: Vec2) -> Color {
+        if self.texels.is_empty() {
+            return Color::black();
+        }
+        let x = uv.x.rem_euclid(1.0) * self.width as f32;
+        let y = (1.0 - uv.y.rem_euclid(1.0)) * self.height as f32;
+
+        let [r, g, b] = match self.sampler {
+            Sampler::Nearest => self.texel(x as i64, y as i64),
+            Sampler::Bilinear => {
+                // Centros de texel en +0.5; se mezclan los 4 vecinos
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (tx, ty) = (x - x0, y - y0);
+                let (x0, y0) = (x0 as i64, y0 as i64);
+                let lerp = |a: [f32; 3], b: [f32; 3], t: f32| {
+                    [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, a[2] + (b[2] - a[2]) * t]
+                };
+                let top = lerp(self.texel(x0, y0), self.texel(x0 + 1, y0), tx);
+                let bottom = lerp(self.texel(x0, y0 + 1), self.texel(x0 + 1, y0 + 1), tx);
+                lerp(top, bottom, ty)
+            }
+        };
+        Color::new(r as u8, g as u8, b as u8)
+    }
+
+    // Texel con repetición en ambos ejes
+    fn texel(&self, x: i64, y: i64) -> [f32; 3] {
+        let x = x.rem_euclid(self.width as i64) as usize;
+        let y = y.rem_euclid(self.height as i64) as usize;
+        self.texels[y * self.width + x]
+    }
+}
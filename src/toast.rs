@@ -0,0 +1,116 @@
+use std::collections::VecDeque;
+use crate::framebuffer::Framebuffer;
+use crate::text::{draw_text_styled, measure_text, TextStyle};
+
+const TOAST_DURATION: f32 = 3.0;  // Segundos en pantalla
+const FADE_TIME: f32 = 0.6;       // Los últimos segundos se va desvaneciendo
+const MAX_VISIBLE: usize = 5;     // Las que exceden esperan en cola
+const MARGIN: usize = 6;
+const PADDING: usize = 4;
+const SPACING: usize = 3;
+
+const PANEL_COLOR: u32 = 0x000000;
+const PANEL_ALPHA: f32 = 0.65;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToastKind {
+    Info,
+    Warning,
+}
+
+impl ToastKind {
+    fn color(self) -> u32 {
+        match self {
+            ToastKind::Info => 0xC8D2DC,
+            ToastKind::Warning => 0xFFB030,
+        }
+    }
+}
+
+struct Toast {
+    message: String,
+    kind: ToastKind,
+    age: f32,
+}
+
+// Mensajes cortos en pantalla (cambios de modo, warps, colisiones). Se muestran apilados
+// en la esquina inferior izquierda, el más nuevo abajo, y se desvanecen solos.
+pub struct Toasts {
+    visible: Vec<Toast>,
+    queued: VecDeque<Toast>,
+}
+
+impl Default for Toasts {
+    fn default() -> Self {
+        Toasts::new()
+    }
+}
+
+impl Toasts {
+    pub fn new() -> Self {
+        Toasts { visible: Vec::new(), queued: VecDeque::new() }
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(message.into(), ToastKind::Info);
+    }
+
+    pub fn warning(&mut self, message: impl Into<String>) {
+        self.push(message.into(), ToastKind::Warning);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.visible.is_empty() && self.queued.is_empty()
+    }
+
+    fn push(&mut self, message: String, kind: ToastKind) {
+        // Repetir el mismo mensaje solo reinicia su tiempo (p. ej. alternar algo varias veces)
+        if let Some(existing) = self.visible.iter_mut().find(|toast| toast.message == message) {
+            existing.age = 0.0;
+            return;
+        }
+        self.queued.push_back(Toast { message, kind, age: 0.0 });
+        self.promote();
+    }
+
+    fn promote(&mut self) {
+        while self.visible.len() < MAX_VISIBLE {
+            match self.queued.pop_front() {
+                Some(toast) => self.visible.push(toast),
+                None => break,
+            }
+        }
+    }
+
+    // Usa el tiempo real del frame, así los avisos se apagan también con la simulación en pausa
+    pub fn update(&mut self, delta_time: f32) {
+        for toast in &mut self.visible {
+            toast.age += delta_time;
+        }
+        self.visible.retain(|toast| toast.age < TOAST_DURATION);
+        self.promote();
+    }
+
+    pub fn render(&self, framebuffer: &mut Framebuffer) {
+        let mut bottom = framebuffer.height.saturating_sub(MARGIN);
+        for toast in self.visible.iter().rev() {
+            let alpha = ((TOAST_DURATION - toast.age) / FADE_TIME).clamp(0.0, 1.0);
+            let style = TextStyle::new(fade(toast.kind.color(), alpha));
+            let (text_width, text_height) = measure_text(&toast.message, &style);
+            let (width, height) = (text_width + 2 * PADDING, text_height + 2 * PADDING);
+            if bottom < height {
+                break;
+            }
+            let top = bottom - height;
+            framebuffer.blend_rect(MARGIN, top, width, height, PANEL_COLOR, PANEL_ALPHA * alpha);
+            draw_text_styled(framebuffer, (MARGIN + PADDING) as i32, (top + PADDING) as i32, &toast.message, &style);
+            bottom = top.saturating_sub(SPACING);
+        }
+    }
+}
+
+// El texto no tiene alfa: se oscurece el color hacia el negro del panel
+fn fade(color: u32, alpha: f32) -> u32 {
+    let channel = |shift: u32| ((((color >> shift) & 0xFF) as f32 * alpha) as u32) << shift;
+    channel(16) | channel(8) | channel(0)
+}
@@ -1,4 +1,5 @@
 use nalgebra_glm::{Vec3, dot};
+use crate::shaders::SUN_DIRECTION;
 use crate::fragment::Fragment;
 use crate::vertex::Vertex;
 use crate::color::Color;
@@ -9,8 +10,6 @@ pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<Fragment> {
 
    let (min_x, min_y, max_x, max_y) = calculate_bounding_box(&a, &b, &c);
 
-   let light_dir = Vec3::new(0.0, 0.0, 1.0);
-
    let triangle_area = edge_function(&a, &b, &c);
 
    // Iterate over each pixel in the bounding box
@@ -22,9 +21,9 @@ pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<Fragment> {
          let (w1, w2, w3) = barycentric_coordinates(&point, &a, &b, &c, triangle_area);
 
          // Check if the point is inside the triangle
-         if w1 >= 0.0 && w1 <= 1.0 && 
-            w2 >= 0.0 && w2 <= 1.0 &&
-            w3 >= 0.0 && w3 <= 1.0 {
+         if (0.0..=1.0).contains(&w1) &&
+            (0.0..=1.0).contains(&w2) &&
+            (0.0..=1.0).contains(&w3) {
                
                // Interpolate normal
                let normal = (v1.transformed_normal * w1 + 
@@ -34,22 +33,28 @@ pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<Fragment> {
                // Interpolate vertex position (for shader effects)
                let vertex_position = v1.position * w1 + v2.position * w2 + v3.position * w3;
 
+               // Interpolate texture coordinates
+               let tex_coords = v1.tex_coords * w1 + v2.tex_coords * w2 + v3.tex_coords * w3;
+
                // Calculate lighting intensity
-               let intensity = dot(&normal, &light_dir).max(0.0);
+               let intensity = dot(&normal, &SUN_DIRECTION).max(0.0);
 
                // Interpolate depth
                let depth = a.z * w1 + b.z * w2 + c.z * w3;
 
                // Create fragment with all necessary data
-               fragments.push(Fragment::new_with_data(
-                  x as f32,
-                  y as f32,
-                  Color::new(255, 255, 255), // Color will be determined by shader
-                  depth,
-                  normal,
-                  vertex_position,
-                  intensity,
-               ));
+               fragments.push(Fragment {
+                  tex_coords,
+                  ..Fragment::new_with_data(
+                     x as f32,
+                     y as f32,
+                     Color::new(255, 255, 255), // Color will be determined by shader
+                     depth,
+                     normal,
+                     vertex_position,
+                     intensity,
+                  )
+               });
          }
       }
    }
@@ -0,0 +1,663 @@
+use nalgebra_glm::{Mat4, Vec3};
+use std::borrow::Cow;
+use log::{debug, info, warn};
+use crate::vertex::Vertex;
+use crate::renderable::{DrawItem, Renderable, RenderPass, RenderView, Shading};
+use crate::obj_loader::{MeshOptions, Model, MtlMaterial, ProcessedModel};
+use crate::collision::{CollisionMode, CollisionShape, rotation_matrix};
+use crate::ship_material::ShipMaterial;
+use crate::play_area::{BoundaryEvent, PlayArea};
+use std::f32::consts::PI;
+use crate::texture::{Sampler, Texture};
+use crate::assets::{Assets, Handle};
+use crate::embedded;
+use crate::geometry::Mesh;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const MODELS_DIR: &str = "models";  // Relativo a la carpeta de assets
+const DEFAULT_MODEL: &str = "NavePrototipo2.obj";
+// Malla de los planetas, que vive en la misma carpeta pero no es una nave
+const PLANET_MODEL: &str = "sphere.obj";
+// Alabeo máximo (radianes) al girar y qué tan rápido se alcanza
+const MAX_BANK: f32 = 0.5;
+const BANK_RESPONSE: f32 = 5.0;
+// Fracción de la velocidad que conserva la nave cada 1/60 s (frenado sin depender de los FPS;
+// un frame de 0 s, como en pausa, no la frena)
+const DRAG_PER_TICK: f32 = 0.95;
+const DRAG_TICKS_PER_SECOND: f32 = 60.0;
+
+// Fracción de la velocidad que queda tras `delta_time` segundos de roce
+pub fn drag_factor(delta_time: f32) -> f32 {
+    DRAG_PER_TICK.powf(delta_time * DRAG_TICKS_PER_SECOND)
+}
+
+// Tamaño (eje más largo, en unidades de modelo) al que se normalizan todas las naves
+const SHIP_MODEL_SIZE: f32 = 2.0;
+
+// Parámetros del warp drive de la nave (distinto del warp animado de la cámara)
+const WARP_CHARGE_RATE: f32 = 0.5;      // Carga por segundo (1.0 = carga completa)
+const WARP_MIN_CHARGE: f32 = 0.15;      // Carga mínima para poder saltar
+const WARP_JUMP_DURATION: f32 = 0.4;    // Duración del salto en segundos
+const WARP_MAX_SPEED: f32 = 40.0;       // Velocidad del salto con carga completa
+const WARP_COOLDOWN: f32 = 3.0;         // Enfriamiento después de saltar
+
+// API común de todo lo que vuela: la nave del jugador y, más adelante, naves NPC.
+// El bucle principal solo necesita esto para simular, renderizar y resolver colisiones.
+pub trait Vehicle {
+    fn update(&mut self, delta_time: f32);
+    fn position(&self) -> Vec3;
+    fn velocity(&self) -> Vec3;
+    fn forward(&self) -> Vec3;
+    fn render_data(&self) -> RenderData<'_>;
+
+    // Radio de la esfera envolvente del modelo ya escalado
+    fn bounding_radius(&self) -> f32;
+    fn check_collision(&self, body_position: Vec3, body_radius: f32) -> bool;
+    fn handle_collision(&mut self, body_position: Vec3);
+
+    // Distancia entre la esfera envolvente y la superficie del cuerpo (negativa si se superponen)
+    fn surface_distance(&self, body_position: Vec3, body_radius: f32) -> f32 {
+        (self.position() - body_position).magnitude() - body_radius - self.bounding_radius()
+    }
+}
+
+// Lo necesario para dibujar un vehículo en un frame
+pub struct RenderData<'a> {
+    pub position: Vec3,
+    pub rotation: Vec3,
+    pub scale: f32,
+    pub vertices: &'a [Vertex],
+    pub indices: &'a [u32],
+    pub surfaces: &'a [Surface],
+    pub material: &'a ShipMaterial,
+}
+
+// Tramo del índice de triángulos que se dibuja con un mismo material del .mtl
+// (None = sin material, se usa la paleta procedural)
+#[derive(Debug, Clone)]
+pub struct Surface {
+    pub material: Option<MtlMaterial>,
+    pub texture: Option<Handle<Texture>>,  // map_Kd del material, si se pudo cargar
+    pub image: Option<Arc<Texture>>,       // La misma textura ya resuelta: se dibuja sin pasar por `Assets`
+    pub indices: Range<usize>,
+}
+
+// Parte con nombre del .obj (`o` / `g`) que se puede mover sola, como una cápsula desacoplable
+// o una torreta. `transform` es rígida y en espacio de modelo de la nave (ya escalado a
+// SHIP_MODEL_SIZE), antes de `model_matrix`; las partes no comparten vértices entre sí.
+#[derive(Debug, Clone)]
+pub struct ShipPart {
+    pub name: String,
+    pub indices: Range<usize>,
+    pub transform: Mat4,
+}
+
+// Geometría lista para dibujar: vértices únicos, índices (3 por triángulo), superficies y partes
+struct ShipMesh {
+    model: Option<Handle<ProcessedModel>>,  // None para la nave embebida
+    geometry: Mesh,
+    surfaces: Vec<Surface>,
+    parts: Vec<ShipPart>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WarpDrive {
+    Idle,
+    Charging { charge: f32 },
+    Jumping { speed: f32, remaining: f32 },
+    Cooldown { remaining: f32 },
+}
+
+pub struct Spaceship {
+    pub position: Vec3,
+    pub rotation: Vec3,
+    pub scale: f32,
+    pub velocity: Vec3,
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    pub surfaces: Vec<Surface>,
+    parts: Vec<ShipPart>,
+    posed_vertices: Option<Vec<Vertex>>,  // Vértices con las partes movidas; None si ninguna se movió
+    model_handle: Option<Handle<ProcessedModel>>,
+    pub is_loaded: bool,
+    pub model_paths: Vec<PathBuf>,
+    pub model_index: usize,
+    pub material: ShipMaterial,
+    model_name: String,
+    pub warp_drive: WarpDrive,
+    turn_input: f32,
+    pub collision_shape: CollisionShape,
+    pub collision_mode: CollisionMode,
+    pub play_area: PlayArea,
+}
+
+impl Spaceship {
+    pub fn new(assets: &mut Assets) -> Self {
+        // Buscar todos los modelos disponibles y empezar con la nave por defecto
+        let models_dir = assets.resolve(MODELS_DIR);
+        let model_paths = Self::scan_models(&models_dir);
+        let model_index = model_paths
+            .iter()
+            .position(|path| path.file_name().is_some_and(|name| name == DEFAULT_MODEL))
+            .unwrap_or(0);
+        info!("Found {} ship model(s) in {}", model_paths.len(), models_dir.display());
+
+        let material = Self::load_material(model_paths.get(model_index));
+        let (mesh, model_name) = Self::load_model(assets, model_paths.get(model_index), &material);
+        let is_loaded = model_name.is_some();
+        let collision_shape = CollisionShape::from_vertices(&mesh.geometry.vertices);
+
+        Spaceship {
+            position: Vec3::new(2.0, 0.0, 2.0), // Start near the scene
+            rotation: Vec3::new(0.0, 0.0, 0.0),
+            scale: 0.3,
+            velocity: Vec3::new(0.0, 0.0, 0.0),
+            vertices: mesh.geometry.vertices,
+            indices: mesh.geometry.indices,
+            surfaces: mesh.surfaces,
+            parts: mesh.parts,
+            posed_vertices: None,
+            model_handle: mesh.model,
+            is_loaded,
+            model_paths,
+            model_index,
+            material,
+            model_name: model_name.unwrap_or_default(),
+            warp_drive: WarpDrive::Idle,
+            turn_input: 0.0,
+            collision_shape,
+            collision_mode: CollisionMode::Hull,
+            play_area: PlayArea::default(),
+        }
+    }
+
+    // Lista ordenada de naves en el directorio de modelos: cada .obj menos la esfera de los
+    // planetas. El .ship es opcional (sin él se usa el material por defecto), así se puede
+    // probar cualquier malla propia
+    fn scan_models(dir: &Path) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = match std::fs::read_dir(dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|ext| ext.eq_ignore_ascii_case("obj"))
+                })
+                .filter(|path| path.file_name().is_none_or(|name| !name.eq_ignore_ascii_case(PLANET_MODEL)))
+                .collect(),
+            Err(e) => {
+                warn!("Could not read {}: {}", dir.display(), e);
+                Vec::new()
+            }
+        };
+        paths.sort();
+        paths
+    }
+
+    // Carga un modelo normalizado; devuelve el modelo de respaldo (y None) si falla
+    fn load_model(assets: &mut Assets, path: Option<&PathBuf>, material: &ShipMaterial) -> (ShipMesh, Option<String>) {
+        let Some(path) = path else {
+            warn!("No ship models found, using built-in ship");
+            return (Self::create_fallback_model(assets, material), None);
+        };
+
+        info!("Loading spaceship model {}...", path.display());
+        let loaded = assets.load_model(path, Self::mesh_options(material))
+            .map(|handle| (handle, assets.model(handle).expect("handle was just acquired")));
+        match loaded {
+            Ok((handle, model)) => {
+                info!("Spaceship model loaded successfully!");
+                let mut mesh = Self::convert_model_to_vertices(assets, &model, material);
+                mesh.model = Some(handle);
+                debug!("Ship mesh: {} unique vertices, {} triangles", mesh.geometry.vertices.len(), mesh.geometry.triangle_count());
+                (mesh, Some(Self::model_name_from_path(path)))
+            }
+            Err(e) => {
+                warn!("Could not load spaceship model: {}; using built-in ship", e);
+                (Self::create_fallback_model(assets, material), None)
+            }
+        }
+    }
+
+    // Textura difusa (map_Kd) de un material del .mtl
+    fn load_texture(assets: &mut Assets, material: &MtlMaterial, sampler: Sampler) -> Option<(Handle<Texture>, Arc<Texture>)> {
+        let path = material.diffuse_map.as_ref()?;
+        match assets.load_texture(path, sampler) {
+            Ok(handle) => {
+                info!("Texture loaded: {}", path.display());
+                Some((handle, assets.texture(handle).expect("handle was just acquired")))
+            }
+            Err(e) => {
+                warn!("Could not load texture {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    // Material de la nave (archivo .ship junto al .obj, o el valor por defecto si no existe)
+    fn load_material(path: Option<&PathBuf>) -> ShipMaterial {
+        match path {
+            Some(path) => ShipMaterial::for_model(path),
+            // Sin modelos en disco se usa la nave embebida, con su propio material
+            None => ShipMaterial::parse(embedded::DEFAULT_SHIP_MATERIAL),
+        }
+    }
+
+    fn model_name_from_path(path: &Path) -> String {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string())
+    }
+
+    // Pasa al siguiente modelo de assets/models (vuelve al primero al final de la lista)
+    pub fn cycle_model(&mut self, assets: &mut Assets) {
+        if self.model_paths.len() < 2 {
+            return;
+        }
+        self.select_model(assets, (self.model_index + 1) % self.model_paths.len());
+    }
+
+    // Carga el modelo `index` de `model_paths` (índices fuera de rango se ignoran)
+    pub fn select_model(&mut self, assets: &mut Assets, index: usize) {
+        if index >= self.model_paths.len() {
+            return;
+        }
+        self.model_index = index;
+
+        self.release_textures(assets);
+        if let Some(handle) = self.model_handle.take() {
+            assets.release_model(handle);
+        }
+        self.material = Self::load_material(self.model_paths.get(self.model_index));
+        let (mesh, model_name) = Self::load_model(assets, self.model_paths.get(self.model_index), &self.material);
+        self.is_loaded = model_name.is_some();
+        self.model_name = model_name.unwrap_or_default();
+        self.set_mesh(mesh);
+    }
+
+    // Reconstruye la geometría desde el modelo actual del gestor (tras `Assets::reload_all`)
+    pub fn reload_model(&mut self, assets: &mut Assets) {
+        let Some(model) = self.model_handle.and_then(|handle| assets.model(handle)) else {
+            return;
+        };
+        let material = Self::load_material(self.model_paths.get(self.model_index));
+        if Self::mesh_options(&material) != model.options {
+            // El .ship pide otro procesado: hay que volver a cargar el modelo con esas opciones
+            self.select_model(assets, self.model_index);
+            return;
+        }
+        self.release_textures(assets);
+        self.material = material;
+        let mut mesh = Self::convert_model_to_vertices(assets, &model, &self.material);
+        mesh.model = self.model_handle;
+        self.set_mesh(mesh);
+    }
+
+    fn set_mesh(&mut self, mesh: ShipMesh) {
+        self.collision_shape = CollisionShape::from_vertices(&mesh.geometry.vertices);
+        self.vertices = mesh.geometry.vertices;
+        self.indices = mesh.geometry.indices;
+        self.surfaces = mesh.surfaces;
+        self.parts = mesh.parts;
+        self.posed_vertices = None;
+        self.model_handle = mesh.model;
+    }
+
+    // Partes del modelo actual; al cambiar o recargar el modelo vuelven a su lugar
+    pub fn parts(&self) -> &[ShipPart] {
+        &self.parts
+    }
+
+    // Mueve la parte `name` (ver ShipPart); false si el modelo no tiene una parte con ese nombre.
+    // La forma de colisión sigue siendo la del modelo armado.
+    pub fn set_part_transform(&mut self, name: &str, transform: Mat4) -> bool {
+        match self.parts.iter_mut().find(|part| part.name == name) {
+            Some(part) => part.transform = transform,
+            None => return false,
+        }
+        self.posed_vertices = Self::pose_parts(&self.vertices, &self.indices, &self.parts);
+        true
+    }
+
+    fn pose_parts(vertices: &[Vertex], indices: &[u32], parts: &[ShipPart]) -> Option<Vec<Vertex>> {
+        let identity = Mat4::identity();
+        if parts.iter().all(|part| part.transform == identity) {
+            return None;
+        }
+        let mut posed = vertices.to_vec();
+        for part in parts.iter().filter(|part| part.transform != identity) {
+            let rotation = part.transform.fixed_view::<3, 3>(0, 0).into_owned();
+            let mut moved = vec![false; posed.len()];
+            for &index in &indices[part.indices.clone()] {
+                let index = index as usize;
+                if std::mem::replace(&mut moved[index], true) {
+                    continue;
+                }
+                let vertex = &mut posed[index];
+                vertex.position = (part.transform * vertex.position.push(1.0)).xyz();
+                vertex.transformed_position = vertex.position;
+                vertex.normal = (rotation * vertex.normal).normalize();
+                vertex.transformed_normal = vertex.normal;
+                vertex.tangent = rotation * vertex.tangent;
+                vertex.bitangent = rotation * vertex.bitangent;
+            }
+        }
+        Some(posed)
+    }
+
+    fn release_textures(&mut self, assets: &mut Assets) {
+        for surface in &mut self.surfaces {
+            surface.image = None;
+            if let Some(handle) = surface.texture.take() {
+                assets.release_texture(handle);
+            }
+        }
+    }
+
+    // Nave embebida en el binario, para cuando no hay modelos en disco o fallan al cargar
+    fn create_fallback_model(assets: &mut Assets, material: &ShipMaterial) -> ShipMesh {
+        let model = Model::from_obj_bytes(embedded::DEFAULT_SHIP_OBJ, embedded::DEFAULT_SHIP_NAME)
+            .expect("embedded default ship must be a valid OBJ")
+            .process(Self::mesh_options(material));
+        Self::convert_model_to_vertices(assets, &model, material)
+    }
+
+    fn mesh_options(material: &ShipMaterial) -> MeshOptions {
+        MeshOptions { crease_angle: material.smooth_normals }
+    }
+
+    // Geometría del modelo ya procesado (centrado y de tamaño unitario) escalada a SHIP_MODEL_SIZE
+    fn convert_model_to_vertices(assets: &mut Assets, model: &ProcessedModel, material: &ShipMaterial) -> ShipMesh {
+        let sampler = material.texture_sampler;
+        // La escala es uniforme, así que normales y tangentes no cambian
+        let mut mesh = model.mesh.clone();
+        for vertex in &mut mesh.vertices {
+            vertex.position *= SHIP_MODEL_SIZE;
+            vertex.transformed_position = vertex.position;
+        }
+
+        // Cada grupo de caras del .obj (3 índices por cara) se vuelve una superficie
+        let mut surfaces: Vec<Surface> = model.groups
+            .iter()
+            .map(|group| {
+                let material = group.material.and_then(|index| model.materials.get(index).cloned());
+                let (texture, image) = material
+                    .as_ref()
+                    .and_then(|material| Self::load_texture(assets, material, sampler))
+                    .unzip();
+                Surface { material, texture, image, indices: group.faces.start * 3..group.faces.end * 3 }
+            })
+            .collect();
+        if surfaces.is_empty() {
+            surfaces.push(Surface { material: None, texture: None, image: None, indices: 0..mesh.indices.len() });
+        }
+
+        let parts = Self::split_parts(&mut mesh, model);
+        ShipMesh { model: None, geometry: mesh, surfaces, parts }
+    }
+
+    // Una parte por objeto del .obj. Los vértices que funde `to_mesh` entre dos objetos se
+    // duplican, para que mover una parte no arrastre a la otra.
+    fn split_parts(mesh: &mut Mesh, model: &ProcessedModel) -> Vec<ShipPart> {
+        let mut owner: Vec<Option<usize>> = vec![None; mesh.vertices.len()];
+        let mut copies: HashMap<(usize, u32), u32> = HashMap::new();
+        let mut parts = Vec::with_capacity(model.objects.len());
+        for (part, object) in model.objects.iter().enumerate() {
+            let indices = object.faces.start * 3..object.faces.end * 3;
+            for slot in indices.clone() {
+                let index = mesh.indices[slot];
+                match owner[index as usize] {
+                    None => owner[index as usize] = Some(part),
+                    Some(other) if other == part => {}
+                    Some(_) => {
+                        mesh.indices[slot] = *copies.entry((part, index)).or_insert_with(|| {
+                            mesh.vertices.push(mesh.vertices[index as usize].clone());
+                            (mesh.vertices.len() - 1) as u32
+                        });
+                    }
+                }
+            }
+            debug!("Ship part `{}`: {} faces", object.name, object.faces.len());
+            parts.push(ShipPart { name: object.name.clone(), indices, transform: Mat4::identity() });
+        }
+        parts
+    }
+
+    fn update_warp_drive(&mut self, delta_time: f32) {
+        self.warp_drive = match self.warp_drive {
+            WarpDrive::Jumping { speed, remaining } if remaining > delta_time => {
+                WarpDrive::Jumping { speed, remaining: remaining - delta_time }
+            }
+            WarpDrive::Jumping { .. } => {
+                // Al salir del salto conservar solo un poco de inercia
+                self.velocity *= 0.1;
+                WarpDrive::Cooldown { remaining: WARP_COOLDOWN }
+            }
+            WarpDrive::Cooldown { remaining } if remaining > delta_time => {
+                WarpDrive::Cooldown { remaining: remaining - delta_time }
+            }
+            WarpDrive::Cooldown { .. } => WarpDrive::Idle,
+            state => state,
+        };
+    }
+
+    // Mantener la tecla de warp carga el salto; no hace nada durante el enfriamiento
+    pub fn charge_warp(&mut self, delta_time: f32) {
+        self.warp_drive = match self.warp_drive {
+            WarpDrive::Idle => WarpDrive::Charging { charge: (WARP_CHARGE_RATE * delta_time).min(1.0) },
+            WarpDrive::Charging { charge } => WarpDrive::Charging {
+                charge: (charge + WARP_CHARGE_RATE * delta_time).min(1.0),
+            },
+            state => state,
+        };
+    }
+
+    // Al soltar la tecla se salta si hay carga suficiente; si no, se descarga sin efecto
+    pub fn release_warp(&mut self) {
+        if let WarpDrive::Charging { charge } = self.warp_drive {
+            self.warp_drive = if charge >= WARP_MIN_CHARGE {
+                WarpDrive::Jumping {
+                    speed: WARP_MAX_SPEED * charge,
+                    remaining: WARP_JUMP_DURATION,
+                }
+            } else {
+                WarpDrive::Idle
+            };
+        }
+    }
+
+    // Intensidad del efecto visual del warp (0.0 sin efecto, 1.0 salto en curso)
+    pub fn warp_effect_intensity(&self) -> f32 {
+        match self.warp_drive {
+            WarpDrive::Charging { charge } => charge * 0.35,
+            WarpDrive::Jumping { remaining, .. } => (remaining / WARP_JUMP_DURATION).sqrt().max(0.35),
+            _ => 0.0,
+        }
+    }
+
+    pub fn apply_thrust(&mut self, thrust: f32) {
+        self.velocity += self.forward() * thrust;
+    }
+
+    pub fn rotate(&mut self, delta_y: f32) {
+        self.rotation.y += delta_y;
+        self.turn_input += delta_y.signum();
+        // Keep rotation in [0, 2π] range
+        if self.rotation.y > 2.0 * PI {
+            self.rotation.y -= 2.0 * PI;
+        } else if self.rotation.y < 0.0 {
+            self.rotation.y += 2.0 * PI;
+        }
+    }
+
+    pub fn get_model_name(&self) -> &str {
+        if self.is_loaded {
+            &self.model_name
+        } else {
+            "Built-in Ship"
+        }
+    }
+}
+
+impl Vehicle for Spaceship {
+    fn update(&mut self, delta_time: f32) {
+        self.update_warp_drive(delta_time);
+
+        // Alabeo visual: la nave se inclina hacia el lado del giro y vuelve a nivel al soltar
+        let target_roll = -self.turn_input.clamp(-1.0, 1.0) * MAX_BANK;
+        self.rotation.z += (target_roll - self.rotation.z) * (BANK_RESPONSE * delta_time).min(1.0);
+        self.turn_input = 0.0;
+
+        // Durante el salto la nave avanza a velocidad fija hacia donde apunta
+        if let WarpDrive::Jumping { speed, .. } = self.warp_drive {
+            self.velocity = self.forward() * speed;
+        }
+
+        // Update position based on velocity
+        self.position += self.velocity * delta_time;
+        
+        // Apply some drag (no durante el salto)
+        if !matches!(self.warp_drive, WarpDrive::Jumping { .. }) {
+            self.velocity *= drag_factor(delta_time);
+        }
+        
+        // Límite de la zona de juego: empuje suave de regreso o reaparición del otro lado
+        let event = self.play_area.apply(&mut self.position, &mut self.velocity, delta_time);
+        if event == BoundaryEvent::Pushed && matches!(self.warp_drive, WarpDrive::Jumping { .. }) {
+            // Un salto que sale del sistema se corta ahí
+            self.velocity *= 0.1;
+            self.warp_drive = WarpDrive::Cooldown { remaining: WARP_COOLDOWN };
+        }
+    }
+
+    fn position(&self) -> Vec3 {
+        self.position
+    }
+
+    fn velocity(&self) -> Vec3 {
+        self.velocity
+    }
+
+    fn forward(&self) -> Vec3 {
+        Vec3::new(
+            self.rotation.y.sin(),
+            0.0,
+            self.rotation.y.cos(),
+        )
+    }
+
+    fn render_data(&self) -> RenderData<'_> {
+        RenderData {
+            position: self.position,
+            rotation: self.rotation,
+            scale: self.scale,
+            vertices: self.posed_vertices.as_deref().unwrap_or(&self.vertices),
+            indices: &self.indices,
+            surfaces: &self.surfaces,
+            material: &self.material,
+        }
+    }
+
+    fn bounding_radius(&self) -> f32 {
+        self.collision_shape.radius * self.scale
+    }
+
+    fn check_collision(&self, body_position: Vec3, body_radius: f32) -> bool {
+        self.collision_shape.intersects_sphere(
+            self.collision_mode,
+            self.position,
+            &rotation_matrix(self.rotation),
+            self.scale,
+            body_position,
+            body_radius,
+        )
+    }
+
+    fn handle_collision(&mut self, body_position: Vec3) {
+        // Empujar la nave lejos del cuerpo
+        let direction = (self.position - body_position).normalize();
+        self.velocity = direction * 2.0; // Rebote
+        self.position += direction * 0.5; // Separar inmediatamente
+    }
+}
+
+impl Renderable for Spaceship {
+    fn render_pass(&self) -> RenderPass {
+        RenderPass::Opaque
+    }
+
+    fn name(&self) -> &str {
+        "Spaceship"
+    }
+
+    // Con la orientación de collision::rotation_matrix y no la de create_model_matrix (la de
+    // los cuerpos), para que la nave se dibuje igual que choca y el alabeo sea sobre su eje
+    fn model_matrix(&self) -> Mat4 {
+        let rotation = rotation_matrix(self.rotation).to_homogeneous();
+        nalgebra_glm::translation(&self.position) * nalgebra_glm::scaling(&Vec3::repeat(self.scale)) * rotation
+    }
+
+    // Una pasada por material del .mtl (una sola si el modelo no trae materiales)
+    fn draw_items<'a>(&'a self, view: &RenderView<'a>) -> Vec<DrawItem<'a>> {
+        let vertices = self.posed_vertices.as_deref().unwrap_or(&self.vertices);
+        self.surfaces
+            .iter()
+            .map(|surface| DrawItem {
+                vertices: Cow::Borrowed(vertices),
+                indices: Cow::Borrowed(&self.indices[surface.indices.clone()]),
+                shading: Shading::Ship {
+                    material: &self.material,
+                    surface_material: surface.material.as_ref(),
+                    texture: surface.image.clone(),
+                },
+                time: view.time,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Dos objetos que comparten la arista 2-3
+    const TWO_PARTS: &[u8] = b"v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\no Hull\nf 1 2 3\no Pod\nf 2 4 3\n";
+
+    fn split(obj: &[u8]) -> (Mesh, Vec<ShipPart>) {
+        let model = Model::from_obj_bytes(obj, "parts.obj").unwrap().process(MeshOptions::default());
+        let mut mesh = model.mesh.clone();
+        let parts = Spaceship::split_parts(&mut mesh, &model);
+        (mesh, parts)
+    }
+
+    fn part_vertices(mesh: &Mesh, part: &ShipPart) -> Vec<u32> {
+        mesh.indices[part.indices.clone()].to_vec()
+    }
+
+    #[test]
+    fn parts_do_not_share_vertices() {
+        let (mesh, parts) = split(TWO_PARTS);
+        assert_eq!(parts.iter().map(|part| part.name.as_str()).collect::<Vec<_>>(), ["Hull", "Pod"]);
+        let hull = part_vertices(&mesh, &parts[0]);
+        assert!(part_vertices(&mesh, &parts[1]).iter().all(|index| !hull.contains(index)));
+        assert_eq!(mesh.vertices.len(), 6);
+    }
+
+    #[test]
+    fn moving_a_part_leaves_the_others_in_place() {
+        let (mesh, mut parts) = split(TWO_PARTS);
+        assert!(Spaceship::pose_parts(&mesh.vertices, &mesh.indices, &parts).is_none());
+
+        let offset = Vec3::new(0.0, 0.0, 2.0);
+        parts[1].transform = nalgebra_glm::translation(&offset);
+        let posed = Spaceship::pose_parts(&mesh.vertices, &mesh.indices, &parts).unwrap();
+        for index in part_vertices(&mesh, &parts[0]) {
+            assert_eq!(posed[index as usize].position, mesh.vertices[index as usize].position);
+        }
+        for index in part_vertices(&mesh, &parts[1]) {
+            assert_eq!(posed[index as usize].position, mesh.vertices[index as usize].position + offset);
+        }
+    }
+}
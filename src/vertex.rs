@@ -9,6 +9,8 @@ pub tex_coords: Vec2,
 pub color: Color,
 pub transformed_position: Vec3,
 pub transformed_normal: Vec3,
+pub tangent: Vec3,    // Dirección de +u en espacio de modelo (ver `normals::compute_tangents`)
+pub bitangent: Vec3,  // Dirección de +v, con la orientación de las UV
 }
 
 impl Vertex {
@@ -20,6 +22,8 @@ pub fn new(position: Vec3, normal: Vec3, tex_coords: Vec2) -> Self {
    color: Color::black(),
    transformed_position: position,
    transformed_normal: normal,
+   tangent: Vec3::new(0.0, 0.0, 0.0),
+   bitangent: Vec3::new(0.0, 0.0, 0.0),
    }
 }
 
@@ -31,6 +35,8 @@ pub fn new_with_color(position: Vec3, color: Color) -> Self {
    color,
    transformed_position: Vec3::new(0.0, 0.0, 0.0),
    transformed_normal: Vec3::new(0.0, 0.0, 0.0),
+   tangent: Vec3::new(0.0, 0.0, 0.0),
+   bitangent: Vec3::new(0.0, 0.0, 0.0),
    }
 }
 
@@ -49,6 +55,8 @@ fn default() -> Self {
    color: Color::black(),
    transformed_position: Vec3::new(0.0, 0.0, 0.0),
    transformed_normal: Vec3::new(0.0, 1.0, 0.0),
+   tangent: Vec3::new(1.0, 0.0, 0.0),
+   bitangent: Vec3::new(0.0, 0.0, 1.0),
    }
 }
 }
@@ -0,0 +1,87 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use log::error;
+use wasm_bindgen::prelude::*;
+use crate::backend::{Backend, BackendKind};
+use crate::engine::{Engine, FrameStatus};
+use crate::frame_stats::Stage;
+use crate::input::InputState;
+use crate::logging;
+use crate::settings::{Settings, SETTINGS_FILE};
+
+const WIDTH: usize = 800;
+const HEIGHT: usize = 600;
+
+// Callback de requestAnimationFrame que se vuelve a agendar a sí mismo
+type FrameCallback = Rc<RefCell<Option<Closure<dyn FnMut()>>>>;
+
+// Punto de entrada del build web. El navegador marca el ritmo con requestAnimationFrame
+// (no hay bucle ni `thread::sleep`): cada callback corre un frame y pide el siguiente.
+#[wasm_bindgen(start)]
+pub fn start() -> Result<(), JsValue> {
+    logging::init(logging::DEFAULT_LEVEL);
+    let backend = BackendKind::Minifb.open("Space Travel", WIDTH, HEIGHT).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let engine = Engine::new(WIDTH, HEIGHT, Settings::load(SETTINGS_FILE));
+    engine.print_controls();
+
+    let mut app = WebApp { backend, engine, input: InputState::new(), last_frame_ms: now_ms() };
+
+    // El closure se agenda a sí mismo, así que necesita una referencia a su propio Rc
+    let callback: FrameCallback = Rc::new(RefCell::new(None));
+    let next = callback.clone();
+    *callback.borrow_mut() = Some(Closure::new(move || {
+        if app.frame() {
+            if let Some(next) = next.borrow().as_ref() {
+                request_animation_frame(next);
+            }
+        }
+    }));
+    if let Some(callback) = callback.borrow().as_ref() {
+        request_animation_frame(callback);
+    }
+    Ok(())
+}
+
+struct WebApp {
+    backend: Box<dyn Backend>,
+    engine: Engine,
+    input: InputState,
+    last_frame_ms: f64,
+}
+
+impl WebApp {
+    // Un frame; false cuando se eligió "Quit" en el menú o el canvas dejó de aceptar frames
+    // (queda con la última imagen)
+    fn frame(&mut self) -> bool {
+        let now = now_ms();
+        let frame_time = ((now - self.last_frame_ms) / 1000.0) as f32;
+        self.last_frame_ms = now;
+
+        self.backend.poll(&mut self.input);
+        if self.engine.frame(&self.input, frame_time) == FrameStatus::Quit {
+            return false;
+        }
+
+        let renderer = &mut self.engine.renderer;
+        let framebuffer = &renderer.framebuffer;
+        let backend = &mut self.backend;
+        if let Err(e) = renderer.frame_stats.time(Stage::Present, || backend.present(framebuffer)) {
+            error!("{}", e);
+            return false;
+        }
+        renderer.frame_stats.end_frame(frame_time);
+        true
+    }
+}
+
+fn request_animation_frame(callback: &Closure<dyn FnMut()>) {
+    if let Some(window) = web_sys::window() {
+        let _ = window.request_animation_frame(callback.as_ref().unchecked_ref());
+    }
+}
+
+fn now_ms() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or(0.0, |performance| performance.now())
+}
@@ -0,0 +1,257 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+use winit::window::Window;
+use log::info;
+use crate::error::Error;
+use crate::framebuffer::Framebuffer;
+use crate::winit_backend::Presenter;
+
+// Triángulo que cubre la pantalla y muestrea el framebuffer subido como textura.
+// El alfa del framebuffer (0RGB) no se usa.
+const PRESENT_SHADER: &str = r#"
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+@group(0) @binding(0) var frame_texture: texture_2d<f32>;
+@group(0) @binding(1) var frame_sampler: sampler;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(textureSample(frame_texture, frame_sampler, in.uv).rgb, 1.0);
+}
+"#;
+
+// Presentación por GPU: el rasterizador sigue siendo por software, pero el framebuffer se
+// sube como textura cada frame y se dibuja escalado a la superficie de la ventana (tamaño
+// físico, con vsync). Deja el camino listo para post-procesos en la GPU.
+pub struct WgpuPresenter {
+    surface: wgpu::Surface<'static>,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    config: wgpu::SurfaceConfiguration,
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    texture_format: wgpu::TextureFormat,
+    frame_texture: Option<FrameTexture>,
+}
+
+// Textura del tamaño del framebuffer; se recrea cuando cambia la resolución interna
+struct FrameTexture {
+    texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+    size: (usize, usize),
+}
+
+impl WgpuPresenter {
+    pub fn new(window: Arc<Window>) -> Result<Self, String> {
+        let size = window.inner_size();
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let surface = instance.create_surface(window).map_err(|e| e.to_string())?;
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            compatible_surface: Some(&surface),
+            ..Default::default()
+        }))
+        .map_err(|e| e.to_string())?;
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+            label: Some("present device"),
+            required_limits: wgpu::Limits::downlevel_webgl2_defaults().using_resolution(adapter.limits()),
+            ..Default::default()
+        }))
+        .map_err(|e| e.to_string())?;
+
+        let mut config = surface
+            .get_default_config(&adapter, size.width.max(1), size.height.max(1))
+            .ok_or("the GPU adapter cannot present to this window")?;
+        config.present_mode = wgpu::PresentMode::AutoVsync;
+        surface.configure(&device, &config);
+        info!("GPU presenter: {} ({:?}, {:?})", adapter.get_info().name, adapter.get_info().backend, config.format);
+
+        // Con una superficie sRGB la textura también lo es: se decodifica al muestrear y se
+        // vuelve a codificar al escribir, así los colores llegan tal cual los dejó el rasterizador
+        let texture_format = if config.format.is_srgb() {
+            wgpu::TextureFormat::Bgra8UnormSrgb
+        } else {
+            wgpu::TextureFormat::Bgra8Unorm
+        };
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("present shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(PRESENT_SHADER)),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("present bind group layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("present pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("present pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(config.format.into())],
+            }),
+            multiview: None,
+            cache: None,
+        });
+        // Vecino más cercano, igual que los otros backends (píxeles nítidos al escalar)
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("present sampler"),
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Ok(WgpuPresenter {
+            surface,
+            device,
+            queue,
+            config,
+            pipeline,
+            bind_group_layout,
+            sampler,
+            texture_format,
+            frame_texture: None,
+        })
+    }
+
+    fn ensure_frame_texture(&mut self, width: usize, height: usize) {
+        if self.frame_texture.as_ref().is_none_or(|frame| frame.size != (width, height)) {
+            let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("framebuffer"),
+                size: wgpu::Extent3d { width: width as u32, height: height as u32, depth_or_array_layers: 1 },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.texture_format,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("present bind group"),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+                ],
+            });
+            self.frame_texture = Some(FrameTexture { texture, bind_group, size: (width, height) });
+        }
+    }
+}
+
+impl Presenter for WgpuPresenter {
+    fn present(&mut self, window: &Window, framebuffer: &Framebuffer) -> Result<(), Error> {
+        let size = window.inner_size();
+        if size.width == 0 || size.height == 0 {
+            return Ok(());  // Minimizada
+        }
+        if (size.width, size.height) != (self.config.width, self.config.height) {
+            self.config.width = size.width;
+            self.config.height = size.height;
+            self.surface.configure(&self.device, &self.config);
+        }
+
+        let frame = match self.surface.get_current_texture() {
+            Ok(frame) => frame,
+            Err(wgpu::SurfaceError::Outdated | wgpu::SurfaceError::Lost) => {
+                self.surface.configure(&self.device, &self.config);
+                return Ok(());
+            }
+            // Sin memoria no hay forma de seguir; los demás (timeout, ventana tapada) pasan solos
+            Err(wgpu::SurfaceError::OutOfMemory) => return Err(Error::Present("out of GPU memory".to_string())),
+            Err(_) => return Ok(()),
+        };
+
+        // 0RGB en u32 little-endian son los bytes B, G, R, 0: BGRA sin conversión
+        let (width, height) = (framebuffer.width, framebuffer.height);
+        self.ensure_frame_texture(width, height);
+        let Some(frame_texture) = &self.frame_texture else {
+            return Ok(());
+        };
+        let pixels: Vec<u8> = framebuffer.buffer.iter().flat_map(|pixel| pixel.to_le_bytes()).collect();
+        self.queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &frame_texture.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &pixels,
+            wgpu::TexelCopyBufferLayout { offset: 0, bytes_per_row: Some(width as u32 * 4), rows_per_image: None },
+            wgpu::Extent3d { width: width as u32, height: height as u32, depth_or_array_layers: 1 },
+        );
+
+        let view = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("present") });
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("present pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), store: wgpu::StoreOp::Store },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &frame_texture.bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+        self.queue.submit([encoder.finish()]);
+        window.pre_present_notify();
+        frame.present();
+        Ok(())
+    }
+
+    fn vsync(&self) -> bool {
+        true
+    }
+}
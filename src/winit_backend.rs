@@ -0,0 +1,326 @@
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use std::time::Duration;
+use minifb::Key;
+use softbuffer::{Context, Surface};
+use winit::application::ApplicationHandler;
+use winit::dpi::LogicalSize;
+use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, EventLoop};
+use winit::keyboard::{KeyCode, PhysicalKey};
+use winit::platform::pump_events::{EventLoopExtPumpEvents, PumpStatus};
+use winit::window::{Window, WindowId};
+use log::warn;
+use crate::backend::{Backend, BackendKind};
+use crate::error::Error;
+use crate::framebuffer::Framebuffer;
+use crate::input::{InputEvent, InputState};
+
+// Backends alternativos: ventana de winit, presentada por software (softbuffer) o con la
+// GPU (wgpu). Funcionan nativos en Wayland y escalan el framebuffer al tamaño físico de la
+// ventana, así que en pantallas HiDPI (retina) la imagen ocupa la ventana entera.
+pub struct WinitBackend {
+    event_loop: EventLoop<()>,
+    app: WinitApp,
+    presenter: Box<dyn Presenter>,
+    kind: BackendKind,
+}
+
+// Cómo llega el framebuffer a la ventana de winit
+pub trait Presenter {
+    fn present(&mut self, window: &Window, framebuffer: &Framebuffer) -> Result<(), Error>;
+
+    fn vsync(&self) -> bool {
+        false
+    }
+}
+
+// Lo que winit maneja desde sus callbacks: la ventana y los eventos pendientes
+struct WinitApp {
+    title: String,
+    size: (usize, usize),
+    window: Option<Arc<Window>>,
+    pending: Vec<InputEvent>,
+    focus_lost: bool,
+    open: bool,
+}
+
+impl WinitBackend {
+    pub fn new(title: &str, width: usize, height: usize, kind: BackendKind) -> Result<Self, Error> {
+        let mut event_loop = EventLoop::new().map_err(|e| Error::Window(e.to_string()))?;
+        let mut app = WinitApp {
+            title: title.to_string(),
+            size: (width, height),
+            window: None,
+            pending: Vec::new(),
+            focus_lost: false,
+            open: true,
+        };
+
+        // La ventana se crea en `resumed`, que llega con los primeros eventos
+        event_loop.pump_app_events(Some(Duration::ZERO), &mut app);
+        let window = app.window.clone().ok_or_else(|| Error::Window("winit did not create a window".to_string()))?;
+
+        let presenter: Box<dyn Presenter> = match kind {
+            #[cfg(feature = "wgpu")]
+            BackendKind::Wgpu => Box::new(crate::wgpu_presenter::WgpuPresenter::new(window).map_err(Error::Window)?),
+            _ => Box::new(SoftbufferPresenter::new(window).map_err(Error::Window)?),
+        };
+        Ok(WinitBackend { event_loop, app, presenter, kind })
+    }
+}
+
+impl Backend for WinitBackend {
+    fn kind(&self) -> BackendKind {
+        self.kind
+    }
+
+    fn is_open(&self) -> bool {
+        self.app.open
+    }
+
+    fn poll(&mut self, input: &mut InputState) {
+        input.begin_frame();
+        if let PumpStatus::Exit(_) = self.event_loop.pump_app_events(Some(Duration::ZERO), &mut self.app) {
+            self.app.open = false;
+        }
+
+        if std::mem::take(&mut self.app.focus_lost) {
+            input.set_held([]);
+        }
+        for event in self.app.pending.drain(..) {
+            input.push(event);
+        }
+    }
+
+    // winit vuelve apenas llega un evento, sin esperar el resto del plazo
+    fn wait(&mut self, timeout: Duration) {
+        if let PumpStatus::Exit(_) = self.event_loop.pump_app_events(Some(timeout), &mut self.app) {
+            self.app.open = false;
+        }
+    }
+
+    fn present(&mut self, framebuffer: &Framebuffer) -> Result<(), Error> {
+        match &self.app.window {
+            Some(window) => self.presenter.present(window, framebuffer),
+            None => Ok(()),
+        }
+    }
+
+    fn vsync(&self) -> bool {
+        self.presenter.vsync()
+    }
+}
+
+// Presentación por software: se copia el framebuffer escalado al buffer de la ventana
+pub struct SoftbufferPresenter {
+    surface: Surface<Arc<Window>, Arc<Window>>,
+}
+
+impl SoftbufferPresenter {
+    pub fn new(window: Arc<Window>) -> Result<Self, String> {
+        let context = Context::new(window.clone()).map_err(|e| e.to_string())?;
+        let surface = Surface::new(&context, window).map_err(|e| e.to_string())?;
+        Ok(SoftbufferPresenter { surface })
+    }
+}
+
+impl Presenter for SoftbufferPresenter {
+    fn present(&mut self, window: &Window, framebuffer: &Framebuffer) -> Result<(), Error> {
+        let size = window.inner_size();
+        let (Some(width), Some(height)) = (NonZeroU32::new(size.width), NonZeroU32::new(size.height)) else {
+            return Ok(());  // Minimizada
+        };
+        let present_error = |e: softbuffer::SoftBufferError| Error::Present(e.to_string());
+        self.surface.resize(width, height).map_err(present_error)?;
+        let mut buffer = self.surface.buffer_mut().map_err(present_error)?;
+
+        // Escalado por vecino más cercano del framebuffer al tamaño físico de la ventana
+        let (target_width, target_height) = (size.width as usize, size.height as usize);
+        for y in 0..target_height {
+            let source_row = (y * framebuffer.height / target_height) * framebuffer.width;
+            let target_row = y * target_width;
+            for x in 0..target_width {
+                buffer[target_row + x] = framebuffer.buffer[source_row + x * framebuffer.width / target_width];
+            }
+        }
+        // softbuffer espera 0RGB, igual que el framebuffer
+        buffer.present().map_err(present_error)
+    }
+}
+
+impl ApplicationHandler for WinitApp {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.window.is_some() {
+            return;
+        }
+        let attributes = Window::default_attributes()
+            .with_title(self.title.clone())
+            .with_inner_size(LogicalSize::new(self.size.0 as f64, self.size.1 as f64));
+        match event_loop.create_window(attributes) {
+            Ok(window) => self.window = Some(Arc::new(window)),
+            Err(e) => {
+                warn!("Could not create winit window: {}", e);
+                event_loop.exit();
+            }
+        }
+    }
+
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, _window_id: WindowId, event: WindowEvent) {
+        match event {
+            WindowEvent::CloseRequested => {
+                self.open = false;
+                event_loop.exit();
+            }
+            WindowEvent::Focused(false) => self.focus_lost = true,
+            WindowEvent::KeyboardInput { event, .. } => {
+                let PhysicalKey::Code(code) = event.physical_key else {
+                    return;
+                };
+                let Some(key) = key_from_code(code) else {
+                    return;
+                };
+                self.pending.push(match (event.state, event.repeat) {
+                    (ElementState::Pressed, false) => InputEvent::KeyPressed(key),
+                    (ElementState::Pressed, true) => InputEvent::KeyRepeated(key),
+                    (ElementState::Released, _) => InputEvent::KeyReleased(key),
+                });
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                let scale = self.window.as_ref().map_or(1.0, |window| window.scale_factor());
+                let logical = position.to_logical::<f32>(scale);
+                self.pending.push(InputEvent::MouseMoved { x: logical.x, y: logical.y });
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                let button = match button {
+                    MouseButton::Left => 0,
+                    MouseButton::Middle => 1,
+                    MouseButton::Right => 2,
+                    _ => return,
+                };
+                self.pending.push(InputEvent::MouseButton { button, down: state == ElementState::Pressed });
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let (x, y) = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => (x, y),
+                    MouseScrollDelta::PixelDelta(position) => (position.x as f32, position.y as f32),
+                };
+                self.pending.push(InputEvent::Scroll { x, y });
+            }
+            _ => {}
+        }
+    }
+}
+
+// Teclas físicas de winit al conjunto de `minifb::Key` que usan keymap y menús
+fn key_from_code(code: KeyCode) -> Option<Key> {
+    Some(match code {
+        KeyCode::Digit0 => Key::Key0,
+        KeyCode::Digit1 => Key::Key1,
+        KeyCode::Digit2 => Key::Key2,
+        KeyCode::Digit3 => Key::Key3,
+        KeyCode::Digit4 => Key::Key4,
+        KeyCode::Digit5 => Key::Key5,
+        KeyCode::Digit6 => Key::Key6,
+        KeyCode::Digit7 => Key::Key7,
+        KeyCode::Digit8 => Key::Key8,
+        KeyCode::Digit9 => Key::Key9,
+        KeyCode::KeyA => Key::A,
+        KeyCode::KeyB => Key::B,
+        KeyCode::KeyC => Key::C,
+        KeyCode::KeyD => Key::D,
+        KeyCode::KeyE => Key::E,
+        KeyCode::KeyF => Key::F,
+        KeyCode::KeyG => Key::G,
+        KeyCode::KeyH => Key::H,
+        KeyCode::KeyI => Key::I,
+        KeyCode::KeyJ => Key::J,
+        KeyCode::KeyK => Key::K,
+        KeyCode::KeyL => Key::L,
+        KeyCode::KeyM => Key::M,
+        KeyCode::KeyN => Key::N,
+        KeyCode::KeyO => Key::O,
+        KeyCode::KeyP => Key::P,
+        KeyCode::KeyQ => Key::Q,
+        KeyCode::KeyR => Key::R,
+        KeyCode::KeyS => Key::S,
+        KeyCode::KeyT => Key::T,
+        KeyCode::KeyU => Key::U,
+        KeyCode::KeyV => Key::V,
+        KeyCode::KeyW => Key::W,
+        KeyCode::KeyX => Key::X,
+        KeyCode::KeyY => Key::Y,
+        KeyCode::KeyZ => Key::Z,
+        KeyCode::F1 => Key::F1,
+        KeyCode::F2 => Key::F2,
+        KeyCode::F3 => Key::F3,
+        KeyCode::F4 => Key::F4,
+        KeyCode::F5 => Key::F5,
+        KeyCode::F6 => Key::F6,
+        KeyCode::F7 => Key::F7,
+        KeyCode::F8 => Key::F8,
+        KeyCode::F9 => Key::F9,
+        KeyCode::F10 => Key::F10,
+        KeyCode::F11 => Key::F11,
+        KeyCode::F12 => Key::F12,
+        KeyCode::F13 => Key::F13,
+        KeyCode::F14 => Key::F14,
+        KeyCode::F15 => Key::F15,
+        KeyCode::ArrowDown => Key::Down,
+        KeyCode::ArrowLeft => Key::Left,
+        KeyCode::ArrowRight => Key::Right,
+        KeyCode::ArrowUp => Key::Up,
+        KeyCode::Quote => Key::Apostrophe,
+        KeyCode::Backquote => Key::Backquote,
+        KeyCode::Backslash => Key::Backslash,
+        KeyCode::Comma => Key::Comma,
+        KeyCode::Equal => Key::Equal,
+        KeyCode::BracketLeft => Key::LeftBracket,
+        KeyCode::Minus => Key::Minus,
+        KeyCode::Period => Key::Period,
+        KeyCode::BracketRight => Key::RightBracket,
+        KeyCode::Semicolon => Key::Semicolon,
+        KeyCode::Slash => Key::Slash,
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Delete => Key::Delete,
+        KeyCode::End => Key::End,
+        KeyCode::Enter => Key::Enter,
+        KeyCode::Escape => Key::Escape,
+        KeyCode::Home => Key::Home,
+        KeyCode::Insert => Key::Insert,
+        KeyCode::ContextMenu => Key::Menu,
+        KeyCode::PageDown => Key::PageDown,
+        KeyCode::PageUp => Key::PageUp,
+        KeyCode::Pause => Key::Pause,
+        KeyCode::Space => Key::Space,
+        KeyCode::Tab => Key::Tab,
+        KeyCode::NumLock => Key::NumLock,
+        KeyCode::CapsLock => Key::CapsLock,
+        KeyCode::ScrollLock => Key::ScrollLock,
+        KeyCode::ShiftLeft => Key::LeftShift,
+        KeyCode::ShiftRight => Key::RightShift,
+        KeyCode::ControlLeft => Key::LeftCtrl,
+        KeyCode::ControlRight => Key::RightCtrl,
+        KeyCode::Numpad0 => Key::NumPad0,
+        KeyCode::Numpad1 => Key::NumPad1,
+        KeyCode::Numpad2 => Key::NumPad2,
+        KeyCode::Numpad3 => Key::NumPad3,
+        KeyCode::Numpad4 => Key::NumPad4,
+        KeyCode::Numpad5 => Key::NumPad5,
+        KeyCode::Numpad6 => Key::NumPad6,
+        KeyCode::Numpad7 => Key::NumPad7,
+        KeyCode::Numpad8 => Key::NumPad8,
+        KeyCode::Numpad9 => Key::NumPad9,
+        KeyCode::NumpadDecimal => Key::NumPadDot,
+        KeyCode::NumpadDivide => Key::NumPadSlash,
+        KeyCode::NumpadMultiply => Key::NumPadAsterisk,
+        KeyCode::NumpadSubtract => Key::NumPadMinus,
+        KeyCode::NumpadAdd => Key::NumPadPlus,
+        KeyCode::NumpadEnter => Key::NumPadEnter,
+        KeyCode::AltLeft => Key::LeftAlt,
+        KeyCode::AltRight => Key::RightAlt,
+        KeyCode::SuperLeft => Key::LeftSuper,
+        KeyCode::SuperRight => Key::RightSuper,
+        _ => return None,
+    })
+}
@@ -0,0 +1,28 @@
+// Imágenes de referencia: cada caso de `GoldenCase::defaults` se dibuja sin ventana y se compara
+// con su PNG en tests/golden. Para aceptar un cambio visual a propósito se regeneran con
+// `GOLDEN_UPDATE=1 cargo test --test golden` (o `cargo run -- --golden --update-golden`).
+use std::path::Path;
+use space_travel_simulator::golden::{GoldenCase, GoldenOutcome, Tolerance, GOLDEN_DIR};
+
+#[test]
+fn frames_match_golden_images() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(GOLDEN_DIR);
+    let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+    let update = std::env::var_os("GOLDEN_UPDATE").is_some();
+    let tolerance = Tolerance::default();
+
+    let mut changed = Vec::new();
+    for case in GoldenCase::defaults() {
+        match case.check(&dir, update, &tolerance, Some(&assets)) {
+            Ok(GoldenOutcome::Mismatch(comparison)) => changed.push(format!(
+                "{} ({:.2}% of the pixels differ, see {}.diff.png)",
+                case.name,
+                comparison.differing_fraction() * 100.0,
+                case.name,
+            )),
+            Ok(_) => {}
+            Err(e) => changed.push(format!("{}: {}", case.name, e)),
+        }
+    }
+    assert!(changed.is_empty(), "golden images changed: {}", changed.join(", "));
+}